@@ -174,6 +174,38 @@ impl ActivityIndicator {
     }
 
     fn content_to_render(&mut self, cx: &mut ViewContext<Self>) -> Content {
+        // Show the progress of any large file being loaded.
+        let mut large_file_loads = Vec::new();
+        for worktree in self.project.read(cx).worktrees() {
+            if let Some(worktree) = worktree.read(cx).as_local() {
+                large_file_loads.extend(worktree.large_file_loads().map(|(path, load)| {
+                    let file_name = path.file_name().unwrap_or(path.as_os_str());
+                    let loaded_percentage = load.loaded_len() * 100 / load.len.max(1);
+                    (file_name.to_string_lossy().to_string(), loaded_percentage)
+                }));
+            }
+        }
+        if let Some((file_name, loaded_percentage)) = large_file_loads.first() {
+            let mut message = format!("Loading {file_name} ({loaded_percentage}%)");
+            if large_file_loads.len() > 1 {
+                write!(&mut message, " + {} more", large_file_loads.len() - 1).unwrap();
+            }
+            return Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+            };
+        }
+
         // Show any language server has pending activity.
         let mut pending_work = self.pending_language_server_work(cx);
         if let Some(PendingWork {
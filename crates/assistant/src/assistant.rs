@@ -1,8 +1,10 @@
 pub mod assistant_panel;
 pub mod assistant_settings;
 mod completion_provider;
+mod context_budget;
 mod context_store;
 mod inline_assistant;
+mod message_tree;
 mod model_selector;
 mod prompt_library;
 mod prompts;
@@ -19,15 +21,17 @@ use command_palette_hooks::CommandPaletteFilter;
 pub(crate) use completion_provider::*;
 pub(crate) use context_store::*;
 use gpui::{actions, AppContext, Global, SharedString, UpdateGlobal};
+use indexed_docs::IndexedDocsRegistry;
 pub(crate) use inline_assistant::*;
+pub(crate) use message_tree::*;
 pub(crate) use model_selector::*;
-use rustdoc::RustdocStore;
 use semantic_index::{CloudEmbeddingProvider, SemanticIndex};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use slash_command::{
-    active_command, default_command, diagnostics_command, fetch_command, file_command, now_command,
-    project_command, prompt_command, rustdoc_command, search_command, tabs_command, term_command,
+    active_command, default_command, diagnostics_command, diff_command, docs_command,
+    fetch_command, file_command, now_command, project_command, prompt_command, search_command,
+    tabs_command, term_command,
 };
 use std::{
     fmt::{self, Display},
@@ -49,7 +53,14 @@ actions!(
         ToggleHistory,
         ApplyEdit,
         ConfirmCommand,
-        ToggleModelSelector
+        ToggleModelSelector,
+        RegenerateMessage,
+        EditMessageInNewBranch,
+        SelectNextBranch,
+        SelectPrevBranch,
+        DiffBranches,
+        GenerateCommitMessage,
+        GeneratePullRequestDescription
     ]
 );
 
@@ -138,7 +149,7 @@ impl LanguageModel {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LanguageModelRequestMessage {
     pub role: Role,
     pub content: String,
@@ -159,7 +170,7 @@ impl LanguageModelRequestMessage {
     }
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct LanguageModelRequest {
     pub model: LanguageModel,
     pub messages: Vec<LanguageModelRequestMessage>,
@@ -289,7 +300,7 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
     register_slash_commands(cx);
     assistant_panel::init(cx);
     inline_assistant::init(client.telemetry().clone(), cx);
-    RustdocStore::init_global(cx);
+    IndexedDocsRegistry::init_global(cx);
 
     CommandPaletteFilter::update_global(cx, |filter, _cx| {
         filter.hide_namespace(Assistant::NAMESPACE);
@@ -320,7 +331,9 @@ fn register_slash_commands(cx: &mut AppContext) {
     slash_command_registry.register_command(term_command::TermSlashCommand, true);
     slash_command_registry.register_command(now_command::NowSlashCommand, true);
     slash_command_registry.register_command(diagnostics_command::DiagnosticsCommand, true);
-    slash_command_registry.register_command(rustdoc_command::RustdocSlashCommand, false);
+    slash_command_registry.register_command(diff_command::DiffSlashCommand, true);
+    slash_command_registry.register_command(docs_command::DocsSlashCommand, false);
+    slash_command_registry.register_command(docs_command::RustdocSlashCommand, false);
     slash_command_registry.register_command(fetch_command::FetchSlashCommand, false);
 }
 
@@ -1,15 +1,24 @@
 use crate::{
-    assistant_settings::{AssistantDockPosition, AssistantSettings},
+    assistant_settings::{AssistantDockPosition, AssistantSettings, ContextOverflowPolicy},
+    context_budget::{
+        last_referenced_message_ix, sections_to_trim, BudgetedSection, SectionTokenCount,
+    },
     prompt_library::open_prompt_library,
+    prompts::{generate_commit_message_prompt, generate_pull_request_description_prompt},
     search::*,
     slash_command::{
-        default_command::DefaultSlashCommand, SlashCommandCompletionProvider, SlashCommandLine,
-        SlashCommandRegistry,
+        default_command::DefaultSlashCommand,
+        diff_command::{collect_branch_changes, collect_diffs, recent_commit_subjects, DiffKind},
+        docs_command::{DocsSlashCommand, DocsSlashCommandArgs, RustdocSlashCommand},
+        SlashCommandCompletionProvider, SlashCommandLine, SlashCommandRegistry,
     },
-    ApplyEdit, Assist, CompletionProvider, ConfirmCommand, ContextStore, CycleMessageRole,
-    InlineAssist, InlineAssistant, LanguageModelRequest, LanguageModelRequestMessage, MessageId,
-    MessageMetadata, MessageStatus, ModelSelector, QuoteSelection, ResetKey, Role, SavedContext,
-    SavedContextMetadata, SavedMessage, Split, ToggleFocus, ToggleHistory, ToggleModelSelector,
+    ApplyEdit, ArchivedMessage, Assist, CompletionProvider, ConfirmCommand, ContextStore,
+    CycleMessageRole, DiffBranches, EditMessageInNewBranch, GenerateCommitMessage,
+    GeneratePullRequestDescription, InlineAssist, InlineAssistant, LanguageModel,
+    LanguageModelRequest, LanguageModelRequestMessage, MessageId, MessageMetadata, MessageStatus,
+    MessageTree, ModelSelector, QuoteSelection, RegenerateMessage, ResetKey, Role, SavedContext,
+    SavedContextMetadata, SavedMessage, SelectNextBranch, SelectPrevBranch, Split, ToggleFocus,
+    ToggleHistory, ToggleModelSelector,
 };
 use anyhow::{anyhow, Result};
 use assistant_slash_command::{SlashCommand, SlashCommandOutput, SlashCommandOutputSection};
@@ -26,7 +35,7 @@ use editor::{
 use editor::{display_map::CreaseId, FoldPlaceholder};
 use file_icons::FileIcons;
 use fs::Fs;
-use futures::future::Shared;
+use futures::future::{BoxFuture, Shared};
 use futures::{FutureExt, StreamExt};
 use gpui::{
     div, percentage, point, rems, Action, Animation, AnimationExt, AnyElement, AnyView, AppContext,
@@ -36,6 +45,7 @@ use gpui::{
     Subscription, Task, Transformation, UpdateGlobal, View, ViewContext, VisualContext, WeakView,
     WindowContext,
 };
+use indexed_docs::IndexedDocsStore;
 use language::{
     language_settings::SoftWrap, AnchorRangeExt as _, AutoindentMode, Buffer, LanguageRegistry,
     LspAdapterDelegate, OffsetRangeExt as _, Point, ToOffset as _,
@@ -44,12 +54,14 @@ use multi_buffer::MultiBufferRow;
 use paths::contexts_dir;
 use picker::{Picker, PickerDelegate};
 use project::{Project, ProjectLspAdapterDelegate, ProjectTransaction};
-use rustdoc::{CrateName, RustdocStore};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use settings::Settings;
 use std::{
     cmp::{self, Ordering},
+    collections::hash_map::DefaultHasher,
     fmt::Write,
+    future::Future,
+    hash::{Hash, Hasher},
     iter,
     ops::Range,
     path::PathBuf,
@@ -66,6 +78,7 @@ use uuid::Uuid;
 use workspace::NewFile;
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
+    notifications::NotifyTaskExt as _,
     searchable::Direction,
     Save, ToggleZoom, Toolbar, Workspace,
 };
@@ -83,6 +96,8 @@ pub fn init(cx: &mut AppContext) {
                     workspace.toggle_panel_focus::<AssistantPanel>(cx);
                 })
                 .register_action(AssistantPanel::inline_assist)
+                .register_action(AssistantPanel::generate_commit_message)
+                .register_action(AssistantPanel::generate_pull_request_description)
                 .register_action(ContextEditor::quote_selection);
         },
     )
@@ -350,6 +365,104 @@ impl AssistantPanel {
         }
     }
 
+    /// Opens a new buffer containing a commit message for the staged changes,
+    /// streamed from the current model.
+    fn generate_commit_message(
+        workspace: &mut Workspace,
+        _: &GenerateCommitMessage,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        const RECENT_COMMIT_COUNT: usize = 10;
+
+        let project = workspace.project().clone();
+        let diffs = collect_diffs(project.clone(), DiffKind::Staged, cx);
+        let recent_subjects = recent_commit_subjects(project, RECENT_COMMIT_COUNT, cx);
+        Self::generate_into_new_buffer(
+            workspace,
+            async move {
+                let diffs = diffs.await?;
+                if diffs.is_empty() {
+                    return Err(anyhow!("there are no staged changes to commit"));
+                }
+                let diff = diffs
+                    .iter()
+                    .map(|file_diff| file_diff.diff.as_str())
+                    .collect::<String>();
+                let recent_subjects = recent_subjects.await.log_err().unwrap_or_default();
+                Ok(generate_commit_message_prompt(&diff, &recent_subjects))
+            },
+            cx,
+        );
+    }
+
+    /// Opens a new buffer containing a pull request description for the changes on
+    /// the current branch, streamed from the current model.
+    fn generate_pull_request_description(
+        workspace: &mut Workspace,
+        _: &GeneratePullRequestDescription,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let changes = collect_branch_changes(workspace.project().clone(), cx);
+        Self::generate_into_new_buffer(
+            workspace,
+            async move {
+                let changes = changes.await;
+                if changes.is_empty() {
+                    return Err(anyhow!("there are no changes on the current branch"));
+                }
+                Ok(generate_pull_request_description_prompt(&changes))
+            },
+            cx,
+        );
+    }
+
+    /// Opens a new buffer and streams the current model's response to the given
+    /// prompt into it.
+    fn generate_into_new_buffer(
+        workspace: &mut Workspace,
+        prompt: impl Future<Output = Result<String>> + 'static,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let settings = AssistantSettings::get_global(cx);
+        if !settings.enabled {
+            return;
+        }
+        if !CompletionProvider::global(cx).is_authenticated() {
+            workspace.focus_panel::<AssistantPanel>(cx);
+            return;
+        }
+
+        let project = workspace.project().clone();
+        let buffer = project.update(cx, |project, cx| project.create_local_buffer("", None, cx));
+        let editor = cx.new_view(|cx| Editor::for_buffer(buffer.clone(), Some(project), cx));
+        workspace.add_item_to_center(Box::new(editor), cx);
+
+        let task: Task<Result<()>> = cx.spawn(|_, mut cx| async move {
+            let prompt = prompt.await?;
+            let request = cx.update(|cx| LanguageModelRequest {
+                model: CompletionProvider::global(cx).model(),
+                messages: vec![LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: prompt,
+                }],
+                stop: vec![],
+                temperature: 1.0,
+            })?;
+            let mut chunks = cx
+                .update(|cx| CompletionProvider::global(cx).complete(request))?
+                .await?;
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk?;
+                buffer.update(&mut cx, |buffer, cx| {
+                    let end = buffer.len();
+                    buffer.edit([(end..end, chunk)], None, cx);
+                })?;
+            }
+            Ok(())
+        });
+        task.detach_and_notify_err(cx);
+    }
+
     pub fn inline_assist(
         workspace: &mut Workspace,
         _: &InlineAssist,
@@ -789,9 +902,14 @@ impl AssistantPanel {
                                     .icon_size(IconSize::Small)
                                     .on_click({
                                         let language_registry = self.languages.clone();
+                                        let fs = self.fs.clone();
                                         cx.listener(move |_this, _event, cx| {
-                                            open_prompt_library(language_registry.clone(), cx)
-                                                .detach_and_log_err(cx);
+                                            open_prompt_library(
+                                                language_registry.clone(),
+                                                fs.clone(),
+                                                cx,
+                                            )
+                                            .detach_and_log_err(cx);
                                         })
                                     })
                                     .tooltip(|cx| Tooltip::text("Prompt Library…", cx)),
@@ -863,7 +981,8 @@ impl AssistantPanel {
         context: &Model<Context>,
         cx: &mut ViewContext<Self>,
     ) -> Option<impl IntoElement> {
-        let remaining_tokens = context.read(cx).remaining_tokens(cx)?;
+        let context = context.read(cx);
+        let remaining_tokens = context.remaining_tokens(cx)?;
         let remaining_tokens_color = if remaining_tokens <= 0 {
             Color::Error
         } else if remaining_tokens <= 500 {
@@ -871,10 +990,39 @@ impl AssistantPanel {
         } else {
             Color::Muted
         };
+
+        let buffer = context.buffer.read(cx);
+        let mut section_token_counts = context
+            .section_token_counts
+            .iter()
+            .filter(|section| section.range.start.is_valid(buffer))
+            .collect::<Vec<_>>();
+        section_token_counts.sort_by_key(|section| cmp::Reverse(section.token_count));
+        let breakdown = section_token_counts
+            .into_iter()
+            .map(|section| format!("{}: {} tokens", section.label, section.token_count))
+            .collect::<Vec<_>>()
+            .join("\n");
+
         Some(
-            Label::new(remaining_tokens.to_string())
-                .size(LabelSize::Small)
-                .color(remaining_tokens_color),
+            div()
+                .id("remaining-tokens")
+                .child(
+                    Label::new(remaining_tokens.to_string())
+                        .size(LabelSize::Small)
+                        .color(remaining_tokens_color),
+                )
+                .when(!breakdown.is_empty(), |this| {
+                    let breakdown = SharedString::from(breakdown);
+                    this.tooltip(move |cx| {
+                        Tooltip::with_meta(
+                            "Slash Command Output Tokens",
+                            None,
+                            breakdown.clone(),
+                            cx,
+                        )
+                    })
+                }),
         )
     }
 }
@@ -1017,12 +1165,15 @@ pub struct Context {
     slash_command_output_sections: Vec<SlashCommandOutputSection<language::Anchor>>,
     message_anchors: Vec<MessageAnchor>,
     messages_metadata: HashMap<MessageId, MessageMetadata>,
+    message_tree: MessageTree,
     next_message_id: MessageId,
     summary: Option<Summary>,
     pending_summary: Task<Option<()>>,
     completion_count: usize,
     pending_completions: Vec<PendingCompletion>,
     token_count: Option<usize>,
+    section_token_counts: Vec<SectionTokenCount<language::Anchor>>,
+    section_summaries: HashMap<u64, String>,
     pending_token_count: Task<Option<()>>,
     pending_edit_suggestion_parse: Option<Task<()>>,
     pending_save: Task<Result<()>>,
@@ -1053,6 +1204,7 @@ impl Context {
             id: Some(Uuid::new_v4().to_string()),
             message_anchors: Default::default(),
             messages_metadata: Default::default(),
+            message_tree: Default::default(),
             next_message_id: Default::default(),
             edit_suggestions: Vec::new(),
             pending_slash_commands: Vec::new(),
@@ -1063,6 +1215,8 @@ impl Context {
             completion_count: Default::default(),
             pending_completions: Default::default(),
             token_count: None,
+            section_token_counts: Vec::new(),
+            section_summaries: HashMap::default(),
             pending_token_count: Task::ready(None),
             pending_edit_suggestion_parse: None,
             _subscriptions: vec![cx.subscribe(&buffer, Self::handle_buffer_event)],
@@ -1094,6 +1248,10 @@ impl Context {
 
     fn serialize(&self, cx: &AppContext) -> SavedContext {
         let buffer = self.buffer.read(cx);
+        let active_message_ids = self
+            .messages(cx)
+            .map(|message| message.id)
+            .collect::<HashSet<_>>();
         SavedContext {
             id: self.id.clone(),
             zed: "context".into(),
@@ -1128,6 +1286,7 @@ impl Context {
                     }
                 })
                 .collect(),
+            archived_messages: self.message_tree.reachable_messages(&active_message_ids),
         }
     }
 
@@ -1147,7 +1306,13 @@ impl Context {
 
         let markdown = language_registry.language_for_name("Markdown");
         let mut message_anchors = Vec::new();
-        let mut next_message_id = MessageId(0);
+        let mut next_message_id = saved_context
+            .archived_messages
+            .iter()
+            .map(|message| MessageId(message.id.0 + 1))
+            .max()
+            .unwrap_or_default();
+        let message_tree = MessageTree::new(saved_context.archived_messages);
         let buffer = cx.new_model(|cx| {
             let mut buffer = Buffer::local(saved_context.text, cx);
             for message in saved_context.messages {
@@ -1176,6 +1341,7 @@ impl Context {
                 id,
                 message_anchors,
                 messages_metadata: saved_context.message_metadata,
+                message_tree,
                 next_message_id,
                 edit_suggestions: Vec::new(),
                 pending_slash_commands: Vec::new(),
@@ -1201,6 +1367,8 @@ impl Context {
                 completion_count: Default::default(),
                 pending_completions: Default::default(),
                 token_count: None,
+                section_token_counts: Vec::new(),
+                section_summaries: HashMap::default(),
                 pending_edit_suggestion_parse: None,
                 pending_token_count: Task::ready(None),
                 _subscriptions: vec![cx.subscribe(&buffer, Self::handle_buffer_event)],
@@ -1250,18 +1418,38 @@ impl Context {
 
     pub(crate) fn count_remaining_tokens(&mut self, cx: &mut ModelContext<Self>) {
         let request = self.to_completion_request(cx);
+        let buffer = self.buffer.read(cx);
+        let sections = self
+            .slash_command_output_sections
+            .iter()
+            .filter(|section| section.range.start.is_valid(buffer))
+            .map(|section| {
+                let text = buffer
+                    .text_for_range(section.range.clone())
+                    .collect::<String>();
+                (section.range.clone(), section.label.clone(), text)
+            })
+            .collect::<Vec<_>>();
         self.pending_token_count = cx.spawn(|this, mut cx| {
             async move {
                 cx.background_executor()
                     .timer(Duration::from_millis(200))
                     .await;
 
-                let token_count = cx
-                    .update(|cx| CompletionProvider::global(cx).count_tokens(request, cx))?
-                    .await?;
+                let (token_count, section_token_counts) = cx.update(|cx| {
+                    let section_token_counts =
+                        count_section_tokens(sections, request.model.clone(), cx);
+                    (
+                        CompletionProvider::global(cx).count_tokens(request, cx),
+                        section_token_counts,
+                    )
+                })?;
+                let token_count = token_count.await?;
+                let section_token_counts = section_token_counts.await?;
 
                 this.update(&mut cx, |this, cx| {
                     this.token_count = Some(token_count);
+                    this.section_token_counts = section_token_counts;
                     cx.notify()
                 })?;
                 anyhow::Ok(())
@@ -1595,8 +1783,7 @@ impl Context {
                 return Default::default();
             }
 
-            let request = self.to_completion_request(cx);
-            let stream = CompletionProvider::global(cx).complete(request);
+            let request = self.budgeted_completion_request(cx);
             let assistant_message = self
                 .insert_message_after(last_message_id, Role::Assistant, MessageStatus::Pending, cx)
                 .unwrap();
@@ -1612,8 +1799,11 @@ impl Context {
                     let assistant_message_id = assistant_message.id;
                     let mut response_latency = None;
                     let stream_completion = async {
+                        let request = request.await?;
                         let request_start = Instant::now();
-                        let mut messages = stream.await?;
+                        let mut messages = cx
+                            .update(|cx| CompletionProvider::global(cx).complete(request))?
+                            .await?;
 
                         while let Some(message) = messages.next().await {
                             if response_latency.is_none() {
@@ -1719,6 +1909,202 @@ impl Context {
         }
     }
 
+    /// Builds the request for the next completion, trimming the least recently
+    /// referenced slash command output if the context doesn't fit into the model's
+    /// context window.
+    ///
+    /// Tokens are counted for the request being sent, rather than relying on the
+    /// debounced [`Context::token_count`], which may not reflect the latest edits.
+    /// That count is only trusted to skip counting when it's well under the budget.
+    /// If counting fails, the request is sent untrimmed.
+    fn budgeted_completion_request(
+        &mut self,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<LanguageModelRequest>> {
+        let request = self.to_completion_request(cx);
+        let settings = AssistantSettings::get_global(cx).context_budget.clone();
+        if settings.overflow_policy == ContextOverflowPolicy::Ignore {
+            return Task::ready(Ok(request));
+        }
+        let budget = request
+            .model
+            .max_token_count()
+            .saturating_sub(settings.reserved_output_tokens);
+        if self
+            .token_count
+            .map_or(false, |token_count| token_count <= budget / 2)
+        {
+            return Task::ready(Ok(request));
+        }
+
+        let buffer = self.buffer.read(cx).snapshot();
+        let messages = self
+            .messages(cx)
+            .filter(|message| matches!(message.status, MessageStatus::Done))
+            .collect::<Vec<_>>();
+        let message_texts = messages
+            .iter()
+            .map(|message| {
+                let text = buffer
+                    .text_for_range(message.offset_range.clone())
+                    .collect::<String>();
+                (message.offset_range.clone(), text)
+            })
+            .collect::<Vec<_>>();
+        let sections = self
+            .slash_command_output_sections
+            .iter()
+            .filter(|section| section.range.start.is_valid(&buffer))
+            .map(|section| {
+                let range = section.range.to_offset(&buffer);
+                let text = buffer.text_for_range(range.clone()).collect::<String>();
+                (range, section.label.clone(), text)
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(|this, mut cx| async move {
+            let token_count = cx
+                .update(|cx| CompletionProvider::global(cx).count_tokens(request.clone(), cx))?
+                .await
+                .log_err();
+            let Some(token_count) = token_count.filter(|token_count| *token_count > budget) else {
+                return Ok(request);
+            };
+
+            let Some(section_token_counts) = cx
+                .update(|cx| count_section_tokens(sections, request.model.clone(), cx))?
+                .await
+                .log_err()
+            else {
+                return Ok(request);
+            };
+            let sections = section_token_counts
+                .into_iter()
+                .filter_map(|section| {
+                    Some(BudgetedSection {
+                        last_referenced_message_ix: last_referenced_message_ix(
+                            &section.range,
+                            &section.label,
+                            &message_texts,
+                        )?,
+                        range: section.range,
+                        label: section.label,
+                        token_count: section.token_count,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut trimmed_sections = sections_to_trim(&sections, token_count, budget)
+                .into_iter()
+                .map(|ix| sections[ix].clone())
+                .collect::<Vec<_>>();
+            trimmed_sections.sort_by_key(|section| section.range.start);
+            log::info!(
+                "context has {token_count} tokens, exceeding its budget of {budget}; \
+                trimming {} sections",
+                trimmed_sections.len()
+            );
+
+            match settings.overflow_policy {
+                ContextOverflowPolicy::Ignore => Ok(request),
+                ContextOverflowPolicy::Drop => {
+                    let replacements = trimmed_sections
+                        .into_iter()
+                        .map(|section| (section.range, omitted_section_text(&section.label)))
+                        .collect::<Vec<_>>();
+                    Ok(LanguageModelRequest {
+                        messages: request_messages_with_replacements(
+                            &messages,
+                            &buffer,
+                            &replacements,
+                        ),
+                        ..request
+                    })
+                }
+                ContextOverflowPolicy::Summarize => {
+                    let mut replacements = Vec::new();
+                    for section in trimmed_sections {
+                        let text = buffer
+                            .text_for_range(section.range.clone())
+                            .collect::<String>();
+                        let key = {
+                            let mut hasher = DefaultHasher::new();
+                            text.hash(&mut hasher);
+                            hasher.finish()
+                        };
+
+                        let cached_summary = this
+                            .read_with(&cx, |this, _| this.section_summaries.get(&key).cloned())?;
+                        let summary = match cached_summary {
+                            Some(summary) => Some(summary),
+                            None => Self::summarize_section(
+                                &section.label,
+                                text,
+                                request.model.clone(),
+                                &mut cx,
+                            )
+                            .await
+                            .log_err(),
+                        };
+
+                        let replacement = if let Some(summary) = summary {
+                            this.update(&mut cx, |this, _| {
+                                this.section_summaries.insert(key, summary.clone());
+                            })?;
+                            format!("[Summary of {}]\n{}", section.label, summary.trim())
+                        } else {
+                            omitted_section_text(&section.label)
+                        };
+                        replacements.push((section.range, replacement));
+                    }
+
+                    Ok(LanguageModelRequest {
+                        messages: request_messages_with_replacements(
+                            &messages,
+                            &buffer,
+                            &replacements,
+                        ),
+                        ..request
+                    })
+                }
+            }
+        })
+    }
+
+    async fn summarize_section(
+        label: &str,
+        text: String,
+        model: LanguageModel,
+        cx: &mut AsyncAppContext,
+    ) -> Result<String> {
+        let request = LanguageModelRequest {
+            model,
+            messages: vec![
+                LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: text,
+                },
+                LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: format!(
+                        "Summarize the above output of `{label}` as concisely as possible, \
+                        keeping any details that could be relevant to a programmer."
+                    ),
+                },
+            ],
+            stop: vec![],
+            temperature: 0.0,
+        };
+
+        let mut chunks = cx
+            .update(|cx| CompletionProvider::global(cx).complete(request))?
+            .await?;
+        let mut summary = String::new();
+        while let Some(chunk) = chunks.next().await {
+            summary.push_str(&chunk?);
+        }
+        Ok(summary)
+    }
+
     fn cancel_last_assist(&mut self) -> bool {
         self.pending_completions.pop().is_some()
     }
@@ -1885,6 +2271,302 @@ impl Context {
         }
     }
 
+    /// Regenerates the given assistant message, keeping the previous response (and
+    /// everything that followed it) as a sibling branch.
+    fn regenerate_message(
+        &mut self,
+        message_id: MessageId,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<MessageAnchor> {
+        let Some(parent) = self.message_parent(message_id, cx).flatten() else {
+            return Vec::new();
+        };
+        let role = |id: MessageId| {
+            self.messages_metadata
+                .get(&id)
+                .map(|metadata| metadata.role)
+        };
+        if role(message_id) != Some(Role::Assistant) || role(parent) == Some(Role::Assistant) {
+            return Vec::new();
+        }
+
+        self.archive_messages_from(message_id, cx);
+        self.assist(HashSet::from_iter([parent]), cx)
+    }
+
+    /// Replaces the given message and everything following it with a copy of the
+    /// message, so that it can be edited without losing the original conversation.
+    fn edit_message_in_new_branch(
+        &mut self,
+        message_id: MessageId,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<MessageAnchor> {
+        let role = self.messages_metadata.get(&message_id)?.role;
+        self.archive_messages_from(message_id, cx)?;
+        let archived_message =
+            ArchivedMessage::clone(self.message_tree.branch(message_id).first()?);
+
+        let message = ArchivedMessage {
+            id: MessageId(post_inc(&mut self.next_message_id.0)),
+            ..archived_message
+        };
+        self.messages_metadata.insert(
+            message.id,
+            MessageMetadata {
+                role,
+                status: MessageStatus::Done,
+            },
+        );
+        self.restore_messages(vec![message], cx).pop()
+    }
+
+    /// Replaces the given message and everything following it with the next or
+    /// previous sibling branch.
+    fn select_branch(
+        &mut self,
+        message_id: MessageId,
+        direction: Direction,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<MessageAnchor> {
+        let target_id = self.sibling_branch(message_id, direction, cx)?;
+        self.archive_messages_from(message_id, cx)?;
+        let messages = self.message_tree.restore_branch(target_id);
+        self.restore_messages(messages, cx).into_iter().next()
+    }
+
+    /// Returns the message preceding the given message, which is `None` for the first
+    /// message of the context.
+    fn message_parent(&self, message_id: MessageId, cx: &AppContext) -> Option<Option<MessageId>> {
+        let mut parent = None;
+        for message in self.messages(cx) {
+            if message.id == message_id {
+                return Some(parent);
+            }
+            parent = Some(message.id);
+        }
+        None
+    }
+
+    /// Returns the alternatives to the given message, including the message itself.
+    fn message_siblings(&self, message_id: MessageId, cx: &AppContext) -> Vec<MessageId> {
+        self.message_parent(message_id, cx)
+            .map(|parent| self.message_tree.siblings(parent, message_id))
+            .unwrap_or_default()
+    }
+
+    fn sibling_branch(
+        &self,
+        message_id: MessageId,
+        direction: Direction,
+        cx: &AppContext,
+    ) -> Option<MessageId> {
+        let siblings = self.message_siblings(message_id, cx);
+        let ix = siblings.iter().position(|id| *id == message_id)?;
+        match direction {
+            Direction::Next => siblings.get(ix + 1).copied(),
+            Direction::Prev => siblings.get(ix.checked_sub(1)?).copied(),
+        }
+    }
+
+    /// Returns a unified diff between the active branch starting at the given message
+    /// and the adjacent sibling branch.
+    fn branch_diff(&self, message_id: MessageId, cx: &AppContext) -> Option<String> {
+        let sibling_id = self
+            .sibling_branch(message_id, Direction::Next, cx)
+            .or_else(|| self.sibling_branch(message_id, Direction::Prev, cx))?;
+
+        let buffer = self.buffer.read(cx);
+        let active_branch = self
+            .messages(cx)
+            .skip_while(|message| message.id != message_id)
+            .map(|message| {
+                let text = buffer
+                    .text_for_range(message.offset_range)
+                    .collect::<String>();
+                (message.role, text)
+            })
+            .collect::<Vec<_>>();
+        let sibling_branch = self
+            .message_tree
+            .branch(sibling_id)
+            .into_iter()
+            .filter_map(|message| {
+                let metadata = self.messages_metadata.get(&message.id)?;
+                Some((metadata.role, message.text.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        let format_branch = |messages: Vec<(Role, String)>| {
+            let mut text = String::new();
+            for (role, message_text) in messages {
+                writeln!(text, "## {role}\n\n{}\n", message_text.trim_end()).unwrap();
+            }
+            text
+        };
+        let active_text = format_branch(active_branch);
+        let sibling_text = format_branch(sibling_branch);
+
+        Some(
+            similar::TextDiff::from_lines(&active_text, &sibling_text)
+                .unified_diff()
+                .header(
+                    &format!("message {}", message_id.0),
+                    &format!("message {}", sibling_id.0),
+                )
+                .to_string(),
+        )
+    }
+
+    /// Moves the given message and every message following it out of the buffer and
+    /// into the message tree.
+    fn archive_messages_from(
+        &mut self,
+        message_id: MessageId,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<()> {
+        let messages = self.messages(cx).collect::<Vec<_>>();
+        let ix = messages
+            .iter()
+            .position(|message| message.id == message_id)?;
+        let parent = ix.checked_sub(1).map(|ix| messages[ix].id);
+
+        let buffer = self.buffer.read(cx);
+        let archived_messages = messages[ix..]
+            .iter()
+            .map(|message| {
+                let message_start = message.offset_range.start;
+                let mut text = buffer
+                    .text_for_range(message.offset_range.clone())
+                    .collect::<String>();
+                // Strip the newline separating this message from the next one.
+                if message.offset_range.end < buffer.len() && text.ends_with('\n') {
+                    text.pop();
+                }
+
+                let slash_command_output_sections = self
+                    .slash_command_output_sections
+                    .iter()
+                    .filter_map(|section| {
+                        if !section.range.start.is_valid(buffer) {
+                            return None;
+                        }
+                        let range = section.range.to_offset(buffer);
+                        if range.is_empty()
+                            || range.start < message_start
+                            || range.end > message_start + text.len()
+                        {
+                            return None;
+                        }
+                        Some(SlashCommandOutputSection {
+                            range: range.start - message_start..range.end - message_start,
+                            icon: section.icon,
+                            label: section.label.clone(),
+                        })
+                    })
+                    .collect();
+
+                ArchivedMessage {
+                    id: message.id,
+                    parent: None,
+                    selected_child: None,
+                    text,
+                    slash_command_output_sections,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Remove the archived messages along with the newline that precedes them.
+        let archived_range = messages[ix].offset_range.start.saturating_sub(1)..buffer.len();
+        let archived_ids = archived_messages
+            .iter()
+            .map(|message| message.id)
+            .collect::<HashSet<_>>();
+        if archived_ids.iter().any(|id| {
+            self.messages_metadata.get(id).map_or(false, |metadata| {
+                matches!(metadata.status, MessageStatus::Pending)
+            })
+        }) {
+            self.pending_completions.clear();
+        }
+        self.slash_command_output_sections.retain(|section| {
+            !section.range.start.is_valid(buffer)
+                || section.range.start.to_offset(buffer) < archived_range.start
+        });
+        if ix == 0 {
+            self.message_anchors.clear();
+        } else {
+            self.message_anchors
+                .retain(|message| !archived_ids.contains(&message.id));
+        }
+        self.message_tree.archive_branch(parent, archived_messages);
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.edit([(archived_range, "")], None, cx);
+        });
+        cx.emit(ContextEvent::MessagesEdited);
+
+        Some(())
+    }
+
+    /// Appends the given messages to the end of the buffer.
+    fn restore_messages(
+        &mut self,
+        messages: Vec<ArchivedMessage>,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<MessageAnchor> {
+        let mut needs_separator = !self.message_anchors.is_empty();
+        let message_starts = self.buffer.update(cx, |buffer, cx| {
+            let start = buffer.len();
+            let mut new_text = String::new();
+            let mut message_starts = Vec::new();
+            for message in &messages {
+                if needs_separator {
+                    new_text.push('\n');
+                }
+                needs_separator = true;
+                message_starts.push(start + new_text.len());
+                new_text.push_str(&message.text);
+            }
+            buffer.edit([(start..start, new_text)], None, cx);
+            message_starts
+        });
+
+        let buffer = self.buffer.read(cx);
+        let mut restored_messages = Vec::new();
+        let mut restored_sections = Vec::new();
+        for (message, message_start) in messages.into_iter().zip(message_starts) {
+            let anchor = MessageAnchor {
+                id: message.id,
+                start: buffer.anchor_before(message_start),
+            };
+            self.message_anchors.push(anchor.clone());
+            restored_messages.push(anchor);
+            restored_sections.extend(message.slash_command_output_sections.into_iter().map(
+                |section| SlashCommandOutputSection {
+                    range: buffer.anchor_after(message_start + section.range.start)
+                        ..buffer.anchor_before(message_start + section.range.end),
+                    icon: section.icon,
+                    label: section.label,
+                },
+            ));
+        }
+
+        if !restored_sections.is_empty() {
+            self.slash_command_output_sections
+                .extend(restored_sections.iter().cloned());
+            self.slash_command_output_sections
+                .sort_by(|a, b| a.range.cmp(&b.range, buffer));
+            cx.emit(ContextEvent::SlashCommandFinished {
+                output_range: restored_sections[0].range.start
+                    ..restored_sections[restored_sections.len() - 1].range.end,
+                sections: restored_sections,
+                run_commands_in_output: false,
+            });
+        }
+        cx.emit(ContextEvent::MessagesEdited);
+
+        restored_messages
+    }
+
     fn summarize(&mut self, cx: &mut ModelContext<Self>) {
         if self.message_anchors.len() >= 2 && self.summary.is_none() {
             if !CompletionProvider::global(cx).is_authenticated() {
@@ -2322,6 +3004,94 @@ impl ContextEditor {
                 .collect();
             context.assist(selected_messages, cx)
         });
+        self.select_new_user_messages(&user_messages, cx);
+    }
+
+    fn regenerate_message(&mut self, _: &RegenerateMessage, cx: &mut ViewContext<Self>) {
+        let cursor = self.newest_cursor(cx);
+        let user_messages = self.context.update(cx, |context, cx| {
+            let Some(message) = context.message_for_offset(cursor, cx) else {
+                return Vec::new();
+            };
+            context.regenerate_message(message.id, cx)
+        });
+        self.select_new_user_messages(&user_messages, cx);
+    }
+
+    fn edit_message_in_new_branch(
+        &mut self,
+        _: &EditMessageInNewBranch,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let cursor = self.newest_cursor(cx);
+        let new_message = self.context.update(cx, |context, cx| {
+            let message = context.message_for_offset(cursor, cx)?;
+            context.edit_message_in_new_branch(message.id, cx)
+        });
+        if new_message.is_some() {
+            let end = self.context.read(cx).buffer.read(cx).len();
+            self.editor.update(cx, |editor, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                    selections.select_ranges([end..end])
+                });
+            });
+        }
+    }
+
+    fn select_next_branch(&mut self, _: &SelectNextBranch, cx: &mut ViewContext<Self>) {
+        self.select_branch(Direction::Next, cx);
+    }
+
+    fn select_prev_branch(&mut self, _: &SelectPrevBranch, cx: &mut ViewContext<Self>) {
+        self.select_branch(Direction::Prev, cx);
+    }
+
+    fn select_branch(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let cursor = self.newest_cursor(cx);
+        let restored_message = self.context.update(cx, |context, cx| {
+            let message = context.message_for_offset(cursor, cx)?;
+            context.select_branch(message.id, direction, cx)
+        });
+        if let Some(restored_message) = restored_message {
+            let start = restored_message
+                .start
+                .to_offset(self.context.read(cx).buffer.read(cx));
+            self.editor.update(cx, |editor, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                    selections.select_ranges([start..start])
+                });
+            });
+        }
+    }
+
+    fn diff_branches(&mut self, _: &DiffBranches, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let cursor = self.newest_cursor(cx);
+        let context = self.context.read(cx);
+        let Some(diff) = context
+            .message_for_offset(cursor, cx)
+            .and_then(|message| context.branch_diff(message.id, cx))
+        else {
+            return;
+        };
+
+        workspace.update(cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            let buffer = project.update(cx, |project, cx| {
+                project.create_local_buffer(&diff, None, cx)
+            });
+            let editor = cx.new_view(|cx| Editor::for_buffer(buffer, Some(project), cx));
+            workspace.add_item_to_center(Box::new(editor), cx);
+        });
+    }
+
+    fn select_new_user_messages(
+        &mut self,
+        user_messages: &[MessageAnchor],
+        cx: &mut ViewContext<Self>,
+    ) {
         let new_selections = user_messages
             .iter()
             .map(|message| {
@@ -2365,6 +3135,10 @@ impl ContextEditor {
         });
     }
 
+    fn newest_cursor(&self, cx: &AppContext) -> usize {
+        self.editor.read(cx).selections.newest::<usize>(cx).head()
+    }
+
     fn cursors(&self, cx: &AppContext) -> Vec<usize> {
         let selections = self.editor.read(cx).selections.all::<usize>(cx);
         selections
@@ -2602,8 +3376,10 @@ impl ContextEditor {
                                     // TODO: In the future we should investigate how we can expose
                                     // this as a hook on the `SlashCommand` trait so that we don't
                                     // need to special-case it here.
-                                    if command.name == "rustdoc" {
-                                        return render_rustdoc_slash_command_trailer(
+                                    if command.name == DocsSlashCommand::NAME
+                                        || command.name == RustdocSlashCommand::NAME
+                                    {
+                                        return render_docs_slash_command_trailer(
                                             row,
                                             command.clone(),
                                             cx,
@@ -2809,11 +3585,16 @@ impl ContextEditor {
             let buffer = editor.buffer().read(cx).snapshot(cx);
             let excerpt_id = *buffer.as_singleton().unwrap().0;
             let old_blocks = std::mem::take(&mut self.blocks);
-            let new_blocks = self
-                .context
-                .read(cx)
+            let context = self.context.read(cx);
+            let mut parent = None;
+            let new_blocks = context
                 .messages(cx)
-                .map(|message| BlockProperties {
+                .map(|message| {
+                    let siblings = context.message_tree.siblings(parent, message.id);
+                    parent = Some(message.id);
+                    (message, siblings)
+                })
+                .map(|(message, siblings)| BlockProperties {
                     position: buffer
                         .anchor_in_excerpt(excerpt_id, message.anchor)
                         .unwrap(),
@@ -2823,6 +3604,61 @@ impl ContextEditor {
                         let context = self.context.clone();
                         move |cx| {
                             let message_id = message.id;
+                            let branch_ix = siblings
+                                .iter()
+                                .position(|id| *id == message_id)
+                                .unwrap_or_default();
+                            let branch_switcher = (siblings.len() > 1).then(|| {
+                                h_flex()
+                                    .gap_0p5()
+                                    .child(
+                                        IconButton::new(
+                                            ("prev_branch", message_id.0),
+                                            IconName::ChevronLeft,
+                                        )
+                                        .icon_size(IconSize::XSmall)
+                                        .disabled(branch_ix == 0)
+                                        .tooltip(|cx| Tooltip::text("Previous Branch", cx))
+                                        .on_click({
+                                            let context = context.clone();
+                                            move |_, cx| {
+                                                context.update(cx, |context, cx| {
+                                                    context.select_branch(
+                                                        message_id,
+                                                        Direction::Prev,
+                                                        cx,
+                                                    );
+                                                });
+                                            }
+                                        }),
+                                    )
+                                    .child(
+                                        Label::new(format!("{}/{}", branch_ix + 1, siblings.len()))
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                    .child(
+                                        IconButton::new(
+                                            ("next_branch", message_id.0),
+                                            IconName::ChevronRight,
+                                        )
+                                        .icon_size(IconSize::XSmall)
+                                        .disabled(branch_ix + 1 == siblings.len())
+                                        .tooltip(|cx| Tooltip::text("Next Branch", cx))
+                                        .on_click({
+                                            let context = context.clone();
+                                            move |_, cx| {
+                                                context.update(cx, |context, cx| {
+                                                    context.select_branch(
+                                                        message_id,
+                                                        Direction::Next,
+                                                        cx,
+                                                    );
+                                                });
+                                            }
+                                        }),
+                                    )
+                            });
                             let sender = ButtonLike::new("role")
                                 .style(ButtonStyle::Filled)
                                 .child(match message.role {
@@ -2858,6 +3694,7 @@ impl ContextEditor {
                                 .relative()
                                 .gap_1()
                                 .child(sender)
+                                .children(branch_switcher)
                                 .children(
                                     if let MessageStatus::Error(error) = message.status.clone() {
                                         Some(
@@ -3192,6 +4029,11 @@ impl Render for ContextEditor {
             .capture_action(cx.listener(ContextEditor::confirm_command))
             .on_action(cx.listener(ContextEditor::assist))
             .on_action(cx.listener(ContextEditor::split))
+            .on_action(cx.listener(ContextEditor::regenerate_message))
+            .on_action(cx.listener(ContextEditor::edit_message_in_new_branch))
+            .on_action(cx.listener(ContextEditor::select_next_branch))
+            .on_action(cx.listener(ContextEditor::select_prev_branch))
+            .on_action(cx.listener(ContextEditor::diff_branches))
             .on_action(cx.listener(ContextEditor::apply_edit))
             .size_full()
             .v_flex()
@@ -3235,6 +4077,68 @@ impl Message {
     }
 }
 
+/// Counts the tokens in each of the given slash command output sections.
+fn count_section_tokens<T: Send + 'static>(
+    sections: Vec<(Range<T>, SharedString, String)>,
+    model: LanguageModel,
+    cx: &AppContext,
+) -> BoxFuture<'static, Result<Vec<SectionTokenCount<T>>>> {
+    let provider = CompletionProvider::global(cx);
+    futures::future::try_join_all(sections.into_iter().map(|(range, label, text)| {
+        let request = LanguageModelRequest {
+            model: model.clone(),
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: text,
+            }],
+            stop: vec![],
+            temperature: 1.0,
+        };
+        let token_count = provider.count_tokens(request, cx);
+        async move {
+            anyhow::Ok(SectionTokenCount {
+                range,
+                label,
+                token_count: token_count.await?,
+            })
+        }
+    }))
+    .boxed()
+}
+
+fn omitted_section_text(label: &str) -> String {
+    format!("[Output of `{label}` omitted to fit the context window]")
+}
+
+/// Converts the given messages into request messages, replacing the given (sorted)
+/// ranges of the buffer with new text.
+fn request_messages_with_replacements(
+    messages: &[Message],
+    buffer: &language::BufferSnapshot,
+    replacements: &[(Range<usize>, String)],
+) -> Vec<LanguageModelRequestMessage> {
+    messages
+        .iter()
+        .map(|message| {
+            let mut content = String::new();
+            let mut offset = message.offset_range.start;
+            for (range, replacement) in replacements {
+                if range.start < offset || range.end > message.offset_range.end {
+                    continue;
+                }
+                content.extend(buffer.text_for_range(offset..range.start));
+                content.push_str(replacement);
+                offset = range.end;
+            }
+            content.extend(buffer.text_for_range(offset..message.offset_range.end));
+            LanguageModelRequestMessage {
+                role: message.role,
+                content,
+            }
+        })
+        .collect()
+}
+
 type ToggleFold = Arc<dyn Fn(bool, &mut WindowContext) + Send + Sync>;
 
 fn render_slash_command_output_toggle(
@@ -3275,23 +4179,31 @@ fn render_pending_slash_command_gutter_decoration(
     icon.into_any_element()
 }
 
-fn render_rustdoc_slash_command_trailer(
+fn render_docs_slash_command_trailer(
     row: MultiBufferRow,
     command: PendingSlashCommand,
     cx: &mut WindowContext,
 ) -> AnyElement {
-    let rustdoc_store = RustdocStore::global(cx);
+    let Some(mut argument) = command.argument else {
+        return Empty.into_any();
+    };
+    if command.name == RustdocSlashCommand::NAME {
+        argument = RustdocSlashCommand::docs_argument(&argument);
+    }
 
-    let Some((crate_name, _)) = command
-        .argument
-        .as_ref()
-        .and_then(|arg| arg.split_once(':'))
+    let args = DocsSlashCommandArgs::parse(&argument);
+    let Some(store) = args
+        .provider()
+        .and_then(|provider| IndexedDocsStore::try_global(provider, cx).ok())
     else {
         return Empty.into_any();
     };
 
-    let crate_name = CrateName::from(crate_name);
-    if !rustdoc_store.is_indexing(&crate_name) {
+    let Some(package) = args.package() else {
+        return Empty.into_any();
+    };
+
+    if !store.is_indexing(&package) {
         return Empty.into_any();
     }
 
@@ -3302,7 +4214,10 @@ fn render_rustdoc_slash_command_trailer(
             Animation::new(Duration::from_secs(4)).repeat(),
             |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
         ))
-        .tooltip(move |cx| Tooltip::text(format!("Indexing {crate_name}…"), cx))
+        .tooltip({
+            let package = package.clone();
+            move |cx| Tooltip::text(format!("Indexing {package}…"), cx)
+        })
         .into_any_element()
 }
 
@@ -3853,6 +4768,102 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_budgeted_completion_request(cx: &mut TestAppContext) {
+        let settings_store = cx.update(SettingsStore::test);
+        cx.set_global(settings_store);
+        cx.set_global(CompletionProvider::Fake(FakeCompletionProvider::default()));
+        cx.update(init);
+        let set_budget = |budget: usize, cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                let max_token_count = CompletionProvider::global(cx).model().max_token_count();
+                SettingsStore::update_global(cx, |store, cx| {
+                    store
+                        .set_user_settings(
+                            &json!({
+                                "assistant": {
+                                    "version": "1",
+                                    "context_budget": {
+                                        "reserved_output_tokens": max_token_count - budget
+                                    }
+                                }
+                            })
+                            .to_string(),
+                            cx,
+                        )
+                        .unwrap();
+                });
+            });
+        };
+
+        let registry = Arc::new(LanguageRegistry::test(cx.executor()));
+        let context =
+            cx.new_model(|cx| Context::new(registry.clone(), Default::default(), None, cx));
+        let buffer = context.read_with(cx, |context, _| context.buffer.clone());
+        let output = "one two three four five six seven eight";
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, format!("{output}\nWhat does it say?"))], None, cx);
+        });
+        context.update(cx, |context, cx| {
+            let buffer = context.buffer.read(cx);
+            let range = buffer.anchor_after(0)..buffer.anchor_before(output.len());
+            context
+                .slash_command_output_sections
+                .push(SlashCommandOutputSection {
+                    range,
+                    icon: IconName::File,
+                    label: "notes.txt".into(),
+                });
+        });
+
+        // The request is counted when it's built, without waiting for the debounced count.
+        set_budget(12, cx);
+        let request = context
+            .update(cx, |context, cx| context.budgeted_completion_request(cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            request.messages[0].content,
+            format!("{output}\nWhat does it say?")
+        );
+
+        set_budget(10, cx);
+        let request = context
+            .update(cx, |context, cx| context.budgeted_completion_request(cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            request.messages[0].content,
+            format!("{}\nWhat does it say?", omitted_section_text("notes.txt"))
+        );
+
+        // A context well under its budget isn't counted again before sending.
+        context.update(cx, |context, _| context.token_count = Some(5));
+        let request = context
+            .update(cx, |context, cx| context.budgeted_completion_request(cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            request.messages[0].content,
+            format!("{output}\nWhat does it say?")
+        );
+
+        // Failing to count tokens sends the request untrimmed.
+        context.update(cx, |context, _| context.token_count = None);
+        cx.update(|cx| match CompletionProvider::global(cx) {
+            CompletionProvider::Fake(provider) => provider.set_fail_token_counts(true),
+            _ => unreachable!(),
+        });
+        let request = context
+            .update(cx, |context, cx| context.budgeted_completion_request(cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            request.messages[0].content,
+            format!("{output}\nWhat does it say?")
+        );
+    }
+
     #[gpui::test]
     async fn test_serialization(cx: &mut TestAppContext) {
         let settings_store = cx.update(SettingsStore::test);
@@ -3920,6 +4931,95 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_message_branches(cx: &mut TestAppContext) {
+        let settings_store = cx.update(SettingsStore::test);
+        cx.set_global(settings_store);
+        cx.set_global(CompletionProvider::Fake(FakeCompletionProvider::default()));
+        cx.update(init);
+        let registry = Arc::new(LanguageRegistry::test(cx.executor()));
+        let context =
+            cx.new_model(|cx| Context::new(registry.clone(), Default::default(), None, cx));
+        let buffer = context.read_with(cx, |context, _| context.buffer.clone());
+        let message_0 = context.read_with(cx, |context, _| context.message_anchors[0].id);
+        let message_1 = context.update(cx, |context, cx| {
+            context
+                .insert_message_after(message_0, Role::Assistant, MessageStatus::Done, cx)
+                .unwrap()
+        });
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "a"), (1..1, "b")], None, cx);
+        });
+        assert_eq!(
+            cx.read(|cx| messages(&context, cx)),
+            [
+                (message_0, Role::User, 0..2),
+                (message_1.id, Role::Assistant, 2..3),
+            ]
+        );
+
+        // Editing the first message in a new branch keeps a copy of its text but drops
+        // the rest of the conversation.
+        let message_2 = context
+            .update(cx, |context, cx| {
+                context.edit_message_in_new_branch(message_0, cx)
+            })
+            .unwrap();
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "a");
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..1, "c")], None, cx));
+        assert_eq!(
+            cx.read(|cx| messages(&context, cx)),
+            [(message_2.id, Role::User, 0..1)]
+        );
+        assert_eq!(
+            context.read_with(cx, |context, cx| context.message_siblings(message_2.id, cx)),
+            [message_0, message_2.id]
+        );
+
+        // Switching back restores the original conversation.
+        context.update(cx, |context, cx| {
+            context.select_branch(message_2.id, Direction::Prev, cx)
+        });
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "a\nb");
+        assert_eq!(
+            cx.read(|cx| messages(&context, cx)),
+            [
+                (message_0, Role::User, 0..2),
+                (message_1.id, Role::Assistant, 2..3),
+            ]
+        );
+        let diff = context
+            .read_with(cx, |context, cx| context.branch_diff(message_0, cx))
+            .unwrap();
+        assert!(diff.contains("-a\n"), "{diff}");
+        assert!(diff.contains("+c\n"), "{diff}");
+
+        // Branches survive serialization.
+        let deserialized_context = Context::deserialize(
+            context.read_with(cx, |context, cx| context.serialize(cx)),
+            Default::default(),
+            registry.clone(),
+            Default::default(),
+            None,
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let deserialized_buffer =
+            deserialized_context.read_with(cx, |context, _| context.buffer.clone());
+        deserialized_context.update(cx, |context, cx| {
+            context.select_branch(message_0, Direction::Next, cx)
+        });
+        assert_eq!(
+            deserialized_buffer.read_with(cx, |buffer, _| buffer.text()),
+            "c"
+        );
+        assert_eq!(
+            cx.read(|cx| messages(&deserialized_context, cx)),
+            [(message_2.id, Role::User, 0..1)]
+        );
+    }
+
     fn messages(context: &Model<Context>, cx: &AppContext) -> Vec<(MessageId, Role, Range<usize>)> {
         context
             .read(cx)
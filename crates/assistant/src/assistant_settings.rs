@@ -217,6 +217,38 @@ pub enum AssistantProviderContent {
     },
 }
 
+/// What to do when a context doesn't fit into the model's context window.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextOverflowPolicy {
+    /// Send the context as-is.
+    Ignore,
+    /// Omit the least recently referenced slash command output.
+    #[default]
+    Drop,
+    /// Replace the least recently referenced slash command output with a summary.
+    Summarize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContextBudgetSettings {
+    pub overflow_policy: ContextOverflowPolicy,
+    pub reserved_output_tokens: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema, Debug, Default)]
+pub struct ContextBudgetSettingsContent {
+    /// What to do with slash command output when a context doesn't fit into the
+    /// model's context window.
+    ///
+    /// Default: drop
+    pub overflow_policy: Option<ContextOverflowPolicy>,
+    /// The number of tokens to keep free for the model's response.
+    ///
+    /// Default: 1024
+    pub reserved_output_tokens: Option<usize>,
+}
+
 #[derive(Debug, Default)]
 pub struct AssistantSettings {
     pub enabled: bool,
@@ -225,6 +257,7 @@ pub struct AssistantSettings {
     pub default_width: Pixels,
     pub default_height: Pixels,
     pub provider: AssistantProvider,
+    pub context_budget: ContextBudgetSettings,
 }
 
 /// Assistant panel settings
@@ -282,6 +315,7 @@ impl AssistantSettingsContent {
                         }
                     })
                 },
+                context_budget: None,
             },
         }
     }
@@ -381,6 +415,7 @@ impl Default for VersionedAssistantSettingsContent {
             default_width: None,
             default_height: None,
             provider: None,
+            context_budget: None,
         })
     }
 }
@@ -412,6 +447,8 @@ pub struct AssistantSettingsContentV1 {
     /// This can either be the internal `zed.dev` service or an external `openai` service,
     /// each with their respective default models and configurations.
     provider: Option<AssistantProviderContent>,
+    /// How to keep contexts within the model's context window.
+    context_budget: Option<ContextBudgetSettingsContent>,
 }
 
 #[derive(Clone, Serialize, Deserialize, JsonSchema, Debug)]
@@ -466,6 +503,16 @@ impl Settings for AssistantSettings {
                 &mut settings.default_height,
                 value.default_height.map(Into::into),
             );
+            if let Some(context_budget) = value.context_budget.as_ref() {
+                merge(
+                    &mut settings.context_budget.overflow_policy,
+                    context_budget.overflow_policy,
+                );
+                merge(
+                    &mut settings.context_budget.reserved_output_tokens,
+                    context_budget.reserved_output_tokens,
+                );
+            }
             if let Some(provider) = value.provider.clone() {
                 match (&mut settings.provider, provider) {
                     (
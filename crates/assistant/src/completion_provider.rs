@@ -301,7 +301,7 @@ impl CompletionProvider {
             CompletionProvider::Cloud(provider) => provider.count_tokens(request, cx),
             CompletionProvider::Ollama(provider) => provider.count_tokens(request, cx),
             #[cfg(test)]
-            CompletionProvider::Fake(_) => futures::FutureExt::boxed(futures::future::ready(Ok(0))),
+            CompletionProvider::Fake(provider) => provider.count_tokens(request),
         }
     }
 
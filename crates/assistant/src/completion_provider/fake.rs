@@ -1,10 +1,15 @@
-use anyhow::Result;
+use crate::LanguageModelRequest;
+use anyhow::{anyhow, Result};
 use futures::{channel::mpsc, future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 #[derive(Clone, Default)]
 pub struct FakeCompletionProvider {
     current_completion_tx: Arc<parking_lot::Mutex<Option<mpsc::UnboundedSender<String>>>>,
+    fail_token_counts: Arc<AtomicBool>,
 }
 
 impl FakeCompletionProvider {
@@ -14,6 +19,23 @@ impl FakeCompletionProvider {
         async move { Ok(rx.map(Ok).boxed()) }.boxed()
     }
 
+    /// Counts every whitespace-separated word as a token.
+    pub fn count_tokens(&self, request: LanguageModelRequest) -> BoxFuture<'static, Result<usize>> {
+        if self.fail_token_counts.load(Ordering::SeqCst) {
+            return futures::future::ready(Err(anyhow!("invalid model"))).boxed();
+        }
+        let token_count = request
+            .messages
+            .iter()
+            .map(|message| message.content.split_whitespace().count())
+            .sum();
+        futures::future::ready(Ok(token_count)).boxed()
+    }
+
+    pub fn set_fail_token_counts(&self, fail: bool) {
+        self.fail_token_counts.store(fail, Ordering::SeqCst);
+    }
+
     pub fn send_completion(&self, chunk: String) {
         self.current_completion_tx
             .lock()
@@ -0,0 +1,131 @@
+use gpui::SharedString;
+use std::ops::Range;
+
+/// The number of tokens taken up by a slash command output section of a context.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SectionTokenCount<T> {
+    pub range: Range<T>,
+    pub label: SharedString,
+    pub token_count: usize,
+}
+
+/// A section considered for trimming when a context exceeds its token budget.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BudgetedSection {
+    pub range: Range<usize>,
+    pub label: SharedString,
+    pub token_count: usize,
+    /// The index of the last message that contains or mentions this section.
+    pub last_referenced_message_ix: usize,
+}
+
+/// Returns the index of the last message referencing a section: either the message
+/// containing it or a later message mentioning the section's label.
+pub fn last_referenced_message_ix(
+    section_range: &Range<usize>,
+    section_label: &str,
+    messages: &[(Range<usize>, String)],
+) -> Option<usize> {
+    let containing_message_ix = messages
+        .iter()
+        .position(|(range, _)| range.contains(&section_range.start))?;
+    let last_mention_ix = messages
+        .iter()
+        .enumerate()
+        .skip(containing_message_ix + 1)
+        .rev()
+        .find(|(_, (_, text))| !section_label.is_empty() && text.contains(section_label))
+        .map(|(ix, _)| ix);
+    Some(last_mention_ix.unwrap_or(containing_message_ix))
+}
+
+/// Chooses which sections to trim so that `total_token_count` fits into `budget`.
+///
+/// The least recently referenced sections are trimmed first. Ties are broken by
+/// trimming the largest section first, so that as little as possible is lost.
+/// Returns the indices of the sections to trim, in the order they should be trimmed.
+pub fn sections_to_trim(
+    sections: &[BudgetedSection],
+    total_token_count: usize,
+    budget: usize,
+) -> Vec<usize> {
+    let mut candidates = (0..sections.len()).collect::<Vec<_>>();
+    candidates.sort_by_key(|ix| {
+        let section = &sections[*ix];
+        (
+            section.last_referenced_message_ix,
+            std::cmp::Reverse(section.token_count),
+            section.range.start,
+        )
+    });
+
+    let mut token_count = total_token_count;
+    let mut result = Vec::new();
+    for ix in candidates {
+        if token_count <= budget {
+            break;
+        }
+        token_count = token_count.saturating_sub(sections[ix].token_count);
+        result.push(ix);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(
+        range: Range<usize>,
+        token_count: usize,
+        last_referenced_message_ix: usize,
+    ) -> BudgetedSection {
+        BudgetedSection {
+            range,
+            label: "section".into(),
+            token_count,
+            last_referenced_message_ix,
+        }
+    }
+
+    #[test]
+    fn test_last_referenced_message_ix() {
+        let messages = vec![
+            (0..10, "/file src/main.rs".to_string()),
+            (10..20, "Looks fine.".to_string()),
+            (20..30, "What does src/main.rs do?".to_string()),
+            (30..40, "It prints.".to_string()),
+        ];
+        assert_eq!(
+            last_referenced_message_ix(&(2..8), "src/main.rs", &messages),
+            Some(2)
+        );
+        assert_eq!(
+            last_referenced_message_ix(&(12..18), "src/lib.rs", &messages),
+            Some(1)
+        );
+        assert_eq!(
+            last_referenced_message_ix(&(50..60), "src/lib.rs", &messages),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sections_to_trim() {
+        let sections = vec![
+            section(0..10, 100, 3),
+            section(10..20, 50, 1),
+            section(20..30, 200, 1),
+        ];
+
+        // Everything fits.
+        assert_eq!(sections_to_trim(&sections, 400, 1000), Vec::<usize>::new());
+
+        // The least recently referenced sections are trimmed first, largest first.
+        assert_eq!(sections_to_trim(&sections, 400, 250), vec![2]);
+        assert_eq!(sections_to_trim(&sections, 400, 180), vec![2, 1]);
+
+        // If trimming everything isn't enough, everything is trimmed.
+        assert_eq!(sections_to_trim(&sections, 400, 10), vec![2, 1, 0]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{assistant_settings::OpenAiModel, MessageId, MessageMetadata};
+use crate::{assistant_settings::OpenAiModel, ArchivedMessage, MessageId, MessageMetadata};
 use anyhow::{anyhow, Result};
 use assistant_slash_command::SlashCommandOutputSection;
 use collections::HashMap;
@@ -29,10 +29,23 @@ pub struct SavedContext {
     pub message_metadata: HashMap<MessageId, MessageMetadata>,
     pub summary: String,
     pub slash_command_output_sections: Vec<SlashCommandOutputSection<usize>>,
+    pub archived_messages: Vec<ArchivedMessage>,
 }
 
 impl SavedContext {
-    pub const VERSION: &'static str = "0.3.0";
+    pub const VERSION: &'static str = "0.4.0";
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedContextV0_3_0 {
+    pub id: Option<String>,
+    pub zed: String,
+    pub version: String,
+    pub text: String,
+    pub messages: Vec<SavedMessage>,
+    pub message_metadata: HashMap<MessageId, MessageMetadata>,
+    pub summary: String,
+    pub slash_command_output_sections: Vec<SlashCommandOutputSection<usize>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -113,6 +126,22 @@ impl ContextStore {
                     SavedContext::VERSION => {
                         Ok(serde_json::from_value::<SavedContext>(saved_context_json)?)
                     }
+                    "0.3.0" => {
+                        let saved_context =
+                            serde_json::from_value::<SavedContextV0_3_0>(saved_context_json)?;
+                        Ok(SavedContext {
+                            id: saved_context.id,
+                            zed: saved_context.zed,
+                            version: saved_context.version,
+                            text: saved_context.text,
+                            messages: saved_context.messages,
+                            message_metadata: saved_context.message_metadata,
+                            summary: saved_context.summary,
+                            slash_command_output_sections: saved_context
+                                .slash_command_output_sections,
+                            archived_messages: Vec::new(),
+                        })
+                    }
                     "0.2.0" => {
                         let saved_context =
                             serde_json::from_value::<SavedContextV0_2_0>(saved_context_json)?;
@@ -125,6 +154,7 @@ impl ContextStore {
                             message_metadata: saved_context.message_metadata,
                             summary: saved_context.summary,
                             slash_command_output_sections: Vec::new(),
+                            archived_messages: Vec::new(),
                         })
                     }
                     "0.1.0" => {
@@ -139,6 +169,7 @@ impl ContextStore {
                             message_metadata: saved_context.message_metadata,
                             summary: saved_context.summary,
                             slash_command_output_sections: Vec::new(),
+                            archived_messages: Vec::new(),
                         })
                     }
                     _ => Err(anyhow!("unrecognized saved context version: {}", version)),
@@ -0,0 +1,194 @@
+use crate::MessageId;
+use assistant_slash_command::SlashCommandOutputSection;
+use collections::{BTreeMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+/// A message that is not part of the active branch of a context.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ArchivedMessage {
+    pub id: MessageId,
+    /// The message this one follows, or `None` if it is the first message of the context.
+    pub parent: Option<MessageId>,
+    /// The child that was active when this message was archived, which is restored
+    /// along with this message when switching back to its branch.
+    pub selected_child: Option<MessageId>,
+    pub text: String,
+    /// Slash command output sections, relative to the start of the message.
+    pub slash_command_output_sections: Vec<SlashCommandOutputSection<usize>>,
+}
+
+/// The messages of a context that aren't in its buffer.
+///
+/// The buffer only ever contains a single branch of the conversation. Whenever a
+/// message is regenerated or edited in a new branch, the old message and everything
+/// following it is moved into this tree, keyed by the message it followed. Each
+/// message's alternatives are its siblings: the messages that share its parent.
+#[derive(Default)]
+pub struct MessageTree {
+    archived_messages: BTreeMap<MessageId, ArchivedMessage>,
+}
+
+impl MessageTree {
+    pub fn new(archived_messages: impl IntoIterator<Item = ArchivedMessage>) -> Self {
+        Self {
+            archived_messages: archived_messages
+                .into_iter()
+                .map(|message| (message.id, message))
+                .collect(),
+        }
+    }
+
+    /// Archives a branch of the conversation that followed `parent`.
+    ///
+    /// The messages are linked to each other in the order they're given, ignoring
+    /// their `parent` and `selected_child` fields.
+    pub fn archive_branch(
+        &mut self,
+        parent: Option<MessageId>,
+        messages: impl IntoIterator<Item = ArchivedMessage>,
+    ) {
+        let mut parent = parent;
+        for mut message in messages {
+            message.parent = parent;
+            message.selected_child = None;
+            if let Some(parent) = parent.and_then(|id| self.archived_messages.get_mut(&id)) {
+                parent.selected_child = Some(message.id);
+            }
+            parent = Some(message.id);
+            self.archived_messages.insert(message.id, message);
+        }
+    }
+
+    /// Returns the archived branch starting at the given message, following the
+    /// most recently active child of each message.
+    pub fn branch(&self, id: MessageId) -> Vec<&ArchivedMessage> {
+        let mut branch = Vec::new();
+        let mut next = self.archived_messages.get(&id);
+        while let Some(message) = next {
+            branch.push(message);
+            next = message
+                .selected_child
+                .and_then(|id| self.archived_messages.get(&id))
+                .or_else(|| self.children(Some(message.id)).last());
+        }
+        branch
+    }
+
+    /// Removes the branch starting at the given message from the tree, returning
+    /// its messages in order.
+    pub fn restore_branch(&mut self, id: MessageId) -> Vec<ArchivedMessage> {
+        let ids = self
+            .branch(id)
+            .into_iter()
+            .map(|message| message.id)
+            .collect::<Vec<_>>();
+        ids.into_iter()
+            .filter_map(|id| self.archived_messages.remove(&id))
+            .collect()
+    }
+
+    /// Returns the alternatives for the active message with the given id, including
+    /// the message itself, ordered by when they were created.
+    pub fn siblings(&self, parent: Option<MessageId>, id: MessageId) -> Vec<MessageId> {
+        let mut siblings = self
+            .children(parent)
+            .map(|message| message.id)
+            .chain(Some(id))
+            .collect::<Vec<_>>();
+        siblings.sort();
+        siblings.dedup();
+        siblings
+    }
+
+    /// Returns the archived messages that can still be reached from the given active
+    /// messages, dropping branches whose parent has since been deleted.
+    pub fn reachable_messages(&self, active_ids: &HashSet<MessageId>) -> Vec<ArchivedMessage> {
+        let mut reachable = HashSet::default();
+        loop {
+            let reachable_count = reachable.len();
+            for message in self.archived_messages.values() {
+                let parent_is_reachable = message.parent.map_or(true, |parent| {
+                    active_ids.contains(&parent) || reachable.contains(&parent)
+                });
+                if parent_is_reachable {
+                    reachable.insert(message.id);
+                }
+            }
+
+            if reachable.len() == reachable_count {
+                break;
+            }
+        }
+
+        self.archived_messages
+            .values()
+            .filter(|message| reachable.contains(&message.id))
+            .cloned()
+            .collect()
+    }
+
+    fn children(&self, parent: Option<MessageId>) -> impl Iterator<Item = &ArchivedMessage> {
+        self.archived_messages
+            .values()
+            .filter(move |message| message.parent == parent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: usize, text: &str) -> ArchivedMessage {
+        ArchivedMessage {
+            id: MessageId(id),
+            parent: None,
+            selected_child: None,
+            text: text.into(),
+            slash_command_output_sections: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_archiving_and_restoring_branches() {
+        let mut tree = MessageTree::default();
+
+        // Message 0 is active; messages 1 and 2 were a continuation that got regenerated.
+        tree.archive_branch(Some(MessageId(0)), [message(1, "a"), message(2, "b")]);
+        assert_eq!(
+            tree.siblings(Some(MessageId(0)), MessageId(3)),
+            vec![MessageId(1), MessageId(3)]
+        );
+        assert_eq!(
+            tree.siblings(Some(MessageId(3)), MessageId(4)),
+            vec![MessageId(4)]
+        );
+
+        let branch = tree.branch(MessageId(1));
+        assert_eq!(
+            branch.iter().map(|message| message.id).collect::<Vec<_>>(),
+            vec![MessageId(1), MessageId(2)]
+        );
+        assert_eq!(branch[1].parent, Some(MessageId(1)));
+
+        // Switch back to the first branch, archiving the second one.
+        let restored = tree.restore_branch(MessageId(1));
+        assert_eq!(
+            restored
+                .iter()
+                .map(|message| message.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        tree.archive_branch(Some(MessageId(0)), [message(3, "c"), message(4, "d")]);
+        assert_eq!(
+            tree.siblings(Some(MessageId(0)), MessageId(1)),
+            vec![MessageId(1), MessageId(3)]
+        );
+
+        // Branches whose parent is gone are unreachable.
+        let active_ids = HashSet::from_iter([MessageId(0), MessageId(1), MessageId(2)]);
+        assert_eq!(tree.reachable_messages(&active_ids).len(), 2);
+        let active_ids = HashSet::from_iter([MessageId(5)]);
+        assert_eq!(tree.reachable_messages(&active_ids).len(), 0);
+    }
+}
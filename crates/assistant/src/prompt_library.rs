@@ -5,29 +5,33 @@ use crate::{
 use anyhow::{anyhow, Result};
 use assistant_slash_command::SlashCommandRegistry;
 use chrono::{DateTime, Utc};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use editor::{actions::Tab, CurrentLineHighlight, Editor, EditorEvent};
+use fs::Fs;
 use futures::{
     future::{self, BoxFuture, Shared},
-    FutureExt,
+    FutureExt, StreamExt,
 };
 use fuzzy::StringMatchCandidate;
 use gpui::{
     actions, percentage, point, size, Animation, AnimationExt, AppContext, BackgroundExecutor,
-    Bounds, EventEmitter, Global, PromptLevel, ReadGlobal, Subscription, Task, TitlebarOptions,
-    Transformation, UpdateGlobal, View, WindowBounds, WindowHandle, WindowOptions,
+    Bounds, EventEmitter, Global, Model, PathPromptOptions, PromptLevel, ReadGlobal, Subscription,
+    Task, TitlebarOptions, Transformation, UpdateGlobal, View, WindowBounds, WindowHandle,
+    WindowOptions,
 };
 use heed::{types::SerdeBincode, Database, RoTxn};
 use language::{language_settings::SoftWrap, Buffer, LanguageRegistry};
 use parking_lot::RwLock;
 use picker::{Picker, PickerDelegate};
+use project::{PathChange, Project, WorktreeId};
 use rope::Rope;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use std::{
     cmp::Reverse,
+    fmt::Write,
     future::Future,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{atomic::AtomicBool, Arc},
     time::Duration,
 };
@@ -42,7 +46,13 @@ use workspace::Workspace;
 
 actions!(
     prompt_library,
-    [NewPrompt, DeletePrompt, ToggleDefaultPrompt]
+    [
+        NewPrompt,
+        DeletePrompt,
+        ToggleDefaultPrompt,
+        ImportPrompts,
+        ExportPrompts
+    ]
 );
 
 /// Init starts loading the PromptStore in the background and assigns
@@ -53,7 +63,96 @@ pub fn init(cx: &mut AppContext) {
         .then(|result| future::ready(result.map(Arc::new).map_err(Arc::new)))
         .boxed()
         .shared();
-    cx.set_global(GlobalPromptStore(prompt_store_future))
+    cx.set_global(GlobalPromptStore(prompt_store_future));
+
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        let project = workspace.project().clone();
+        cx.subscribe(&project, |_, project, event, cx| {
+            load_project_prompts(project, event, cx);
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Keeps the prompts in the `.zed/prompts` folder of the project's worktrees in sync
+/// with the prompt store.
+fn load_project_prompts(project: Model<Project>, event: &project::Event, cx: &mut AppContext) {
+    match event {
+        project::Event::WorktreeUpdatedEntries(worktree_id, changes) => {
+            let Some(worktree) = project.read(cx).worktree_for_id(*worktree_id, cx) else {
+                return;
+            };
+            let worktree = worktree.read(cx);
+            if worktree.is_remote() {
+                return;
+            }
+
+            let mut changed_prompts = Vec::new();
+            for (path, _, change) in changes.iter() {
+                let is_prompt_file = path.parent()
+                    == Some(paths::local_prompts_dir_relative_path())
+                    && path
+                        .extension()
+                        .map_or(false, |extension| extension == "md");
+                if !is_prompt_file {
+                    continue;
+                }
+                if let Some(abs_path) = worktree.absolutize(path).log_err() {
+                    changed_prompts.push((abs_path, *change == PathChange::Removed));
+                }
+            }
+            if changed_prompts.is_empty() {
+                return;
+            }
+
+            let worktree_id = *worktree_id;
+            let fs = project.read(cx).fs().clone();
+            let store = PromptStore::global(cx);
+            cx.spawn(|cx| async move {
+                let store = store.await?;
+                for (abs_path, removed) in changed_prompts {
+                    if removed {
+                        store.remove_project_prompt(&abs_path);
+                    } else if let Some(content) = fs.load(&abs_path).await.log_err() {
+                        store.insert_project_prompt(worktree_id, &abs_path, &content);
+                    }
+                }
+                cx.update(refresh_prompt_library)
+            })
+            .detach_and_log_err(cx);
+        }
+        project::Event::WorktreeRemoved(worktree_id) => {
+            let worktree_id = *worktree_id;
+            let store = PromptStore::global(cx);
+            cx.spawn(|cx| async move {
+                store
+                    .await?
+                    .remove_project_prompts_for_worktree(worktree_id);
+                cx.update(refresh_prompt_library)
+            })
+            .detach_and_log_err(cx);
+        }
+        _ => {}
+    }
+}
+
+/// Updates the prompt list of the open prompt library window, if any, after the
+/// store's prompts changed outside of it.
+fn refresh_prompt_library(cx: &mut AppContext) {
+    let prompt_library = cx
+        .windows()
+        .into_iter()
+        .find_map(|window| window.downcast::<PromptLibrary>());
+    if let Some(prompt_library) = prompt_library {
+        prompt_library
+            .update(cx, |prompt_library, cx| {
+                prompt_library
+                    .picker
+                    .update(cx, |picker, cx| picker.refresh(cx));
+            })
+            .log_err();
+    }
 }
 
 /// This function opens a new prompt library window if one doesn't exist already.
@@ -64,6 +163,7 @@ pub fn init(cx: &mut AppContext) {
 /// to a prompt library.
 pub fn open_prompt_library(
     language_registry: Arc<LanguageRegistry>,
+    fs: Arc<dyn Fs>,
     cx: &mut AppContext,
 ) -> Task<Result<WindowHandle<PromptLibrary>>> {
     let existing_window = cx
@@ -91,7 +191,7 @@ pub fn open_prompt_library(
                         window_bounds: Some(WindowBounds::Windowed(bounds)),
                         ..Default::default()
                     },
-                    |cx| cx.new_view(|cx| PromptLibrary::new(store, language_registry, cx)),
+                    |cx| cx.new_view(|cx| PromptLibrary::new(store, language_registry, fs, cx)),
                 )
             })?
         })
@@ -101,6 +201,7 @@ pub fn open_prompt_library(
 pub struct PromptLibrary {
     store: Arc<PromptStore>,
     language_registry: Arc<LanguageRegistry>,
+    fs: Arc<dyn Fs>,
     prompt_editors: HashMap<PromptId, PromptEditor>,
     active_prompt_id: Option<PromptId>,
     picker: View<Picker<PromptPickerDelegate>>,
@@ -202,14 +303,20 @@ impl PickerDelegate for PromptPickerDelegate {
         let prompt = self.matches.get(ix)?;
         let default = prompt.default;
         let prompt_id = prompt.id;
+        let is_project_prompt = self.store.is_project_prompt(prompt_id);
         let element = ListItem::new(ix)
             .inset(true)
             .spacing(ListItemSpacing::Sparse)
             .selected(selected)
+            .start_slot::<Icon>(is_project_prompt.then(|| {
+                Icon::new(IconName::Folder)
+                    .size(IconSize::Small)
+                    .color(Color::Muted)
+            }))
             .child(h_flex().h_5().line_height(relative(1.)).child(Label::new(
                 prompt.title.clone().unwrap_or("Untitled".into()),
             )))
-            .end_slot::<IconButton>(default.then(|| {
+            .end_slot::<IconButton>((default && !is_project_prompt).then(|| {
                 IconButton::new("toggle-default-prompt", IconName::SparkleFilled)
                     .selected(true)
                     .icon_color(Color::Accent)
@@ -219,39 +326,41 @@ impl PickerDelegate for PromptPickerDelegate {
                         cx.emit(PromptPickerEvent::ToggledDefault { prompt_id })
                     }))
             }))
-            .end_hover_slot(
-                h_flex()
-                    .gap_2()
-                    .child(
-                        IconButton::new("delete-prompt", IconName::Trash)
-                            .icon_color(Color::Muted)
-                            .shape(IconButtonShape::Square)
-                            .tooltip(move |cx| Tooltip::text("Delete Prompt", cx))
-                            .on_click(cx.listener(move |_, _, cx| {
-                                cx.emit(PromptPickerEvent::Deleted { prompt_id })
-                            })),
-                    )
-                    .child(
-                        IconButton::new("toggle-default-prompt", IconName::Sparkle)
-                            .selected(default)
-                            .selected_icon(IconName::SparkleFilled)
-                            .icon_color(if default { Color::Accent } else { Color::Muted })
-                            .shape(IconButtonShape::Square)
-                            .tooltip(move |cx| {
-                                Tooltip::text(
-                                    if default {
-                                        "Remove from Default Prompt"
-                                    } else {
-                                        "Add to Default Prompt"
-                                    },
-                                    cx,
-                                )
-                            })
-                            .on_click(cx.listener(move |_, _, cx| {
-                                cx.emit(PromptPickerEvent::ToggledDefault { prompt_id })
-                            })),
-                    ),
-            );
+            .when(!is_project_prompt, |item| {
+                item.end_hover_slot(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            IconButton::new("delete-prompt", IconName::Trash)
+                                .icon_color(Color::Muted)
+                                .shape(IconButtonShape::Square)
+                                .tooltip(move |cx| Tooltip::text("Delete Prompt", cx))
+                                .on_click(cx.listener(move |_, _, cx| {
+                                    cx.emit(PromptPickerEvent::Deleted { prompt_id })
+                                })),
+                        )
+                        .child(
+                            IconButton::new("toggle-default-prompt", IconName::Sparkle)
+                                .selected(default)
+                                .selected_icon(IconName::SparkleFilled)
+                                .icon_color(if default { Color::Accent } else { Color::Muted })
+                                .shape(IconButtonShape::Square)
+                                .tooltip(move |cx| {
+                                    Tooltip::text(
+                                        if default {
+                                            "Remove from Default Prompt"
+                                        } else {
+                                            "Add to Default Prompt"
+                                        },
+                                        cx,
+                                    )
+                                })
+                                .on_click(cx.listener(move |_, _, cx| {
+                                    cx.emit(PromptPickerEvent::ToggledDefault { prompt_id })
+                                })),
+                        ),
+                )
+            });
         Some(element)
     }
 
@@ -272,6 +381,7 @@ impl PromptLibrary {
     fn new(
         store: Arc<PromptStore>,
         language_registry: Arc<LanguageRegistry>,
+        fs: Arc<dyn Fs>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
         let delegate = PromptPickerDelegate {
@@ -290,6 +400,7 @@ impl PromptLibrary {
         Self {
             store: store.clone(),
             language_registry,
+            fs,
             prompt_editors: HashMap::default(),
             active_prompt_id: None,
             pending_load: Task::ready(()),
@@ -412,6 +523,10 @@ impl PromptLibrary {
     }
 
     pub fn toggle_default_for_prompt(&mut self, prompt_id: PromptId, cx: &mut ViewContext<Self>) {
+        if self.store.is_project_prompt(prompt_id) {
+            return;
+        }
+
         if let Some(prompt_metadata) = self.store.metadata(prompt_id) {
             self.store
                 .save_metadata(prompt_id, prompt_metadata.title, !prompt_metadata.default)
@@ -433,6 +548,7 @@ impl PromptLibrary {
             let language_registry = self.language_registry.clone();
             let commands = SlashCommandRegistry::global(cx);
             let prompt = self.store.load(prompt_id);
+            let read_only = self.store.is_project_prompt(prompt_id);
             self.pending_load = cx.spawn(|this, mut cx| async move {
                 let prompt = prompt.await;
                 let markdown = language_registry.language_for_name("Markdown").await;
@@ -451,6 +567,7 @@ impl PromptLibrary {
                             editor.set_show_wrap_guides(false, cx);
                             editor.set_show_indent_guides(false, cx);
                             editor.set_use_modal_editing(false);
+                            editor.set_read_only(read_only);
                             editor.set_current_line_highlight(Some(CurrentLineHighlight::None));
                             editor.set_completion_provider(Box::new(
                                 SlashCommandCompletionProvider::new(commands, None, None),
@@ -517,6 +634,10 @@ impl PromptLibrary {
     }
 
     pub fn delete_prompt(&mut self, prompt_id: PromptId, cx: &mut ViewContext<Self>) {
+        if self.store.is_project_prompt(prompt_id) {
+            return;
+        }
+
         if let Some(metadata) = self.store.metadata(prompt_id) {
             let confirmation = cx.prompt(
                 PromptLevel::Warning,
@@ -546,6 +667,46 @@ impl PromptLibrary {
         }
     }
 
+    pub fn import_prompts(&mut self, cx: &mut ViewContext<Self>) {
+        let directory = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+        });
+        let store = self.store.clone();
+        let fs = self.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            let Some(directory) = directory.await?.and_then(|paths| paths.into_iter().next())
+            else {
+                return Ok(());
+            };
+            store.import(fs, directory).await?;
+            this.update(&mut cx, |this, cx| {
+                this.picker.update(cx, |picker, cx| picker.refresh(cx));
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn export_prompts(&mut self, cx: &mut ViewContext<Self>) {
+        let directory = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+        });
+        let store = self.store.clone();
+        let fs = self.fs.clone();
+        cx.spawn(|_, _| async move {
+            let Some(directory) = directory.await?.and_then(|paths| paths.into_iter().next())
+            else {
+                return Ok(());
+            };
+            store.export(fs, directory).await
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn focus_active_prompt(&mut self, _: &Tab, cx: &mut ViewContext<Self>) {
         if let Some(active_prompt) = self.active_prompt_id {
             self.prompt_editors[&active_prompt]
@@ -684,6 +845,29 @@ impl PromptLibrary {
                     .w_full()
                     .flex_none()
                     .justify_end()
+                    .gap_1()
+                    .child(
+                        IconButton::new("import-prompts", IconName::Download)
+                            .style(ButtonStyle::Transparent)
+                            .shape(IconButtonShape::Square)
+                            .tooltip(move |cx| {
+                                Tooltip::for_action("Import Prompts", &ImportPrompts, cx)
+                            })
+                            .on_click(|_, cx| {
+                                cx.dispatch_action(Box::new(ImportPrompts));
+                            }),
+                    )
+                    .child(
+                        IconButton::new("export-prompts", IconName::FolderOpen)
+                            .style(ButtonStyle::Transparent)
+                            .shape(IconButtonShape::Square)
+                            .tooltip(move |cx| {
+                                Tooltip::for_action("Export Prompts", &ExportPrompts, cx)
+                            })
+                            .on_click(|_, cx| {
+                                cx.dispatch_action(Box::new(ExportPrompts));
+                            }),
+                    )
                     .child(
                         IconButton::new("new-prompt", IconName::Plus)
                             .style(ButtonStyle::Transparent)
@@ -710,6 +894,7 @@ impl PromptLibrary {
             .children(self.active_prompt_id.and_then(|prompt_id| {
                 let buffer_font = ThemeSettings::get_global(cx).buffer_font.family.clone();
                 let prompt_metadata = self.store.metadata(prompt_id)?;
+                let is_project_prompt = self.store.is_project_prompt(prompt_id);
                 let prompt_editor = &self.prompt_editors[&prompt_id];
                 let focus_handle = prompt_editor.editor.focus_handle(cx);
                 let current_model = CompletionProvider::global(cx).model();
@@ -779,9 +964,13 @@ impl PromptLibrary {
                                         )
                                     },
                                 ))
-                                .child(
-                                    h_flex().justify_center().w_12().h_8().child(
-                                        IconButton::new("toggle-default-prompt", IconName::Sparkle)
+                                .when(!is_project_prompt, |this| {
+                                    this.child(
+                                        h_flex().justify_center().w_12().h_8().child(
+                                            IconButton::new(
+                                                "toggle-default-prompt",
+                                                IconName::Sparkle,
+                                            )
                                             .style(ButtonStyle::Transparent)
                                             .selected(prompt_metadata.default)
                                             .selected_icon(IconName::SparkleFilled)
@@ -801,29 +990,34 @@ impl PromptLibrary {
                                                     cx,
                                                 )
                                             })
-                                            .on_click(|_, cx| {
-                                                cx.dispatch_action(Box::new(ToggleDefaultPrompt));
-                                            }),
-                                    ),
-                                )
-                                .child(
-                                    h_flex().justify_center().w_12().h_8().child(
-                                        IconButton::new("delete-prompt", IconName::Trash)
-                                            .size(ButtonSize::Large)
-                                            .style(ButtonStyle::Transparent)
-                                            .shape(IconButtonShape::Square)
-                                            .tooltip(move |cx| {
-                                                Tooltip::for_action(
-                                                    "Delete Prompt",
-                                                    &DeletePrompt,
-                                                    cx,
-                                                )
-                                            })
-                                            .on_click(|_, cx| {
-                                                cx.dispatch_action(Box::new(DeletePrompt));
-                                            }),
-                                    ),
-                                ),
+                                            .on_click(
+                                                |_, cx| {
+                                                    cx.dispatch_action(Box::new(
+                                                        ToggleDefaultPrompt,
+                                                    ));
+                                                },
+                                            ),
+                                        ),
+                                    )
+                                    .child(
+                                        h_flex().justify_center().w_12().h_8().child(
+                                            IconButton::new("delete-prompt", IconName::Trash)
+                                                .size(ButtonSize::Large)
+                                                .style(ButtonStyle::Transparent)
+                                                .shape(IconButtonShape::Square)
+                                                .tooltip(move |cx| {
+                                                    Tooltip::for_action(
+                                                        "Delete Prompt",
+                                                        &DeletePrompt,
+                                                        cx,
+                                                    )
+                                                })
+                                                .on_click(|_, cx| {
+                                                    cx.dispatch_action(Box::new(DeletePrompt));
+                                                }),
+                                        ),
+                                    )
+                                }),
                         ),
                 )
             }))
@@ -843,6 +1037,8 @@ impl Render for PromptLibrary {
             .on_action(cx.listener(|this, &ToggleDefaultPrompt, cx| {
                 this.toggle_default_for_active_prompt(cx)
             }))
+            .on_action(cx.listener(|this, &ImportPrompts, cx| this.import_prompts(cx)))
+            .on_action(cx.listener(|this, &ExportPrompts, cx| this.export_prompts(cx)))
             .size_full()
             .overflow_hidden()
             .font(ui_font)
@@ -867,6 +1063,14 @@ impl PromptId {
     pub fn new() -> PromptId {
         PromptId(Uuid::new_v4())
     }
+
+    /// Returns a stable id for the prompt stored at the given path.
+    fn for_path(abs_path: &Path) -> PromptId {
+        PromptId(Uuid::new_v5(
+            &Uuid::NAMESPACE_URL,
+            abs_path.to_string_lossy().as_bytes(),
+        ))
+    }
 }
 
 pub struct PromptStore {
@@ -875,6 +1079,14 @@ pub struct PromptStore {
     bodies: Database<SerdeBincode<PromptId>, SerdeBincode<String>>,
     metadata: Database<SerdeBincode<PromptId>, SerdeBincode<PromptMetadata>>,
     metadata_cache: RwLock<MetadataCache>,
+    /// Prompts loaded from the `.zed/prompts` folder of open worktrees. These aren't
+    /// persisted in the database and can't be edited from the prompt library.
+    project_prompts: RwLock<HashMap<PromptId, ProjectPrompt>>,
+}
+
+struct ProjectPrompt {
+    worktree_id: WorktreeId,
+    body: String,
 }
 
 #[derive(Default)]
@@ -953,12 +1165,17 @@ impl PromptStore {
                     bodies,
                     metadata,
                     metadata_cache: RwLock::new(metadata_cache),
+                    project_prompts: RwLock::default(),
                 })
             }
         })
     }
 
     pub fn load(&self, id: PromptId) -> Task<Result<String>> {
+        if let Some(prompt) = self.project_prompts.read().get(&id) {
+            return Task::ready(Ok(prompt.body.clone()));
+        }
+
         let env = self.env.clone();
         let bodies = self.bodies;
         self.executor.spawn(async move {
@@ -998,6 +1215,103 @@ impl PromptStore {
         })
     }
 
+    pub fn is_project_prompt(&self, id: PromptId) -> bool {
+        self.project_prompts.read().contains_key(&id)
+    }
+
+    /// Adds or replaces the prompt loaded from the given file of a worktree.
+    pub fn insert_project_prompt(&self, worktree_id: WorktreeId, abs_path: &Path, content: &str) {
+        let id = PromptId::for_path(abs_path);
+        let file = PromptFile::parse(content);
+        self.metadata_cache.write().insert(PromptMetadata {
+            id,
+            title: file.title,
+            default: file.default,
+            saved_at: Utc::now(),
+        });
+        self.project_prompts.write().insert(
+            id,
+            ProjectPrompt {
+                worktree_id,
+                body: file.body,
+            },
+        );
+    }
+
+    pub fn remove_project_prompt(&self, abs_path: &Path) {
+        let id = PromptId::for_path(abs_path);
+        if self.project_prompts.write().remove(&id).is_some() {
+            self.metadata_cache.write().remove(id);
+        }
+    }
+
+    pub fn remove_project_prompts_for_worktree(&self, worktree_id: WorktreeId) {
+        let mut project_prompts = self.project_prompts.write();
+        let mut metadata_cache = self.metadata_cache.write();
+        project_prompts.retain(|id, prompt| {
+            if prompt.worktree_id == worktree_id {
+                metadata_cache.remove(*id);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Imports every Markdown file in the given directory into the library,
+    /// replacing the prompts that have the same title.
+    pub fn import(self: Arc<Self>, fs: Arc<dyn Fs>, directory: PathBuf) -> Task<Result<()>> {
+        let executor = self.executor.clone();
+        executor.spawn(async move {
+            let mut paths = fs.read_dir(&directory).await?;
+            while let Some(path) = paths.next().await {
+                let path = path?;
+                if path.extension().map_or(true, |extension| extension != "md") {
+                    continue;
+                }
+
+                let file = PromptFile::parse(&fs.load(&path).await?);
+                let id = file
+                    .title
+                    .as_ref()
+                    .and_then(|title| self.id_for_title(title))
+                    .filter(|id| !self.is_project_prompt(*id))
+                    .unwrap_or_else(PromptId::new);
+                self.save(id, file.title, file.default, Rope::from(file.body.as_str()))
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Writes every prompt of the library to the given directory as a Markdown file.
+    pub fn export(self: Arc<Self>, fs: Arc<dyn Fs>, directory: PathBuf) -> Task<Result<()>> {
+        let prompts = self
+            .metadata_cache
+            .read()
+            .metadata
+            .iter()
+            .filter(|metadata| !self.is_project_prompt(metadata.id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let executor = self.executor.clone();
+        executor.spawn(async move {
+            fs.create_dir(&directory).await?;
+            let mut file_names = HashSet::default();
+            for metadata in prompts {
+                let file = PromptFile {
+                    body: self.load(metadata.id).await?,
+                    title: metadata.title,
+                    default: metadata.default,
+                };
+                let file_name = file_name_for_title(file.title.as_deref(), &mut file_names);
+                fs.atomic_write(directory.join(file_name), file.to_markdown())
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
     fn metadata(&self, id: PromptId) -> Option<PromptMetadata> {
         self.metadata_cache.read().metadata_by_id.get(&id).cloned()
     }
@@ -1136,3 +1450,306 @@ fn title_from_body(body: impl IntoIterator<Item = char>) -> Option<SharedString>
         None
     }
 }
+
+/// A prompt stored as a Markdown file, with an optional front-matter block:
+///
+/// ```markdown
+/// ---
+/// title: Code Review
+/// default: true
+/// ---
+/// # Code Review
+/// ...
+/// ```
+#[derive(Debug, PartialEq)]
+struct PromptFile {
+    title: Option<SharedString>,
+    default: bool,
+    body: String,
+}
+
+impl PromptFile {
+    fn parse(content: &str) -> Self {
+        let mut title = None;
+        let mut default = false;
+        let mut body = content;
+
+        if let Some(front_matter) = content
+            .strip_prefix("---\n")
+            .or_else(|| content.strip_prefix("---\r\n"))
+        {
+            let mut offset = 0;
+            for line in front_matter.split_inclusive('\n') {
+                if line.trim_end() == "---" {
+                    for field in front_matter[..offset].lines() {
+                        let Some((key, value)) = field.split_once(':') else {
+                            continue;
+                        };
+                        let value = value.trim().trim_matches('"');
+                        match key.trim() {
+                            "title" if !value.is_empty() => title = Some(value.to_string().into()),
+                            "default" => default = value == "true",
+                            _ => {}
+                        }
+                    }
+                    body = front_matter[offset + line.len()..].trim_start_matches(['\r', '\n']);
+                    break;
+                }
+                offset += line.len();
+            }
+        }
+
+        // Prompts in the library always start with their title as a heading.
+        let body = match &title {
+            Some(title) if title_from_body(body.chars()).is_none() => {
+                format!("# {title}\n\n{body}")
+            }
+            _ => body.to_string(),
+        };
+        let title = title.or_else(|| title_from_body(body.chars()));
+
+        Self {
+            title,
+            default,
+            body,
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = String::from("---\n");
+        if let Some(title) = &self.title {
+            writeln!(markdown, "title: {title}").unwrap();
+        }
+        writeln!(markdown, "default: {}", self.default).unwrap();
+        markdown.push_str("---\n");
+        markdown.push_str(&self.body);
+        if !markdown.ends_with('\n') {
+            markdown.push('\n');
+        }
+        markdown
+    }
+}
+
+/// Returns a file name for an exported prompt that isn't in `used_file_names` yet.
+fn file_name_for_title(title: Option<&str>, used_file_names: &mut HashSet<String>) -> String {
+    let mut stem = title
+        .unwrap_or("untitled")
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    stem = stem
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if stem.is_empty() {
+        stem = "untitled".into();
+    }
+
+    let mut file_name = format!("{stem}.md");
+    let mut suffix = 1;
+    while !used_file_names.insert(file_name.clone()) {
+        suffix += 1;
+        file_name = format!("{stem}-{suffix}.md");
+    }
+    file_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_import_and_export_prompts(cx: &mut TestAppContext) {
+        let db_dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(
+            PromptStore::new(
+                db_dir.path().join("prompts-library-db.0.mdb"),
+                cx.executor(),
+            )
+            .await
+            .unwrap(),
+        );
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/imported",
+            json!({
+                "code-review.md": "---\ntitle: Code Review\ndefault: true\n---\nReview this.\n",
+                "rust.md": "# Rust Expert\nYou know Rust.\n",
+                "notes.txt": "Not a prompt.",
+            }),
+        )
+        .await;
+
+        store
+            .clone()
+            .import(fs.clone(), "/imported".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            prompt_titles(&store),
+            [
+                ("Code Review".to_string(), true),
+                ("Rust Expert".to_string(), false)
+            ]
+        );
+
+        // Importing a prompt with the title of an existing one replaces it.
+        fs.insert_file(
+            "/imported/rust.md",
+            "# Rust Expert\nYou really know Rust.\n".into(),
+        )
+        .await;
+        store
+            .clone()
+            .import(fs.clone(), "/imported".into())
+            .await
+            .unwrap();
+        assert_eq!(prompt_titles(&store).len(), 2);
+        let rust_id = store.id_for_title("Rust Expert").unwrap();
+        assert_eq!(
+            store.load(rust_id).await.unwrap(),
+            "# Rust Expert\nYou really know Rust.\n"
+        );
+
+        // Project prompts aren't exported.
+        store.insert_project_prompt(
+            WorktreeId::from_usize(1),
+            Path::new("/project/.zed/prompts/local.md"),
+            "# Local\nOnly here.\n",
+        );
+        store
+            .clone()
+            .export(fs.clone(), "/exported".into())
+            .await
+            .unwrap();
+        let mut exported_paths = fs
+            .read_dir(Path::new("/exported"))
+            .await
+            .unwrap()
+            .map(|path| path.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        exported_paths.sort();
+        assert_eq!(
+            exported_paths,
+            [
+                PathBuf::from("/exported/code-review.md"),
+                PathBuf::from("/exported/rust-expert.md"),
+            ]
+        );
+        assert_eq!(
+            PromptFile::parse(
+                &fs.load(Path::new("/exported/code-review.md"))
+                    .await
+                    .unwrap()
+            ),
+            PromptFile {
+                title: Some("Code Review".into()),
+                default: true,
+                body: "# Code Review\n\nReview this.\n".into(),
+            }
+        );
+
+        // Exported prompts can be imported back.
+        let other_db_dir = tempfile::tempdir().unwrap();
+        let other_store = Arc::new(
+            PromptStore::new(
+                other_db_dir.path().join("prompts-library-db.0.mdb"),
+                cx.executor(),
+            )
+            .await
+            .unwrap(),
+        );
+        other_store
+            .clone()
+            .import(fs.clone(), "/exported".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            prompt_titles(&other_store),
+            [
+                ("Code Review".to_string(), true),
+                ("Rust Expert".to_string(), false)
+            ]
+        );
+    }
+
+    fn prompt_titles(store: &PromptStore) -> Vec<(String, bool)> {
+        store
+            .metadata_cache
+            .read()
+            .metadata
+            .iter()
+            .map(|metadata| {
+                (
+                    metadata.title.as_deref().unwrap_or_default().to_string(),
+                    metadata.default,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_prompt_file() {
+        assert_eq!(
+            PromptFile::parse("---\ntitle: Code Review\ndefault: true\n---\nReview this.\n"),
+            PromptFile {
+                title: Some("Code Review".into()),
+                default: true,
+                body: "# Code Review\n\nReview this.\n".into(),
+            }
+        );
+        assert_eq!(
+            PromptFile::parse("# Rust Expert\nYou know Rust.\n"),
+            PromptFile {
+                title: Some("Rust Expert".into()),
+                default: false,
+                body: "# Rust Expert\nYou know Rust.\n".into(),
+            }
+        );
+        // An unterminated front-matter block is part of the body.
+        assert_eq!(
+            PromptFile::parse("---\ntitle: Oops\n"),
+            PromptFile {
+                title: None,
+                default: false,
+                body: "---\ntitle: Oops\n".into(),
+            }
+        );
+
+        let file =
+            PromptFile::parse("---\ntitle: Tests\ndefault: false\n---\n# Tests\nWrite tests.\n");
+        assert_eq!(PromptFile::parse(&file.to_markdown()), file);
+    }
+
+    #[test]
+    fn test_file_name_for_title() {
+        let mut used_file_names = HashSet::default();
+        assert_eq!(
+            file_name_for_title(Some("Code Review: Rust!"), &mut used_file_names),
+            "code-review-rust.md"
+        );
+        assert_eq!(
+            file_name_for_title(Some("code review rust"), &mut used_file_names),
+            "code-review-rust-2.md"
+        );
+        assert_eq!(
+            file_name_for_title(None, &mut used_file_names),
+            "untitled.md"
+        );
+        assert_eq!(
+            file_name_for_title(Some("!!!"), &mut used_file_names),
+            "untitled-2.md"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use git::repository::BranchChanges;
 use language::BufferSnapshot;
 use std::{fmt::Write, ops::Range};
 
@@ -121,3 +122,93 @@ pub fn generate_content_prompt(
 
     Ok(prompt)
 }
+
+pub fn generate_commit_message_prompt(diff: &str, recent_subjects: &[String]) -> String {
+    let mut prompt = String::new();
+    writeln!(
+        prompt,
+        "You are an expert engineer writing a git commit message."
+    )
+    .unwrap();
+    writeln!(prompt, "These are the staged changes:").unwrap();
+    writeln!(prompt, "```diff").unwrap();
+    prompt.push_str(diff);
+    if !prompt.ends_with('\n') {
+        prompt.push('\n');
+    }
+    writeln!(prompt, "```").unwrap();
+    prompt.push('\n');
+
+    if !recent_subjects.is_empty() {
+        writeln!(
+            prompt,
+            "These are the subjects of the repository's most recent commits. \
+            Match their style, casing and conventions:"
+        )
+        .unwrap();
+        for subject in recent_subjects {
+            writeln!(prompt, "- {subject}").unwrap();
+        }
+        prompt.push('\n');
+    }
+
+    writeln!(
+        prompt,
+        "Write a commit message for the staged changes: a subject line of at most 72 characters, \
+        followed by a blank line and a short body explaining what changed and why, if needed."
+    )
+    .unwrap();
+    writeln!(prompt, "Never make remarks about the output.").unwrap();
+    writeln!(
+        prompt,
+        "Do not return anything else, except the commit message."
+    )
+    .unwrap();
+    prompt
+}
+
+pub fn generate_pull_request_description_prompt(changes: &[BranchChanges]) -> String {
+    let mut prompt = String::new();
+    writeln!(
+        prompt,
+        "You are an expert engineer writing the description of a pull request."
+    )
+    .unwrap();
+    for branch_changes in changes {
+        writeln!(
+            prompt,
+            "These are the changes on the current branch, relative to `{}`:",
+            branch_changes.base_branch
+        )
+        .unwrap();
+        writeln!(prompt, "```diff").unwrap();
+        prompt.push_str(&branch_changes.diff);
+        if !prompt.ends_with('\n') {
+            prompt.push('\n');
+        }
+        writeln!(prompt, "```").unwrap();
+        prompt.push('\n');
+
+        if !branch_changes.commit_subjects.is_empty() {
+            writeln!(prompt, "These are the subjects of the branch's commits:").unwrap();
+            for subject in &branch_changes.commit_subjects {
+                writeln!(prompt, "- {subject}").unwrap();
+            }
+            prompt.push('\n');
+        }
+    }
+
+    writeln!(
+        prompt,
+        "Write a description for a pull request with these changes, in Markdown: \
+        a short summary of what changed and why, followed by any details a reviewer should know."
+    )
+    .unwrap();
+    writeln!(prompt, "Never make remarks about the output.").unwrap();
+    writeln!(
+        prompt,
+        "Do not return anything else, except the pull request description."
+    )
+    .unwrap();
+    prompt
+}
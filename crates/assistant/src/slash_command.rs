@@ -20,12 +20,13 @@ use workspace::Workspace;
 pub mod active_command;
 pub mod default_command;
 pub mod diagnostics_command;
+pub mod diff_command;
+pub mod docs_command;
 pub mod fetch_command;
 pub mod file_command;
 pub mod now_command;
 pub mod project_command;
 pub mod prompt_command;
-pub mod rustdoc_command;
 pub mod search_command;
 pub mod tabs_command;
 pub mod term_command;
@@ -0,0 +1,436 @@
+use super::{create_label_for_command, SlashCommand, SlashCommandOutput};
+use anyhow::{anyhow, Result};
+use assistant_slash_command::SlashCommandOutputSection;
+use fs::Fs;
+use git::repository::{BranchChanges, GitRepository};
+use gpui::{AppContext, Model, Task, WeakView};
+use language::LspAdapterDelegate;
+use project::Project;
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc},
+};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::Workspace;
+
+pub(crate) struct DiffSlashCommand;
+
+const STAGED_ARGUMENT: &str = "--staged";
+
+/// Which two versions of the changed files to compare.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DiffKind {
+    /// The index against the working tree.
+    Unstaged,
+    /// HEAD against the index.
+    Staged,
+}
+
+impl DiffKind {
+    fn parse(argument: Option<&str>) -> Result<Self> {
+        match argument.map(str::trim) {
+            None | Some("") => Ok(Self::Unstaged),
+            Some(STAGED_ARGUMENT) => Ok(Self::Staged),
+            Some(argument) => Err(anyhow!("unknown argument `{argument}`")),
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            DiffKind::Unstaged => "Unstaged changes",
+            DiffKind::Staged => "Staged changes",
+        }
+    }
+}
+
+/// The unified diff of a single file in one of the project's repositories.
+pub(crate) struct FileDiff {
+    pub path: PathBuf,
+    pub diff: String,
+}
+
+impl SlashCommand for DiffSlashCommand {
+    fn name(&self) -> String {
+        "diff".into()
+    }
+
+    fn label(&self, cx: &AppContext) -> language::CodeLabel {
+        create_label_for_command("diff", &[STAGED_ARGUMENT], cx)
+    }
+
+    fn description(&self) -> String {
+        "insert the git diff of the working tree or the index".into()
+    }
+
+    fn menu_text(&self) -> String {
+        "Insert Git Diff".into()
+    }
+
+    fn requires_argument(&self) -> bool {
+        false
+    }
+
+    fn complete_argument(
+        self: Arc<Self>,
+        _query: String,
+        _cancel: Arc<AtomicBool>,
+        _workspace: Option<WeakView<Workspace>>,
+        _cx: &mut AppContext,
+    ) -> Task<Result<Vec<String>>> {
+        Task::ready(Ok(vec![STAGED_ARGUMENT.to_string()]))
+    }
+
+    fn run(
+        self: Arc<Self>,
+        argument: Option<&str>,
+        workspace: WeakView<Workspace>,
+        _delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let kind = match DiffKind::parse(argument) {
+            Ok(kind) => kind,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let diffs = collect_diffs(workspace.read(cx).project().clone(), kind, cx);
+        cx.background_executor()
+            .spawn(async move { diff_output(diffs.await?, kind) })
+    }
+}
+
+/// Renders the given diffs as the output of `/diff`, with a section for each file
+/// nested in a section for all of them.
+fn diff_output(diffs: Vec<FileDiff>, kind: DiffKind) -> Result<SlashCommandOutput> {
+    if diffs.is_empty() {
+        return Err(anyhow!("no changes found"));
+    }
+
+    let mut text = String::new();
+    let mut sections = Vec::new();
+    for file_diff in diffs {
+        let start = text.len();
+        writeln!(text, "```diff").unwrap();
+        text.push_str(&file_diff.diff);
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        writeln!(text, "```").unwrap();
+        sections.push(SlashCommandOutputSection {
+            range: start..text.len(),
+            icon: IconName::FileGit,
+            label: file_diff.path.to_string_lossy().to_string().into(),
+        });
+    }
+    sections.push(SlashCommandOutputSection {
+        range: 0..text.len(),
+        icon: IconName::FileGit,
+        label: kind.label().into(),
+    });
+
+    Ok(SlashCommandOutput {
+        text,
+        sections,
+        run_commands_in_text: false,
+    })
+}
+
+/// A git repository in one of the project's visible local worktrees.
+struct ProjectRepository {
+    /// The path of the repository's work directory, including the worktree's root name.
+    work_directory: PathBuf,
+    abs_work_directory: PathBuf,
+    repository: Arc<dyn GitRepository>,
+}
+
+fn project_repositories(project: &Project, cx: &AppContext) -> Vec<ProjectRepository> {
+    let mut repositories = Vec::new();
+    for worktree in project.visible_worktrees(cx) {
+        let Some(worktree) = worktree.read(cx).as_local() else {
+            continue;
+        };
+        let snapshot = worktree.snapshot();
+        for (work_directory, repository_entry) in snapshot.repositories() {
+            if let Some(repository) = worktree.get_local_repo(repository_entry) {
+                repositories.push(ProjectRepository {
+                    work_directory: Path::new(snapshot.root_name()).join(work_directory),
+                    abs_work_directory: worktree.abs_path().join(work_directory),
+                    repository: repository.repo().clone(),
+                });
+            }
+        }
+    }
+    repositories
+}
+
+/// Computes the diff of every changed file in the repositories of the project's
+/// visible local worktrees.
+pub(crate) fn collect_diffs(
+    project: Model<Project>,
+    kind: DiffKind,
+    cx: &mut AppContext,
+) -> Task<Result<Vec<FileDiff>>> {
+    let project = project.read(cx);
+    let fs = project.fs().clone();
+    let repositories = project_repositories(project, cx);
+    cx.background_executor().spawn(async move {
+        let mut diffs = Vec::new();
+        for ProjectRepository {
+            work_directory,
+            abs_work_directory,
+            repository,
+        } in repositories
+        {
+            let status = repository.statuses(Path::new(""))?;
+            for (repo_path, _) in status.entries.iter() {
+                let (old_text, new_text) = match kind {
+                    DiffKind::Unstaged => {
+                        // Like `git diff`, leave out untracked files, which aren't in the index.
+                        let Some(index_text) = repository.load_index_text(repo_path) else {
+                            continue;
+                        };
+                        (
+                            Some(index_text),
+                            load_file_text(fs.as_ref(), &abs_work_directory.join(repo_path)).await,
+                        )
+                    }
+                    DiffKind::Staged => (
+                        repository.load_committed_text(repo_path),
+                        repository.load_index_text(repo_path),
+                    ),
+                };
+                if old_text == new_text {
+                    continue;
+                }
+
+                let path = work_directory.join(repo_path);
+                diffs.push(FileDiff {
+                    diff: unified_diff(&path, old_text.as_deref(), new_text.as_deref()),
+                    path,
+                });
+            }
+        }
+        Ok(diffs)
+    })
+}
+
+/// Returns the subjects of the most recent commits of the project's repositories.
+pub(crate) fn recent_commit_subjects(
+    project: Model<Project>,
+    limit: usize,
+    cx: &mut AppContext,
+) -> Task<Result<Vec<String>>> {
+    let repositories = project_repositories(project.read(cx), cx);
+    cx.background_executor().spawn(async move {
+        let mut subjects = Vec::new();
+        for ProjectRepository { repository, .. } in repositories {
+            subjects.extend(repository.recent_commit_subjects(limit)?);
+        }
+        Ok(subjects)
+    })
+}
+
+/// Returns the changes on the current branch of each of the project's repositories
+/// that has any, relative to the repository's default branch.
+pub(crate) fn collect_branch_changes(
+    project: Model<Project>,
+    cx: &mut AppContext,
+) -> Task<Vec<BranchChanges>> {
+    let repositories = project_repositories(project.read(cx), cx);
+    cx.background_executor().spawn(async move {
+        let mut changes = Vec::new();
+        for ProjectRepository { repository, .. } in repositories {
+            if let Some(branch_changes) = repository.branch_changes().log_err() {
+                if !branch_changes.diff.is_empty() {
+                    changes.push(branch_changes);
+                }
+            }
+        }
+        changes
+    })
+}
+
+async fn load_file_text(fs: &dyn Fs, abs_path: &Path) -> Option<String> {
+    if !fs.is_file(abs_path).await {
+        return None;
+    }
+    fs.load(abs_path).await.ok()
+}
+
+fn unified_diff(path: &Path, old_text: Option<&str>, new_text: Option<&str>) -> String {
+    let path = path.to_string_lossy();
+    let old_header = if old_text.is_some() {
+        format!("a/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    let new_header = if new_text.is_some() {
+        format!("b/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    similar::TextDiff::from_lines(old_text.unwrap_or(""), new_text.unwrap_or(""))
+        .unified_diff()
+        .header(&old_header, &new_header)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use git::repository::GitFileStatus;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_collect_diffs(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/repo",
+            json!({
+                ".git": {},
+                "modified.txt": "one\ntwo\n",
+                "staged.txt": "new\n",
+                "untracked.txt": "untracked\n",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/repo/.git");
+        fs.set_head_for_repo(
+            dot_git,
+            &[
+                (Path::new("modified.txt"), "one\n".into()),
+                (Path::new("staged.txt"), "old\n".into()),
+            ],
+        );
+        fs.set_index_for_repo(
+            dot_git,
+            &[
+                (Path::new("modified.txt"), "one\n".into()),
+                (Path::new("staged.txt"), "new\n".into()),
+            ],
+        );
+        fs.set_status_for_repo_via_git_operation(
+            dot_git,
+            &[
+                (Path::new("modified.txt"), GitFileStatus::Modified),
+                (Path::new("staged.txt"), GitFileStatus::Modified),
+                (Path::new("untracked.txt"), GitFileStatus::Added),
+            ],
+        );
+        fs.with_git_state(dot_git, false, |state| {
+            state.commit_subjects = vec!["Fix the thing".into(), "Add the thing".into()];
+        });
+
+        let project = Project::test(fs.clone(), ["/repo".as_ref()], cx).await;
+        cx.executor().run_until_parked();
+
+        // Untracked files are left out, like in `git diff`.
+        let diffs = cx
+            .update(|cx| collect_diffs(project.clone(), DiffKind::Unstaged, cx))
+            .await
+            .unwrap();
+        let output = diff_output(diffs, DiffKind::Unstaged).unwrap();
+        assert_eq!(
+            output.text,
+            "```diff\n--- a/repo/modified.txt\n+++ b/repo/modified.txt\n@@ -1 +1,2 @@\n one\n+two\n```\n"
+        );
+        assert_eq!(
+            output
+                .sections
+                .iter()
+                .map(|section| (section.label.to_string(), section.range.clone()))
+                .collect::<Vec<_>>(),
+            [
+                ("repo/modified.txt".to_string(), 0..output.text.len()),
+                ("Unstaged changes".to_string(), 0..output.text.len()),
+            ]
+        );
+
+        let diffs = cx
+            .update(|cx| collect_diffs(project.clone(), DiffKind::Staged, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            diffs
+                .iter()
+                .map(|diff| diff.path.as_path())
+                .collect::<Vec<_>>(),
+            [Path::new("repo/staged.txt")]
+        );
+        assert!(diffs[0].diff.contains("-old\n+new\n"));
+
+        let subjects = cx
+            .update(|cx| recent_commit_subjects(project.clone(), 1, cx))
+            .await
+            .unwrap();
+        assert_eq!(subjects, ["Fix the thing"]);
+
+        // Repositories whose default branch can't be found are skipped.
+        let changes = cx
+            .update(|cx| collect_branch_changes(project.clone(), cx))
+            .await;
+        assert!(changes.is_empty());
+        let branch_changes = BranchChanges {
+            base_branch: "main".into(),
+            commit_subjects: vec!["Add the thing".into()],
+            diff: "--- a/staged.txt\n+++ b/staged.txt\n".into(),
+        };
+        fs.with_git_state(dot_git, false, |state| {
+            state.branch_changes = Some(branch_changes.clone());
+        });
+        let changes = cx
+            .update(|cx| collect_branch_changes(project.clone(), cx))
+            .await;
+        assert_eq!(changes, [branch_changes]);
+
+        // Nothing is staged once the index matches HEAD.
+        fs.set_head_for_repo(
+            dot_git,
+            &[
+                (Path::new("modified.txt"), "one\n".into()),
+                (Path::new("staged.txt"), "new\n".into()),
+            ],
+        );
+        let diffs = cx
+            .update(|cx| collect_diffs(project.clone(), DiffKind::Staged, cx))
+            .await
+            .unwrap();
+        assert!(diff_output(diffs, DiffKind::Staged).is_err());
+    }
+
+    #[test]
+    fn test_unified_diff_headers() {
+        let diff = unified_diff(Path::new("src/main.rs"), Some("a\nb\n"), Some("a\nc\n"));
+        assert_eq!(
+            diff,
+            "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n"
+        );
+
+        let diff = unified_diff(Path::new("new.txt"), None, Some("hello\n"));
+        assert!(diff.starts_with("--- /dev/null\n+++ b/new.txt\n"));
+
+        let diff = unified_diff(Path::new("old.txt"), Some("bye\n"), None);
+        assert!(diff.starts_with("--- a/old.txt\n+++ /dev/null\n"));
+    }
+
+    #[test]
+    fn test_parse_diff_kind() {
+        assert_eq!(DiffKind::parse(None).unwrap(), DiffKind::Unstaged);
+        assert_eq!(DiffKind::parse(Some(" ")).unwrap(), DiffKind::Unstaged);
+        assert_eq!(DiffKind::parse(Some("--staged")).unwrap(), DiffKind::Staged);
+        assert!(DiffKind::parse(Some("--cached")).is_err());
+    }
+}
@@ -0,0 +1,463 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use assistant_slash_command::{SlashCommand, SlashCommandOutput, SlashCommandOutputSection};
+use gpui::{AppContext, Global, Model, Task, WeakView};
+use indexed_docs::{
+    DocsDotRsProvider, GoDocProvider, IndexedDocsRegistry, IndexedDocsStore, LocalRustdocProvider,
+    NpmPackageProvider, PackageName, ProviderId, PythonDocsProvider,
+};
+use language::LspAdapterDelegate;
+use project::{Project, ProjectPath};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::Workspace;
+
+pub(crate) struct DocsSlashCommand;
+
+impl DocsSlashCommand {
+    pub const NAME: &'static str = "docs";
+
+    fn path_to_worktree_file(
+        project: &Model<Project>,
+        file: &str,
+        cx: &mut AppContext,
+    ) -> Option<Arc<Path>> {
+        let worktree = project.read(cx).worktrees().next()?;
+        let worktree = worktree.read(cx);
+        let entry = worktree.entry_for_path(file)?;
+        let path = ProjectPath {
+            worktree_id: worktree.id(),
+            path: entry.path.clone(),
+        };
+        Some(Arc::from(
+            project.read(cx).absolute_path(&path, cx)?.as_path(),
+        ))
+    }
+
+    fn project_root(project: &Model<Project>, cx: &mut AppContext) -> Option<Arc<Path>> {
+        let worktree = project.read(cx).worktrees().next()?;
+        let root_path = worktree.read(cx).abs_path();
+        Some(root_path)
+    }
+
+    /// Ensures that the built-in docs providers are registered for the given workspace's
+    /// project.
+    ///
+    /// The providers are configured based on the first worktree in the project. As the
+    /// registry is global, they are re-registered whenever the command is used in a
+    /// project with a different root.
+    pub fn ensure_docs_providers_are_registered(
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut AppContext,
+    ) {
+        let Some(workspace) = workspace.and_then(|workspace| workspace.upgrade()) else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        let roots = DocsProviderRoots {
+            project_root: Self::project_root(&project, cx).map(|root| root.to_path_buf()),
+            cargo_workspace_root: Self::path_to_worktree_file(&project, "Cargo.toml", cx)
+                .and_then(|path| path.parent().map(|path| path.to_path_buf())),
+        };
+        if cx
+            .try_global::<DocsProviderRoots>()
+            .is_some_and(|registered_roots| *registered_roots == roots)
+        {
+            return;
+        }
+
+        let fs = project.read(cx).fs().clone();
+        let http_client = workspace.read(cx).client().http_client();
+        let indexed_docs_registry = IndexedDocsRegistry::global(cx);
+
+        if let Some(cargo_workspace_root) = roots.cargo_workspace_root.clone() {
+            indexed_docs_registry.register_provider(Box::new(LocalRustdocProvider::new(
+                fs.clone(),
+                cargo_workspace_root,
+            )));
+        } else {
+            indexed_docs_registry
+                .register_provider(Box::new(DocsDotRsProvider::new(http_client.clone())));
+        }
+
+        let local_build_dir = roots
+            .project_root
+            .as_ref()
+            .map(|project_root| project_root.join("docs/_build/html"));
+        indexed_docs_registry.register_provider(Box::new(PythonDocsProvider::new(
+            fs.clone(),
+            http_client.clone(),
+            local_build_dir,
+        )));
+
+        indexed_docs_registry.register_provider(Box::new(NpmPackageProvider::new(
+            fs.clone(),
+            http_client.clone(),
+            roots.project_root.clone(),
+        )));
+
+        indexed_docs_registry
+            .register_provider(Box::new(GoDocProvider::new(roots.project_root.clone())));
+
+        cx.set_global(roots);
+    }
+}
+
+/// The project roots that the built-in docs providers are currently configured for.
+#[derive(Debug, PartialEq)]
+struct DocsProviderRoots {
+    project_root: Option<PathBuf>,
+    cargo_workspace_root: Option<PathBuf>,
+}
+
+impl Global for DocsProviderRoots {}
+
+impl SlashCommand for DocsSlashCommand {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn description(&self) -> String {
+        "insert docs".into()
+    }
+
+    fn menu_text(&self) -> String {
+        "Insert Documentation".into()
+    }
+
+    fn requires_argument(&self) -> bool {
+        true
+    }
+
+    fn complete_argument(
+        self: Arc<Self>,
+        query: String,
+        _cancel: Arc<AtomicBool>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut AppContext,
+    ) -> Task<Result<Vec<String>>> {
+        Self::ensure_docs_providers_are_registered(workspace, cx);
+
+        let indexed_docs_registry = IndexedDocsRegistry::global(cx);
+        let args = DocsSlashCommandArgs::parse(&query);
+        let store = args
+            .provider()
+            .ok_or_else(|| anyhow!("no docs provider specified"))
+            .and_then(|provider| IndexedDocsStore::try_global(provider, cx));
+        cx.background_executor().spawn(async move {
+            match args {
+                DocsSlashCommandArgs::NoProvider => {
+                    let providers = indexed_docs_registry.list_providers();
+                    if providers.is_empty() {
+                        return Ok(vec![
+                            "No docs providers registered; open a project first.".into()
+                        ]);
+                    }
+
+                    Ok(providers
+                        .into_iter()
+                        .map(|provider| provider.to_string())
+                        .collect())
+                }
+                DocsSlashCommandArgs::SearchPackageDocs {
+                    provider,
+                    package,
+                    index,
+                } => {
+                    let store = store?;
+
+                    if index {
+                        // We don't need to hold onto this task, as the `IndexedDocsStore` will hold it
+                        // until it completes.
+                        let _ = store.clone().index(package.as_str().into());
+                    }
+
+                    let suggested_packages = store
+                        .clone()
+                        .suggest_packages()
+                        .await
+                        .log_err()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|package| package.to_string())
+                        .filter(|suggested_package| suggested_package.contains(&package))
+                        .collect::<Vec<_>>();
+                    let items = store.search(package).await;
+
+                    Ok(suggested_packages
+                        .into_iter()
+                        .filter(|suggested_package| !items.contains(suggested_package))
+                        .chain(items)
+                        .map(|item| format!("{provider} {item}"))
+                        .collect())
+                }
+            }
+        })
+    }
+
+    fn run(
+        self: Arc<Self>,
+        argument: Option<&str>,
+        workspace: WeakView<Workspace>,
+        _delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let Some(argument) = argument else {
+            return Task::ready(Err(anyhow!("missing argument")));
+        };
+
+        Self::ensure_docs_providers_are_registered(Some(workspace), cx);
+
+        let args = DocsSlashCommandArgs::parse(argument);
+        let text = cx.background_executor().spawn({
+            let store = args
+                .provider()
+                .ok_or_else(|| anyhow!("no docs provider specified"))
+                .and_then(|provider| IndexedDocsStore::try_global(provider, cx));
+            async move {
+                let (provider, key) = match args {
+                    DocsSlashCommandArgs::NoProvider => bail!("no docs provider specified"),
+                    DocsSlashCommandArgs::SearchPackageDocs {
+                        provider, package, ..
+                    } => (provider, package.trim().to_string()),
+                };
+
+                if key.is_empty() {
+                    bail!("no package name provided");
+                }
+
+                let store = store?;
+                let docs = match store.load(key.clone()).await {
+                    Ok(docs) => docs,
+                    Err(_) => {
+                        // The docs haven't been indexed yet, so we index the package and try again.
+                        let package = key.split("::").next().unwrap_or_default();
+                        store
+                            .clone()
+                            .index(package.into())
+                            .await
+                            .map_err(|err| anyhow!(err))?;
+                        store.load(key.clone()).await?
+                    }
+                };
+
+                anyhow::Ok((provider, key, docs.to_string()))
+            }
+        });
+
+        cx.foreground_executor().spawn(async move {
+            let (provider, key, text) = text.await?;
+            let range = 0..text.len();
+            Ok(SlashCommandOutput {
+                text,
+                sections: vec![SlashCommandOutputSection {
+                    range,
+                    icon: if provider == LocalRustdocProvider::id() {
+                        IconName::FileRust
+                    } else {
+                        IconName::FileDoc
+                    },
+                    label: format!("docs ({provider}): {key}").into(),
+                }],
+                run_commands_in_text: false,
+            })
+        })
+    }
+}
+
+/// `/rustdoc`, which predates `/docs` and is kept as an alias for `/docs rustdoc`.
+pub(crate) struct RustdocSlashCommand;
+
+impl RustdocSlashCommand {
+    pub const NAME: &'static str = "rustdoc";
+
+    /// Returns the `/docs` argument equivalent to the given `/rustdoc` argument.
+    pub fn docs_argument(argument: &str) -> String {
+        format!("{} {argument}", LocalRustdocProvider::id())
+    }
+}
+
+impl SlashCommand for RustdocSlashCommand {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn description(&self) -> String {
+        "insert Rust docs".into()
+    }
+
+    fn menu_text(&self) -> String {
+        "Insert Rust Documentation".into()
+    }
+
+    fn requires_argument(&self) -> bool {
+        true
+    }
+
+    fn complete_argument(
+        self: Arc<Self>,
+        query: String,
+        cancel: Arc<AtomicBool>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut AppContext,
+    ) -> Task<Result<Vec<String>>> {
+        let completions = Arc::new(DocsSlashCommand).complete_argument(
+            Self::docs_argument(&query),
+            cancel,
+            workspace,
+            cx,
+        );
+        cx.background_executor().spawn(async move {
+            let prefix = Self::docs_argument("");
+            Ok(completions
+                .await?
+                .into_iter()
+                .filter_map(|completion| completion.strip_prefix(&prefix).map(ToOwned::to_owned))
+                .collect())
+        })
+    }
+
+    fn run(
+        self: Arc<Self>,
+        argument: Option<&str>,
+        workspace: WeakView<Workspace>,
+        delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let argument = argument.map(Self::docs_argument);
+        Arc::new(DocsSlashCommand).run(argument.as_deref(), workspace, delegate, cx)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum DocsSlashCommandArgs {
+    NoProvider,
+    SearchPackageDocs {
+        provider: ProviderId,
+        package: String,
+        index: bool,
+    },
+}
+
+impl DocsSlashCommandArgs {
+    pub fn parse(argument: &str) -> Self {
+        let Some((provider, argument)) = argument.split_once(' ') else {
+            return Self::NoProvider;
+        };
+
+        let provider = ProviderId(provider.into());
+
+        // Item paths are delimited with `::` for every provider, so indexing is triggered
+        // once the package name is followed by a `:` (e.g., `tokio:`). This way we don't
+        // index every prefix of the package name as it is typed.
+        if let Some((package, rest)) = argument.split_once(':') {
+            if rest.is_empty() || rest == ":" {
+                return Self::SearchPackageDocs {
+                    provider,
+                    package: package.to_owned(),
+                    index: true,
+                };
+            }
+        }
+
+        Self::SearchPackageDocs {
+            provider,
+            package: argument.to_owned(),
+            index: false,
+        }
+    }
+
+    pub fn provider(&self) -> Option<ProviderId> {
+        match self {
+            Self::NoProvider => None,
+            Self::SearchPackageDocs { provider, .. } => Some(provider.clone()),
+        }
+    }
+
+    /// Returns the name of the package these arguments refer to, if any.
+    pub fn package(&self) -> Option<PackageName> {
+        match self {
+            Self::NoProvider => None,
+            Self::SearchPackageDocs { package, .. } => {
+                let package = package.split(':').next()?.trim();
+                (!package.is_empty()).then(|| PackageName::from(package))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_docs_slash_command_args() {
+        assert_eq!(
+            DocsSlashCommandArgs::parse(""),
+            DocsSlashCommandArgs::NoProvider
+        );
+        assert_eq!(
+            DocsSlashCommandArgs::parse("rustdoc"),
+            DocsSlashCommandArgs::NoProvider
+        );
+
+        assert_eq!(
+            DocsSlashCommandArgs::parse("rustdoc "),
+            DocsSlashCommandArgs::SearchPackageDocs {
+                provider: ProviderId("rustdoc".into()),
+                package: "".into(),
+                index: false
+            }
+        );
+        assert_eq!(
+            DocsSlashCommandArgs::parse("rustdoc tokio"),
+            DocsSlashCommandArgs::SearchPackageDocs {
+                provider: ProviderId("rustdoc".into()),
+                package: "tokio".into(),
+                index: false
+            }
+        );
+        assert_eq!(
+            DocsSlashCommandArgs::parse("rustdoc tokio::"),
+            DocsSlashCommandArgs::SearchPackageDocs {
+                provider: ProviderId("rustdoc".into()),
+                package: "tokio".into(),
+                index: true
+            }
+        );
+        assert_eq!(
+            DocsSlashCommandArgs::parse("rustdoc tokio:"),
+            DocsSlashCommandArgs::SearchPackageDocs {
+                provider: ProviderId("rustdoc".into()),
+                package: "tokio".into(),
+                index: true
+            }
+        );
+        assert_eq!(
+            DocsSlashCommandArgs::parse("rustdoc tokio::sync"),
+            DocsSlashCommandArgs::SearchPackageDocs {
+                provider: ProviderId("rustdoc".into()),
+                package: "tokio::sync".into(),
+                index: false
+            }
+        );
+
+        assert_eq!(
+            DocsSlashCommandArgs::parse("npm @types/node:"),
+            DocsSlashCommandArgs::SearchPackageDocs {
+                provider: ProviderId("npm".into()),
+                package: "@types/node".into(),
+                index: true
+            }
+        );
+
+        assert_eq!(
+            DocsSlashCommandArgs::parse("go net/http::Client").package(),
+            Some(PackageName::from("net/http"))
+        );
+        assert_eq!(DocsSlashCommandArgs::parse("rustdoc ").package(), None);
+    }
+}
@@ -0,0 +1,151 @@
+use editor::Editor;
+use gpui::{
+    div, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    SharedString, Subscription, View, ViewContext, VisualContext,
+};
+use language::Buffer;
+use text::Point;
+use ui::{prelude::*, Label};
+use workspace::{bookmarks::BookmarkStore, ModalView, Workspace};
+
+use crate::AnnotateBookmark;
+
+/// Edits the note of the bookmark on the cursor's line, adding a bookmark
+/// there if there isn't one yet.
+pub(crate) struct BookmarkNote {
+    note_editor: View<Editor>,
+    bookmark_store: Model<BookmarkStore>,
+    buffer: Model<Buffer>,
+    row: u32,
+    _subscription: Subscription,
+}
+
+impl BookmarkNote {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &AnnotateBookmark,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let head = editor.update(cx, |editor, cx| {
+            editor.selections.newest::<Point>(cx).head()
+        });
+        let Some((buffer, offset, _)) = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .point_to_buffer_offset(head, cx)
+        else {
+            return;
+        };
+        if buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .is_none()
+        {
+            return;
+        }
+        let row = buffer.read(cx).offset_to_point(offset).row;
+
+        let bookmark_store = workspace.bookmark_store().clone();
+        workspace.toggle_modal(cx, move |cx| Self::new(bookmark_store, buffer, row, cx));
+    }
+
+    fn new(
+        bookmark_store: Model<BookmarkStore>,
+        buffer: Model<Buffer>,
+        row: u32,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let note = bookmark_store
+            .read(cx)
+            .bookmark_at(&buffer, row, cx)
+            .and_then(|bookmark| bookmark.note.clone());
+        let note_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Bookmark note", cx);
+            if let Some(note) = note {
+                editor.set_text(note.to_string(), cx);
+                editor.select_all(&editor::actions::SelectAll, cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe(&note_editor, |_, _, event, cx| {
+            if let editor::EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        Self {
+            note_editor,
+            bookmark_store,
+            buffer,
+            row,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let note = self.note_editor.read(cx).text(cx);
+        let note = note.trim();
+        let note = (!note.is_empty()).then(|| SharedString::from(note.to_string()));
+        self.bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.set_note(&self.buffer, self.row, note, cx)
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for BookmarkNote {}
+
+impl FocusableView for BookmarkNote {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.note_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkNote {}
+
+impl Render for BookmarkNote {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .elevation_2(cx)
+            .key_context("BookmarkNote")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        v_flex()
+                            .py_0p5()
+                            .px_1()
+                            .child(div().px_1().py_0p5().child(self.note_editor.clone())),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(
+                        h_flex().justify_between().px_2().py_1().child(
+                            Label::new(format!("Note for the bookmark on line {}", self.row + 1))
+                                .color(Color::Muted),
+                        ),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,103 @@
+mod bookmark_note;
+mod bookmarks_picker;
+
+use std::path::PathBuf;
+
+use bookmark_note::BookmarkNote;
+use bookmarks_picker::BookmarksPicker;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{actions, AppContext, ViewContext};
+use text::{Bias, Point};
+use workspace::Workspace;
+
+actions!(
+    bookmarks,
+    [
+        ShowBookmarks,
+        GoToNextBookmark,
+        GoToPreviousBookmark,
+        AnnotateBookmark
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(register).detach();
+}
+
+fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(BookmarksPicker::toggle);
+    workspace.register_action(BookmarkNote::toggle);
+    workspace.register_action(|workspace, _: &GoToNextBookmark, cx| {
+        go_to_adjacent_bookmark(workspace, Direction::Next, cx)
+    });
+    workspace.register_action(|workspace, _: &GoToPreviousBookmark, cx| {
+        go_to_adjacent_bookmark(workspace, Direction::Prev, cx)
+    });
+}
+
+enum Direction {
+    Prev,
+    Next,
+}
+
+/// Moves to the bookmark after or before the cursor of the active editor,
+/// across all files, wrapping around at either end.
+fn go_to_adjacent_bookmark(
+    workspace: &mut Workspace,
+    direction: Direction,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let bookmarks = workspace.bookmark_store().read(cx).bookmarks(cx);
+    let position = workspace
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+        .and_then(|editor| {
+            let editor = editor.read(cx);
+            let buffer = editor.buffer().read(cx).as_singleton()?;
+            let file = buffer.read(cx).file()?.as_local()?;
+            let row = editor.selections.newest::<Point>(cx).head().row;
+            Some((file.abs_path(cx), row))
+        });
+
+    let bookmark = match (direction, position) {
+        (Direction::Next, Some((path, row))) => bookmarks
+            .iter()
+            .find(|bookmark| (&bookmark.path, bookmark.row) > (&path, row))
+            .or_else(|| bookmarks.first()),
+        (Direction::Prev, Some((path, row))) => bookmarks
+            .iter()
+            .rev()
+            .find(|bookmark| (&bookmark.path, bookmark.row) < (&path, row))
+            .or_else(|| bookmarks.last()),
+        (Direction::Next, None) => bookmarks.first(),
+        (Direction::Prev, None) => bookmarks.last(),
+    };
+    if let Some(bookmark) = bookmark {
+        open_bookmark(workspace, bookmark.path.clone(), bookmark.row, cx);
+    }
+}
+
+fn open_bookmark(
+    workspace: &mut Workspace,
+    path: PathBuf,
+    row: u32,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let open_task = workspace.open_abs_path(path, true, cx);
+    cx.spawn(|_, mut cx| async move {
+        let item = open_task.await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update(&mut cx, |editor, cx| {
+                let snapshot = editor.snapshot(cx).display_snapshot;
+                let point = snapshot
+                    .buffer_snapshot
+                    .clip_point(Point::new(row, 0), Bias::Left);
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([point..point])
+                });
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
@@ -0,0 +1,303 @@
+use std::sync::Arc;
+
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    ParentElement, Render, SharedString, Styled, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{bookmarks::BookmarkEntry, ModalView, Workspace};
+
+use crate::{open_bookmark, ShowBookmarks};
+
+/// Lists the bookmarks of the workspace, to open or remove them.
+pub(crate) struct BookmarksPicker {
+    picker: View<Picker<BookmarksPickerDelegate>>,
+}
+
+impl BookmarksPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &ShowBookmarks,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let delegate =
+                BookmarksPickerDelegate::new(cx.view().downgrade(), workspace_handle, cx);
+            let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+            Self { picker }
+        });
+    }
+}
+
+impl Render for BookmarksPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for BookmarksPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarksPicker {}
+impl ModalView for BookmarksPicker {}
+
+struct BookmarkItem {
+    entry: BookmarkEntry,
+    /// The file name and line of the bookmark.
+    location: String,
+    /// The bookmark's note, or the text of its line if it has none.
+    description: String,
+}
+
+pub(crate) struct BookmarksPickerDelegate {
+    bookmarks_picker: WeakView<BookmarksPicker>,
+    workspace: WeakView<Workspace>,
+    bookmarks: Vec<BookmarkItem>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarksPickerDelegate {
+    fn new(
+        bookmarks_picker: WeakView<BookmarksPicker>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<BookmarksPicker>,
+    ) -> Self {
+        let mut this = Self {
+            bookmarks_picker,
+            workspace,
+            bookmarks: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        this.load_bookmarks(cx);
+        this
+    }
+
+    fn load_bookmarks(&mut self, cx: &mut WindowContext) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let entries = workspace.read(cx).bookmark_store().read(cx).bookmarks(cx);
+        self.bookmarks = entries
+            .into_iter()
+            .map(|entry| {
+                let file_name = entry
+                    .path
+                    .file_name()
+                    .unwrap_or(entry.path.as_os_str())
+                    .to_string_lossy();
+                let location = format!("{}:{}", file_name, entry.row + 1);
+                let description = match &entry.note {
+                    Some(note) => note.to_string(),
+                    None => entry.line_text.trim().to_string(),
+                };
+                BookmarkItem {
+                    entry,
+                    location,
+                    description,
+                }
+            })
+            .collect();
+    }
+}
+
+impl PickerDelegate for BookmarksPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search bookmarks...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.bookmarks.is_empty() {
+            "No bookmarks".into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(mat) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let entry = &self.bookmarks[mat.candidate_id].entry;
+        let path = entry.path.clone();
+        let row = entry.row;
+
+        if secondary {
+            // Keep the picker open, so that several bookmarks can be removed.
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace.bookmark_store().update(cx, |bookmark_store, cx| {
+                        bookmark_store.remove_bookmark(&path, row, cx)
+                    })
+                })
+                .log_err();
+            self.load_bookmarks(cx);
+            cx.defer(|picker, cx| picker.refresh(cx));
+        } else {
+            self.workspace
+                .update(cx, |workspace, cx| open_bookmark(workspace, path, row, cx))
+                .log_err();
+            self.dismissed(cx);
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.bookmarks_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .bookmarks
+            .iter()
+            .enumerate()
+            .map(|(id, bookmark)| {
+                let string = format!("{} {}", bookmark.location, bookmark.description);
+                StringMatchCandidate::new(id, string)
+            })
+            .collect::<Vec<_>>();
+        let max_results = candidates.len();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    max_results,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let bookmark = &self.bookmarks[mat.candidate_id];
+        // The candidate is the location, a space, and the description.
+        let description_start = bookmark.location.len() + 1;
+        let location_positions = mat
+            .positions
+            .iter()
+            .copied()
+            .filter(|&position| position < bookmark.location.len())
+            .collect();
+        let description_positions = mat
+            .positions
+            .iter()
+            .filter_map(|position| position.checked_sub(description_start))
+            .collect();
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            bookmark.location.clone(),
+                            location_positions,
+                        ))
+                        .child(
+                            HighlightedLabel::new(
+                                bookmark.description.clone(),
+                                description_positions,
+                            )
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(
+            h_flex()
+                .w_full()
+                .h_8()
+                .p_2()
+                .gap_2()
+                .justify_end()
+                .rounded_b_md()
+                .bg(cx.theme().colors().ghost_element_selected)
+                .child(
+                    Button::new("open-bookmark", "Open")
+                        .label_size(LabelSize::Small)
+                        .when_some(
+                            KeyBinding::for_action(&menu::Confirm, cx),
+                            |this, keybind| this.key_binding(keybind),
+                        )
+                        .on_click(|_, cx| cx.dispatch_action(menu::Confirm.boxed_clone())),
+                )
+                .child(
+                    Button::new("remove-bookmark", "Remove")
+                        .label_size(LabelSize::Small)
+                        .when_some(
+                            KeyBinding::for_action(&menu::SecondaryConfirm, cx),
+                            |this, keybind| this.key_binding(keybind),
+                        )
+                        .on_click(|_, cx| cx.dispatch_action(menu::SecondaryConfirm.boxed_clone())),
+                )
+                .into_any_element(),
+        )
+    }
+}
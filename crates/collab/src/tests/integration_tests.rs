@@ -3522,7 +3522,12 @@ async fn test_buffer_reloading(
     let new_contents = Rope::from("d\ne\nf");
     client_a
         .fs()
-        .save("/dir/a.txt".as_ref(), &new_contents, LineEnding::Windows)
+        .save(
+            "/dir/a.txt".as_ref(),
+            &new_contents,
+            LineEnding::Windows,
+            Default::default(),
+        )
         .await
         .unwrap();
 
@@ -4289,6 +4294,7 @@ async fn test_reloading_buffer_manually(
             "/a/a.rs".as_ref(),
             &Rope::from("let seven = 7;"),
             LineEnding::Unix,
+            Default::default(),
         )
         .await
         .unwrap();
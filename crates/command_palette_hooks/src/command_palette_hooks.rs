@@ -2,16 +2,17 @@
 
 #![deny(missing_docs)]
 
-use std::any::TypeId;
+use std::{any::TypeId, rc::Rc};
 
 use collections::HashSet;
 use derive_more::{Deref, DerefMut};
-use gpui::{Action, AppContext, BorrowAppContext, Global};
+use gpui::{Action, AppContext, BorrowAppContext, Global, WindowContext};
 
 /// Initializes the command palette hooks.
 pub fn init(cx: &mut AppContext) {
     cx.set_global(GlobalCommandPaletteFilter::default());
     cx.set_global(GlobalCommandPaletteInterceptor::default());
+    cx.set_global(GlobalCommandPalettePreview::default());
 }
 
 /// A filter for the command palette.
@@ -140,3 +141,48 @@ impl CommandPaletteInterceptor {
         self.0 = Some(handler);
     }
 }
+
+/// A handler that previews the effect of the command being typed into the
+/// command palette.
+#[derive(Default)]
+pub struct CommandPalettePreview(Option<Rc<dyn Fn(Option<&str>, &mut WindowContext)>>);
+
+#[derive(Default)]
+struct GlobalCommandPalettePreview(CommandPalettePreview);
+
+impl Global for GlobalCommandPalettePreview {}
+
+impl CommandPalettePreview {
+    /// Updates the global [`CommandPalettePreview`] using the given closure.
+    pub fn update_global<F, R>(cx: &mut AppContext, update: F) -> R
+    where
+        F: FnOnce(&mut Self, &mut AppContext) -> R,
+    {
+        cx.update_global(|this: &mut GlobalCommandPalettePreview, cx| update(&mut this.0, cx))
+    }
+
+    /// Previews the given query from the command palette, or removes the
+    /// preview when `None` is given because the command palette was closed.
+    pub fn preview(query: Option<&str>, cx: &mut WindowContext) {
+        let Some(handler) = cx
+            .try_global::<GlobalCommandPalettePreview>()
+            .and_then(|preview| preview.0 .0.clone())
+        else {
+            return;
+        };
+
+        (handler)(query, cx)
+    }
+
+    /// Clears the global preview handler.
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+
+    /// Sets the global preview handler.
+    ///
+    /// This will override the previous handler, if it exists.
+    pub fn set(&mut self, handler: Box<dyn Fn(Option<&str>, &mut WindowContext)>) {
+        self.0 = Some(Rc::from(handler));
+    }
+}
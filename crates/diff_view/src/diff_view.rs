@@ -0,0 +1,738 @@
+mod text_diff;
+
+use std::{mem, ops::Range, time::Duration};
+
+use anyhow::Result;
+use collections::HashSet;
+use editor::{
+    display_map::{BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    Editor, EditorEvent,
+};
+use gpui::{
+    actions, impl_actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
+    HighlightStyle, Model, Render, SharedString, Subscription, Task, View, ViewContext,
+    VisualContext, WindowContext,
+};
+use language::{language_settings::SoftWrap, Buffer, Capability, Point};
+use project::{Project, ProjectPath};
+use serde::Deserialize;
+use text_diff::{diff_hunks, DiffHunk};
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent, ItemHandle, TabContentParams},
+    notifications::DetachAndPromptErr,
+    Workspace,
+};
+
+actions!(diff_view, [CompareWithClipboard, CopyHunkToOtherSide]);
+
+/// Compares the active file with its contents at a git revision.
+#[derive(PartialEq, Clone, Debug, Deserialize)]
+pub struct CompareWithRevision {
+    #[serde(default = "head_revision")]
+    pub revision: String,
+}
+
+fn head_revision() -> String {
+    "HEAD".to_string()
+}
+
+impl_actions!(diff_view, [CompareWithRevision]);
+
+/// How long to wait after an edit before comparing the buffers again.
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(DiffView::register).detach();
+}
+
+enum DiffViewRows {}
+enum DiffViewWords {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Old,
+    New,
+}
+
+impl Side {
+    fn rows(self, hunk: &DiffHunk) -> &Range<u32> {
+        match self {
+            Side::Old => &hunk.old_rows,
+            Side::New => &hunk.new_rows,
+        }
+    }
+
+    fn word_ranges(self, hunk: &DiffHunk) -> &[Range<usize>] {
+        match self {
+            Side::Old => &hunk.old_word_ranges,
+            Side::New => &hunk.new_word_ranges,
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Side::Old => Side::New,
+            Side::New => Side::Old,
+        }
+    }
+}
+
+struct DiffSide {
+    editor: View<Editor>,
+    buffer: Model<Buffer>,
+    title: Option<SharedString>,
+    spacers: HashSet<BlockId>,
+}
+
+impl DiffSide {
+    fn new(
+        buffer: Model<Buffer>,
+        title: Option<SharedString>,
+        project: &Model<Project>,
+        cx: &mut WindowContext,
+    ) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), Some(project.clone()), cx);
+            // Soft wrapping would break the alignment of the two sides.
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor
+        });
+        Self {
+            editor,
+            buffer,
+            title,
+            spacers: HashSet::default(),
+        }
+    }
+
+    fn title(&self, cx: &AppContext) -> SharedString {
+        if let Some(title) = &self.title {
+            return title.clone();
+        }
+        buffer_title(&self.buffer, cx)
+    }
+
+    /// Highlights the hunks' rows and words on this side, and pads it with
+    /// spacers wherever the other side has more rows.
+    fn show_hunks(&mut self, hunks: &[DiffHunk], side: Side, cx: &mut WindowContext) {
+        let spacers = mem::take(&mut self.spacers);
+        self.spacers = self.editor.update(cx, |editor, cx| {
+            editor.remove_blocks(spacers, None, cx);
+            editor.clear_row_highlights::<DiffViewRows>();
+
+            let status = cx.theme().status();
+            let (mut line_color, mut word_color) = match side {
+                Side::Old => (status.deleted, status.deleted),
+                Side::New => (status.created, status.created),
+            };
+            line_color.fade_out(0.8);
+            word_color.fade_out(0.5);
+
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let max_point = snapshot.max_point();
+            let mut word_ranges = Vec::new();
+            let mut blocks = Vec::new();
+            for hunk in hunks {
+                let rows = side.rows(hunk);
+                if !rows.is_empty() {
+                    let start = snapshot.anchor_before(Point::new(rows.start, 0));
+                    let end = snapshot.anchor_after(Point::new(rows.end - 1, 0));
+                    editor.highlight_rows::<DiffViewRows>(start..=end, Some(line_color), false, cx);
+
+                    let hunk_start = snapshot.point_to_offset(Point::new(rows.start, 0));
+                    word_ranges.extend(side.word_ranges(hunk).iter().map(|range| {
+                        snapshot.anchor_after(hunk_start + range.start)
+                            ..snapshot.anchor_before(hunk_start + range.end)
+                    }));
+                }
+
+                let (position, disposition) = if rows.end > max_point.row {
+                    (snapshot.anchor_after(max_point), BlockDisposition::Below)
+                } else {
+                    (
+                        snapshot.anchor_before(Point::new(rows.end, 0)),
+                        BlockDisposition::Above,
+                    )
+                };
+                let mut spacer_rows = side.other().rows(hunk).len().saturating_sub(rows.len());
+                while spacer_rows > 0 {
+                    let height = spacer_rows.min(u8::MAX as usize);
+                    blocks.push(BlockProperties {
+                        position,
+                        height: height as u8,
+                        style: BlockStyle::Fixed,
+                        render: Box::new(|_| div().into_any_element()),
+                        disposition,
+                    });
+                    spacer_rows -= height;
+                }
+            }
+
+            editor.highlight_text::<DiffViewWords>(
+                word_ranges,
+                HighlightStyle {
+                    background_color: Some(word_color),
+                    ..Default::default()
+                },
+                cx,
+            );
+            editor.insert_blocks(blocks, None, cx).into_iter().collect()
+        });
+    }
+}
+
+/// Shows two buffers side by side, with the rows that differ between them
+/// highlighted and aligned by spacers, and with their scroll positions kept
+/// in sync.
+pub struct DiffView {
+    old: DiffSide,
+    new: DiffSide,
+    hunks: Vec<DiffHunk>,
+    /// Whether the buffers were edited since the hunks were computed.
+    hunks_outdated: bool,
+    focus_handle: FocusHandle,
+    update_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DiffView {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::compare_with_clipboard);
+        workspace.register_action(Self::compare_with_revision);
+    }
+
+    /// Creates a view comparing `old_buffer` with `new_buffer`. The sides are
+    /// titled after their buffers' files, unless a title is given.
+    pub fn new(
+        old_buffer: Model<Buffer>,
+        old_title: Option<SharedString>,
+        new_buffer: Model<Buffer>,
+        new_title: Option<SharedString>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let old = DiffSide::new(old_buffer, old_title, &project, cx);
+        let new = DiffSide::new(new_buffer, new_title, &project, cx);
+
+        let focus_handle = cx.focus_handle();
+        cx.on_focus_in(&focus_handle, |this, cx| {
+            if this.focus_handle.is_focused(cx) {
+                this.new.editor.focus_handle(cx).focus(cx);
+            }
+        })
+        .detach();
+
+        let mut subscriptions = Vec::new();
+        for side in [Side::Old, Side::New] {
+            let (buffer, editor) = match side {
+                Side::Old => (&old.buffer, &old.editor),
+                Side::New => (&new.buffer, &new.editor),
+            };
+            subscriptions.push(
+                cx.subscribe(buffer, |this, _, event: &language::Event, cx| match event {
+                    language::Event::Edited | language::Event::Reloaded => {
+                        this.update_diff(true, cx)
+                    }
+                    _ => {}
+                }),
+            );
+            subscriptions.push(
+                cx.subscribe(
+                    editor,
+                    move |this, _, event: &EditorEvent, cx| match event {
+                        EditorEvent::ScrollPositionChanged { local: true, .. } => {
+                            this.sync_scroll_position(side, cx)
+                        }
+                        EditorEvent::DirtyChanged
+                        | EditorEvent::Saved
+                        | EditorEvent::TitleChanged => cx.emit(event.clone()),
+                        _ => {}
+                    },
+                ),
+            );
+        }
+
+        let mut this = Self {
+            old,
+            new,
+            hunks: Vec::new(),
+            hunks_outdated: true,
+            focus_handle,
+            update_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.update_diff(false, cx);
+        this
+    }
+
+    fn compare_with_clipboard(
+        workspace: &mut Workspace,
+        _: &CompareWithClipboard,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(buffer) = active_buffer(workspace, cx) else {
+            return;
+        };
+        let Some(clipboard) = cx.read_from_clipboard() else {
+            return;
+        };
+        let clipboard_buffer = text_buffer(clipboard.text().clone(), &buffer, false, cx);
+        let project = workspace.project().clone();
+        let view = cx.new_view(|cx| {
+            DiffView::new(
+                clipboard_buffer,
+                Some("Clipboard".into()),
+                buffer,
+                None,
+                project,
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(view), None, cx);
+    }
+
+    fn compare_with_revision(
+        workspace: &mut Workspace,
+        action: &CompareWithRevision,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(buffer) = active_buffer(workspace, cx) else {
+            return;
+        };
+        let revision = action.revision.clone();
+        let load = workspace.project().read(cx).load_buffer_text_at_revision(
+            &buffer,
+            revision.clone(),
+            cx,
+        );
+        cx.spawn(|workspace, mut cx| async move {
+            // A file that didn't exist at the revision is shown as empty.
+            let text = load.await?.unwrap_or_default();
+            workspace.update(&mut cx, |workspace, cx| {
+                let title = format!("{} @ {revision}", buffer_title(&buffer, cx));
+                Self::open_for_text(text, title.into(), buffer, workspace, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to compare with revision", cx, |error, _| {
+            Some(error.to_string())
+        });
+    }
+
+    /// Opens a view comparing a read-only copy of `old_text` with `new_buffer`.
+    pub fn open_for_text(
+        old_text: String,
+        old_title: SharedString,
+        new_buffer: Model<Buffer>,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Self> {
+        let project = workspace.project().clone();
+        let old_buffer = text_buffer(old_text, &new_buffer, true, cx);
+        let view = cx.new_view(|cx| {
+            DiffView::new(old_buffer, Some(old_title), new_buffer, None, project, cx)
+        });
+        workspace.add_item_to_active_pane(Box::new(view.clone()), None, cx);
+        view
+    }
+
+    /// Opens a view comparing read-only copies of two texts, in the language
+    /// of `like`.
+    pub fn open_for_texts(
+        old_text: String,
+        old_title: SharedString,
+        new_text: String,
+        new_title: SharedString,
+        like: &Model<Buffer>,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Self> {
+        let project = workspace.project().clone();
+        let old_buffer = text_buffer(old_text, like, true, cx);
+        let new_buffer = text_buffer(new_text, like, true, cx);
+        let view = cx.new_view(|cx| {
+            DiffView::new(
+                old_buffer,
+                Some(old_title),
+                new_buffer,
+                Some(new_title),
+                project,
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(view.clone()), None, cx);
+        view
+    }
+
+    /// Opens a view comparing the files at the given paths.
+    pub fn open_for_paths(
+        old_path: ProjectPath,
+        new_path: ProjectPath,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<View<Self>>> {
+        let project = workspace.project().clone();
+        let old_buffer = project.update(cx, |project, cx| project.open_buffer(old_path, cx));
+        let new_buffer = project.update(cx, |project, cx| project.open_buffer(new_path, cx));
+        cx.spawn(|workspace, mut cx| async move {
+            let old_buffer = old_buffer.await?;
+            let new_buffer = new_buffer.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let view = cx
+                    .new_view(|cx| DiffView::new(old_buffer, None, new_buffer, None, project, cx));
+                workspace.add_item_to_active_pane(Box::new(view.clone()), None, cx);
+                view
+            })
+        })
+    }
+
+    fn update_diff(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        self.hunks_outdated = true;
+        self.update_task = cx.spawn(|this, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            }
+            let Ok((old_snapshot, new_snapshot)) = this.update(&mut cx, |this, cx| {
+                (
+                    this.old.buffer.read(cx).snapshot(),
+                    this.new.buffer.read(cx).snapshot(),
+                )
+            }) else {
+                return;
+            };
+            let hunks = cx
+                .background_executor()
+                .spawn({
+                    let old_text = old_snapshot.text();
+                    let new_text = new_snapshot.text();
+                    async move { diff_hunks(&old_text, &new_text) }
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                // Hunks computed for text that has since been edited are
+                // dropped, as another update is pending.
+                if this.old.buffer.read(cx).version() == *old_snapshot.version()
+                    && this.new.buffer.read(cx).version() == *new_snapshot.version()
+                {
+                    this.set_hunks(hunks, cx);
+                }
+            })
+            .ok();
+        });
+    }
+
+    /// Compares the buffers right away if they were edited since the hunks
+    /// were computed, instead of waiting for the pending update.
+    fn flush_diff_update(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.hunks_outdated {
+            return;
+        }
+        let hunks = diff_hunks(
+            &self.old.buffer.read(cx).text(),
+            &self.new.buffer.read(cx).text(),
+        );
+        self.update_task = Task::ready(());
+        self.set_hunks(hunks, cx);
+    }
+
+    fn set_hunks(&mut self, hunks: Vec<DiffHunk>, cx: &mut ViewContext<Self>) {
+        self.old.show_hunks(&hunks, Side::Old, cx);
+        self.new.show_hunks(&hunks, Side::New, cx);
+        self.hunks = hunks;
+        self.hunks_outdated = false;
+        cx.notify();
+    }
+
+    fn side(&self, side: Side) -> &DiffSide {
+        match side {
+            Side::Old => &self.old,
+            Side::New => &self.new,
+        }
+    }
+
+    fn sync_scroll_position(&mut self, source: Side, cx: &mut ViewContext<Self>) {
+        let position = self
+            .side(source)
+            .editor
+            .update(cx, |editor, cx| editor.scroll_position(cx));
+        // Both sides have the same number of display rows, thanks to the
+        // spacers, so their scroll positions match.
+        self.side(source.other()).editor.update(cx, |editor, cx| {
+            if editor.scroll_position(cx) != position {
+                editor.set_scroll_position(position, cx);
+            }
+        });
+    }
+
+    /// Replaces the other side's rows of the hunk containing the cursor with
+    /// the rows of the focused side.
+    fn copy_hunk_to_other_side(&mut self, _: &CopyHunkToOtherSide, cx: &mut ViewContext<Self>) {
+        let source = if self.old.editor.focus_handle(cx).contains_focused(cx) {
+            Side::Old
+        } else {
+            Side::New
+        };
+        let target = source.other();
+        let target_buffer = self.side(target).buffer.clone();
+        if target_buffer.read(cx).read_only() {
+            return;
+        }
+
+        self.flush_diff_update(cx);
+        let row = self.side(source).editor.update(cx, |editor, cx| {
+            editor.selections.newest::<Point>(cx).head().row
+        });
+        let Some(hunk) = self.hunks.iter().find(|hunk| {
+            let rows = source.rows(hunk);
+            rows.contains(&row) || (rows.is_empty() && rows.start == row)
+        }) else {
+            return;
+        };
+
+        let source_buffer = self.side(source).buffer.read(cx);
+        let source_range = row_range_to_points(source.rows(hunk), source_buffer);
+        let text = source_buffer
+            .text_for_range(source_range)
+            .collect::<String>();
+        let target_range = row_range_to_points(target.rows(hunk), target_buffer.read(cx));
+        target_buffer.update(cx, |buffer, cx| {
+            buffer.edit([(target_range, text)], None, cx);
+        });
+    }
+
+    /// The buffers that are backed by files, and so can be saved.
+    fn file_buffers(&self, cx: &AppContext) -> HashSet<Model<Buffer>> {
+        [&self.old.buffer, &self.new.buffer]
+            .into_iter()
+            .filter(|buffer| buffer.read(cx).file().is_some())
+            .cloned()
+            .collect()
+    }
+}
+
+fn active_buffer(workspace: &Workspace, cx: &AppContext) -> Option<Model<Buffer>> {
+    let (_, buffer, _) = workspace
+        .active_item(cx)?
+        .act_as::<Editor>(cx)?
+        .read(cx)
+        .active_excerpt(cx)?;
+    Some(buffer)
+}
+
+/// Creates a buffer that isn't backed by a file, in the language of `like`.
+fn text_buffer(
+    text: String,
+    like: &Model<Buffer>,
+    read_only: bool,
+    cx: &mut AppContext,
+) -> Model<Buffer> {
+    let language = like.read(cx).language().cloned();
+    cx.new_model(|cx| {
+        let mut buffer = Buffer::local(text, cx);
+        buffer.set_language(language, cx);
+        if read_only {
+            buffer.set_capability(Capability::ReadOnly, cx);
+        }
+        buffer
+    })
+}
+
+fn buffer_title(buffer: &Model<Buffer>, cx: &AppContext) -> SharedString {
+    match buffer.read(cx).file() {
+        Some(file) => file.file_name(cx).to_string_lossy().to_string().into(),
+        None => "untitled".into(),
+    }
+}
+
+fn row_range_to_points(rows: &Range<u32>, buffer: &Buffer) -> Range<Point> {
+    let max_point = buffer.max_point();
+    let point = |row: u32| {
+        if row > max_point.row {
+            max_point
+        } else {
+            Point::new(row, 0)
+        }
+    };
+    point(rows.start)..point(rows.end)
+}
+
+impl EventEmitter<EditorEvent> for DiffView {}
+
+impl FocusableView for DiffView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DiffView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        let pane = |side: &DiffSide, cx: &AppContext| {
+            v_flex()
+                .w_1_2()
+                .h_full()
+                .child(
+                    h_flex()
+                        .px_2()
+                        .py_1()
+                        .border_b_1()
+                        .border_color(border_color)
+                        .child(
+                            Label::new(side.title(cx))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .child(div().flex_1().child(side.editor.clone()))
+        };
+
+        h_flex()
+            .key_context("DiffView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::copy_hunk_to_other_side))
+            .size_full()
+            .child(pane(&self.old, cx))
+            .child(pane(&self.new, cx).border_l_1().border_color(border_color))
+    }
+}
+
+impl Item for DiffView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        Label::new(format!("{} ↔ {}", self.old.title(cx), self.new.title(cx)))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("diff view")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.old.editor.for_each_project_item(cx, f);
+        self.new.editor.for_each_project_item(cx, f);
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.file_buffers(cx)
+            .iter()
+            .any(|buffer| buffer.read(cx).is_dirty())
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.file_buffers(cx)
+            .iter()
+            .any(|buffer| buffer.read(cx).has_conflict())
+    }
+
+    fn can_save(&self, cx: &AppContext) -> bool {
+        !self.file_buffers(cx).is_empty()
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffers = self.file_buffers(cx);
+        project.update(cx, |project, cx| project.save_buffers(buffers, cx))
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let buffers = self.file_buffers(cx);
+        let reload = project.update(cx, |project, cx| project.reload_buffers(buffers, true, cx));
+        cx.background_executor().spawn(async move {
+            reload.await?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_copy_hunk_to_other_side(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let old_buffer = cx.new_model(|cx| Buffer::local("one\ntwo\nthree\nfour\nfive\n", cx));
+        let new_buffer = cx.new_model(|cx| Buffer::local("one\nTWO\nthree\nfour\nfive\n", cx));
+        let (view, cx) = cx.add_window_view(|cx| {
+            DiffView::new(
+                old_buffer.clone(),
+                None,
+                new_buffer.clone(),
+                None,
+                project,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        let copy_hunk_at = |row: u32, cx: &mut gpui::VisualTestContext| {
+            view.update(cx, |view, cx| {
+                view.new.editor.update(cx, |editor, cx| {
+                    let point = Point::new(row, 0);
+                    editor.change_selections(None, cx, |selections| {
+                        selections.select_ranges([point..point])
+                    });
+                });
+                view.copy_hunk_to_other_side(&CopyHunkToOtherSide, cx);
+            });
+        };
+        copy_hunk_at(1, cx);
+        assert_eq!(
+            old_buffer.read_with(cx, |buffer, _| buffer.text()),
+            "one\nTWO\nthree\nfour\nfive\n"
+        );
+
+        // A hunk is copied even when the diff hasn't been updated since the edit
+        // that created it.
+        new_buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(4, 0)..Point::new(4, 4), "FIVE")], None, cx)
+        });
+        copy_hunk_at(4, cx);
+        assert_eq!(
+            old_buffer.read_with(cx, |buffer, _| buffer.text()),
+            "one\nTWO\nthree\nfour\nFIVE\n"
+        );
+
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| assert!(view.hunks.is_empty()));
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+}
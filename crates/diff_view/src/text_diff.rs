@@ -0,0 +1,124 @@
+use std::ops::Range;
+
+use similar::{ChangeTag, DiffTag, TextDiff};
+
+/// A run of lines that differ between the two sides of a diff.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// The rows of the old text that were removed or changed.
+    pub old_rows: Range<u32>,
+    /// The rows of the new text that were added or changed.
+    pub new_rows: Range<u32>,
+    /// The changed words of the old rows, as byte ranges relative to the start
+    /// of the hunk's first old row.
+    pub old_word_ranges: Vec<Range<usize>>,
+    /// The changed words of the new rows, as byte ranges relative to the start
+    /// of the hunk's first new row.
+    pub new_word_ranges: Vec<Range<usize>>,
+}
+
+/// Computes the line-level hunks between two texts, along with the words that
+/// changed within the hunks that modify lines rather than only adding or
+/// removing them.
+pub fn diff_hunks(old_text: &str, new_text: &str) -> Vec<DiffHunk> {
+    let diff = TextDiff::from_lines(old_text, new_text);
+    let mut hunks = Vec::<DiffHunk>::new();
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        let old_rows = old_range.start as u32..old_range.end as u32;
+        let new_rows = new_range.start as u32..new_range.end as u32;
+        match hunks.last_mut() {
+            Some(hunk)
+                if hunk.old_rows.end == old_rows.start && hunk.new_rows.end == new_rows.start =>
+            {
+                hunk.old_rows.end = old_rows.end;
+                hunk.new_rows.end = new_rows.end;
+            }
+            _ => hunks.push(DiffHunk {
+                old_rows,
+                new_rows,
+                ..Default::default()
+            }),
+        }
+    }
+
+    let old_lines = old_text.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new_text.split_inclusive('\n').collect::<Vec<_>>();
+    for hunk in &mut hunks {
+        if hunk.old_rows.is_empty() || hunk.new_rows.is_empty() {
+            continue;
+        }
+        let old = old_lines[hunk.old_rows.start as usize..hunk.old_rows.end as usize].concat();
+        let new = new_lines[hunk.new_rows.start as usize..hunk.new_rows.end as usize].concat();
+        let mut old_offset = 0;
+        let mut new_offset = 0;
+        for change in TextDiff::from_words(old.as_str(), new.as_str()).iter_all_changes() {
+            let len = change.value().len();
+            match change.tag() {
+                ChangeTag::Equal => {
+                    old_offset += len;
+                    new_offset += len;
+                }
+                ChangeTag::Delete => {
+                    push_range(&mut hunk.old_word_ranges, old_offset..old_offset + len);
+                    old_offset += len;
+                }
+                ChangeTag::Insert => {
+                    push_range(&mut hunk.new_word_ranges, new_offset..new_offset + len);
+                    new_offset += len;
+                }
+            }
+        }
+    }
+    hunks
+}
+
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    if let Some(last) = ranges.last_mut() {
+        if last.end == range.start {
+            last.end = range.end;
+            return;
+        }
+    }
+    ranges.push(range);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_hunks() {
+        let old_text = "one\ntwo\nthree\nfour\nfive\n";
+        let new_text = "zero\none\ntwo\nthree 3\nfour\n";
+        assert_eq!(
+            diff_hunks(old_text, new_text),
+            [
+                DiffHunk {
+                    old_rows: 0..0,
+                    new_rows: 0..1,
+                    ..Default::default()
+                },
+                DiffHunk {
+                    old_rows: 2..3,
+                    new_rows: 3..4,
+                    old_word_ranges: vec![],
+                    new_word_ranges: vec![5..7],
+                },
+                DiffHunk {
+                    old_rows: 4..5,
+                    new_rows: 5..5,
+                    ..Default::default()
+                },
+            ]
+        );
+
+        let hunks = diff_hunks("let a = b + c;\n", "let a = b - d;\n");
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_word_ranges, [10..11, 12..14]);
+        assert_eq!(hunks[0].new_word_ranges, [10..11, 12..14]);
+    }
+}
@@ -0,0 +1,392 @@
+use std::{mem, ops::Range, time::Duration};
+
+use git::{
+    conflict::{parse_rope_conflicts, Conflict},
+    repository::GitFileStatus,
+};
+use gpui::{Action, AppContext, ElementId, Hsla, SharedString, Task, ViewContext, WeakView};
+use multi_buffer::{Anchor, MultiBufferSnapshot, ToOffset, ToPoint};
+use project::Item as _;
+use text::Point;
+use ui::{prelude::*, ButtonStyle, Tooltip};
+
+use crate::{
+    scroll::Autoscroll, AcceptConflictBoth, AcceptConflictOurs, AcceptConflictTheirs, BlockContext,
+    BlockDisposition, BlockId, BlockProperties, BlockStyle, CompareConflict, Editor, EditorMode,
+    GoToConflict, GoToPrevConflict,
+};
+
+/// How long to wait after an edit before looking for conflicts again.
+const CONFLICTS_UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+enum ConflictMarkerRows {}
+enum ConflictOursRows {}
+enum ConflictBaseRows {}
+enum ConflictTheirsRows {}
+
+/// The conflict regions of the editor's buffer, and the blocks showing their
+/// actions.
+#[derive(Default)]
+pub(crate) struct Conflicts {
+    regions: Vec<ConflictRegion>,
+    blocks: Vec<BlockId>,
+    update_task: Option<Task<()>>,
+}
+
+/// A region of the buffer left with conflict markers by a merge or rebase.
+/// The sides' ranges cover whole lines, without their markers.
+#[derive(Clone, Debug)]
+pub struct ConflictRegion {
+    pub range: Range<Anchor>,
+    pub ours: Range<Anchor>,
+    pub base: Option<Range<Anchor>>,
+    pub theirs: Range<Anchor>,
+    pub ours_label: SharedString,
+    pub theirs_label: SharedString,
+}
+
+impl ConflictRegion {
+    fn new(conflict: Conflict, snapshot: &MultiBufferSnapshot) -> Self {
+        let anchor_range = |range: Range<usize>| {
+            snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end)
+        };
+        Self {
+            range: anchor_range(conflict.range),
+            ours: anchor_range(conflict.ours),
+            base: conflict.base.map(anchor_range),
+            theirs: anchor_range(conflict.theirs),
+            ours_label: conflict.ours_label.into(),
+            theirs_label: conflict.theirs_label.into(),
+        }
+    }
+}
+
+/// Which sides of a conflict to keep when resolving it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    /// Our side followed by theirs.
+    Both,
+}
+
+impl Editor {
+    /// Looks for conflict markers in the editor's buffer, waiting for a
+    /// debounce first if `debounce` is true. Only buffers whose files git
+    /// reports as conflicted are searched.
+    pub(crate) fn refresh_conflicts(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        let buffer = match self.buffer.read(cx).as_singleton() {
+            Some(buffer) if self.mode == EditorMode::Full => buffer,
+            _ => {
+                self.conflicts.update_task = None;
+                return;
+            }
+        };
+        let is_conflicted = !buffer.read(cx).large_file_mode()
+            && buffer
+                .read(cx)
+                .project_path(cx)
+                .zip(self.project.as_ref())
+                .and_then(|(path, project)| project.read(cx).entry_for_path(&path, cx))
+                .map_or(false, |entry| {
+                    entry.git_status == Some(GitFileStatus::Conflict)
+                });
+        if !is_conflicted {
+            self.conflicts.update_task = None;
+            self.set_conflicts(Vec::new(), cx);
+            return;
+        }
+
+        let text = buffer.read(cx).as_rope().clone();
+        self.conflicts.update_task = Some(cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(CONFLICTS_UPDATE_DEBOUNCE)
+                    .await;
+            }
+            let conflicts = cx
+                .background_executor()
+                .spawn(async move { parse_rope_conflicts(&text) })
+                .await;
+            // Edits replace this task, so the conflicts are still up to date.
+            editor
+                .update(&mut cx, |editor, cx| editor.set_conflicts(conflicts, cx))
+                .ok();
+        }));
+    }
+
+    fn set_conflicts(&mut self, conflicts: Vec<Conflict>, cx: &mut ViewContext<Self>) {
+        if conflicts.is_empty() && self.conflicts.blocks.is_empty() {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        self.conflicts.regions = conflicts
+            .into_iter()
+            .map(|conflict| ConflictRegion::new(conflict, &snapshot))
+            .collect();
+
+        self.clear_row_highlights::<ConflictMarkerRows>();
+        self.clear_row_highlights::<ConflictOursRows>();
+        self.clear_row_highlights::<ConflictBaseRows>();
+        self.clear_row_highlights::<ConflictTheirsRows>();
+        let status = cx.theme().status();
+        let faded = |mut color: Hsla| {
+            color.fade_out(0.8);
+            color
+        };
+        let (marker_color, ours_color, base_color, theirs_color) = (
+            faded(status.conflict),
+            faded(status.info),
+            faded(status.hidden),
+            faded(status.created),
+        );
+
+        let mut blocks = Vec::with_capacity(self.conflicts.regions.len());
+        let editor = cx.view().downgrade();
+        for region in self.conflicts.regions.clone() {
+            let start_row = region.range.start.to_point(&snapshot).row;
+            let end = region.range.end.to_point(&snapshot);
+            let ours_rows = row_range(&region.ours, &snapshot);
+            let theirs_rows = row_range(&region.theirs, &snapshot);
+            let base_rows = region.base.as_ref().map(|base| row_range(base, &snapshot));
+
+            // The region ends at the start of the line after the `>>>>>>>`
+            // marker, unless the marker is on the last line.
+            let end_marker_row = if end.column > 0 {
+                end.row
+            } else {
+                end.row.saturating_sub(1)
+            };
+            let mut marker_rows = vec![start_row, theirs_rows.start - 1, end_marker_row];
+            if let Some(base_rows) = &base_rows {
+                marker_rows.push(base_rows.start - 1);
+            }
+            for row in marker_rows {
+                self.highlight_conflict_rows::<ConflictMarkerRows>(
+                    row..row + 1,
+                    marker_color,
+                    &snapshot,
+                    cx,
+                );
+            }
+            self.highlight_conflict_rows::<ConflictOursRows>(ours_rows, ours_color, &snapshot, cx);
+            self.highlight_conflict_rows::<ConflictTheirsRows>(
+                theirs_rows,
+                theirs_color,
+                &snapshot,
+                cx,
+            );
+            if let Some(base_rows) = base_rows {
+                self.highlight_conflict_rows::<ConflictBaseRows>(
+                    base_rows, base_color, &snapshot, cx,
+                );
+            }
+
+            let position = region.range.start;
+            let editor = editor.clone();
+            blocks.push(BlockProperties {
+                position,
+                height: 1,
+                style: BlockStyle::Flex,
+                render: Box::new(move |cx| render_conflict_actions(&editor, position, cx)),
+                disposition: BlockDisposition::Above,
+            });
+        }
+
+        let blocks_to_remove = mem::take(&mut self.conflicts.blocks);
+        self.remove_blocks(blocks_to_remove.into_iter().collect(), None, cx);
+        self.conflicts.blocks = self.insert_blocks(blocks, None, cx);
+        cx.notify();
+    }
+
+    fn highlight_conflict_rows<T: 'static>(
+        &mut self,
+        rows: Range<u32>,
+        color: Hsla,
+        snapshot: &MultiBufferSnapshot,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if rows.is_empty() {
+            return;
+        }
+        let start = snapshot.anchor_before(Point::new(rows.start, 0));
+        let end = snapshot.anchor_after(Point::new(rows.end - 1, 0));
+        self.highlight_rows::<T>(start..=end, Some(color), false, cx);
+    }
+
+    /// The conflict regions of the buffer, in order.
+    pub fn conflicts(&self) -> &[ConflictRegion] {
+        &self.conflicts.regions
+    }
+
+    /// Returns the conflict region containing the newest cursor.
+    pub fn conflict_at_cursor(&self, cx: &AppContext) -> Option<&ConflictRegion> {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let head = self.selections.newest_anchor().head().to_offset(&snapshot);
+        self.conflicts.regions.iter().find(|region| {
+            region.range.start.to_offset(&snapshot) <= head
+                && head < region.range.end.to_offset(&snapshot)
+        })
+    }
+
+    /// Replaces the conflict region starting at `position`, markers included,
+    /// with the given sides of it.
+    pub fn resolve_conflict(
+        &mut self,
+        position: Anchor,
+        resolution: ConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(ix) = self
+            .conflicts
+            .regions
+            .iter()
+            .position(|region| region.range.start == position)
+        else {
+            return;
+        };
+        let region = self.conflicts.regions.remove(ix);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let side_text =
+            |range: &Range<Anchor>| snapshot.text_for_range(range.clone()).collect::<String>();
+        let text = match resolution {
+            ConflictResolution::Ours => side_text(&region.ours),
+            ConflictResolution::Theirs => side_text(&region.theirs),
+            ConflictResolution::Both => side_text(&region.ours) + &side_text(&region.theirs),
+        };
+        self.transact(cx, |editor, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(region.range.clone(), text)], None, cx)
+            });
+        });
+    }
+
+    fn resolve_conflict_at_cursor(
+        &mut self,
+        resolution: ConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(position) = self.conflict_at_cursor(cx).map(|region| region.range.start) {
+            self.resolve_conflict(position, resolution, cx);
+        }
+    }
+
+    pub fn accept_conflict_ours(&mut self, _: &AcceptConflictOurs, cx: &mut ViewContext<Self>) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Ours, cx);
+    }
+
+    pub fn accept_conflict_theirs(&mut self, _: &AcceptConflictTheirs, cx: &mut ViewContext<Self>) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Theirs, cx);
+    }
+
+    pub fn accept_conflict_both(&mut self, _: &AcceptConflictBoth, cx: &mut ViewContext<Self>) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Both, cx);
+    }
+
+    pub fn go_to_conflict(&mut self, _: &GoToConflict, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let head = self.selections.newest_anchor().head().to_offset(&snapshot);
+        let region = self
+            .conflicts
+            .regions
+            .iter()
+            .find(|region| region.range.start.to_offset(&snapshot) > head)
+            .or_else(|| self.conflicts.regions.first());
+        if let Some(position) = region.map(|region| region.range.start) {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchor_ranges([position..position])
+            });
+        }
+    }
+
+    pub fn go_to_prev_conflict(&mut self, _: &GoToPrevConflict, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let head = self.selections.newest_anchor().head().to_offset(&snapshot);
+        let region = self
+            .conflicts
+            .regions
+            .iter()
+            .rev()
+            .find(|region| region.range.start.to_offset(&snapshot) < head)
+            .or_else(|| self.conflicts.regions.last());
+        if let Some(position) = region.map(|region| region.range.start) {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchor_ranges([position..position])
+            });
+        }
+    }
+}
+
+/// Returns the rows covered by a side of a conflict, which ends at the start
+/// of the line after it.
+fn row_range(range: &Range<Anchor>, snapshot: &MultiBufferSnapshot) -> Range<u32> {
+    let start = range.start.to_point(snapshot);
+    let end = range.end.to_point(snapshot);
+    let end_row = if end.column > 0 { end.row + 1 } else { end.row };
+    start.row..end_row
+}
+
+fn render_conflict_actions(
+    editor: &WeakView<Editor>,
+    position: Anchor,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let block_id = cx.block_id;
+    let resolve_button = |id: &'static str, label: &'static str, resolution| {
+        let editor = editor.clone();
+        Button::new(ElementId::NamedInteger(id.into(), block_id), label)
+            .style(ButtonStyle::Subtle)
+            .label_size(LabelSize::Small)
+            .on_click(move |_, cx| {
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.resolve_conflict(position, resolution, cx)
+                    })
+                    .ok();
+            })
+    };
+    let compare_editor = editor.clone();
+
+    h_flex()
+        .size_full()
+        .pl(cx.gutter_dimensions.full_width())
+        .gap_1()
+        .child(resolve_button(
+            "accept-conflict-ours",
+            "Accept Ours",
+            ConflictResolution::Ours,
+        ))
+        .child(resolve_button(
+            "accept-conflict-theirs",
+            "Accept Theirs",
+            ConflictResolution::Theirs,
+        ))
+        .child(resolve_button(
+            "accept-conflict-both",
+            "Accept Both",
+            ConflictResolution::Both,
+        ))
+        .child(
+            Button::new(
+                ElementId::NamedInteger("compare-conflict".into(), block_id),
+                "Compare",
+            )
+            .style(ButtonStyle::Subtle)
+            .label_size(LabelSize::Small)
+            .tooltip(|cx| Tooltip::for_action("Compare Ours with Theirs", &CompareConflict, cx))
+            .on_click(move |_, cx| {
+                // The comparison is opened for the conflict at the cursor.
+                compare_editor
+                    .update(cx, |editor, cx| {
+                        editor.change_selections(None, cx, |s| {
+                            s.select_anchor_ranges([position..position])
+                        });
+                        cx.focus_self();
+                    })
+                    .ok();
+                cx.dispatch_action(CompareConflict.boxed_clone());
+            }),
+        )
+        .into_any_element()
+}
@@ -15,6 +15,7 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod conflicts;
 mod debounced_delay;
 pub mod display_map;
 mod editor_settings;
@@ -27,8 +28,10 @@ mod hunk_diff;
 mod indent_guides;
 mod inlay_hint_cache;
 mod inline_completion_provider;
+mod inline_diagnostics;
 pub mod items;
 mod linked_editing_ranges;
+mod minimap;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
@@ -50,15 +53,17 @@ use blink_manager::BlinkManager;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
+pub use conflicts::{ConflictRegion, ConflictResolution};
 use convert_case::{Case, Casing};
 use debounced_delay::DebouncedDelay;
 use display_map::*;
 pub use display_map::{DisplayPoint, FoldPlaceholder};
+use editor_settings::DiagnosticSeverityFilter;
 pub use editor_settings::{CurrentLineHighlight, EditorSettings};
-use element::LineWithInvisibles;
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
 };
+use element::{LineWithInvisibles, StickyHeaderCache};
 use futures::FutureExt;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use git::blame::GitBlame;
@@ -93,9 +98,12 @@ use language::{BufferRow, Runnable, RunnableRange};
 use linked_editing_ranges::refresh_linked_ranges;
 use task::{ResolvedTask, TaskTemplate, TaskVariables};
 
+use conflicts::Conflicts;
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
+use inline_diagnostics::{inline_diagnostics, InlineDiagnostics};
 pub use lsp::CompletionContext;
 use lsp::{CompletionTriggerKind, DiagnosticSeverity, LanguageServerId};
+use minimap::MinimapCache;
 use mouse_context_menu::MouseContextMenu;
 use movement::TextLayoutDetails;
 pub use multi_buffer::{
@@ -146,7 +154,8 @@ use util::{defer, maybe, post_inc, RangeExt, ResultExt, TryFutureExt};
 use workspace::item::{ItemHandle, PreviewTabsSettings};
 use workspace::notifications::{DetachAndPromptErr, NotificationId};
 use workspace::{
-    searchable::SearchEvent, ItemNavHistory, SplitDirection, ViewId, Workspace, WorkspaceId,
+    bookmarks::BookmarkStore, searchable::SearchEvent, ItemNavHistory, SplitDirection, ViewId,
+    Workspace, WorkspaceId,
 };
 use workspace::{OpenInTerminal, OpenTerminal, TabBarSettings, Toast};
 
@@ -235,6 +244,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Diagnostic(usize),
 }
 
 impl InlayId {
@@ -242,6 +252,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Diagnostic(id) => *id,
         }
     }
 }
@@ -249,7 +260,9 @@ impl InlayId {
 enum DiffRowHighlight {}
 enum DocumentHighlightRead {}
 enum DocumentHighlightWrite {}
+enum InlineDiagnosticHighlight {}
 enum InputComposition {}
+enum ReplacementPreview {}
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum Direction {
@@ -493,6 +506,7 @@ pub struct Editor {
     background_highlights: TreeMap<TypeId, BackgroundHighlight>,
     gutter_highlights: TreeMap<TypeId, GutterHighlight>,
     scrollbar_marker_state: ScrollbarMarkerState,
+    minimap_cache: MinimapCache,
     active_indent_guides_state: ActiveIndentGuidesState,
     nav_history: Option<ItemNavHistory>,
     context_menu: RwLock<Option<ContextMenu>>,
@@ -528,9 +542,14 @@ pub struct Editor {
     inlay_hint_cache: InlayHintCache,
     expanded_hunks: ExpandedHunks,
     next_inlay_id: usize,
+    replacement_previews: Vec<InlayId>,
+    inline_diagnostics: InlineDiagnostics,
+    conflicts: Conflicts,
+    bookmark_store: Option<Model<BookmarkStore>>,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
     gutter_dimensions: GutterDimensions,
+    sticky_header_cache: StickyHeaderCache,
     pub vim_replace_map: HashMap<Range<usize>, String>,
     style: Option<EditorStyle>,
     next_editor_action_id: EditorActionId,
@@ -1715,6 +1734,8 @@ impl Editor {
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
                     if let project::Event::RefreshInlayHints = event {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    } else if let project::Event::WorktreeUpdatedGitRepositories = event {
+                        editor.refresh_conflicts(false, cx);
                     } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                         if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                             let focus_handle = editor.focus_handle(cx);
@@ -1794,6 +1815,7 @@ impl Editor {
             background_highlights: Default::default(),
             gutter_highlights: TreeMap::default(),
             scrollbar_marker_state: ScrollbarMarkerState::default(),
+            minimap_cache: MinimapCache::default(),
             active_indent_guides_state: ActiveIndentGuidesState::default(),
             nav_history: None,
             context_menu: RwLock::new(None),
@@ -1803,6 +1825,10 @@ impl Editor {
             next_completion_id: 0,
             completion_documentation_pre_resolve_debounce: DebouncedDelay::new(),
             next_inlay_id: 0,
+            replacement_previews: Vec::new(),
+            inline_diagnostics: InlineDiagnostics::default(),
+            conflicts: Conflicts::default(),
+            bookmark_store: None,
             available_code_actions: Default::default(),
             code_actions_task: Default::default(),
             document_highlights_task: Default::default(),
@@ -1834,6 +1860,7 @@ impl Editor {
             last_bounds: None,
             expect_bounds_change: None,
             gutter_dimensions: GutterDimensions::default(),
+            sticky_header_cache: StickyHeaderCache::default(),
             style: None,
             show_cursor_names: false,
             hovered_cursors: Default::default(),
@@ -1876,6 +1903,7 @@ impl Editor {
             breadcrumb_header: None,
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
+        this.refresh_conflicts(false, cx);
         this._subscriptions.extend(project_subscriptions);
 
         this.end_selection(cx);
@@ -4996,6 +5024,26 @@ impl Editor {
             }))
     }
 
+    fn render_bookmark_indicator(
+        &self,
+        row: DisplayRow,
+        note: Option<SharedString>,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        IconButton::new(
+            ("bookmark_indicator", row.0 as usize),
+            ui::IconName::Bookmark,
+        )
+        .shape(ui::IconButtonShape::Square)
+        .icon_size(IconSize::XSmall)
+        .icon_color(Color::Accent)
+        .tooltip(move |cx| Tooltip::text(note.clone().unwrap_or("Bookmark".into()), cx))
+        .on_click(cx.listener(move |editor, _e, cx| {
+            let point = DisplayPoint::new(row, 0).to_point(&editor.snapshot(cx));
+            editor.toggle_bookmarks_at_rows([MultiBufferRow(point.row)], cx);
+        }))
+    }
+
     pub fn context_menu_visible(&self) -> bool {
         self.context_menu
             .read()
@@ -9599,6 +9647,115 @@ impl Editor {
         }
     }
 
+    /// Shows the most severe diagnostic of each line after the end of the
+    /// line, waiting for the configured debounce first if `debounce` is true.
+    fn refresh_inline_diagnostics(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        let settings = EditorSettings::get_global(cx).inline_diagnostics;
+        if !settings.enabled || self.mode != EditorMode::Full {
+            self.inline_diagnostics.update_task = None;
+            self.clear_inline_diagnostics(cx);
+            return;
+        }
+
+        let delay = Duration::from_millis(settings.debounce_ms);
+        if !debounce || delay.is_zero() {
+            self.inline_diagnostics.update_task = None;
+            self.update_inline_diagnostics(settings.min_severity, cx);
+            return;
+        }
+        self.inline_diagnostics.update_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor().timer(delay).await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.update_inline_diagnostics(settings.min_severity, cx)
+                })
+                .ok();
+        }));
+    }
+
+    fn update_inline_diagnostics(
+        &mut self,
+        min_severity: DiagnosticSeverityFilter,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let diagnostics =
+            inline_diagnostics(&snapshot, min_severity, self.inline_diagnostics.max_columns);
+
+        let to_remove = mem::take(&mut self.inline_diagnostics.inlays);
+        let mut to_insert = Vec::with_capacity(diagnostics.len());
+        let mut highlights_by_severity = BTreeMap::<DiagnosticSeverity, Vec<InlayHighlight>>::new();
+        for diagnostic in diagnostics {
+            // Inlays after the end of the line are biased to the right, so that
+            // they aren't counted when measuring the line.
+            let position = snapshot.buffer_snapshot.anchor_after(diagnostic.position);
+            let inlay =
+                Inlay::diagnostic(post_inc(&mut self.next_inlay_id), position, diagnostic.text);
+            highlights_by_severity
+                .entry(diagnostic.severity)
+                .or_default()
+                .push(InlayHighlight {
+                    inlay: inlay.id,
+                    inlay_position: position,
+                    range: 0..inlay.text.len(),
+                });
+            self.inline_diagnostics.inlays.push(inlay.id);
+            to_insert.push(inlay);
+        }
+
+        self.clear_highlights::<InlineDiagnosticHighlight>(cx);
+        for (severity, highlights) in highlights_by_severity {
+            let color = diagnostic_style(severity, true, cx.theme().status());
+            self.highlight_inlays::<InlineDiagnosticHighlight>(
+                highlights,
+                HighlightStyle {
+                    color: Some(color),
+                    ..Default::default()
+                },
+                cx,
+            );
+        }
+        self.splice_inlays(to_remove, to_insert, cx);
+    }
+
+    fn clear_inline_diagnostics(&mut self, cx: &mut ViewContext<Self>) {
+        self.clear_highlights::<InlineDiagnosticHighlight>(cx);
+        if !self.inline_diagnostics.inlays.is_empty() {
+            let to_remove = mem::take(&mut self.inline_diagnostics.inlays);
+            self.splice_inlays(to_remove, Vec::new(), cx);
+        }
+    }
+
+    /// Sets the number of columns that fit in the editor, to which the inline
+    /// diagnostics are truncated. Returns whether they were updated.
+    pub(crate) fn set_inline_diagnostics_max_columns(
+        &mut self,
+        max_columns: u32,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        if self.inline_diagnostics.max_columns == Some(max_columns) {
+            return false;
+        }
+        self.inline_diagnostics.max_columns = Some(max_columns);
+        if EditorSettings::get_global(cx).inline_diagnostics.enabled {
+            self.refresh_inline_diagnostics(false, cx);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the text shown after the end of each line by
+    /// [`Editor::refresh_inline_diagnostics`].
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn inline_diagnostics(&self, cx: &AppContext) -> Vec<String> {
+        let inlays = self.display_map.read(cx).current_inlays();
+        inlays
+            .filter(|inlay| self.inline_diagnostics.inlays.contains(&inlay.id))
+            .map(|inlay| inlay.text.to_string())
+            .collect()
+    }
+
     fn activate_diagnostics(&mut self, group_id: usize, cx: &mut ViewContext<Self>) -> bool {
         self.dismiss_diagnostics(cx);
         let snapshot = self.snapshot(cx);
@@ -10067,6 +10224,10 @@ impl Editor {
     }
 
     pub fn soft_wrap_mode(&self, cx: &AppContext) -> SoftWrap {
+        // Wrapping a large file would require measuring all of its lines.
+        if self.is_large_file(cx) {
+            return SoftWrap::None;
+        }
         let settings = self.buffer.read(cx).settings_at(0, cx);
         let mode = self
             .soft_wrap_mode_override
@@ -10081,6 +10242,14 @@ impl Editor {
         }
     }
 
+    /// Whether the editor shows a buffer in large file mode.
+    pub fn is_large_file(&self, cx: &AppContext) -> bool {
+        self.buffer
+            .read(cx)
+            .as_singleton()
+            .map_or(false, |buffer| buffer.read(cx).large_file_mode())
+    }
+
     pub fn set_soft_wrap_mode(
         &mut self,
         mode: language_settings::SoftWrap,
@@ -10161,6 +10330,85 @@ impl Editor {
         EditorSettings::override_global(editor_settings, cx);
     }
 
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, cx: &mut ViewContext<Self>) {
+        let rows = self
+            .selections
+            .all::<Point>(cx)
+            .into_iter()
+            .map(|selection| MultiBufferRow(selection.head().row))
+            .collect::<Vec<_>>();
+        self.toggle_bookmarks_at_rows(rows, cx);
+    }
+
+    fn toggle_bookmarks_at_rows(
+        &mut self,
+        rows: impl IntoIterator<Item = MultiBufferRow>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut buffer_rows = Vec::new();
+        for row in rows {
+            if let Some((buffer, range)) = snapshot.buffer_line_for_row(row) {
+                let buffer_row = (buffer.remote_id(), range.start.row);
+                if !buffer_rows.contains(&buffer_row) {
+                    buffer_rows.push(buffer_row);
+                }
+            }
+        }
+        let multibuffer = self.buffer.clone();
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            for (buffer_id, row) in buffer_rows {
+                if let Some(buffer) = multibuffer.read(cx).buffer(buffer_id) {
+                    bookmark_store.toggle_bookmark(&buffer, row, cx);
+                }
+            }
+        });
+    }
+
+    /// Returns the display rows of the bookmarks in the editor's buffers, along
+    /// with their notes.
+    fn bookmark_rows(
+        &self,
+        snapshot: &EditorSnapshot,
+        cx: &AppContext,
+    ) -> Vec<(DisplayRow, Option<SharedString>)> {
+        let Some(bookmark_store) = self.bookmark_store.as_ref() else {
+            return Vec::new();
+        };
+        let bookmark_store = bookmark_store.read(cx);
+        let multibuffer = &snapshot.buffer_snapshot;
+        let mut rows = Vec::new();
+        for (excerpt_id, buffer, range) in multibuffer.excerpts() {
+            for bookmark in bookmark_store.buffer_bookmarks(buffer.remote_id()) {
+                let Some(anchor) = bookmark.anchor else {
+                    continue;
+                };
+                if anchor.cmp(&range.context.start, buffer).is_lt()
+                    || anchor.cmp(&range.context.end, buffer).is_gt()
+                {
+                    continue;
+                }
+                let Some(anchor) = multibuffer.anchor_in_excerpt(excerpt_id, anchor) else {
+                    continue;
+                };
+                let point = anchor.to_point(multibuffer);
+                if snapshot.is_line_folded(MultiBufferRow(point.row)) {
+                    continue;
+                }
+                rows.push((
+                    point.to_display_point(snapshot).row(),
+                    bookmark.note.clone(),
+                ));
+            }
+        }
+        rows.sort_by_key(|(row, _)| *row);
+        rows.dedup_by_key(|(row, _)| *row);
+        rows
+    }
+
     pub fn set_show_gutter(&mut self, show_gutter: bool, cx: &mut ViewContext<Self>) {
         self.show_gutter = show_gutter;
         cx.notify();
@@ -10271,7 +10519,7 @@ impl Editor {
                 return;
             };
 
-            if buffer.read(cx).file().is_none() {
+            if buffer.read(cx).file().is_none() || buffer.read(cx).large_file_mode() {
                 return;
             }
 
@@ -10925,6 +11173,58 @@ impl Editor {
         }
     }
 
+    /// Previews the given replacements without editing the buffer, by
+    /// striking through each range and showing its new text after it.
+    pub fn preview_replacements(
+        &mut self,
+        replacements: Vec<(Range<Anchor>, String)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let to_remove = mem::take(&mut self.replacement_previews);
+        let mut ranges = Vec::with_capacity(replacements.len());
+        let mut to_insert = Vec::new();
+        for (range, text) in replacements {
+            if !text.is_empty() {
+                let inlay = Inlay::suggestion(post_inc(&mut self.next_inlay_id), range.end, text);
+                self.replacement_previews.push(inlay.id);
+                to_insert.push(inlay);
+            }
+            ranges.push(range);
+        }
+        self.highlight_text::<ReplacementPreview>(
+            ranges,
+            HighlightStyle {
+                strikethrough: Some(StrikethroughStyle {
+                    thickness: 1.0.into(),
+                    ..Default::default()
+                }),
+                fade_out: Some(0.5),
+                ..Default::default()
+            },
+            cx,
+        );
+        self.splice_inlays(to_remove, to_insert, cx);
+    }
+
+    /// Removes the preview shown by [`Editor::preview_replacements`].
+    pub fn clear_replacement_previews(&mut self, cx: &mut ViewContext<Self>) {
+        self.clear_highlights::<ReplacementPreview>(cx);
+        if !self.replacement_previews.is_empty() {
+            let to_remove = mem::take(&mut self.replacement_previews);
+            self.splice_inlays(to_remove, Vec::new(), cx);
+        }
+    }
+
+    /// Returns the text shown after each range previewed with
+    /// [`Editor::preview_replacements`].
+    pub fn replacement_previews(&self, cx: &AppContext) -> Vec<String> {
+        let inlays = self.display_map.read(cx).current_inlays();
+        inlays
+            .filter(|inlay| self.replacement_previews.contains(&inlay.id))
+            .map(|inlay| inlay.text.to_string())
+            .collect()
+    }
+
     pub fn show_local_cursors(&self, cx: &WindowContext) -> bool {
         (self.read_only(cx) || self.blink_manager.read(cx).visible())
             && self.focus_handle.is_focused(cx)
@@ -10952,6 +11252,8 @@ impl Editor {
                 self.scrollbar_marker_state.dirty = true;
                 self.active_indent_guides_state.dirty = true;
                 self.refresh_active_diagnostics(cx);
+                self.refresh_inline_diagnostics(true, cx);
+                self.refresh_conflicts(true, cx);
                 self.refresh_code_actions(cx);
                 if self.has_active_inline_completion(cx) {
                     self.update_visible_inline_completion(cx);
@@ -11042,6 +11344,7 @@ impl Editor {
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
+                self.refresh_inline_diagnostics(true, cx);
                 self.scrollbar_marker_state.dirty = true;
                 cx.notify();
             }
@@ -11067,6 +11370,7 @@ impl Editor {
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
+        self.refresh_inline_diagnostics(false, cx);
 
         if self.mode == EditorMode::Full {
             let inline_blame_enabled = ProjectSettings::get_global(cx).git.inline_blame_enabled();
@@ -11825,12 +12129,14 @@ impl EditorSnapshot {
 
         let show_runnables = self.show_runnables.unwrap_or(gutter_settings.runnables);
 
+        let show_bookmarks = gutter_settings.bookmarks && self.bookmark_store.is_some();
+
         let git_blame_entries_width = self
             .render_git_blame_gutter
             .then_some(em_width * GIT_BLAME_GUTTER_WIDTH_CHARS);
 
         let mut left_padding = git_blame_entries_width.unwrap_or(Pixels::ZERO);
-        left_padding += if show_code_actions || show_runnables {
+        left_padding += if show_code_actions || show_runnables || show_bookmarks {
             em_width * 3.0
         } else if show_git_gutter && show_line_numbers {
             em_width * 2.0
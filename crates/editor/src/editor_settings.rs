@@ -1,4 +1,5 @@
 use gpui::AppContext;
+use lsp::DiagnosticSeverity;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
@@ -14,7 +15,10 @@ pub struct EditorSettings {
     pub use_on_type_format: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
+    pub minimap: Minimap,
     pub gutter: Gutter,
+    pub sticky_scroll: StickyScroll,
+    pub inline_diagnostics: InlineDiagnostics,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
     pub scroll_sensitivity: f32,
@@ -86,9 +90,72 @@ pub struct Gutter {
     pub line_numbers: bool,
     pub code_actions: bool,
     pub runnables: bool,
+    pub bookmarks: bool,
     pub folds: bool,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct Minimap {
+    pub show: ShowMinimap,
+}
+
+/// When to show the minimap in the editor.
+///
+/// Default: off
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowMinimap {
+    /// Always show the minimap.
+    Always,
+    /// Show the minimap while the mouse is over it.
+    OnHover,
+    /// Never show the minimap.
+    Off,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScroll {
+    pub enabled: bool,
+    pub max_depth: usize,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InlineDiagnostics {
+    pub enabled: bool,
+    pub min_severity: DiagnosticSeverityFilter,
+    pub debounce_ms: u64,
+}
+
+/// The least severe diagnostics to show.
+///
+/// Default: hint
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverityFilter {
+    /// Only show errors.
+    Error,
+    /// Show errors and warnings.
+    Warning,
+    /// Show errors, warnings and information.
+    Info,
+    /// Show all diagnostics.
+    Hint,
+}
+
+impl DiagnosticSeverityFilter {
+    /// Whether diagnostics of the given severity pass the filter.
+    pub fn includes(&self, severity: DiagnosticSeverity) -> bool {
+        let min_severity = match self {
+            Self::Error => DiagnosticSeverity::ERROR,
+            Self::Warning => DiagnosticSeverity::WARNING,
+            Self::Info => DiagnosticSeverity::INFORMATION,
+            Self::Hint => DiagnosticSeverity::HINT,
+        };
+        // More severe diagnostics have lower values.
+        severity <= min_severity
+    }
+}
+
 /// When to show the scrollbar in the editor.
 ///
 /// Default: auto
@@ -173,8 +240,14 @@ pub struct EditorSettingsContent {
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
     pub scrollbar: Option<ScrollbarContent>,
+    /// Minimap related settings
+    pub minimap: Option<MinimapContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
+    /// Inline diagnostics related settings
+    pub inline_diagnostics: Option<InlineDiagnosticsContent>,
     /// Whether the editor will scroll beyond the last line.
     ///
     /// Default: one_page
@@ -266,6 +339,15 @@ pub struct ScrollbarContent {
     pub cursors: Option<bool>,
 }
 
+/// Minimap related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct MinimapContent {
+    /// When to show the minimap in the editor.
+    ///
+    /// Default: off
+    pub show: Option<ShowMinimap>,
+}
+
 /// Gutter related settings
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct GutterContent {
@@ -281,12 +363,49 @@ pub struct GutterContent {
     ///
     /// Default: true
     pub runnables: Option<bool>,
+    /// Whether to show bookmarks in the gutter.
+    ///
+    /// Default: true
+    pub bookmarks: Option<bool>,
     /// Whether to show fold buttons in the gutter.
     ///
     /// Default: true
     pub folds: Option<bool>,
 }
 
+/// Sticky scroll related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to pin the headers of the scopes enclosing the first visible
+    /// line, such as functions and impl blocks, to the top of the editor.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The maximum number of nested scopes to pin.
+    ///
+    /// Default: 5
+    pub max_depth: Option<usize>,
+}
+
+/// Inline diagnostics related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InlineDiagnosticsContent {
+    /// Whether to show the most severe diagnostic of each line after the
+    /// end of the line.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The least severe diagnostics to show.
+    ///
+    /// Default: hint
+    pub min_severity: Option<DiagnosticSeverityFilter>,
+    /// How long to wait after the buffer or its diagnostics change before
+    /// updating the inline diagnostics, in milliseconds.
+    ///
+    /// Default: 150
+    pub debounce_ms: Option<u64>,
+}
+
 impl Settings for EditorSettings {
     const KEY: Option<&'static str> = None;
 
@@ -1,5 +1,6 @@
 use super::*;
 use crate::{
+    editor_settings::InlineDiagnosticsContent,
     scroll::scroll_amount::ScrollAmount,
     test::{
         assert_text_with_selections, build_editor, editor_hunks,
@@ -8307,6 +8308,177 @@ async fn go_to_prev_overlapping_diagnostic(
     "});
 }
 
+#[gpui::test]
+async fn test_inline_diagnostics(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.inline_diagnostics = Some(InlineDiagnosticsContent {
+                    enabled: Some(true),
+                    min_severity: Some(DiagnosticSeverityFilter::Warning),
+                    debounce_ms: Some(150),
+                });
+            });
+        })
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let project = cx.update_editor(|editor, _| editor.project.clone().unwrap());
+    cx.set_state(indoc! {"
+        ˇlet a = b;
+        c
+    "});
+
+    _ = cx.update(|cx| {
+        _ = project.update(cx, |project, cx| {
+            project
+                .update_diagnostics(
+                    LanguageServerId(0),
+                    lsp::PublishDiagnosticsParams {
+                        uri: lsp::Url::from_file_path("/root/file").unwrap(),
+                        version: None,
+                        diagnostics: vec![
+                            lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 4),
+                                    lsp::Position::new(0, 5),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::WARNING),
+                                message: "unused variable".to_string(),
+                                ..Default::default()
+                            },
+                            lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 8),
+                                    lsp::Position::new(0, 9),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                message: "mismatched types\nexpected `u32`".to_string(),
+                                ..Default::default()
+                            },
+                            lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(1, 0),
+                                    lsp::Position::new(1, 1),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::HINT),
+                                message: "consider removing this".to_string(),
+                                ..Default::default()
+                            },
+                        ],
+                    },
+                    &[],
+                    cx,
+                )
+                .unwrap()
+        });
+    });
+
+    // The diagnostics are only shown once the debounce has elapsed.
+    executor.run_until_parked();
+    cx.update_editor(|editor, cx| assert!(editor.inline_diagnostics(cx).is_empty()));
+    executor.advance_clock(Duration::from_millis(150));
+    executor.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.inline_diagnostics(cx), ["    mismatched types"]);
+    });
+
+    // Messages are truncated to fit in the editor, or hidden if too little of
+    // them would be visible.
+    cx.update_editor(|editor, cx| {
+        editor.inline_diagnostics.max_columns = Some(29);
+        editor.refresh_inline_diagnostics(false, cx);
+        assert_eq!(editor.inline_diagnostics(cx), ["    mismatched typ…"]);
+
+        editor.inline_diagnostics.max_columns = Some(20);
+        editor.refresh_inline_diagnostics(false, cx);
+        assert!(editor.inline_diagnostics(cx).is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_resolving_conflicts(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let fs = cx.update_editor(|editor, cx| editor.project.as_ref().unwrap().read(cx).fs().clone());
+    fs.as_fake().insert_tree("/root/.git", json!({})).await;
+    cx.set_state(indoc! {"
+        fn main() {
+        <<<<<<< HEAD
+            ˇours();
+        =======
+            theirs();
+        >>>>>>> feature
+        }
+        <<<<<<< HEAD
+        a
+        ||||||| base
+        b
+        =======
+        c
+        >>>>>>> feature
+    "});
+    executor.advance_clock(Duration::from_millis(50));
+    executor.run_until_parked();
+    // Markers are ignored in files that git doesn't report as conflicted.
+    cx.update_editor(|editor, _| assert!(editor.conflicts().is_empty()));
+
+    fs.as_fake().set_status_for_repo_via_git_operation(
+        Path::new("/root/.git"),
+        &[(Path::new("file"), git::repository::GitFileStatus::Conflict)],
+    );
+    executor.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.conflicts().len(), 2);
+        let region = editor.conflict_at_cursor(cx).unwrap();
+        assert_eq!(region.ours_label, "HEAD");
+        assert_eq!(region.theirs_label, "feature");
+
+        editor.accept_conflict_theirs(&AcceptConflictTheirs, cx);
+        assert_eq!(
+            editor.text(cx),
+            indoc! {"
+                fn main() {
+                    theirs();
+                }
+                <<<<<<< HEAD
+                a
+                ||||||| base
+                b
+                =======
+                c
+                >>>>>>> feature
+            "}
+        );
+    });
+
+    executor.advance_clock(Duration::from_millis(50));
+    executor.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.conflicts().len(), 1);
+        assert!(editor.conflict_at_cursor(cx).is_none());
+
+        editor.go_to_conflict(&GoToConflict, cx);
+        editor.accept_conflict_both(&AcceptConflictBoth, cx);
+        assert_eq!(
+            editor.text(cx),
+            indoc! {"
+                fn main() {
+                    theirs();
+                }
+                a
+                c
+            "}
+        );
+    });
+
+    executor.advance_clock(Duration::from_millis(50));
+    executor.run_until_parked();
+    cx.update_editor(|editor, _| assert!(editor.conflicts().is_empty()));
+}
+
 #[gpui::test]
 async fn go_to_hunk(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -5,7 +5,8 @@ use crate::{
         BlockContext, BlockStyle, DisplaySnapshot, HighlightedChunk, ToDisplayPoint, TransformBlock,
     },
     editor_settings::{
-        CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ShowScrollbar,
+        CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ShowMinimap,
+        ShowScrollbar,
     },
     git::{
         blame::{CommitDetails, GitBlame},
@@ -16,8 +17,9 @@ use crate::{
     },
     hunk_status,
     items::BufferSearchHighlights,
+    minimap::MINIMAP_COLUMNS,
     mouse_context_menu::{self, MouseContextMenu},
-    scroll::scroll_amount::ScrollAmount,
+    scroll::{scroll_amount::ScrollAmount, Autoscroll},
     CodeActionsMenu, CursorShape, DisplayPoint, DisplayRow, DocumentHighlightRead,
     DocumentHighlightWrite, Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle,
     ExpandExcerpts, GutterDimensions, HalfPageDown, HalfPageUp, HoveredCursor, HunkToExpand,
@@ -25,7 +27,7 @@ use crate::{
     Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
 };
 use client::ParticipantIndex;
-use collections::{BTreeMap, HashMap};
+use collections::{BTreeMap, HashMap, HashSet};
 use git::{blame::BlameEntry, diff::DiffHunkStatus, Oid};
 use gpui::{
     anchored, deferred, div, fill, outline, point, px, quad, relative, size, svg,
@@ -34,12 +36,16 @@ use gpui::{
     FontId, GlobalElementId, Hitbox, Hsla, InteractiveElement, IntoElement, Length,
     ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
     ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size,
-    StatefulInteractiveElement, Style, Styled, TextRun, TextStyle, TextStyleRefinement, View,
-    ViewContext, WeakView, WindowContext,
+    StatefulInteractiveElement, Style, Styled, StyledText, TextRun, TextStyle, TextStyleRefinement,
+    View, ViewContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
-use language::language_settings::{
-    IndentGuideBackgroundColoring, IndentGuideColoring, IndentGuideSettings, ShowWhitespaceSetting,
+use language::{
+    language_settings::{
+        IndentGuideBackgroundColoring, IndentGuideColoring, IndentGuideSettings,
+        ShowWhitespaceSetting,
+    },
+    OutlineItem,
 };
 use lsp::DiagnosticSeverity;
 use multi_buffer::{Anchor, MultiBufferPoint, MultiBufferRow};
@@ -59,7 +65,7 @@ use std::{
     sync::Arc,
 };
 use sum_tree::Bias;
-use theme::{ActiveTheme, PlayerColor};
+use theme::{ActiveTheme, PlayerColor, SyntaxTheme};
 use ui::prelude::*;
 use ui::{h_flex, ButtonLike, ButtonStyle, ContextMenu, Tooltip};
 use util::ResultExt;
@@ -288,6 +294,11 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::go_to_conflict);
+        register_action(view, cx, Editor::go_to_prev_conflict);
+        register_action(view, cx, Editor::accept_conflict_ours);
+        register_action(view, cx, Editor::accept_conflict_theirs);
+        register_action(view, cx, Editor::accept_conflict_both);
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition(a, cx).detach_and_log_err(cx);
         });
@@ -333,6 +344,7 @@ impl EditorElement {
         register_action(view, cx, Editor::copy_permalink_to_line);
         register_action(view, cx, Editor::open_permalink_to_line);
         register_action(view, cx, Editor::toggle_git_blame);
+        register_action(view, cx, Editor::toggle_bookmark);
         register_action(view, cx, Editor::toggle_git_blame_inline);
         register_action(view, cx, Editor::toggle_hunk_diff);
         register_action(view, cx, Editor::expand_all_hunk_diffs);
@@ -1133,6 +1145,106 @@ impl EditorElement {
         })
     }
 
+    fn minimap_width(&self, cx: &WindowContext) -> Pixels {
+        if EditorSettings::get_global(cx).minimap.show == ShowMinimap::Off {
+            Pixels::ZERO
+        } else {
+            MinimapLayout::WIDTH
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        scroll_position: gpui::Point<f32>,
+        max_scroll_top: f32,
+        visible_buffer_rows: Range<MultiBufferRow>,
+        cx: &mut WindowContext,
+    ) -> Option<MinimapLayout> {
+        if snapshot.mode != EditorMode::Full
+            || EditorSettings::get_global(cx).minimap.show == ShowMinimap::Off
+        {
+            return None;
+        }
+
+        let scrollbar_left = self.scrollbar_left(&bounds);
+        let minimap_bounds = Bounds::from_corners(
+            point(scrollbar_left - MinimapLayout::WIDTH, bounds.top()),
+            point(scrollbar_left, bounds.bottom()),
+        );
+        let line_height = MinimapLayout::LINE_HEIGHT;
+        let total_rows = snapshot.buffer_snapshot.max_point().row + 1;
+        let minimap_rows = minimap_bounds.size.height / line_height;
+        let visible_rows = (visible_buffer_rows.end.0 - visible_buffer_rows.start.0).max(1);
+        let slider_height = (line_height * visible_rows as f32).min(minimap_bounds.size.height);
+
+        // When the whole buffer fits, the minimap's rows line up with the
+        // editor's. Otherwise the minimap scrolls along with the editor, so
+        // that the slider reaches its bottom when the editor does.
+        let (first_row, rows_per_pixel) = if total_rows as f32 <= minimap_rows {
+            (0., px(1.) / line_height)
+        } else {
+            let scroll_fraction = if max_scroll_top > 0. {
+                (scroll_position.y / max_scroll_top).clamp(0., 1.)
+            } else {
+                0.
+            };
+            let track_height = (minimap_bounds.size.height - slider_height).max(px(1.));
+            (
+                scroll_fraction * (total_rows as f32 - minimap_rows),
+                max_scroll_top / (track_height / px(1.)),
+            )
+        };
+        let slider_bounds = Bounds {
+            origin: point(
+                minimap_bounds.left(),
+                minimap_bounds.top() + px(1.) * (scroll_position.y / rows_per_pixel),
+            ),
+            size: size(minimap_bounds.size.width, slider_height),
+        };
+
+        let first_visible_row = first_row.floor() as u32;
+        let rows = MultiBufferRow(first_visible_row)
+            ..MultiBufferRow(first_visible_row + minimap_rows.ceil() as u32 + 1);
+        let lines_origin = minimap_bounds.origin
+            + point(
+                MinimapLayout::PADDING,
+                -line_height * (first_row - first_visible_row as f32),
+            );
+        let syntax = self.style.syntax.clone();
+        let default_color = self.style.text.color;
+        let lines = self.editor.update(cx, |editor, _| {
+            editor
+                .minimap_cache
+                .lines(rows, &snapshot.buffer_snapshot, &syntax, default_color)
+                .enumerate()
+                .flat_map(|(ix, line)| {
+                    line.runs.iter().map(move |(columns, color)| {
+                        let origin = lines_origin
+                            + point(
+                                MinimapLayout::COLUMN_WIDTH * columns.start as f32,
+                                line_height * ix as f32,
+                            );
+                        let size = size(
+                            MinimapLayout::COLUMN_WIDTH * columns.len() as f32,
+                            line_height,
+                        );
+                        fill(Bounds { origin, size }, *color)
+                    })
+                })
+                .collect()
+        });
+
+        Some(MinimapLayout {
+            hitbox: cx.insert_hitbox(minimap_bounds, true),
+            lines,
+            slider_bounds,
+            rows_per_pixel,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn prepaint_gutter_fold_toggles(
         &self,
@@ -1364,6 +1476,106 @@ impl EditorElement {
         Some(element)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_sticky_headers(
+        &self,
+        snapshot: &EditorSnapshot,
+        start_row: DisplayRow,
+        hitbox: &Hitbox,
+        gutter_dimensions: &GutterDimensions,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        em_advance: Pixels,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        let settings = EditorSettings::get_global(cx);
+        if !settings.sticky_scroll.enabled || snapshot.mode != EditorMode::Full {
+            return Vec::new();
+        }
+        let show_line_numbers = snapshot
+            .show_line_numbers
+            .unwrap_or(settings.gutter.line_numbers);
+
+        let headers = self.editor.update(cx, |editor, _| {
+            sticky_header_items(
+                snapshot,
+                start_row,
+                settings.sticky_scroll.max_depth,
+                Some(&self.style.syntax),
+                &mut editor.sticky_header_cache,
+            )
+        });
+        let header_count = headers.len();
+        headers
+            .into_iter()
+            .enumerate()
+            .map(|(ix, header)| {
+                let target = header.range.start;
+                let line_number = snapshot
+                    .buffer_snapshot
+                    .point_to_buffer_offset(target)
+                    .map(|(buffer, offset)| buffer.offset_to_point(offset).row + 1)
+                    .filter(|_| show_line_numbers);
+                let text_x = gutter_dimensions.margin - scroll_pixel_position.x
+                    + em_advance * target.to_display_point(snapshot).column() as f32;
+                let editor = self.editor.clone();
+
+                let mut element = h_flex()
+                    .id(("sticky-header", ix))
+                    .occlude()
+                    .w(hitbox.size.width)
+                    .h(line_height)
+                    .font_family(self.style.text.font().family)
+                    .bg(cx.theme().colors().editor_background)
+                    .when(ix + 1 == header_count, |this| {
+                        this.border_b_1()
+                            .border_color(cx.theme().colors().border_variant)
+                    })
+                    .cursor_pointer()
+                    .child(
+                        h_flex()
+                            .flex_none()
+                            .w(gutter_dimensions.width)
+                            .h_full()
+                            .justify_end()
+                            .pr(gutter_dimensions.right_padding)
+                            .text_color(cx.theme().colors().editor_line_number)
+                            .when_some(line_number, |this, line_number| {
+                                this.child(line_number.to_string())
+                            }),
+                    )
+                    .child(
+                        div().relative().flex_1().h_full().overflow_hidden().child(
+                            div().absolute().left(text_x).child(
+                                StyledText::new(header.text)
+                                    .with_highlights(&self.style.text, header.highlight_ranges),
+                            ),
+                        ),
+                    )
+                    .on_mouse_down(MouseButton::Left, move |_, cx| {
+                        cx.stop_propagation();
+                        editor.update(cx, |editor, cx| {
+                            editor.change_selections(
+                                Some(Autoscroll::top_relative(ix)),
+                                cx,
+                                |selections| selections.select_ranges([target..target]),
+                            );
+                            cx.focus_self();
+                        });
+                    })
+                    .into_any();
+
+                let origin = hitbox.origin + point(Pixels::ZERO, line_height * ix as f32);
+                let available_space = size(
+                    AvailableSpace::Definite(hitbox.size.width),
+                    AvailableSpace::Definite(line_height),
+                );
+                element.prepaint_as_root(origin, available_space, cx);
+                element
+            })
+            .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_blame_entries(
         &self,
@@ -1605,6 +1817,54 @@ impl EditorElement {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_bookmark_indicators(
+        &self,
+        line_height: Pixels,
+        rows: Range<DisplayRow>,
+        code_actions_indicator_row: Option<DisplayRow>,
+        show_runnables: bool,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        snapshot: &EditorSnapshot,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            // Run and code actions indicators take precedence over bookmarks.
+            let mut occupied_rows = HashSet::default();
+            occupied_rows.extend(code_actions_indicator_row);
+            if show_runnables {
+                occupied_rows.extend(editor.tasks.values().map(|tasks| {
+                    tasks
+                        .offset
+                        .0
+                        .to_point(&snapshot.buffer_snapshot)
+                        .to_display_point(snapshot)
+                        .row()
+                }));
+            }
+
+            editor
+                .bookmark_rows(snapshot, cx)
+                .into_iter()
+                .filter(|(row, _)| rows.contains(row) && !occupied_rows.contains(row))
+                .map(|(display_row, note)| {
+                    let button = editor.render_bookmark_indicator(display_row, note, cx);
+                    prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        cx,
+                    )
+                })
+                .collect_vec()
+        })
+    }
+
     fn layout_code_actions_indicator(
         &self,
         line_height: Pixels,
@@ -1875,6 +2135,40 @@ impl EditorElement {
                     font_size,
                 })
                 .collect()
+        } else if (rows.start.0..rows.end.0)
+            .any(|row| snapshot.line_len(DisplayRow(row)) as usize > MAX_LINE_LEN)
+        {
+            // Read only the start of each line, rather than all the chunks of
+            // extremely long lines, of which only the start is laid out.
+            let chunks = (rows.start.0..rows.end.0).flat_map(|row| {
+                let row = DisplayRow(row);
+                let mut line_len = 0;
+                snapshot
+                    .highlighted_chunks(row..row.next_row(), true, &self.style)
+                    .map_while(move |mut chunk| {
+                        if line_len > MAX_LINE_LEN {
+                            return None;
+                        }
+                        chunk.text = chunk.text.trim_end_matches('\n');
+                        line_len += chunk.text.len();
+                        Some(chunk)
+                    })
+                    .chain([HighlightedChunk {
+                        text: "\n",
+                        style: None,
+                        is_tab: false,
+                        renderer: None,
+                    }])
+            });
+            LineWithInvisibles::from_chunks(
+                chunks,
+                &self.style.text,
+                MAX_LINE_LEN,
+                rows.len(),
+                line_number_layouts,
+                snapshot.mode,
+                cx,
+            )
         } else {
             let chunks = snapshot.highlighted_chunks(rows.clone(), true, &self.style);
             LineWithInvisibles::from_chunks(
@@ -3007,6 +3301,10 @@ impl EditorElement {
                 }
             });
 
+            for bookmark_indicator in layout.bookmark_indicators.iter_mut() {
+                bookmark_indicator.paint(cx);
+            }
+
             for test_indicators in layout.test_indicators.iter_mut() {
                 test_indicators.paint(cx);
             }
@@ -3349,6 +3647,118 @@ impl EditorElement {
         }
     }
 
+    fn paint_minimap(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(minimap) = layout.minimap.as_mut() else {
+            return;
+        };
+
+        let show = EditorSettings::get_global(cx).minimap.show;
+        let is_dragging = self.editor.read(cx).scroll_manager.is_dragging_minimap();
+        let visible = show == ShowMinimap::Always || is_dragging || minimap.hitbox.is_hovered(cx);
+        if visible {
+            cx.paint_layer(minimap.hitbox.bounds, |cx| {
+                cx.paint_quad(quad(
+                    minimap.hitbox.bounds,
+                    Corners::default(),
+                    cx.theme().colors().editor_background,
+                    Edges {
+                        top: Pixels::ZERO,
+                        right: Pixels::ZERO,
+                        bottom: Pixels::ZERO,
+                        left: ScrollbarLayout::BORDER_WIDTH,
+                    },
+                    cx.theme().colors().scrollbar_track_border,
+                ));
+                for line in minimap.lines.drain(..) {
+                    cx.paint_quad(line);
+                }
+                cx.paint_quad(fill(
+                    minimap.slider_bounds,
+                    cx.theme().colors().scrollbar_thumb_background,
+                ));
+            });
+        }
+
+        cx.set_cursor_style(CursorStyle::Arrow, &minimap.hitbox);
+
+        let rows_per_pixel = minimap.rows_per_pixel;
+        let slider_bounds = minimap.slider_bounds;
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            let hitbox = minimap.hitbox.clone();
+            let mut mouse_position = cx.mouse_position();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    if event.pressed_button == Some(MouseButton::Left)
+                        && editor.scroll_manager.is_dragging_minimap()
+                    {
+                        let mut position = editor.scroll_position(cx);
+                        position.y +=
+                            (event.position.y - mouse_position.y) / px(1.) * rows_per_pixel;
+                        if position.y < 0.0 {
+                            position.y = 0.0;
+                        }
+                        editor.set_scroll_position(position, cx);
+                        cx.stop_propagation();
+                    } else {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        // Show or hide the minimap as the mouse enters or leaves it.
+                        if show == ShowMinimap::OnHover && hitbox.is_hovered(cx) != visible {
+                            cx.notify();
+                        }
+                    }
+                    mouse_position = event.position;
+                })
+            }
+        });
+
+        if self.editor.read(cx).scroll_manager.is_dragging_minimap() {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                let hitbox = minimap.hitbox.clone();
+                move |event: &MouseDownEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture || !hitbox.is_hovered(cx) {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(true, cx);
+
+                        // Clicking outside of the slider centers it on the mouse.
+                        let y = event.position.y;
+                        if y < slider_bounds.top() || slider_bounds.bottom() < y {
+                            let slider_top = y - hitbox.top() - slider_bounds.size.height / 2.;
+                            let mut position = editor.scroll_position(cx);
+                            position.y = (slider_top / px(1.) * rows_per_pixel).max(0.);
+                            editor.set_scroll_position(position, cx);
+                        }
+
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn collect_fast_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -3601,6 +4011,12 @@ impl EditorElement {
         }
     }
 
+    fn paint_sticky_headers(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        for sticky_header in &mut layout.sticky_headers {
+            sticky_header.paint(cx);
+        }
+    }
+
     fn paint_mouse_context_menu(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         if let Some(mouse_context_menu) = layout.mouse_context_menu.as_mut() {
             mouse_context_menu.paint(cx);
@@ -4586,7 +5002,7 @@ impl Element for EditorElement {
                     let text_width = bounds.size.width - gutter_dimensions.width;
 
                     let right_margin = if snapshot.mode == EditorMode::Full {
-                        EditorElement::SCROLLBAR_WIDTH
+                        EditorElement::SCROLLBAR_WIDTH + self.minimap_width(cx)
                     } else {
                         px(0.)
                     };
@@ -4608,7 +5024,12 @@ impl Element for EditorElement {
                             }
                         };
 
-                        if editor.set_wrap_width(wrap_width, cx) {
+                        let wrap_width_changed = editor.set_wrap_width(wrap_width, cx);
+                        let inline_diagnostics_changed = editor.set_inline_diagnostics_max_columns(
+                            (editor_width / em_advance) as u32,
+                            cx,
+                        );
+                        if wrap_width_changed || inline_diagnostics_changed {
                             editor.snapshot(cx)
                         } else {
                             snapshot
@@ -4900,6 +5321,19 @@ impl Element for EditorElement {
                         );
                     });
 
+                    let sticky_headers = cx.with_element_namespace("sticky_headers", |cx| {
+                        self.layout_sticky_headers(
+                            &snapshot,
+                            start_row,
+                            &hitbox,
+                            &gutter_dimensions,
+                            scroll_pixel_position,
+                            line_height,
+                            em_advance,
+                            cx,
+                        )
+                    });
+
                     let cursors = self.collect_cursors(&snapshot, cx);
                     let visible_row_range = start_row..end_row;
                     let non_visible_cursors = cursors
@@ -4930,6 +5364,15 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let minimap = self.layout_minimap(
+                        &snapshot,
+                        bounds,
+                        scroll_position,
+                        max_scroll_top,
+                        start_buffer_row..end_buffer_row,
+                        cx,
+                    );
+
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
                     let mut _context_menu_visible = false;
@@ -4993,6 +5436,26 @@ impl Element for EditorElement {
                         vec![]
                     };
 
+                    let bookmark_indicators = if gutter_settings.bookmarks {
+                        let code_actions_indicator_row = code_actions_indicator
+                            .as_ref()
+                            .zip(newest_selection_head)
+                            .map(|(_, head)| head.row());
+                        self.layout_bookmark_indicators(
+                            line_height,
+                            start_row..end_row,
+                            code_actions_indicator_row,
+                            gutter_settings.runnables,
+                            scroll_pixel_position,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            &snapshot,
+                            cx,
+                        )
+                    } else {
+                        vec![]
+                    };
+
                     if !cx.has_active_drag() {
                         self.layout_hover_popovers(
                             &snapshot,
@@ -5075,6 +5538,7 @@ impl Element for EditorElement {
                         gutter_dimensions,
                         content_origin,
                         scrollbar_layout,
+                        minimap,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -5086,11 +5550,13 @@ impl Element for EditorElement {
                         blamed_display_rows,
                         inline_blame,
                         blocks,
+                        sticky_headers,
                         cursors,
                         visible_cursors,
                         selections,
                         mouse_context_menu,
                         test_indicators,
+                        bookmark_indicators,
                         code_actions_indicator,
                         gutter_fold_toggles,
                         crease_trailers,
@@ -5179,6 +5645,13 @@ impl Element for EditorElement {
                         self.paint_gutter_indicators(layout, cx);
                     }
 
+                    if !layout.sticky_headers.is_empty() {
+                        cx.with_element_namespace("sticky_headers", |cx| {
+                            self.paint_sticky_headers(layout, cx);
+                        });
+                    }
+
+                    self.paint_minimap(layout, cx);
                     self.paint_scrollbar(layout, cx);
                     self.paint_mouse_context_menu(layout, cx);
                 });
@@ -5203,6 +5676,7 @@ pub struct EditorLayout {
     gutter_dimensions: GutterDimensions,
     content_origin: gpui::Point<Pixels>,
     scrollbar_layout: Option<ScrollbarLayout>,
+    minimap: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
@@ -5215,6 +5689,7 @@ pub struct EditorLayout {
     blamed_display_rows: Option<Vec<AnyElement>>,
     inline_blame: Option<AnyElement>,
     blocks: Vec<BlockLayout>,
+    sticky_headers: Vec<AnyElement>,
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     highlighted_gutter_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     redacted_ranges: Vec<Range<DisplayPoint>>,
@@ -5223,6 +5698,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
+    bookmark_indicators: Vec<AnyElement>,
     gutter_fold_toggles: Vec<Option<AnyElement>>,
     crease_trailers: Vec<Option<CreaseTrailerLayout>>,
     mouse_context_menu: Option<AnyElement>,
@@ -5348,6 +5824,21 @@ impl ScrollbarLayout {
     }
 }
 
+struct MinimapLayout {
+    hitbox: Hitbox,
+    lines: Vec<PaintQuad>,
+    slider_bounds: Bounds<Pixels>,
+    /// How many rows the editor scrolls when the slider moves by a pixel.
+    rows_per_pixel: f32,
+}
+
+impl MinimapLayout {
+    const LINE_HEIGHT: Pixels = px(2.0);
+    const COLUMN_WIDTH: Pixels = px(1.0);
+    const PADDING: Pixels = px(4.0);
+    const WIDTH: Pixels = px(MINIMAP_COLUMNS as f32 * Self::COLUMN_WIDTH.0 + 2. * Self::PADDING.0);
+}
+
 struct CreaseTrailerLayout {
     element: AnyElement,
     bounds: Bounds<Pixels>,
@@ -5441,6 +5932,96 @@ fn layout_line(
         .unwrap()
 }
 
+/// The symbols containing the rows that were pinned during the last layout,
+/// which are reused while the buffer doesn't change.
+#[derive(Default)]
+pub(crate) struct StickyHeaderCache {
+    buffer_version: (usize, usize),
+    theme: Option<Arc<SyntaxTheme>>,
+    symbols: HashMap<usize, Option<Vec<OutlineItem<Anchor>>>>,
+}
+
+impl StickyHeaderCache {
+    fn symbols_containing(
+        &mut self,
+        snapshot: &EditorSnapshot,
+        offset: usize,
+        theme: Option<&Arc<SyntaxTheme>>,
+    ) -> Option<Vec<OutlineItem<Anchor>>> {
+        let buffer_version = (
+            snapshot.buffer_snapshot.edit_count(),
+            snapshot.buffer_snapshot.non_text_state_update_count(),
+        );
+        let theme_changed = match (&self.theme, theme) {
+            (Some(cached), Some(theme)) => !Arc::ptr_eq(cached, theme),
+            (None, None) => false,
+            _ => true,
+        };
+        if self.buffer_version != buffer_version || theme_changed {
+            self.buffer_version = buffer_version;
+            self.theme = theme.cloned();
+            self.symbols.clear();
+        }
+        self.symbols
+            .entry(offset)
+            .or_insert_with(|| {
+                snapshot
+                    .buffer_snapshot
+                    .symbols_containing(offset, theme.map(AsRef::as_ref))
+                    .map(|(_, symbols)| symbols)
+            })
+            .clone()
+    }
+}
+
+/// Returns the outline items to pin to the top of the editor when it's
+/// scrolled to the given row, from the outermost to the innermost.
+///
+/// Each pinned header covers a row, so the n-th header is for a scope that
+/// encloses the n-th visible row and starts above it.
+fn sticky_header_items(
+    snapshot: &EditorSnapshot,
+    start_row: DisplayRow,
+    max_depth: usize,
+    theme: Option<&Arc<SyntaxTheme>>,
+    cache: &mut StickyHeaderCache,
+) -> Vec<OutlineItem<Anchor>> {
+    let max_row = snapshot.max_point().row();
+    let mut offsets = Vec::new();
+    let mut headers = Vec::<OutlineItem<Anchor>>::new();
+    while headers.len() < max_depth {
+        let row = start_row + DisplayRow(headers.len() as u32);
+        if row > max_row {
+            break;
+        }
+
+        let offset = DisplayPoint::new(row, 0).to_offset(snapshot, Bias::Left);
+        offsets.push(offset);
+        let Some(symbols) = cache.symbols_containing(snapshot, offset, theme) else {
+            break;
+        };
+        let mut enclosing = symbols
+            .into_iter()
+            .filter(|symbol| {
+                symbol.range.start.to_display_point(snapshot).row() < row
+                    && symbol.range.end.to_display_point(snapshot).row() >= row
+            })
+            .collect::<Vec<_>>();
+        if enclosing.len() <= headers.len()
+            || enclosing
+                .iter()
+                .zip(&headers)
+                .any(|(symbol, header)| symbol.range != header.range)
+        {
+            break;
+        }
+        headers.push(enclosing.swap_remove(headers.len()));
+    }
+    // Only keep the symbols of the rows that are currently at the top.
+    cache.symbols.retain(|offset, _| offsets.contains(offset));
+    headers
+}
+
 #[derive(Debug)]
 pub struct IndentGuideLayout {
     origin: gpui::Point<Pixels>,
@@ -5729,7 +6310,8 @@ mod tests {
         Editor, MultiBuffer,
     };
     use gpui::{TestAppContext, VisualTestContext};
-    use language::language_settings;
+    use indoc::indoc;
+    use language::{language_settings, Buffer, Language, LanguageConfig};
     use log::info;
     use std::num::NonZeroU32;
     use ui::Context;
@@ -5963,6 +6545,69 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_sticky_header_items(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::language()),
+            )
+            .with_outline_query(
+                r#"
+                (impl_item "impl" @context type: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+        let text = indoc! {"
+            impl Foo {
+                fn a() {
+                    let x = 1;
+                    let y = 2;
+                    let z = 3;
+                }
+                fn b() {}
+            }
+        "};
+        let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+        let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+        let window = cx.add_window(|cx| Editor::new(EditorMode::Full, buffer, None, true, cx));
+        cx.run_until_parked();
+        let snapshot = window.update(cx, |editor, cx| editor.snapshot(cx)).unwrap();
+
+        let mut cache = StickyHeaderCache::default();
+        let mut headers = |snapshot: &EditorSnapshot, start_row: u32, max_depth: usize| {
+            sticky_header_items(snapshot, DisplayRow(start_row), max_depth, None, &mut cache)
+                .into_iter()
+                .map(|item| item.text)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(headers(&snapshot, 0, 5), Vec::<String>::new());
+        assert_eq!(headers(&snapshot, 1, 5), vec!["impl Foo", "fn a"]);
+        assert_eq!(headers(&snapshot, 2, 5), vec!["impl Foo", "fn a"]);
+        assert_eq!(headers(&snapshot, 2, 1), vec!["impl Foo"]);
+        assert_eq!(headers(&snapshot, 4, 5), vec!["impl Foo", "fn a"]);
+        // A scope's header isn't pinned over a row after the scope's end.
+        assert_eq!(headers(&snapshot, 5, 5), vec!["impl Foo"]);
+        assert_eq!(headers(&snapshot, 7, 5), vec!["impl Foo"]);
+        assert_eq!(headers(&snapshot, 8, 5), Vec::<String>::new());
+
+        // The cached symbols aren't reused once the buffer changes.
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "struct S;\n")], None, cx)
+        });
+        cx.run_until_parked();
+        let snapshot = window.update(cx, |editor, cx| editor.snapshot(cx)).unwrap();
+        assert_eq!(headers(&snapshot, 1, 5), Vec::<String>::new());
+        assert_eq!(headers(&snapshot, 2, 5), vec!["impl Foo", "fn a"]);
+    }
+
     #[gpui::test]
     fn test_layout_with_placeholder_text_and_blocks(cx: &mut TestAppContext) {
         init_test(cx, |_| {});
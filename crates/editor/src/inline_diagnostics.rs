@@ -0,0 +1,102 @@
+use std::collections::btree_map;
+
+use collections::BTreeMap;
+use gpui::Task;
+use language::DiagnosticEntry;
+use lsp::DiagnosticSeverity;
+use multi_buffer::MultiBufferRow;
+use text::Point;
+
+use crate::{
+    display_map::{DisplaySnapshot, ToDisplayPoint},
+    editor_settings::DiagnosticSeverityFilter,
+    InlayId,
+};
+
+/// The number of columns between the end of a line and its diagnostic.
+const INLINE_DIAGNOSTIC_PADDING: u32 = 4;
+
+/// Messages that would have to be truncated to fewer columns than this are
+/// hidden instead.
+const MIN_INLINE_DIAGNOSTIC_COLUMNS: u32 = 12;
+
+/// The diagnostics shown after the end of each line, and the pending update
+/// of them.
+#[derive(Default)]
+pub(crate) struct InlineDiagnostics {
+    pub inlays: Vec<InlayId>,
+    pub max_columns: Option<u32>,
+    pub update_task: Option<Task<()>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct InlineDiagnostic {
+    /// The end of the line the diagnostic is shown after.
+    pub position: Point,
+    pub severity: DiagnosticSeverity,
+    /// The text of the inlay, including the padding before the message.
+    pub text: String,
+}
+
+/// Returns the most severe primary diagnostic starting on each line, with its
+/// message truncated so that the line fits in `max_columns` display columns.
+pub(crate) fn inline_diagnostics(
+    snapshot: &DisplaySnapshot,
+    min_severity: DiagnosticSeverityFilter,
+    max_columns: Option<u32>,
+) -> Vec<InlineDiagnostic> {
+    let buffer = &snapshot.buffer_snapshot;
+    let mut entries_by_row = BTreeMap::<u32, DiagnosticEntry<Point>>::default();
+    for entry in buffer.diagnostics_in_range::<_, Point>(Point::zero()..buffer.max_point(), false) {
+        if !entry.diagnostic.is_primary || !min_severity.includes(entry.diagnostic.severity) {
+            continue;
+        }
+        match entries_by_row.entry(entry.range.start.row) {
+            btree_map::Entry::Vacant(slot) => {
+                slot.insert(entry);
+            }
+            btree_map::Entry::Occupied(mut slot) => {
+                // More severe diagnostics have lower values.
+                if entry.diagnostic.severity < slot.get().diagnostic.severity {
+                    slot.insert(entry);
+                }
+            }
+        }
+    }
+
+    entries_by_row
+        .into_iter()
+        .filter_map(|(row, entry)| {
+            let message = entry
+                .diagnostic
+                .message
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())?;
+            let position = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+            let mut text = " ".repeat(INLINE_DIAGNOSTIC_PADDING as usize);
+            if let Some(max_columns) = max_columns {
+                let line_end_column = position.to_display_point(snapshot).column();
+                let available_columns = max_columns
+                    .saturating_sub(line_end_column + INLINE_DIAGNOSTIC_PADDING)
+                    as usize;
+                if available_columns < MIN_INLINE_DIAGNOSTIC_COLUMNS as usize {
+                    return None;
+                }
+                if message.chars().count() > available_columns {
+                    text.extend(message.chars().take(available_columns - 1));
+                    text.push('…');
+                } else {
+                    text.push_str(message);
+                }
+            } else {
+                text.push_str(message);
+            }
+            Some(InlineDiagnostic {
+                position,
+                severity: entry.diagnostic.severity,
+                text,
+            })
+        })
+        .collect()
+}
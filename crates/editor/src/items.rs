@@ -1,7 +1,7 @@
 use crate::{
     editor_settings::SeedQuerySetting, persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll,
-    Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot,
-    NavigationData, SearchWithinRange, ToPoint as _,
+    Editor, EditorEvent, EditorMode, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer,
+    MultiBufferSnapshot, NavigationData, SearchWithinRange, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
@@ -16,7 +16,10 @@ use language::{
     proto::serialize_anchor as serialize_text_anchor, Bias, Buffer, CharKind, Point, SelectionGoal,
 };
 use multi_buffer::AnchorRangeExt;
-use project::{search::SearchQuery, FormatTrigger, Item as _, Project, ProjectPath};
+use project::{
+    search::{SearchQuery, SyntaxContext},
+    FormatTrigger, Item as _, Project, ProjectPath,
+};
 use rpc::proto::{self, update_view, PeerId};
 use settings::Settings;
 use workspace::item::{ItemSettings, TabContentParams};
@@ -848,6 +851,17 @@ impl Item for Editor {
 
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
         self.workspace = Some((workspace.weak_handle(), workspace.database_id()));
+        if self.bookmark_store.is_none() && self.mode() == EditorMode::Full {
+            let bookmark_store = workspace.bookmark_store().clone();
+            bookmark_store.update(cx, |bookmark_store, cx| {
+                for buffer in self.buffer.read(cx).all_buffers() {
+                    bookmark_store.register_buffer(&buffer, cx);
+                }
+            });
+            self._subscriptions
+                .push(cx.observe(&bookmark_store, |_, _, cx| cx.notify()));
+            self.bookmark_store = Some(bookmark_store);
+        }
         let Some(workspace_id) = workspace.database_id() else {
             return;
         };
@@ -1005,6 +1019,15 @@ impl SearchableItem for Editor {
         );
     }
 
+    fn supports_syntax_context(&self, cx: &AppContext) -> bool {
+        self.buffer.read(cx).all_buffers().iter().any(|buffer| {
+            buffer
+                .read(cx)
+                .language()
+                .map_or(false, |language| SyntaxContext::is_supported_by(language))
+        })
+    }
+
     fn has_filtered_search_ranges(&mut self) -> bool {
         self.has_background_highlights::<SearchWithinRange>()
     }
@@ -1086,19 +1109,31 @@ impl SearchableItem for Editor {
         query: &SearchQuery,
         cx: &mut ViewContext<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
-        let text: Cow<_> = if text.len() == 1 {
-            text.first().cloned().unwrap().into()
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let replacement = if query.is_structural() {
+            // Structural replacements are built from the syntax tree of the matched buffer.
+            let range = identifier.to_offset(&snapshot);
+            snapshot
+                .point_to_buffer_offset(range.start)
+                .and_then(|(buffer, start)| {
+                    query.replacement_for_range(buffer, start..start + range.len())
+                })
         } else {
-            let joined_chunks = text.join("");
-            joined_chunks.into()
+            let text = snapshot
+                .text_for_range(identifier.clone())
+                .collect::<Vec<_>>();
+            let text: Cow<_> = if text.len() == 1 {
+                text.first().cloned().unwrap().into()
+            } else {
+                let joined_chunks = text.join("");
+                joined_chunks.into()
+            };
+            query.replacement_for(&text).map(Cow::into_owned)
         };
 
-        if let Some(replacement) = query.replacement_for(&text) {
+        if let Some(replacement) = replacement {
             self.transact(cx, |this, cx| {
-                this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
+                this.edit([(identifier.clone(), Arc::from(replacement))], cx);
             });
         }
     }
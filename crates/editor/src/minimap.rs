@@ -0,0 +1,180 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::HashMap;
+use gpui::Hsla;
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
+use text::Point;
+use theme::SyntaxTheme;
+
+/// The number of columns of each line that are rendered in the minimap.
+pub const MINIMAP_COLUMNS: u32 = 100;
+
+const MINIMAP_TAB_COLUMNS: u32 = 4;
+
+/// A downscaled line of the minimap: the colored runs of non-whitespace
+/// characters, by column.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MinimapLine {
+    pub runs: Vec<(Range<u32>, Hsla)>,
+}
+
+impl MinimapLine {
+    fn push(&mut self, column: u32, color: Hsla) {
+        if let Some((range, last_color)) = self.runs.last_mut() {
+            if range.end == column && *last_color == color {
+                range.end += 1;
+                return;
+            }
+        }
+        self.runs.push((column..column + 1, color));
+    }
+}
+
+/// Caches the lines of the minimap for as long as the buffer's text and
+/// syntax highlighting stay the same, so that only the rows that scroll into
+/// view have to be computed.
+#[derive(Default)]
+pub struct MinimapCache {
+    version: Option<(usize, usize)>,
+    syntax: Option<Arc<SyntaxTheme>>,
+    default_color: Option<Hsla>,
+    lines: HashMap<u32, MinimapLine>,
+}
+
+impl MinimapCache {
+    /// Returns the lines of the minimap for the given rows.
+    pub fn lines(
+        &mut self,
+        rows: Range<MultiBufferRow>,
+        snapshot: &MultiBufferSnapshot,
+        syntax: &Arc<SyntaxTheme>,
+        default_color: Hsla,
+    ) -> impl Iterator<Item = &MinimapLine> {
+        let version = (
+            snapshot.edit_count(),
+            snapshot.non_text_state_update_count(),
+        );
+        if self.version != Some(version)
+            || self.default_color != Some(default_color)
+            || !self
+                .syntax
+                .as_ref()
+                .map_or(false, |cached| Arc::ptr_eq(cached, syntax))
+        {
+            self.version = Some(version);
+            self.syntax = Some(syntax.clone());
+            self.default_color = Some(default_color);
+            self.lines.clear();
+        }
+
+        let rows = rows.start.0..rows.end.0.min(snapshot.max_point().row + 1);
+        let mut missing_start = None;
+        for row in rows.start..=rows.end {
+            let missing = row < rows.end && !self.lines.contains_key(&row);
+            match (missing, missing_start) {
+                (true, None) => missing_start = Some(row),
+                (false, Some(start)) => {
+                    let lines = minimap_lines(start..row, snapshot, syntax, default_color);
+                    self.lines.extend((start..row).zip(lines));
+                    missing_start = None;
+                }
+                _ => {}
+            }
+        }
+
+        rows.filter_map(|row| self.lines.get(&row))
+    }
+}
+
+/// Computes the minimap lines of the given buffer rows.
+fn minimap_lines(
+    rows: Range<u32>,
+    snapshot: &MultiBufferSnapshot,
+    syntax: &SyntaxTheme,
+    default_color: Hsla,
+) -> Vec<MinimapLine> {
+    let start = Point::new(rows.start, 0);
+    let end = if rows.end > snapshot.max_point().row {
+        snapshot.max_point()
+    } else {
+        Point::new(rows.end, 0)
+    };
+
+    let mut lines = vec![MinimapLine::default()];
+    let mut column = 0;
+    for chunk in snapshot.chunks(start..end, true) {
+        let color = chunk
+            .syntax_highlight_id
+            .and_then(|highlight_id| highlight_id.style(syntax))
+            .and_then(|style| style.color)
+            .unwrap_or(default_color);
+        for (ix, text) in chunk.text.split('\n').enumerate() {
+            if ix > 0 {
+                lines.push(MinimapLine::default());
+                column = 0;
+            }
+            let line = lines.last_mut().unwrap();
+            for ch in text.chars() {
+                if ch == '\t' {
+                    column += MINIMAP_TAB_COLUMNS - column % MINIMAP_TAB_COLUMNS;
+                    continue;
+                }
+                if !ch.is_whitespace() && column < MINIMAP_COLUMNS {
+                    line.push(column, color);
+                }
+                column += 1;
+            }
+        }
+    }
+    lines.resize(rows.len(), MinimapLine::default());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MultiBuffer;
+    use gpui::{hsla, TestAppContext};
+
+    #[gpui::test]
+    fn test_minimap_lines(cx: &mut TestAppContext) {
+        let buffer = cx.update(|cx| MultiBuffer::build_simple("fn a() {\n\tlet x = 1;\n}\n", cx));
+        let snapshot = buffer.read_with(cx, |buffer, cx| buffer.snapshot(cx));
+        let syntax = Arc::new(SyntaxTheme::default());
+        let color = hsla(0., 0., 1., 1.);
+        let runs = |ranges: &[Range<u32>]| MinimapLine {
+            runs: ranges.iter().map(|range| (range.clone(), color)).collect(),
+        };
+
+        let mut cache = MinimapCache::default();
+        let lines = cache
+            .lines(
+                MultiBufferRow(0)..MultiBufferRow(10),
+                &snapshot,
+                &syntax,
+                color,
+            )
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                runs(&[0..2, 3..6, 7..8]),
+                runs(&[4..7, 8..9, 10..11, 12..14]),
+                runs(&[0..1]),
+                runs(&[]),
+            ]
+        );
+
+        let lines = cache
+            .lines(
+                MultiBufferRow(1)..MultiBufferRow(2),
+                &snapshot,
+                &syntax,
+                color,
+            )
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [runs(&[4..7, 8..9, 10..11, 12..14])]);
+    }
+}
@@ -0,0 +1,65 @@
+use editor::Editor;
+use gpui::{
+    div, AnchorCorner, IntoElement, ParentElement, Render, Subscription, View, ViewContext,
+};
+use language::Encoding;
+use ui::{prelude::*, Button, ContextMenu, LabelSize, PopoverMenu, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView};
+
+use crate::{ReopenWithEncoding, SaveWithEncoding};
+
+#[derive(Default)]
+pub struct ActiveBufferEncoding {
+    active_encoding: Option<Encoding>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl ActiveBufferEncoding {
+    fn update_encoding(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        self.active_encoding = editor
+            .read(cx)
+            .active_excerpt(cx)
+            .map(|(_, buffer, _)| buffer.read(cx).encoding());
+        cx.notify();
+    }
+}
+
+impl Render for ActiveBufferEncoding {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        div().when_some(self.active_encoding, |el, active_encoding| {
+            el.child(
+                PopoverMenu::new("encoding-menu")
+                    .menu(|cx| {
+                        Some(ContextMenu::build(cx, |menu, _| {
+                            menu.action("Reopen with Encoding…", Box::new(ReopenWithEncoding))
+                                .action("Save with Encoding…", Box::new(SaveWithEncoding))
+                        }))
+                    })
+                    .anchor(AnchorCorner::BottomRight)
+                    .trigger(
+                        Button::new("change-encoding", active_encoding.to_string())
+                            .label_size(LabelSize::Small)
+                            .tooltip(|cx| Tooltip::text("Select Encoding", cx)),
+                    ),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferEncoding {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            self._observe_active_editor = Some(cx.observe(&editor, Self::update_encoding));
+            self.update_encoding(editor, cx);
+        } else {
+            self.active_encoding = None;
+            self._observe_active_editor = None;
+        }
+
+        cx.notify();
+    }
+}
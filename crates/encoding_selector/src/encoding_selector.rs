@@ -0,0 +1,316 @@
+mod active_buffer_encoding;
+
+pub use active_buffer_encoding::ActiveBufferEncoding;
+use anyhow::{anyhow, Result};
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, PromptLevel, Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use language::{Buffer, Encoding};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace};
+
+actions!(encoding_selector, [ReopenWithEncoding, SaveWithEncoding]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(EncodingSelector::register).detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EncodingAction {
+    /// Decode the file on disk again with the chosen encoding.
+    Reopen,
+    /// Write the buffer to disk in the chosen encoding.
+    Save,
+}
+
+pub struct EncodingSelector {
+    picker: View<Picker<EncodingSelectorDelegate>>,
+}
+
+impl EncodingSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(move |workspace, _: &ReopenWithEncoding, cx| {
+            Self::toggle(workspace, EncodingAction::Reopen, cx);
+        });
+        workspace.register_action(move |workspace, _: &SaveWithEncoding, cx| {
+            Self::toggle(workspace, EncodingAction::Save, cx);
+        });
+    }
+
+    fn toggle(
+        workspace: &mut Workspace,
+        action: EncodingAction,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Option<()> {
+        let (_, buffer, _) = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .active_excerpt(cx)?;
+        let project = workspace.project().clone();
+
+        workspace.toggle_modal(cx, move |cx| {
+            EncodingSelector::new(action, buffer, project, cx)
+        });
+        Some(())
+    }
+
+    fn new(
+        action: EncodingAction,
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate =
+            EncodingSelectorDelegate::new(cx.view().downgrade(), action, buffer, project);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for EncodingSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for EncodingSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for EncodingSelector {}
+impl ModalView for EncodingSelector {}
+
+pub struct EncodingSelectorDelegate {
+    encoding_selector: WeakView<EncodingSelector>,
+    action: EncodingAction,
+    buffer: Model<Buffer>,
+    project: Model<Project>,
+    encodings: Vec<Encoding>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl EncodingSelectorDelegate {
+    fn new(
+        encoding_selector: WeakView<EncodingSelector>,
+        action: EncodingAction,
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+    ) -> Self {
+        // A byte order mark is detected when reopening a file, so it can only
+        // be chosen when saving.
+        let mut encodings = Vec::new();
+        for encoding in Encoding::all() {
+            encodings.push(encoding);
+            if action == EncodingAction::Save && encoding.supports_bom() {
+                encodings.push(encoding.with_bom(true));
+            }
+        }
+        let candidates = encodings
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, encoding)| {
+                StringMatchCandidate::new(candidate_id, encoding.to_string())
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            encoding_selector,
+            action,
+            buffer,
+            project,
+            encodings,
+            candidates,
+            matches: vec![],
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for EncodingSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match self.action {
+            EncodingAction::Reopen => "Reopen with encoding...".into(),
+            EncodingAction::Save => "Save with encoding...".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let encoding = self.encodings[mat.candidate_id];
+            let buffer = self.buffer.clone();
+            match self.action {
+                EncodingAction::Reopen => reopen_with_encoding(buffer, encoding, cx)
+                    .detach_and_prompt_err("Failed to reopen file", cx, |error, _| {
+                        Some(error.to_string())
+                    }),
+                EncodingAction::Save => {
+                    save_with_encoding(self.project.clone(), buffer, encoding, cx)
+                        .detach_and_prompt_err("Failed to save file", cx, |error, _| {
+                            Some(error.to_string())
+                        })
+                }
+            }
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.encoding_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let mut label = mat.string.clone();
+        if self.buffer.read(cx).encoding() == self.encodings[mat.candidate_id] {
+            label.push_str(" (current)");
+        }
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(label, mat.positions.clone())),
+        )
+    }
+}
+
+fn reopen_with_encoding(
+    buffer: Model<Buffer>,
+    encoding: Encoding,
+    cx: &mut WindowContext,
+) -> Task<Result<()>> {
+    let Some(file) = buffer.read(cx).file().and_then(|file| file.as_local()) else {
+        return Task::ready(Err(anyhow!(
+            "Only local files can be reopened with a different encoding"
+        )));
+    };
+    // Decode the file up front, so that an encoding that doesn't fit the
+    // file's contents is reported without touching the buffer.
+    let load = file.load(Some(encoding), cx);
+    let is_dirty = buffer.read(cx).is_dirty();
+    cx.spawn(|mut cx| async move {
+        load.await?;
+        if is_dirty {
+            let answer = cx.update(|cx| {
+                cx.prompt(
+                    PromptLevel::Warning,
+                    "This file has unsaved changes.",
+                    Some("Reopening it with a different encoding will discard them."),
+                    &["Reopen", "Cancel"],
+                )
+            })?;
+            if answer.await? != 0 {
+                return Ok(());
+            }
+        }
+        buffer
+            .update(&mut cx, |buffer, cx| {
+                buffer.reload_with_encoding(Some(encoding), cx)
+            })?
+            .await
+            .ok();
+        Ok(())
+    })
+}
+
+fn save_with_encoding(
+    project: Model<Project>,
+    buffer: Model<Buffer>,
+    encoding: Encoding,
+    cx: &mut WindowContext,
+) -> Task<Result<()>> {
+    if !project.read(cx).is_local() {
+        return Task::ready(Err(anyhow!(
+            "Only local files can be saved with a different encoding"
+        )));
+    }
+    let previous_encoding = buffer.read(cx).encoding();
+    buffer.update(cx, |buffer, cx| buffer.set_encoding(encoding, cx));
+    let save = project.update(cx, |project, cx| project.save_buffer(buffer.clone(), cx));
+    cx.spawn(|mut cx| async move {
+        if let Err(error) = save.await {
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.set_encoding(previous_encoding, cx)
+            })?;
+            return Err(error);
+        }
+        Ok(())
+    })
+}
@@ -15,14 +15,17 @@ use git::repository::{GitRepository, RealGitRepository};
 use rope::Rope;
 use smol::io::AsyncWriteExt;
 use std::{
-    io::{self, Write},
+    io::{self, Read, Write},
     path::{Component, Path, PathBuf},
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering::SeqCst},
+        Arc,
+    },
     time::{Duration, SystemTime},
 };
 use tempfile::{NamedTempFile, TempDir};
-use text::LineEnding;
+use text::{ChunkedDecoder, Encoding, LineEnding};
 use util::ResultExt;
 
 #[cfg(any(test, feature = "test-support"))]
@@ -68,8 +71,30 @@ pub trait Fs: Send + Sync {
     }
     async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>>;
     async fn load(&self, path: &Path) -> Result<String>;
+    /// Loads a file, decoding it with the given encoding or, if none is given,
+    /// with the encoding detected from its contents.
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Option<Encoding>,
+    ) -> Result<(String, Encoding)>;
+    /// Loads a file in chunks, decoding each chunk as it's read, and storing
+    /// the number of bytes loaded so far in `progress`. Returns the file's
+    /// text with its line endings normalized, along with the line ending and
+    /// encoding it used.
+    async fn load_large(
+        &self,
+        path: &Path,
+        progress: Arc<AtomicUsize>,
+    ) -> Result<(Rope, LineEnding, Encoding)>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    async fn save(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -124,6 +149,7 @@ pub struct RemoveOptions {
 pub struct Metadata {
     pub inode: u64,
     pub mtime: SystemTime,
+    pub len: u64,
     pub is_symlink: bool,
     pub is_dir: bool,
 }
@@ -319,6 +345,45 @@ impl Fs for RealFs {
         Ok(text)
     }
 
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Option<Encoding>,
+    ) -> Result<(String, Encoding)> {
+        let path = path.to_path_buf();
+        smol::unblock(move || Encoding::decode(&std::fs::read(path)?, encoding)).await
+    }
+
+    async fn load_large(
+        &self,
+        path: &Path,
+        progress: Arc<AtomicUsize>,
+    ) -> Result<(Rope, LineEnding, Encoding)> {
+        const CHUNK_SIZE: usize = 1024 * 1024;
+
+        let path = path.to_path_buf();
+        smol::unblock(move || {
+            let mut file = std::fs::File::open(path)?;
+            let mut decoder = ChunkedDecoder::new();
+            let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+            let mut loaded_len = 0;
+            loop {
+                chunk.clear();
+                let read = (&mut file)
+                    .take(CHUNK_SIZE as u64)
+                    .read_to_end(&mut chunk)?;
+                if read == 0 {
+                    break;
+                }
+                loaded_len += read;
+                progress.store(loaded_len, SeqCst);
+                decoder.push(&chunk)?;
+            }
+            decoder.finish()
+        })
+        .await
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
             let mut tmp_file = if cfg!(target_os = "linux") {
@@ -338,15 +403,35 @@ impl Fs for RealFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        // Encode before creating the file, so that text which can't be
+        // represented in the encoding doesn't leave the file truncated.
+        let encoded = if encoding.is_utf8() {
+            None
+        } else {
+            let text = chunks(text, line_ending).collect::<String>();
+            Some(encoding.encode(&text)?.into_owned())
+        };
+
         let buffer_size = text.summary().len.min(10 * 1024);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
         let file = smol::fs::File::create(path).await?;
         let mut writer = smol::io::BufWriter::with_capacity(buffer_size, file);
-        for chunk in chunks(text, line_ending) {
-            writer.write_all(chunk.as_bytes()).await?;
+        writer.write_all(encoding.bom_bytes()).await?;
+        if let Some(encoded) = encoded {
+            writer.write_all(&encoded).await?;
+        } else {
+            for chunk in chunks(text, line_ending) {
+                writer.write_all(chunk.as_bytes()).await?;
+            }
         }
         writer.flush().await?;
         Ok(())
@@ -396,6 +481,7 @@ impl Fs for RealFs {
         Ok(Some(Metadata {
             inode,
             mtime: metadata.modified().unwrap(),
+            len: metadata.len(),
             is_symlink,
             is_dir: metadata.file_type().is_dir(),
         }))
@@ -982,6 +1068,17 @@ impl FakeFs {
         });
     }
 
+    pub fn set_head_for_repo(&self, dot_git: &Path, head_state: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.head_contents.clear();
+            state.head_contents.extend(
+                head_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -1433,6 +1530,27 @@ impl Fs for FakeFs {
         Ok(String::from_utf8(content.clone())?)
     }
 
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Option<Encoding>,
+    ) -> Result<(String, Encoding)> {
+        let content = self.load_internal(path).await?;
+        Encoding::decode(&content, encoding)
+    }
+
+    async fn load_large(
+        &self,
+        path: &Path,
+        progress: Arc<AtomicUsize>,
+    ) -> Result<(Rope, LineEnding, Encoding)> {
+        let content = self.load_internal(path).await?;
+        progress.store(content.len(), SeqCst);
+        let mut decoder = ChunkedDecoder::new();
+        decoder.push(&content)?;
+        decoder.finish()
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
@@ -1440,14 +1558,22 @@ impl Fs for FakeFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
-        let content = chunks(text, line_ending).collect::<String>();
+        let text = chunks(text, line_ending).collect::<String>();
+        let mut content = encoding.bom_bytes().to_vec();
+        content.extend_from_slice(&encoding.encode(&text)?);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
-        self.write_file_internal(path, content.into_bytes())?;
+        self.write_file_internal(path, content)?;
         Ok(())
     }
 
@@ -1496,15 +1622,21 @@ impl Fs for FakeFs {
 
             let entry = entry.lock();
             Ok(Some(match &*entry {
-                FakeFsEntry::File { inode, mtime, .. } => Metadata {
+                FakeFsEntry::File {
+                    inode,
+                    mtime,
+                    content,
+                } => Metadata {
                     inode: *inode,
                     mtime: *mtime,
+                    len: content.len() as u64,
                     is_dir: false,
                     is_symlink,
                 },
                 FakeFsEntry::Dir { inode, mtime, .. } => Metadata {
                     inode: *inode,
                     mtime: *mtime,
+                    len: 0,
                     is_dir: true,
                     is_symlink,
                 },
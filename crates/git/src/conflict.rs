@@ -0,0 +1,300 @@
+use std::{mem, ops::Range};
+
+use rope::Rope;
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// A region of a file left with conflict markers by a merge or rebase.
+///
+/// All ranges are byte offsets into the text the conflict was parsed from.
+/// The sides' ranges cover whole lines, without their markers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    /// The whole region, from the start of the `<<<<<<<` line to the end of
+    /// the `>>>>>>>` line.
+    pub range: Range<usize>,
+    pub ours: Range<usize>,
+    /// The common ancestor's side, present for `diff3` style conflicts.
+    pub base: Option<Range<usize>>,
+    pub theirs: Range<usize>,
+    /// The text after the `<<<<<<<` marker, such as `HEAD`.
+    pub ours_label: String,
+    /// The text after the `>>>>>>>` marker, such as a branch name.
+    pub theirs_label: String,
+}
+
+#[derive(Default)]
+enum State {
+    #[default]
+    Outside,
+    Ours {
+        start: usize,
+        ours_label: String,
+        ours_start: usize,
+    },
+    Base {
+        start: usize,
+        ours_label: String,
+        ours: Range<usize>,
+        base_start: usize,
+    },
+    Theirs {
+        start: usize,
+        ours_label: String,
+        ours: Range<usize>,
+        base: Option<Range<usize>>,
+        theirs_start: usize,
+    },
+}
+
+/// Finds conflicts in a text fed to it line by line.
+#[derive(Default)]
+struct ConflictParser {
+    state: State,
+    conflicts: Vec<Conflict>,
+}
+
+impl ConflictParser {
+    /// Handles the line spanning `line_start..line_end`, whose newline is
+    /// optional in `line`.
+    fn push_line(&mut self, line: &str, line_start: usize, line_end: usize) {
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        // A new conflict can start anywhere, abandoning an unterminated one.
+        if let Some(label) = marker_label(content, OURS_MARKER) {
+            self.state = State::Ours {
+                start: line_start,
+                ours_label: label.to_string(),
+                ours_start: line_end,
+            };
+        } else {
+            self.state = match mem::take(&mut self.state) {
+                State::Ours {
+                    start,
+                    ours_label,
+                    ours_start,
+                } => {
+                    if marker_label(content, BASE_MARKER).is_some() {
+                        State::Base {
+                            start,
+                            ours_label,
+                            ours: ours_start..line_start,
+                            base_start: line_end,
+                        }
+                    } else if content.trim_end() == SEPARATOR_MARKER {
+                        State::Theirs {
+                            start,
+                            ours_label,
+                            ours: ours_start..line_start,
+                            base: None,
+                            theirs_start: line_end,
+                        }
+                    } else {
+                        State::Ours {
+                            start,
+                            ours_label,
+                            ours_start,
+                        }
+                    }
+                }
+                State::Base {
+                    start,
+                    ours_label,
+                    ours,
+                    base_start,
+                } => {
+                    if content.trim_end() == SEPARATOR_MARKER {
+                        State::Theirs {
+                            start,
+                            ours_label,
+                            ours,
+                            base: Some(base_start..line_start),
+                            theirs_start: line_end,
+                        }
+                    } else {
+                        State::Base {
+                            start,
+                            ours_label,
+                            ours,
+                            base_start,
+                        }
+                    }
+                }
+                State::Theirs {
+                    start,
+                    ours_label,
+                    ours,
+                    base,
+                    theirs_start,
+                } => {
+                    if let Some(theirs_label) = marker_label(content, THEIRS_MARKER) {
+                        self.conflicts.push(Conflict {
+                            range: start..line_end,
+                            ours,
+                            base,
+                            theirs: theirs_start..line_start,
+                            ours_label,
+                            theirs_label: theirs_label.to_string(),
+                        });
+                        State::Outside
+                    } else {
+                        State::Theirs {
+                            start,
+                            ours_label,
+                            ours,
+                            base,
+                            theirs_start,
+                        }
+                    }
+                }
+                State::Outside => State::Outside,
+            };
+        }
+    }
+}
+
+/// Returns the conflicts marked in `text`, in order. Unterminated or
+/// malformed regions are skipped.
+pub fn parse_conflicts(text: &str) -> Vec<Conflict> {
+    let mut parser = ConflictParser::default();
+    if !text.contains(OURS_MARKER) {
+        return parser.conflicts;
+    }
+
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        parser.push_line(line, line_start, line_end);
+        line_start = line_end;
+    }
+    parser.conflicts
+}
+
+/// Like [`parse_conflicts`], but reads the rope line by line instead of
+/// copying it into a string.
+pub fn parse_rope_conflicts(rope: &Rope) -> Vec<Conflict> {
+    let mut parser = ConflictParser::default();
+    let mut lines = rope.chunks().lines();
+    let mut line_start = 0;
+    while let Some(line) = lines.next() {
+        let line_end = (line_start + line.len() + 1).min(rope.len());
+        parser.push_line(line, line_start, line_end);
+        line_start = line_end;
+    }
+    parser.conflicts
+}
+
+/// If the line is the given marker, optionally followed by a space and a
+/// label, returns the label.
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        Some(rest.strip_prefix(' ')?.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use unindent::Unindent as _;
+
+    fn sides(text: &str, conflict: &Conflict) -> (String, Option<String>, String) {
+        (
+            text[conflict.ours.clone()].to_string(),
+            conflict.base.clone().map(|base| text[base].to_string()),
+            text[conflict.theirs.clone()].to_string(),
+        )
+    }
+
+    #[test]
+    fn test_parse_conflicts() {
+        let text = "
+            fn main() {
+            <<<<<<< HEAD
+                println!(\"ours\");
+            =======
+                println!(\"theirs\");
+                println!(\"theirs again\");
+            >>>>>>> feature
+            }
+            <<<<<<< HEAD
+            ||||||| base
+            old
+            =======
+            new
+            >>>>>>> 1234567 (Change)
+        "
+        .unindent();
+
+        let conflicts = parse_conflicts(&text);
+        assert_eq!(conflicts.len(), 2);
+
+        assert_eq!(
+            sides(&text, &conflicts[0]),
+            (
+                "    println!(\"ours\");\n".to_string(),
+                None,
+                "    println!(\"theirs\");\n    println!(\"theirs again\");\n".to_string()
+            )
+        );
+        assert_eq!(conflicts[0].ours_label, "HEAD");
+        assert_eq!(conflicts[0].theirs_label, "feature");
+        assert!(text[conflicts[0].range.clone()].starts_with("<<<<<<< HEAD\n"));
+        assert!(text[conflicts[0].range.clone()].ends_with(">>>>>>> feature\n"));
+
+        assert_eq!(
+            sides(&text, &conflicts[1]),
+            (
+                String::new(),
+                Some("old\n".to_string()),
+                "new\n".to_string()
+            )
+        );
+        assert_eq!(conflicts[1].theirs_label, "1234567 (Change)");
+        assert_eq!(conflicts[1].range.end, text.len());
+
+        assert_eq!(parse_rope_conflicts(&Rope::from(text.as_str())), conflicts);
+        let text = text.trim_end();
+        assert_eq!(
+            parse_rope_conflicts(&Rope::from(text)),
+            parse_conflicts(text)
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_conflicts() {
+        // Markers that aren't whole lines, unterminated conflicts and
+        // conflicts interrupted by another one are ignored.
+        let text = "
+            <<<<<<<< not a marker
+            =======
+            >>>>>>> other
+            <<<<<<< HEAD
+            abandoned
+            <<<<<<< HEAD
+            ours
+            =======
+            theirs
+            >>>>>>> other
+            <<<<<<< HEAD
+            unterminated
+            =======
+        "
+        .unindent();
+
+        let conflicts = parse_conflicts(&text);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            sides(&text, &conflicts[0]),
+            ("ours\n".to_string(), None, "theirs\n".to_string())
+        );
+
+        assert_eq!(parse_conflicts("no conflicts here\n"), Vec::new());
+    }
+}
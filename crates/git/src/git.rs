@@ -13,6 +13,7 @@ pub use crate::hosting_provider::*;
 
 pub mod blame;
 pub mod commit;
+pub mod conflict;
 pub mod diff;
 pub mod repository;
 pub mod status;
@@ -22,6 +22,17 @@ pub struct Branch {
     pub unix_timestamp: Option<i64>,
 }
 
+/// The changes made on a branch since it diverged from another one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BranchChanges {
+    /// The name of the branch the changes are relative to, such as `origin/main`.
+    pub base_branch: String,
+    /// The subject lines of the commits on the branch, newest first.
+    pub commit_subjects: Vec<String>,
+    /// The unified diff of the branch's changes.
+    pub diff: String,
+}
+
 pub trait GitRepository: Send + Sync {
     fn reload_index(&self);
 
@@ -29,6 +40,18 @@ pub trait GitRepository: Send + Sync {
     /// Note that for symlink entries, this will return the contents of the symlink, not the target.
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads a git repository entry's contents as of the HEAD commit.
+    fn load_committed_text(&self, relative_file_path: &Path) -> Option<String>;
+
+    /// Loads a git repository entry's contents as of the given revision, such
+    /// as `HEAD~1` or a commit SHA. Returns `None` if the entry didn't exist
+    /// at that revision.
+    fn load_text_at_revision(
+        &self,
+        relative_file_path: &Path,
+        revision: &str,
+    ) -> Result<Option<String>>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -47,6 +70,18 @@ pub trait GitRepository: Send + Sync {
     fn create_branch(&self, _: &str) -> Result<()>;
 
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame>;
+
+    /// Returns the subject lines of the most recent commits reachable from HEAD,
+    /// newest first.
+    fn recent_commit_subjects(&self, limit: usize) -> Result<Vec<String>>;
+
+    /// Returns the changes made on the current branch since it diverged from the
+    /// repository's default branch.
+    fn branch_changes(&self) -> Result<BranchChanges>;
+
+    /// Adds the current contents of the given entries to the index, which
+    /// marks their merge conflicts as resolved.
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -106,6 +141,50 @@ impl GitRepository for RealGitRepository {
         None
     }
 
+    fn load_committed_text(&self, relative_file_path: &Path) -> Option<String> {
+        fn logic(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<String>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+
+            let tree = repo.head()?.peel_to_tree()?;
+            let oid = match tree.get_path(relative_file_path) {
+                Ok(entry) => entry.id(),
+                Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+
+            let content = repo.find_blob(oid)?.content().to_owned();
+            Ok(Some(String::from_utf8(content)?))
+        }
+
+        match logic(&self.repository.lock(), relative_file_path) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading committed text: {:?}", err),
+        }
+        None
+    }
+
+    fn load_text_at_revision(
+        &self,
+        relative_file_path: &Path,
+        revision: &str,
+    ) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(relative_file_path)?;
+
+        let repo = self.repository.lock();
+        let tree = repo
+            .revparse_single(revision)
+            .with_context(|| format!("unknown revision {revision:?}"))?
+            .peel_to_tree()?;
+        let oid = match tree.get_path(relative_file_path) {
+            Ok(entry) => entry.id(),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let content = repo.find_blob(oid)?.content().to_owned();
+        Ok(Some(String::from_utf8(content)?))
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -200,6 +279,81 @@ impl GitRepository for RealGitRepository {
             self.hosting_provider_registry.clone(),
         )
     }
+
+    fn recent_commit_subjects(&self, limit: usize) -> Result<Vec<String>> {
+        let repo = self.repository.lock();
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_head()?;
+        let mut subjects = Vec::new();
+        for oid in revwalk.take(limit) {
+            let commit = repo.find_commit(oid?)?;
+            if let Some(subject) = commit.summary() {
+                subjects.push(subject.to_string());
+            }
+        }
+        Ok(subjects)
+    }
+
+    fn branch_changes(&self) -> Result<BranchChanges> {
+        const BASE_BRANCHES: [&str; 5] = [
+            "origin/HEAD",
+            "origin/main",
+            "origin/master",
+            "main",
+            "master",
+        ];
+
+        let repo = self.repository.lock();
+        let head = repo.head()?.peel_to_commit()?;
+        let (base_branch, base) = BASE_BRANCHES
+            .iter()
+            .find_map(|name| {
+                let commit = repo.revparse_single(name).ok()?.peel_to_commit().ok()?;
+                Some((name.to_string(), commit))
+            })
+            .context("failed to find the repository's default branch")?;
+        let merge_base = repo.merge_base(head.id(), base.id())?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(head.id())?;
+        revwalk.hide(merge_base)?;
+        let mut commit_subjects = Vec::new();
+        for oid in revwalk {
+            if let Some(subject) = repo.find_commit(oid?)?.summary() {
+                commit_subjects.push(subject.to_string());
+            }
+        }
+
+        let base_tree = repo.find_commit(merge_base)?.tree()?;
+        let changes = repo.diff_tree_to_tree(Some(&base_tree), Some(&head.tree()?), None)?;
+        let mut diff = String::new();
+        changes.print(git2::DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                diff.push(line.origin());
+            }
+            diff.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })?;
+
+        Ok(BranchChanges {
+            base_branch,
+            commit_subjects,
+            diff,
+        })
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let repo = self.repository.lock();
+        let mut index = repo.index()?;
+        for path in paths {
+            check_path_to_repo_path_errors(path)?;
+            index
+                .add_path(path)
+                .with_context(|| format!("failed to stage {:?}", path.0))?;
+        }
+        index.write()?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -210,6 +364,9 @@ pub struct FakeGitRepository {
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
+    pub head_contents: HashMap<PathBuf, String>,
+    pub commit_subjects: Vec<String>,
+    pub branch_changes: Option<BranchChanges>,
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
@@ -229,6 +386,19 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_committed_text(&self, path: &Path) -> Option<String> {
+        let state = self.state.lock();
+        state.head_contents.get(path).cloned()
+    }
+
+    fn load_text_at_revision(&self, path: &Path, revision: &str) -> Result<Option<String>> {
+        let state = self.state.lock();
+        match revision {
+            "HEAD" => Ok(state.head_contents.get(path).cloned()),
+            _ => anyhow::bail!("unknown revision {revision:?}"),
+        }
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -285,6 +455,31 @@ impl GitRepository for FakeGitRepository {
             .with_context(|| format!("failed to get blame for {:?}", path))
             .cloned()
     }
+
+    fn recent_commit_subjects(&self, limit: usize) -> Result<Vec<String>> {
+        let state = self.state.lock();
+        Ok(state.commit_subjects.iter().take(limit).cloned().collect())
+    }
+
+    fn branch_changes(&self) -> Result<BranchChanges> {
+        let state = self.state.lock();
+        state
+            .branch_changes
+            .clone()
+            .context("failed to find the repository's default branch")
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            if let Some(status) = state.worktree_statuses.get_mut(path) {
+                if *status == GitFileStatus::Conflict {
+                    *status = GitFileStatus::Modified;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
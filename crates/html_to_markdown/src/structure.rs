@@ -1 +1,2 @@
+pub mod sphinx;
 pub mod wikipedia;
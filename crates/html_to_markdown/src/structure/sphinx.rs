@@ -0,0 +1,224 @@
+use crate::html_element::HtmlElement;
+use crate::markdown_writer::{HandlerOutcome, MarkdownWriter, StartTagOutcome};
+use crate::HandleTag;
+
+pub struct SphinxChromeRemover;
+
+impl HandleTag for SphinxChromeRemover {
+    fn should_handle(&self, _tag: &str) -> bool {
+        true
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "head" | "script" | "style" | "nav" | "footer" => return StartTagOutcome::Skip,
+            "a" => {
+                if tag.has_class("headerlink") {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            "div" => {
+                if tag.attr("role").as_deref() == Some("navigation") {
+                    return StartTagOutcome::Skip;
+                }
+
+                let classes_to_skip = [
+                    "sphinxsidebar",
+                    "related",
+                    "footer",
+                    "rst-versions",
+                    "wy-nav-side",
+                    "bd-sidebar",
+                ];
+                if tag.has_any_classes(&classes_to_skip) {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+}
+
+/// Renders Sphinx object signatures (e.g., `<dt class="sig">`) as headings.
+pub struct SphinxSignatureHandler;
+
+impl HandleTag for SphinxSignatureHandler {
+    fn should_handle(&self, tag: &str) -> bool {
+        tag == "dt"
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        if tag.has_class("sig") {
+            writer.push_blank_line();
+            writer.push_str("### `");
+        }
+
+        StartTagOutcome::Continue
+    }
+
+    fn handle_tag_end(&mut self, tag: &HtmlElement, writer: &mut MarkdownWriter) {
+        if tag.has_class("sig") {
+            writer.push_str("`");
+            writer.push_blank_line();
+        }
+    }
+
+    fn handle_text(&mut self, text: &str, writer: &mut MarkdownWriter) -> HandlerOutcome {
+        let is_inside_signature = writer
+            .current_element_stack()
+            .iter()
+            .any(|element| element.tag() == "dt" && element.has_class("sig"));
+        if is_inside_signature {
+            let text = text.replace('\n', " ");
+            if writer.markdown.ends_with('`') {
+                writer.push_str(text.trim_start());
+            } else {
+                writer.push_str(&text);
+            }
+            return HandlerOutcome::Handled;
+        }
+
+        HandlerOutcome::NoOp
+    }
+}
+
+pub struct SphinxCodeHandler {
+    language: Option<String>,
+}
+
+impl SphinxCodeHandler {
+    pub fn new() -> Self {
+        Self { language: None }
+    }
+}
+
+impl HandleTag for SphinxCodeHandler {
+    fn should_handle(&self, tag: &str) -> bool {
+        match tag {
+            "div" | "pre" | "code" => true,
+            _ => false,
+        }
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "code" => {
+                if !writer.is_inside("pre") {
+                    writer.push_str("`");
+                }
+            }
+            "div" => {
+                let classes = tag.classes();
+                if let Some(language) = classes.iter().find_map(|class| {
+                    class
+                        .strip_prefix("highlight-")
+                        .map(|language| language.trim().to_owned())
+                }) {
+                    self.language = Some(language);
+                }
+            }
+            "pre" => {
+                writer.push_blank_line();
+                writer.push_str("```");
+                if let Some(language) = self.language.take() {
+                    if language != "default" {
+                        writer.push_str(&language);
+                    }
+                }
+                writer.push_newline();
+            }
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+
+    fn handle_tag_end(&mut self, tag: &HtmlElement, writer: &mut MarkdownWriter) {
+        match tag.tag() {
+            "code" => {
+                if !writer.is_inside("pre") {
+                    writer.push_str("`");
+                }
+            }
+            "pre" => writer.push_str("\n```\n"),
+            _ => {}
+        }
+    }
+
+    fn handle_text(&mut self, text: &str, writer: &mut MarkdownWriter) -> HandlerOutcome {
+        if writer.is_inside("pre") {
+            writer.push_str(&text);
+            return HandlerOutcome::Handled;
+        }
+
+        HandlerOutcome::NoOp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::{convert_html_to_markdown, markdown, TagHandler};
+
+    use super::*;
+
+    fn sphinx_handlers() -> Vec<TagHandler> {
+        vec![
+            Rc::new(RefCell::new(markdown::ParagraphHandler)),
+            Rc::new(RefCell::new(markdown::HeadingHandler)),
+            Rc::new(RefCell::new(markdown::ListHandler)),
+            Rc::new(RefCell::new(markdown::StyledTextHandler)),
+            Rc::new(RefCell::new(SphinxChromeRemover)),
+            Rc::new(RefCell::new(SphinxSignatureHandler)),
+            Rc::new(RefCell::new(SphinxCodeHandler::new())),
+        ]
+    }
+
+    #[test]
+    fn test_signatures_and_code_blocks() {
+        let html = indoc! {r##"
+            <div class="sphinxsidebar" role="navigation"><p>Table of contents</p></div>
+            <dl class="py function">
+            <dt class="sig sig-object py" id="requests.get">
+            <span class="sig-prename descclassname">requests.</span><span class="sig-name descname">get</span>(url)<a class="headerlink" href="#requests.get">¶</a></dt>
+            <dd><p>Sends a GET request.</p>
+            <div class="highlight-python notranslate"><div class="highlight"><pre>requests.get("https://zed.dev")</pre></div></div>
+            </dd>
+            </dl>
+        "##};
+        let expected = indoc! {r#"
+            ### `requests.get(url)`
+
+            Sends a GET request.
+
+            ```python
+            requests.get("https://zed.dev")
+            ```
+        "#}
+        .trim();
+
+        assert_eq!(
+            convert_html_to_markdown(html.as_bytes(), &mut sphinx_handlers()).unwrap(),
+            expected
+        )
+    }
+}
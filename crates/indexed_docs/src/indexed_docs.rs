@@ -0,0 +1,10 @@
+mod providers;
+mod registry;
+mod store;
+
+pub use crate::providers::go::*;
+pub use crate::providers::npm::*;
+pub use crate::providers::python::*;
+pub use crate::providers::rustdoc::*;
+pub use crate::registry::*;
+pub use crate::store::*;
@@ -0,0 +1,37 @@
+pub mod go;
+pub mod npm;
+pub mod python;
+pub mod rustdoc;
+
+use anyhow::{bail, Context, Result};
+use futures::AsyncReadExt;
+use http::{AsyncBody, HttpClient, HttpClientWithUrl};
+
+/// Fetches the body at the given URL, returning `None` if it could not be found.
+pub(crate) async fn fetch_bytes(
+    http_client: &HttpClientWithUrl,
+    url: &str,
+) -> Result<Option<Vec<u8>>> {
+    let mut response = http_client.get(url, AsyncBody::default(), true).await?;
+
+    let mut body = Vec::new();
+    response
+        .body_mut()
+        .read_to_end(&mut body)
+        .await
+        .with_context(|| format!("error reading response body from {url}"))?;
+
+    if response.status().as_u16() == 404 {
+        return Ok(None);
+    }
+
+    if response.status().is_client_error() || response.status().is_server_error() {
+        let text = String::from_utf8_lossy(body.as_slice());
+        bail!(
+            "status error {}, response: {text:?}",
+            response.status().as_u16()
+        );
+    }
+
+    Ok(Some(body))
+}
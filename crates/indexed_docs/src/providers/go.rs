@@ -0,0 +1,233 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+
+use crate::{IndexedDocsDatabase, IndexedDocsProvider, PackageName, ProviderId};
+
+/// A top-level declaration in the output of `go doc -all`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GoDocItem {
+    /// The name of the item (e.g., `Println` or `Reader.Read`).
+    pub name: String,
+    pub docs: String,
+}
+
+/// Splits the output of `go doc -all` into the package overview and its items.
+pub fn parse_go_doc_output(output: &str) -> (String, Vec<GoDocItem>) {
+    let mut overview = String::new();
+    let mut items: Vec<GoDocItem> = Vec::new();
+    let mut in_sections = false;
+
+    for line in output.lines() {
+        // Section headers (e.g., `FUNCTIONS`) are unindented; blank lines in
+        // `go doc` output may still contain whitespace.
+        let trimmed = line.trim();
+        let is_section_header = !trimmed.is_empty()
+            && !line.starts_with(char::is_whitespace)
+            && trimmed
+                .chars()
+                .all(|char| char.is_ascii_uppercase() || char == ' ');
+        if is_section_header {
+            in_sections = true;
+            continue;
+        }
+
+        if in_sections {
+            if let Some(name) = go_declaration_name(line) {
+                items.push(GoDocItem {
+                    name,
+                    docs: String::new(),
+                });
+            }
+        }
+
+        let docs = match items.last_mut() {
+            Some(item) if in_sections => &mut item.docs,
+            _ => &mut overview,
+        };
+        docs.push_str(line);
+        docs.push('\n');
+    }
+
+    for item in &mut items {
+        item.docs = format!("```go\n{}\n```", item.docs.trim_end());
+    }
+
+    (overview.trim().to_string(), items)
+}
+
+/// Returns the name of the declaration on the given line, if it starts one.
+fn go_declaration_name(line: &str) -> Option<String> {
+    let identifier = |text: &str| -> String {
+        text.chars()
+            .take_while(|char| char.is_alphanumeric() || *char == '_')
+            .collect()
+    };
+
+    if let Some(rest) = line.strip_prefix("type ") {
+        return Some(identifier(rest)).filter(|name| !name.is_empty());
+    }
+
+    let rest = line.strip_prefix("func ")?;
+    if let Some(rest) = rest.strip_prefix('(') {
+        // Methods are named after their receiver type, e.g. `func (r *Reader) Read(…)`.
+        let (receiver, rest) = rest.split_once(')')?;
+        let receiver_type = receiver.split_whitespace().last()?.trim_start_matches('*');
+        let receiver_type = identifier(receiver_type);
+        let method = identifier(rest.trim_start());
+        return Some(format!("{receiver_type}.{method}"));
+    }
+
+    Some(identifier(rest)).filter(|name| !name.is_empty())
+}
+
+/// Indexes Go package docs using the local Go toolchain (`go doc`).
+pub struct GoDocProvider {
+    project_root: Option<PathBuf>,
+}
+
+impl GoDocProvider {
+    pub fn id() -> ProviderId {
+        ProviderId("go".into())
+    }
+
+    pub fn new(project_root: Option<PathBuf>) -> Self {
+        Self { project_root }
+    }
+
+    async fn run_go(&self, args: &[&str]) -> Result<String> {
+        let mut command = smol::process::Command::new("go");
+        command.args(args);
+        if let Some(project_root) = self.project_root.as_ref() {
+            command.current_dir(project_root);
+        }
+
+        let output = command
+            .output()
+            .await
+            .context("failed to run `go`; is the Go toolchain installed?")?;
+        if !output.status.success() {
+            bail!(
+                "`go {}` failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(String::from_utf8(output.stdout)?)
+    }
+}
+
+#[async_trait]
+impl IndexedDocsProvider for GoDocProvider {
+    fn id(&self) -> ProviderId {
+        Self::id()
+    }
+
+    fn database_path(&self) -> PathBuf {
+        paths::support_dir().join("docs/go/godoc-db.0.mdb")
+    }
+
+    async fn suggest_packages(&self) -> Result<Vec<PackageName>> {
+        if self.project_root.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let output = self.run_go(&["list", "./..."]).await?;
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(PackageName::from)
+            .collect())
+    }
+
+    async fn index(&self, package: PackageName, database: Arc<IndexedDocsDatabase>) -> Result<()> {
+        let output = self.run_go(&["doc", "-all", package.as_ref()]).await?;
+        let (overview, items) = parse_go_doc_output(&output);
+        if overview.is_empty() && items.is_empty() {
+            return Err(anyhow!("no docs found for {package}"));
+        }
+
+        database.insert(package.to_string(), overview).await?;
+        for item in items {
+            database
+                .insert(format!("{package}::{}", item.name), item.docs)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_go_doc_output() {
+        let output = indoc! {"
+            package greeter // import \"example.com/greeter\"
+
+            Package greeter says hello.
+
+            FUNCTIONS
+
+            func Greet(name string) string
+                Greet returns a greeting for name.
+
+
+            TYPES
+
+            type Greeter struct {
+            	Name string
+            }
+                Greeter greets people.
+
+            func (g *Greeter) Greet() string
+                Greet greets the Greeter's name.
+
+        "};
+
+        let (overview, items) = parse_go_doc_output(output);
+        assert_eq!(
+            overview,
+            "package greeter // import \"example.com/greeter\"\n\nPackage greeter says hello."
+        );
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Greet", "Greeter", "Greeter.Greet"]
+        );
+        assert_eq!(
+            items[1].docs,
+            indoc! {"
+                ```go
+                type Greeter struct {
+                	Name string
+                }
+                    Greeter greets people.
+                ```"
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_go_doc_output_with_whitespace_only_lines() {
+        let output = "package greeter\n\nFUNCTIONS\n\nfunc Greet() string\n    Greet says hello.\n    \n    It is polite.\n";
+
+        let (_, items) = parse_go_doc_output(output);
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].docs,
+            "```go\nfunc Greet() string\n    Greet says hello.\n    \n    It is polite.\n```"
+        );
+    }
+}
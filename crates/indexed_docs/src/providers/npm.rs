@@ -0,0 +1,257 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use fs::Fs;
+use futures::StreamExt;
+use http::HttpClientWithUrl;
+use serde::Deserialize;
+
+use crate::providers::fetch_bytes;
+use crate::{IndexedDocsDatabase, IndexedDocsProvider, PackageName, ProviderId};
+
+#[derive(Debug, Deserialize)]
+struct NpmRegistryPackage {
+    #[serde(default)]
+    readme: Option<String>,
+}
+
+/// A reflection in TypeDoc's JSON output (as produced by `typedoc --json`).
+#[derive(Debug, Deserialize)]
+pub struct TypeDocReflection {
+    pub name: String,
+    #[serde(default)]
+    pub comment: Option<TypeDocComment>,
+    #[serde(default)]
+    pub signatures: Vec<TypeDocReflection>,
+    #[serde(default)]
+    pub children: Vec<TypeDocReflection>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TypeDocComment {
+    #[serde(default)]
+    pub summary: Vec<TypeDocCommentPart>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TypeDocCommentPart {
+    pub text: String,
+}
+
+impl TypeDocReflection {
+    fn summary(&self) -> Option<String> {
+        let comment = self
+            .comment
+            .as_ref()
+            .or_else(|| self.signatures.iter().find_map(|sig| sig.comment.as_ref()))?;
+        let summary = comment
+            .summary
+            .iter()
+            .map(|part| part.text.as_str())
+            .collect::<String>();
+        Some(summary)
+    }
+
+    /// Renders this reflection and its children as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n", self.name);
+        if let Some(summary) = self.summary() {
+            markdown.push('\n');
+            markdown.push_str(summary.trim());
+            markdown.push('\n');
+        }
+
+        for child in &self.children {
+            markdown.push_str(&format!("\n## {}\n", child.name));
+            if let Some(summary) = child.summary() {
+                markdown.push('\n');
+                markdown.push_str(summary.trim());
+                markdown.push('\n');
+            }
+        }
+
+        markdown
+    }
+}
+
+/// Indexes the docs for npm packages.
+///
+/// Each package's README is indexed from `node_modules` when it is installed
+/// locally, falling back to the npm registry. When the package ships TypeDoc
+/// JSON output (`docs.json`), each of its top-level exports is indexed too.
+pub struct NpmPackageProvider {
+    fs: Arc<dyn Fs>,
+    http_client: Arc<HttpClientWithUrl>,
+    project_root: Option<PathBuf>,
+}
+
+impl NpmPackageProvider {
+    pub fn id() -> ProviderId {
+        ProviderId("npm".into())
+    }
+
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        http_client: Arc<HttpClientWithUrl>,
+        project_root: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            fs,
+            http_client,
+            project_root,
+        }
+    }
+
+    fn local_package_dir(&self, package: &PackageName) -> Option<PathBuf> {
+        let project_root = self.project_root.as_ref()?;
+        Some(project_root.join("node_modules").join(package.as_ref()))
+    }
+
+    async fn load_local_readme(&self, package: &PackageName) -> Option<String> {
+        let package_dir = self.local_package_dir(package)?;
+        for file_name in ["README.md", "readme.md", "Readme.md", "README"] {
+            if let Ok(readme) = self.fs.load(&package_dir.join(file_name)).await {
+                return Some(readme);
+            }
+        }
+
+        None
+    }
+
+    async fn load_registry_readme(&self, package: &PackageName) -> Result<Option<String>> {
+        let Some(body) = fetch_bytes(
+            &self.http_client,
+            &format!("https://registry.npmjs.org/{package}"),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let package: NpmRegistryPackage =
+            serde_json::from_slice(&body).context("failed to parse npm registry response")?;
+        Ok(package.readme.filter(|readme| !readme.is_empty()))
+    }
+}
+
+#[async_trait]
+impl IndexedDocsProvider for NpmPackageProvider {
+    fn id(&self) -> ProviderId {
+        Self::id()
+    }
+
+    fn database_path(&self) -> PathBuf {
+        paths::support_dir().join("docs/npm/npm-db.0.mdb")
+    }
+
+    async fn suggest_packages(&self) -> Result<Vec<PackageName>> {
+        let Some(project_root) = self.project_root.as_ref() else {
+            return Ok(Vec::new());
+        };
+
+        let mut entries = self.fs.read_dir(&project_root.join("node_modules")).await?;
+        let mut packages = Vec::new();
+        while let Some(entry) = entries.next().await {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            let Some(name) = entry.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+
+            if name.starts_with('.') {
+                continue;
+            }
+
+            // Scoped packages (e.g., `@types/node`) live one directory deeper.
+            if name.starts_with('@') {
+                let mut scoped_entries = self.fs.read_dir(&entry).await?;
+                while let Some(scoped_entry) = scoped_entries.next().await {
+                    let Ok(scoped_entry) = scoped_entry else {
+                        continue;
+                    };
+                    if let Some(scoped_name) =
+                        scoped_entry.file_name().and_then(|name| name.to_str())
+                    {
+                        packages.push(PackageName::from(format!("{name}/{scoped_name}").as_str()));
+                    }
+                }
+            } else {
+                packages.push(PackageName::from(name));
+            }
+        }
+        packages.sort();
+
+        Ok(packages)
+    }
+
+    async fn index(&self, package: PackageName, database: Arc<IndexedDocsDatabase>) -> Result<()> {
+        let readme = match self.load_local_readme(&package).await {
+            Some(readme) => Some(readme),
+            None => self.load_registry_readme(&package).await?,
+        };
+        let readme = readme.ok_or_else(|| anyhow!("no README found for {package}"))?;
+
+        database.insert(package.to_string(), readme).await?;
+
+        if let Some(package_dir) = self.local_package_dir(&package) {
+            if let Ok(typedoc_json) = self.fs.load(&package_dir.join("docs.json")).await {
+                let project: TypeDocReflection = serde_json::from_str(&typedoc_json)
+                    .with_context(|| format!("failed to parse TypeDoc JSON for {package}"))?;
+                for reflection in &project.children {
+                    database
+                        .insert(
+                            format!("{package}::{}", reflection.name),
+                            reflection.to_markdown(),
+                        )
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_typedoc_reflection_to_markdown() {
+        let json = r#"{
+            "name": "Greeter",
+            "comment": { "summary": [{ "kind": "text", "text": "Greets " }, { "kind": "code", "text": "`people`" }] },
+            "children": [
+                {
+                    "name": "greet",
+                    "signatures": [
+                        { "name": "greet", "comment": { "summary": [{ "kind": "text", "text": "Says hello." }] } }
+                    ]
+                },
+                { "name": "name" }
+            ]
+        }"#;
+
+        let reflection: TypeDocReflection = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            reflection.to_markdown(),
+            indoc! {"
+                # Greeter
+
+                Greets `people`
+
+                ## greet
+
+                Says hello.
+
+                ## name
+            "}
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use std::cell::RefCell;
+use std::io::{BufRead, Read};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use collections::HashSet;
+use fs::Fs;
+use html_to_markdown::markdown::{
+    HeadingHandler, ListHandler, ParagraphHandler, StyledTextHandler, TableHandler,
+};
+use html_to_markdown::structure::sphinx::{
+    SphinxChromeRemover, SphinxCodeHandler, SphinxSignatureHandler,
+};
+use html_to_markdown::{convert_html_to_markdown, TagHandler};
+use http::HttpClientWithUrl;
+
+use crate::providers::fetch_bytes;
+use crate::{IndexedDocsDatabase, IndexedDocsProvider, PackageName, ProviderId};
+
+/// An entry in a Sphinx `objects.inv` inventory.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SphinxInventoryEntry {
+    /// The fully-qualified name of the object (e.g., `requests.get`).
+    pub name: String,
+    /// The domain of the object (e.g., `py` or `std`).
+    pub domain: String,
+    /// The role of the object within its domain (e.g., `function` or `doc`).
+    pub role: String,
+    /// The URI of the object, relative to the root of the docs.
+    pub uri: String,
+}
+
+impl SphinxInventoryEntry {
+    /// Returns the path to the page containing this entry, without the anchor.
+    pub fn page(&self) -> &str {
+        self.uri
+            .split_once('#')
+            .map_or(self.uri.as_str(), |(page, _anchor)| page)
+    }
+}
+
+/// A parsed Sphinx `objects.inv` inventory.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SphinxInventory {
+    pub project: String,
+    pub version: String,
+    pub entries: Vec<SphinxInventoryEntry>,
+}
+
+/// Parses a version 2 Sphinx inventory (`objects.inv`).
+pub fn parse_sphinx_inventory(mut bytes: &[u8]) -> Result<SphinxInventory> {
+    let mut read_header_line = |prefix: &str| -> Result<String> {
+        let mut line = String::new();
+        bytes.read_line(&mut line)?;
+        line.trim_end()
+            .strip_prefix(prefix)
+            .map(|value| value.trim().to_string())
+            .ok_or_else(|| anyhow!("invalid Sphinx inventory header: {line:?}"))
+    };
+
+    read_header_line("# Sphinx inventory version 2")?;
+    let project = read_header_line("# Project:")?;
+    let version = read_header_line("# Version:")?;
+    read_header_line("# The remainder of this file is compressed using zlib.")?;
+
+    let mut contents = String::new();
+    flate2::read::ZlibDecoder::new(bytes)
+        .read_to_string(&mut contents)
+        .context("failed to decompress Sphinx inventory")?;
+
+    let entries = contents
+        .lines()
+        .filter_map(parse_sphinx_inventory_line)
+        .collect();
+
+    Ok(SphinxInventory {
+        project,
+        version,
+        entries,
+    })
+}
+
+/// Parses a single inventory line of the form `name domain:role priority uri display_name`.
+fn parse_sphinx_inventory_line(line: &str) -> Option<SphinxInventoryEntry> {
+    // The name may contain spaces, so we locate the `domain:role` field by looking
+    // for the first field that is followed by a numeric priority.
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let role_ix = (1..fields.len().saturating_sub(2))
+        .find(|&ix| fields[ix].contains(':') && fields[ix + 1].parse::<i32>().is_ok())?;
+
+    let name = fields[..role_ix].join(" ");
+    let (domain, role) = fields[role_ix].split_once(':')?;
+    let uri = fields[role_ix + 2];
+
+    // Sphinx abbreviates URIs ending with the object name with a trailing `$`.
+    let uri = match uri.strip_suffix('$') {
+        Some(prefix) => format!("{prefix}{name}"),
+        None => uri.to_string(),
+    };
+
+    Some(SphinxInventoryEntry {
+        name,
+        domain: domain.to_string(),
+        role: role.to_string(),
+        uri,
+    })
+}
+
+/// Converts the provided Sphinx HTML to Markdown.
+pub fn convert_sphinx_to_markdown(html: impl Read) -> Result<String> {
+    let mut handlers: Vec<TagHandler> = vec![
+        Rc::new(RefCell::new(ParagraphHandler)),
+        Rc::new(RefCell::new(HeadingHandler)),
+        Rc::new(RefCell::new(ListHandler)),
+        Rc::new(RefCell::new(TableHandler::new())),
+        Rc::new(RefCell::new(StyledTextHandler)),
+        Rc::new(RefCell::new(SphinxChromeRemover)),
+        Rc::new(RefCell::new(SphinxSignatureHandler)),
+        Rc::new(RefCell::new(SphinxCodeHandler::new())),
+    ];
+
+    convert_html_to_markdown(html, &mut handlers)
+}
+
+/// The location of a Sphinx documentation build.
+enum SphinxDocsLocation {
+    /// A local Sphinx HTML build directory.
+    Local(PathBuf),
+    /// The base URL of hosted Sphinx documentation.
+    Remote(String),
+}
+
+/// Indexes Python documentation generated by Sphinx.
+///
+/// Packages are resolved against the project's local Sphinx build first, then
+/// the Python standard library docs, and finally Read the Docs.
+pub struct PythonDocsProvider {
+    fs: Arc<dyn Fs>,
+    http_client: Arc<HttpClientWithUrl>,
+    local_build_dir: Option<PathBuf>,
+}
+
+impl PythonDocsProvider {
+    pub fn id() -> ProviderId {
+        ProviderId("python".into())
+    }
+
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        http_client: Arc<HttpClientWithUrl>,
+        local_build_dir: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            fs,
+            http_client,
+            local_build_dir,
+        }
+    }
+
+    async fn local_inventory(&self) -> Option<(PathBuf, SphinxInventory)> {
+        let local_build_dir = self.local_build_dir.clone()?;
+        let bytes = self
+            .read_file(
+                &SphinxDocsLocation::Local(local_build_dir.clone()),
+                "objects.inv",
+            )
+            .await
+            .ok()??;
+        let inventory = parse_sphinx_inventory(&bytes).ok()?;
+        Some((local_build_dir, inventory))
+    }
+
+    async fn docs_location(&self, package: &PackageName) -> SphinxDocsLocation {
+        if let Some((local_build_dir, inventory)) = self.local_inventory().await {
+            if inventory.project.eq_ignore_ascii_case(package) {
+                return SphinxDocsLocation::Local(local_build_dir);
+            }
+        }
+
+        match package.as_ref() {
+            "python" | "stdlib" => {
+                SphinxDocsLocation::Remote("https://docs.python.org/3/".to_string())
+            }
+            package => SphinxDocsLocation::Remote(format!(
+                "https://{package}.readthedocs.io/en/stable/",
+                package = package.to_lowercase()
+            )),
+        }
+    }
+
+    async fn read_file(
+        &self,
+        location: &SphinxDocsLocation,
+        path: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        match location {
+            SphinxDocsLocation::Local(build_dir) => {
+                let path = build_dir.join(path);
+                if !self.fs.is_file(&path).await {
+                    return Ok(None);
+                }
+
+                let mut file = self.fs.open_sync(&path).await?;
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes)?;
+                Ok(Some(bytes))
+            }
+            SphinxDocsLocation::Remote(base_url) => {
+                fetch_bytes(&self.http_client, &format!("{base_url}{path}")).await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl IndexedDocsProvider for PythonDocsProvider {
+    fn id(&self) -> ProviderId {
+        Self::id()
+    }
+
+    fn database_path(&self) -> PathBuf {
+        paths::support_dir().join("docs/python/sphinx-db.0.mdb")
+    }
+
+    async fn suggest_packages(&self) -> Result<Vec<PackageName>> {
+        let mut packages = vec![PackageName::from("python")];
+        if let Some((_, inventory)) = self.local_inventory().await {
+            packages.push(PackageName::from(inventory.project.to_lowercase().as_str()));
+        }
+
+        Ok(packages)
+    }
+
+    async fn index(&self, package: PackageName, database: Arc<IndexedDocsDatabase>) -> Result<()> {
+        let location = self.docs_location(&package).await;
+        let inventory_bytes = self
+            .read_file(&location, "objects.inv")
+            .await?
+            .ok_or_else(|| anyhow!("no Sphinx inventory found for {package}"))?;
+        let inventory = parse_sphinx_inventory(&inventory_bytes)?;
+
+        if let Some(index_html) = self.read_file(&location, "index.html").await? {
+            let markdown = convert_sphinx_to_markdown(index_html.as_slice())?;
+            database.insert(package.to_string(), markdown).await?;
+        }
+
+        // Modules and standalone documents each have their own page, which also
+        // contains the docs for the classes and functions within them.
+        let mut seen_pages = HashSet::default();
+        for entry in &inventory.entries {
+            let is_page = match (entry.domain.as_str(), entry.role.as_str()) {
+                ("py", "module") | ("std", "doc") => true,
+                _ => false,
+            };
+            if !is_page || !seen_pages.insert(entry.page().to_string()) {
+                continue;
+            }
+
+            let Some(html) = self
+                .read_file(&location, entry.page())
+                .await
+                .with_context(|| format!("failed to fetch {}", entry.uri))?
+            else {
+                continue;
+            };
+
+            let markdown = convert_sphinx_to_markdown(html.as_slice())?;
+            database
+                .insert(format!("{package}::{}", entry.name), markdown)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn compress_inventory(header: &str, body: &str) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+
+        let mut bytes = header.as_bytes().to_vec();
+        bytes.extend(encoder.finish().unwrap());
+        bytes
+    }
+
+    #[test]
+    fn test_parse_sphinx_inventory() {
+        let header = indoc! {"
+            # Sphinx inventory version 2
+            # Project: Requests
+            # Version: 2.32
+            # The remainder of this file is compressed using zlib.
+        "};
+        let body = indoc! {"
+            requests py:module 0 api.html#module-$ -
+            requests.get py:function 1 api.html#$ -
+            api std:doc -1 api.html The API Reference
+            Quick start std:label -1 user/quickstart.html#quickstart Quickstart!
+        "};
+
+        let inventory = parse_sphinx_inventory(&compress_inventory(header, body)).unwrap();
+
+        assert_eq!(inventory.project, "Requests");
+        assert_eq!(inventory.version, "2.32");
+        assert_eq!(
+            inventory
+                .entries
+                .iter()
+                .map(|entry| (
+                    entry.name.as_str(),
+                    entry.domain.as_str(),
+                    entry.role.as_str(),
+                    entry.uri.as_str(),
+                    entry.page(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "requests",
+                    "py",
+                    "module",
+                    "api.html#module-requests",
+                    "api.html"
+                ),
+                (
+                    "requests.get",
+                    "py",
+                    "function",
+                    "api.html#requests.get",
+                    "api.html"
+                ),
+                ("api", "std", "doc", "api.html", "api.html"),
+                (
+                    "Quick start",
+                    "std",
+                    "label",
+                    "user/quickstart.html#quickstart",
+                    "user/quickstart.html"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sphinx_inventory_with_invalid_header() {
+        let bytes = compress_inventory("# Sphinx inventory version 1\n", "");
+        assert!(parse_sphinx_inventory(&bytes).is_err());
+    }
+}
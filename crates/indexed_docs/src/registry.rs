@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use collections::HashMap;
+use gpui::{AppContext, BackgroundExecutor, Global, ReadGlobal, UpdateGlobal};
+use parking_lot::RwLock;
+
+use crate::{IndexedDocsProvider, IndexedDocsStore, ProviderId};
+
+struct GlobalIndexedDocsRegistry(Arc<IndexedDocsRegistry>);
+
+impl Global for GlobalIndexedDocsRegistry {}
+
+/// The registry of [`IndexedDocsProvider`]s and their associated [`IndexedDocsStore`]s.
+pub struct IndexedDocsRegistry {
+    executor: BackgroundExecutor,
+    stores_by_provider: RwLock<HashMap<ProviderId, Arc<IndexedDocsStore>>>,
+}
+
+impl IndexedDocsRegistry {
+    pub fn global(cx: &AppContext) -> Arc<Self> {
+        GlobalIndexedDocsRegistry::global(cx).0.clone()
+    }
+
+    pub fn init_global(cx: &mut AppContext) {
+        GlobalIndexedDocsRegistry::set_global(
+            cx,
+            GlobalIndexedDocsRegistry(Arc::new(Self::new(cx.background_executor().clone()))),
+        );
+    }
+
+    pub fn new(executor: BackgroundExecutor) -> Self {
+        Self {
+            executor,
+            stores_by_provider: RwLock::new(HashMap::default()),
+        }
+    }
+
+    /// Returns the IDs of all of the registered providers.
+    pub fn list_providers(&self) -> Vec<ProviderId> {
+        let mut providers = self
+            .stores_by_provider
+            .read()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        providers.sort();
+        providers
+    }
+
+    /// Registers the given provider, replacing any existing provider with the same ID.
+    ///
+    /// A replaced provider's database is kept if the new provider uses the same one.
+    pub fn register_provider(
+        &self,
+        provider: Box<dyn IndexedDocsProvider + Send + Sync + 'static>,
+    ) {
+        let mut stores_by_provider = self.stores_by_provider.write();
+        let store = match stores_by_provider.get(&provider.id()) {
+            Some(existing_store) if existing_store.database_path() == provider.database_path() => {
+                existing_store.with_provider(provider)
+            }
+            _ => IndexedDocsStore::new(provider, self.executor.clone()),
+        };
+        stores_by_provider.insert(store.provider_id(), Arc::new(store));
+    }
+
+    pub fn unregister_provider(&self, provider_id: &ProviderId) {
+        self.stores_by_provider.write().remove(provider_id);
+    }
+
+    pub fn get_provider_store(&self, provider_id: ProviderId) -> Option<Arc<IndexedDocsStore>> {
+        self.stores_by_provider.read().get(&provider_id).cloned()
+    }
+}
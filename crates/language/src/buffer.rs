@@ -14,7 +14,7 @@ use crate::{
         SyntaxSnapshot, ToTreeSitterPoint,
     },
     task_context::RunnableRange,
-    LanguageScope, Outline, RunnableCapture, RunnableTag,
+    LanguageScope, Outline, RunnableCapture, RunnableTag, TextObject,
 };
 use anyhow::{anyhow, Context, Result};
 pub use clock::ReplicaId;
@@ -103,6 +103,7 @@ pub struct Buffer {
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
     parsing_in_background: bool,
+    parsing_idle_waiters: Vec<oneshot::Sender<()>>,
     non_text_state_update_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
@@ -113,6 +114,7 @@ pub struct Buffer {
     capability: Capability,
     has_conflict: bool,
     diff_base_version: usize,
+    large_file_mode: bool,
 }
 
 /// An immutable, cheaply cloneable representation of a fixed
@@ -371,8 +373,10 @@ pub trait LocalFile: File {
     /// Returns the absolute path of this file.
     fn abs_path(&self, cx: &AppContext) -> PathBuf;
 
-    /// Loads the file's contents from disk.
-    fn load(&self, cx: &AppContext) -> Task<Result<String>>;
+    /// Loads the file's contents from disk, decoding them with the given
+    /// encoding or, if none is given, with the one detected from the contents.
+    fn load(&self, encoding: Option<Encoding>, cx: &AppContext)
+        -> Task<Result<(String, Encoding)>>;
 
     /// Returns true if the file should not be shared with collaborators.
     fn is_private(&self, _: &AppContext) -> bool {
@@ -594,6 +598,8 @@ impl Buffer {
             rpc::proto::LineEnding::from_i32(message.line_ending)
                 .ok_or_else(|| anyhow!("missing line_ending"))?,
         ));
+        this.text
+            .set_encoding(proto::deserialize_encoding(message.encoding));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
         Ok(this)
@@ -609,6 +615,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: Some(proto::serialize_encoding(self.encoding())),
         }
     }
 
@@ -703,6 +710,7 @@ impl Buffer {
             capability,
             syntax_map: Mutex::new(SyntaxMap::new()),
             parsing_in_background: false,
+            parsing_idle_waiters: Vec::new(),
             non_text_state_update_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
             autoindent_requests: Default::default(),
@@ -715,6 +723,7 @@ impl Buffer {
             completion_triggers_timestamp: Default::default(),
             deferred_ops: OperationQueue::new(),
             has_conflict: false,
+            large_file_mode: false,
         }
     }
 
@@ -785,6 +794,41 @@ impl Buffer {
         self.syntax_map.lock().language_registry()
     }
 
+    /// Sets the encoding that will be used the next time the buffer is saved.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut ModelContext<Self>) {
+        self.text.set_encoding(encoding);
+        cx.notify();
+    }
+
+    /// Sets the line ending that will be used the next time the buffer is saved.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding, cx: &mut ModelContext<Self>) {
+        self.text.set_line_ending(line_ending);
+        cx.notify();
+    }
+
+    /// Whether the buffer is in large file mode, in which it isn't parsed or
+    /// diffed against its diff base.
+    pub fn large_file_mode(&self) -> bool {
+        self.large_file_mode
+    }
+
+    /// Puts the buffer in or out of large file mode.
+    pub fn set_large_file_mode(&mut self, large_file_mode: bool, cx: &mut ModelContext<Self>) {
+        self.large_file_mode = large_file_mode;
+        if large_file_mode {
+            self.syntax_map.lock().clear();
+            self.diff_base = None;
+            self.git_diff = git::diff::BufferDiff::new();
+            self.non_text_state_update_count += 1;
+        } else {
+            self.reparse(cx);
+            if let Some(recalc_task) = self.git_diff_recalc(cx) {
+                recalc_task.detach();
+            }
+        }
+        cx.notify();
+    }
+
     /// Assign the buffer a new [Capability].
     pub fn set_capability(&mut self, capability: Capability, cx: &mut ModelContext<Self>) {
         self.capability = capability;
@@ -809,19 +853,33 @@ impl Buffer {
     pub fn reload(
         &mut self,
         cx: &mut ModelContext<Self>,
+    ) -> oneshot::Receiver<Option<Transaction>> {
+        // Keep a non-UTF-8 encoding, since it usually can't be told apart from
+        // others by looking at the file's contents.
+        let encoding = Some(self.encoding()).filter(|encoding| !encoding.is_utf8());
+        self.reload_with_encoding(encoding, cx)
+    }
+
+    /// Reloads the contents of the buffer from disk, decoding them with the
+    /// given encoding or, if none is given, with the one detected from the
+    /// contents.
+    pub fn reload_with_encoding(
+        &mut self,
+        encoding: Option<Encoding>,
+        cx: &mut ModelContext<Self>,
     ) -> oneshot::Receiver<Option<Transaction>> {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(|this, mut cx| async move {
             let Some((new_mtime, new_text)) = this.update(&mut cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
-                Some((file.mtime(), file.load(cx)))
+                Some((file.mtime(), file.load(encoding, cx)))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
+            let (new_text, new_encoding) = new_text.await?;
             let diff = this
                 .update(&mut cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -831,7 +889,13 @@ impl Buffer {
                     this.apply_diff(diff, cx);
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
-                    this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
+                    this.did_reload(
+                        this.version(),
+                        this.line_ending(),
+                        new_encoding,
+                        new_mtime,
+                        cx,
+                    );
                 } else {
                     if !diff.edits.is_empty()
                         || this
@@ -842,7 +906,13 @@ impl Buffer {
                         this.has_conflict = true;
                     }
 
-                    this.did_reload(prev_version, this.line_ending(), this.saved_mtime, cx);
+                    this.did_reload(
+                        prev_version,
+                        this.line_ending(),
+                        this.encoding(),
+                        this.saved_mtime,
+                        cx,
+                    );
                 }
 
                 this.reload_task.take();
@@ -856,11 +926,13 @@ impl Buffer {
         &mut self,
         version: clock::Global,
         line_ending: LineEnding,
+        encoding: Encoding,
         mtime: Option<SystemTime>,
         cx: &mut ModelContext<Self>,
     ) {
         self.saved_version = version;
         self.text.set_line_ending(line_ending);
+        self.text.set_encoding(encoding);
         self.saved_mtime = mtime;
         cx.emit(Event::Reloaded);
         cx.notify();
@@ -913,6 +985,9 @@ impl Buffer {
     /// Sets the text that will be used to compute a Git diff
     /// against the buffer text.
     pub fn set_diff_base(&mut self, diff_base: Option<String>, cx: &mut ModelContext<Self>) {
+        if self.large_file_mode {
+            return;
+        }
         self.diff_base = diff_base
             .map(|mut raw_diff_base| {
                 LineEnding::normalize(&mut raw_diff_base);
@@ -940,6 +1015,9 @@ impl Buffer {
 
     /// Recomputes the Git diff status.
     pub fn git_diff_recalc(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<()>> {
+        if self.large_file_mode {
+            return None;
+        }
         let diff_base = self.diff_base.clone()?;
         let snapshot = self.snapshot();
 
@@ -988,6 +1066,20 @@ impl Buffer {
         self.parsing_in_background
     }
 
+    /// Waits until the buffer's syntax tree reflects its text, when it is
+    /// being parsed in the background.
+    pub fn parsing_idle(&mut self) -> impl 'static + Future<Output = ()> {
+        let (tx, rx) = oneshot::channel();
+        if self.parsing_in_background {
+            self.parsing_idle_waiters.push(tx);
+        } else {
+            tx.send(()).ok();
+        }
+        async move {
+            rx.await.ok();
+        }
+    }
+
     /// Indicates whether the buffer contains any regions that may be
     /// written in a language that hasn't been loaded yet.
     pub fn contains_unknown_injections(&self) -> bool {
@@ -1023,7 +1115,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if self.parsing_in_background || self.large_file_mode {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -1080,6 +1172,11 @@ impl Buffer {
                         if parse_again {
                             this.reparse(cx);
                         }
+                        if !this.parsing_in_background {
+                            for waiter in this.parsing_idle_waiters.drain(..) {
+                                waiter.send(()).ok();
+                            }
+                        }
                     })
                     .ok();
                 })
@@ -2981,6 +3078,63 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the syntax-aware text objects that intersect the given range,
+    /// as captured by the `textobjects.scm` queries of the buffer's languages.
+    /// Captures of the same object within one match are combined, so that e.g.
+    /// the statements of a function body form a single object.
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (Range<usize>, TextObject)> + '_ {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset_range, self, |grammar| {
+            grammar
+                .text_object_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.text_object_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut pending = Vec::<(Range<usize>, TextObject)>::new();
+        iter::from_fn(move || loop {
+            if let Some(text_object) = pending.pop() {
+                return Some(text_object);
+            }
+            let mat = syntax_matches.peek()?;
+            if let Some(config) = configs[mat.grammar_index] {
+                for capture in mat.captures {
+                    let Some(text_object) =
+                        config
+                            .text_objects_by_capture_ix
+                            .iter()
+                            .find_map(|(ix, text_object)| {
+                                (*ix == capture.index).then_some(*text_object)
+                            })
+                    else {
+                        continue;
+                    };
+                    let range = capture.node.byte_range();
+                    match pending
+                        .iter_mut()
+                        .find(|(_, pending)| *pending == text_object)
+                    {
+                        Some((pending_range, _)) => {
+                            pending_range.start = pending_range.start.min(range.start);
+                            pending_range.end = pending_range.end.max(range.end);
+                        }
+                        None => pending.push((range, text_object)),
+                    }
+                }
+            }
+            syntax_matches.advance();
+        })
+    }
+
     pub fn runnable_ranges(
         &self,
         range: Range<Anchor>,
@@ -1010,6 +1010,51 @@ fn test_enclosing_bracket_ranges_where_brackets_are_not_outermost_children(cx: &
     );
 }
 
+#[gpui::test]
+fn test_text_object_ranges(cx: &mut AppContext) {
+    let language = rust_lang()
+        .with_text_object_query(
+            r#"
+            (function_item
+                body: (_
+                    "{"
+                    (_)* @function.inside
+                    "}")) @function.around
+            (parameters
+                (_) @parameter.inside)
+            "#,
+        )
+        .unwrap();
+
+    cx.new_model(|cx| {
+        let text = "fn a(b: u8, c: u8) { let d = b; d + c }";
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(language), cx);
+        let snapshot = buffer.snapshot();
+
+        let ranges = snapshot
+            .text_object_ranges(0..text.len())
+            .collect::<Vec<_>>();
+        let texts = |object| {
+            let mut texts = ranges
+                .iter()
+                .filter(|(_, text_object)| *text_object == object)
+                .map(|(range, _)| &text[range.clone()])
+                .collect::<Vec<_>>();
+            texts.sort();
+            texts.dedup();
+            texts
+        };
+
+        assert_eq!(texts(TextObject::AroundFunction), vec![text]);
+        // The statements of the body are combined into a single object
+        assert!(texts(TextObject::InsideFunction).contains(&"let d = b; d + c"));
+        assert_eq!(texts(TextObject::InsideParameter), vec!["b: u8", "c: u8"]);
+        assert!(texts(TextObject::AroundClass).is_empty());
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_range_for_syntax_ancestor(cx: &mut AppContext) {
     cx.new_model(|cx| {
@@ -11,8 +11,9 @@ use schemars::{
     JsonSchema,
 };
 use serde::{Deserialize, Serialize};
-use settings::{Settings, SettingsLocation, SettingsSources};
+use settings::{EditorConfigProperties, Settings, SettingsLocation, SettingsSources};
 use std::{num::NonZeroU32, path::Path, sync::Arc};
+use text::LineEnding;
 use util::serde::default_true;
 
 impl<'a> Into<SettingsLocation<'a>> for &'a dyn File {
@@ -88,6 +89,8 @@ pub struct LanguageSettings {
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     pub ensure_final_newline_on_save: bool,
+    /// The line ending to convert a buffer to when saving it.
+    pub line_ending: LineEndingSetting,
     /// How to perform a buffer format.
     pub formatter: Formatter,
     /// Zed's Prettier integration settings.
@@ -268,6 +271,11 @@ pub struct LanguageSettingsContent {
     /// Default: true
     #[serde(default)]
     pub ensure_final_newline_on_save: Option<bool>,
+    /// The line ending to convert a buffer to when saving it.
+    ///
+    /// Default: detect
+    #[serde(default)]
+    pub line_ending: Option<LineEndingSetting>,
     /// How to perform a buffer format.
     ///
     /// Default: auto
@@ -374,6 +382,30 @@ pub enum SoftWrap {
     PreferredLineLength,
 }
 
+/// Controls which line ending a buffer is saved with.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEndingSetting {
+    /// Keep the line ending detected when the file was opened.
+    #[default]
+    Detect,
+    /// Use `\n` line endings.
+    Unix,
+    /// Use `\r\n` line endings.
+    Windows,
+}
+
+impl LineEndingSetting {
+    /// The line ending to save a buffer with, if it should be converted.
+    pub fn line_ending(&self) -> Option<LineEnding> {
+        match self {
+            LineEndingSetting::Detect => None,
+            LineEndingSetting::Unix => Some(LineEnding::Unix),
+            LineEndingSetting::Windows => Some(LineEnding::Windows),
+        }
+    }
+}
+
 /// Controls the behavior of formatting files when they are saved.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -819,6 +851,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.ensure_final_newline_on_save,
         src.ensure_final_newline_on_save,
     );
+    merge(&mut settings.line_ending, src.line_ending);
     merge(
         &mut settings.enable_language_server,
         src.enable_language_server,
@@ -860,10 +893,116 @@ pub struct PrettierSettings {
     pub options: HashMap<String, serde_json::Value>,
 }
 
+/// Converts the [EditorConfig](https://editorconfig.org) properties of a file
+/// into language settings, or returns `None` if none of them are supported.
+pub fn editorconfig_settings(properties: &EditorConfigProperties) -> Option<serde_json::Value> {
+    let property = |name: &str| properties.get(name).map(String::as_str);
+    let bool_property = |name: &str| match property(name) {
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        _ => None,
+    };
+    let size_property = |name: &str| property(name).and_then(|value| value.parse::<u32>().ok());
+
+    let mut settings = serde_json::Map::new();
+    let hard_tabs = match property("indent_style") {
+        Some("tab") => Some(true),
+        Some("space") => Some(false),
+        _ => None,
+    };
+    if let Some(hard_tabs) = hard_tabs {
+        settings.insert("hard_tabs".into(), hard_tabs.into());
+    }
+    // EditorConfig distinguishes the width of a tab from the number of columns
+    // to indent by, while `tab_size` is used for both.
+    let tab_size = if hard_tabs == Some(true) {
+        size_property("tab_width").or_else(|| size_property("indent_size"))
+    } else {
+        size_property("indent_size").or_else(|| size_property("tab_width"))
+    };
+    if let Some(tab_size) = tab_size.filter(|size| *size > 0) {
+        settings.insert("tab_size".into(), tab_size.into());
+    }
+    let line_ending = match property("end_of_line") {
+        Some("lf") => Some(LineEndingSetting::Unix),
+        Some("crlf") => Some(LineEndingSetting::Windows),
+        _ => None,
+    };
+    if let Some(line_ending) = line_ending {
+        settings.insert(
+            "line_ending".into(),
+            serde_json::to_value(line_ending).ok()?,
+        );
+    }
+    if let Some(insert_final_newline) = bool_property("insert_final_newline") {
+        settings.insert(
+            "ensure_final_newline_on_save".into(),
+            insert_final_newline.into(),
+        );
+    }
+    if let Some(trim_trailing_whitespace) = bool_property("trim_trailing_whitespace") {
+        settings.insert(
+            "remove_trailing_whitespace_on_save".into(),
+            trim_trailing_whitespace.into(),
+        );
+    }
+    if let Some(max_line_length) = size_property("max_line_length") {
+        settings.insert("preferred_line_length".into(), max_line_length.into());
+    }
+
+    (!settings.is_empty()).then_some(serde_json::Value::Object(settings))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    pub fn test_editorconfig_settings() {
+        let settings = |properties: &[(&str, &str)]| {
+            editorconfig_settings(
+                &properties
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+            )
+        };
+
+        assert_eq!(settings(&[]), None);
+        assert_eq!(
+            settings(&[("charset", "utf-8"), ("indent_size", "tab")]),
+            None
+        );
+        assert_eq!(
+            settings(&[
+                ("indent_style", "space"),
+                ("indent_size", "2"),
+                ("tab_width", "8"),
+                ("end_of_line", "crlf"),
+                ("insert_final_newline", "false"),
+                ("trim_trailing_whitespace", "true"),
+                ("max_line_length", "100"),
+            ]),
+            Some(serde_json::json!({
+                "hard_tabs": false,
+                "tab_size": 2,
+                "line_ending": "windows",
+                "ensure_final_newline_on_save": false,
+                "remove_trailing_whitespace_on_save": true,
+                "preferred_line_length": 100,
+            }))
+        );
+        assert_eq!(
+            settings(&[
+                ("indent_style", "tab"),
+                ("indent_size", "tab"),
+                ("tab_width", "8"),
+                ("max_line_length", "off"),
+            ]),
+            Some(serde_json::json!({ "hard_tabs": true, "tab_size": 8 }))
+        );
+    }
+
     #[test]
     pub fn test_resolve_language_servers() {
         fn language_server_names(names: &[&str]) -> Vec<LanguageServerName> {
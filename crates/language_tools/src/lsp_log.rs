@@ -763,6 +763,8 @@ impl SearchableItem for LspLogView {
             case: true,
             word: true,
             regex: true,
+            structural: false,
+            syntax_context: false,
             // LSP log is read-only.
             replacement: false,
             selection: false,
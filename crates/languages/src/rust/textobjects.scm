@@ -0,0 +1,61 @@
+(function_item
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(closure_expression
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(closure_expression) @function.around
+
+(function_signature_item) @function.around
+
+(struct_item
+    body: (_
+        ["{" "("]
+        (_)* @class.inside
+        ["}" ")"])) @class.around
+
+(enum_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(trait_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(impl_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(mod_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(line_comment)+ @comment.around
+
+(block_comment) @comment.around
+
+(parameters
+    [(parameter) (self_parameter)] @parameter.inside)
+
+(closure_parameters
+    (_) @parameter.inside)
+
+(arguments
+    (_) @parameter.inside)
+
+(type_arguments
+    (_) @parameter.inside)
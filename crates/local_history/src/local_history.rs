@@ -0,0 +1,308 @@
+mod local_history_picker;
+mod persistence;
+
+use std::{collections::hash_map, path::PathBuf, time::Duration};
+
+use collections::HashMap;
+use editor::{Editor, EditorMode};
+use gpui::{
+    actions, AppContext, Context, EntityId, Model, ModelContext, Subscription, Task, ViewContext,
+    WeakModel,
+};
+use language::{Buffer, BufferSnapshot};
+use local_history_picker::LocalHistoryPicker;
+use persistence::DB;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources, SettingsStore};
+use time::OffsetDateTime;
+use util::ResultExt;
+
+actions!(local_history, [ShowLocalHistory]);
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+pub fn init(cx: &mut AppContext) {
+    LocalHistorySettings::register(cx);
+
+    let local_history = cx.new_model(LocalHistory::new);
+    cx.observe_new_views(move |editor: &mut Editor, cx: &mut ViewContext<Editor>| {
+        if editor.mode() != EditorMode::Full {
+            return;
+        }
+        if let Some(buffer) = editor.buffer().read(cx).as_singleton() {
+            local_history.update(cx, |local_history, cx| {
+                local_history.track_buffer(buffer, cx)
+            });
+        }
+    })
+    .detach();
+    cx.observe_new_views(LocalHistoryPicker::register).detach();
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LocalHistorySettings {
+    pub enabled: bool,
+    pub snapshot_interval_minutes: u64,
+    pub max_snapshots_per_file: usize,
+    pub max_age_days: u64,
+    pub max_file_size_kb: u64,
+    pub max_total_size_mb: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct LocalHistorySettingsContent {
+    /// Whether to keep snapshots of files when they're saved.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// How often, in minutes, to also snapshot files that were edited since
+    /// their last snapshot. 0 disables periodic snapshots.
+    ///
+    /// Default: 0
+    pub snapshot_interval_minutes: Option<u64>,
+    /// The number of snapshots to keep for each file.
+    ///
+    /// Default: 50
+    pub max_snapshots_per_file: Option<usize>,
+    /// The number of days after which snapshots are deleted.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u64>,
+    /// Files larger than this many kilobytes aren't snapshotted.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<u64>,
+    /// The total size, in megabytes, of the snapshots of all files. The
+    /// oldest snapshots are deleted once it's exceeded.
+    ///
+    /// Default: 100
+    pub max_total_size_mb: Option<u64>,
+}
+
+impl Settings for LocalHistorySettings {
+    const KEY: Option<&'static str> = Some("local_history");
+
+    type FileContent = LocalHistorySettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
+
+/// Stores snapshots of the files open in editors when they're saved and,
+/// optionally, periodically while they're edited.
+struct LocalHistory {
+    buffers: HashMap<EntityId, TrackedBuffer>,
+    snapshot_interval_minutes: u64,
+    snapshot_task: Option<Task<()>>,
+    _settings_subscription: Subscription,
+}
+
+struct TrackedBuffer {
+    buffer: WeakModel<Buffer>,
+    /// The contents the buffer was opened with, which are stored along with
+    /// its first snapshot so that they can be restored after that.
+    initial_snapshot: Option<BufferSnapshot>,
+    /// The version of the buffer when it was last snapshotted.
+    snapshot_version: clock::Global,
+    _subscriptions: [Subscription; 2],
+}
+
+impl LocalHistory {
+    fn new(cx: &mut ModelContext<Self>) -> Self {
+        let mut this = Self {
+            buffers: HashMap::default(),
+            snapshot_interval_minutes: 0,
+            snapshot_task: None,
+            _settings_subscription: cx
+                .observe_global::<SettingsStore>(|this, cx| this.settings_changed(cx)),
+        };
+        this.settings_changed(cx);
+        this
+    }
+
+    fn settings_changed(&mut self, cx: &mut ModelContext<Self>) {
+        let settings = LocalHistorySettings::get_global(cx);
+        let interval_minutes = if settings.enabled {
+            settings.snapshot_interval_minutes
+        } else {
+            0
+        };
+        if interval_minutes == self.snapshot_interval_minutes {
+            return;
+        }
+
+        self.snapshot_interval_minutes = interval_minutes;
+        self.snapshot_task = (interval_minutes > 0).then(|| {
+            cx.spawn(|this, mut cx| async move {
+                loop {
+                    cx.background_executor()
+                        .timer(Duration::from_secs(interval_minutes * 60))
+                        .await;
+                    if this
+                        .update(&mut cx, |this, cx| this.snapshot_edited_buffers(cx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            })
+        });
+    }
+
+    fn track_buffer(&mut self, buffer: Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer_id = buffer.entity_id();
+        let hash_map::Entry::Vacant(entry) = self.buffers.entry(buffer_id) else {
+            return;
+        };
+
+        let initial_snapshot = (!buffer.read(cx).is_dirty()).then(|| buffer.read(cx).snapshot());
+        entry.insert(TrackedBuffer {
+            buffer: buffer.downgrade(),
+            initial_snapshot,
+            snapshot_version: buffer.read(cx).version(),
+            _subscriptions: [
+                cx.subscribe(&buffer, |this, buffer, event: &language::Event, cx| {
+                    if let language::Event::Saved = event {
+                        this.snapshot_buffer(&buffer, cx);
+                    }
+                }),
+                cx.observe_release(&buffer, move |this, _, _| {
+                    this.buffers.remove(&buffer_id);
+                }),
+            ],
+        });
+    }
+
+    fn snapshot_edited_buffers(&mut self, cx: &mut ModelContext<Self>) {
+        let edited_buffers = self
+            .buffers
+            .values()
+            .filter_map(|tracked| {
+                let buffer = tracked.buffer.upgrade()?;
+                let edited = buffer
+                    .read(cx)
+                    .version()
+                    .changed_since(&tracked.snapshot_version);
+                edited.then_some(buffer)
+            })
+            .collect::<Vec<_>>();
+        for buffer in edited_buffers {
+            self.snapshot_buffer(&buffer, cx);
+        }
+    }
+
+    fn snapshot_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let settings = *LocalHistorySettings::get_global(cx);
+        if !settings.enabled {
+            return;
+        }
+        let Some(tracked) = self.buffers.get_mut(&buffer.entity_id()) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let Some(path) = snapshot_path(buffer, &settings, cx) else {
+            return;
+        };
+
+        tracked.snapshot_version = buffer.version();
+        let contents = tracked
+            .initial_snapshot
+            .take()
+            .map(|snapshot| snapshot.text())
+            .into_iter()
+            .chain([buffer.text()])
+            .collect::<Vec<_>>();
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let min_timestamp = timestamp - settings.max_age_days as i64 * SECONDS_PER_DAY;
+        let max_total_size = settings.max_total_size_mb as usize * 1024 * 1024;
+        cx.background_executor()
+            .spawn(async move {
+                for contents in contents {
+                    DB.save_snapshot(
+                        path.clone(),
+                        timestamp,
+                        contents,
+                        settings.max_snapshots_per_file,
+                        min_timestamp,
+                        max_total_size,
+                    )
+                    .await
+                    .log_err();
+                }
+            })
+            .detach();
+    }
+}
+
+/// Returns the path under which to store a snapshot of the buffer, unless it
+/// shouldn't be snapshotted: it isn't backed by a local file, its file is
+/// private, or it's too large.
+fn snapshot_path(
+    buffer: &Buffer,
+    settings: &LocalHistorySettings,
+    cx: &AppContext,
+) -> Option<PathBuf> {
+    let file = buffer.file()?;
+    if file.is_private() || buffer.len() as u64 > settings.max_file_size_kb * 1024 {
+        return None;
+    }
+    Some(file.as_local()?.abs_path(cx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_snapshot_path(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            LocalHistorySettings::register(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".env": "SECRET=1",
+                "large.txt": "a".repeat(2048),
+                "small.txt": "a",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let settings = LocalHistorySettings {
+            max_file_size_kb: 1,
+            ..cx.update(|cx| *LocalHistorySettings::get_global(cx))
+        };
+
+        for (path, expected_path) in [
+            ("/root/.env", None),
+            ("/root/large.txt", None),
+            ("/root/small.txt", Some(PathBuf::from("/root/small.txt"))),
+        ] {
+            let buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(path, cx))
+                .await
+                .unwrap();
+            buffer.read_with(cx, |buffer, cx| {
+                assert_eq!(
+                    snapshot_path(buffer, &settings, cx),
+                    expected_path,
+                    "{path}"
+                );
+            });
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::sync::Arc;
+
+use diff_view::DiffView;
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, SharedString, Styled, View, ViewContext, VisualContext, WeakView,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{persistence::DB, ShowLocalHistory};
+
+/// Lists the snapshots of the active file, to compare them with the file or
+/// restore them.
+pub(crate) struct LocalHistoryPicker {
+    picker: View<Picker<LocalHistoryPickerDelegate>>,
+}
+
+impl LocalHistoryPicker {
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::toggle);
+    }
+
+    fn toggle(workspace: &mut Workspace, _: &ShowLocalHistory, cx: &mut ViewContext<Workspace>) {
+        let Some((_, buffer, _)) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+            .and_then(|editor| editor.read(cx).active_excerpt(cx))
+        else {
+            return;
+        };
+        let Some(file) = buffer.read(cx).file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        let path = file.abs_path(cx);
+        let file_name = SharedString::from(file.file_name(cx).to_string_lossy().to_string());
+        let Some(snapshots) = DB.snapshots(&path).log_err() else {
+            return;
+        };
+
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let now = OffsetDateTime::now_utc();
+            let timezone = cx.local_timezone();
+            let snapshots = snapshots
+                .into_iter()
+                .filter_map(|(id, timestamp, size)| {
+                    let timestamp = OffsetDateTime::from_unix_timestamp(timestamp).ok()?;
+                    Some(SnapshotEntry {
+                        id,
+                        size,
+                        label: time_format::format_localized_timestamp(
+                            timestamp,
+                            now,
+                            timezone,
+                            TimestampFormat::EnhancedAbsolute,
+                        ),
+                        age: time_format::format_localized_timestamp(
+                            timestamp,
+                            now,
+                            timezone,
+                            TimestampFormat::Relative,
+                        ),
+                    })
+                })
+                .collect();
+            let delegate = LocalHistoryPickerDelegate::new(
+                cx.view().downgrade(),
+                workspace_handle,
+                buffer,
+                file_name,
+                snapshots,
+            );
+            let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+            Self { picker }
+        });
+    }
+}
+
+impl Render for LocalHistoryPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for LocalHistoryPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LocalHistoryPicker {}
+impl ModalView for LocalHistoryPicker {}
+
+struct SnapshotEntry {
+    id: i64,
+    size: usize,
+    label: String,
+    age: String,
+}
+
+pub(crate) struct LocalHistoryPickerDelegate {
+    local_history_picker: WeakView<LocalHistoryPicker>,
+    workspace: WeakView<Workspace>,
+    buffer: Model<Buffer>,
+    file_name: SharedString,
+    snapshots: Vec<SnapshotEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl LocalHistoryPickerDelegate {
+    fn new(
+        local_history_picker: WeakView<LocalHistoryPicker>,
+        workspace: WeakView<Workspace>,
+        buffer: Model<Buffer>,
+        file_name: SharedString,
+        snapshots: Vec<SnapshotEntry>,
+    ) -> Self {
+        Self {
+            local_history_picker,
+            workspace,
+            buffer,
+            file_name,
+            snapshots,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for LocalHistoryPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        format!("Local history of {}...", self.file_name).into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.snapshots.is_empty() {
+            "No snapshots of this file".into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(snapshot) = self
+            .matches
+            .get(self.selected_index)
+            .map(|mat| &self.snapshots[mat.candidate_id])
+        else {
+            return;
+        };
+        let Some(contents) = DB.snapshot_contents(snapshot.id).log_err().flatten() else {
+            return;
+        };
+
+        let buffer = self.buffer.clone();
+        if secondary {
+            restore_snapshot(buffer, contents, cx);
+        } else {
+            let title = format!("{} @ {}", self.file_name, snapshot.label);
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    DiffView::open_for_text(contents, title.into(), buffer, workspace, cx);
+                })
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.local_history_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .snapshots
+            .iter()
+            .enumerate()
+            .map(|(id, snapshot)| StringMatchCandidate::new(id, snapshot.label.clone()))
+            .collect::<Vec<_>>();
+        let max_results = candidates.len();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    max_results,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the timeline in order rather than by score.
+                matches.sort_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let snapshot = &self.snapshots[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    snapshot.label.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(
+                    Label::new(format!("{} · {}", snapshot.age, format_size(snapshot.size)))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(
+            h_flex()
+                .w_full()
+                .h_8()
+                .p_2()
+                .gap_2()
+                .justify_end()
+                .rounded_b_md()
+                .bg(cx.theme().colors().ghost_element_selected)
+                .child(
+                    Button::new("compare-snapshot", "Compare")
+                        .label_size(LabelSize::Small)
+                        .when_some(
+                            KeyBinding::for_action(&menu::Confirm, cx),
+                            |this, keybind| this.key_binding(keybind),
+                        )
+                        .on_click(|_, cx| cx.dispatch_action(menu::Confirm.boxed_clone())),
+                )
+                .child(
+                    Button::new("restore-snapshot", "Restore")
+                        .label_size(LabelSize::Small)
+                        .when_some(
+                            KeyBinding::for_action(&menu::SecondaryConfirm, cx),
+                            |this, keybind| this.key_binding(keybind),
+                        )
+                        .on_click(|_, cx| cx.dispatch_action(menu::SecondaryConfirm.boxed_clone())),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+/// Replaces the buffer's contents with a snapshot's, as a single edit that
+/// can be undone.
+fn restore_snapshot(buffer: Model<Buffer>, contents: String, cx: &mut WindowContext) {
+    let diff = buffer.read(cx).diff(contents, cx);
+    cx.spawn(|mut cx| async move {
+        let diff = diff.await;
+        buffer.update(&mut cx, |buffer, cx| {
+            buffer.finalize_last_transaction();
+            buffer.apply_diff(diff, cx);
+            buffer.finalize_last_transaction();
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.;
+    const MB: f64 = KB * 1024.;
+    let bytes_f64 = bytes as f64;
+    if bytes_f64 >= MB {
+        format!("{:.1} MB", bytes_f64 / MB)
+    } else if bytes_f64 >= KB {
+        format!("{:.1} KB", bytes_f64 / KB)
+    } else {
+        format!("{bytes} B")
+    }
+}
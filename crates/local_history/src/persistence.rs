@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+
+define_connection!(
+    // Current schema shape using pseudo-rust syntax:
+    // local_history(
+    //   snapshot_id: usize,
+    //   path: PathBuf,
+    //   timestamp: i64,
+    //   contents: String,
+    // )
+    pub static ref DB: LocalHistoryDb<()> =
+        &[sql! (
+            CREATE TABLE local_history(
+                snapshot_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path BLOB NOT NULL,
+                timestamp INTEGER NOT NULL,
+                contents TEXT NOT NULL
+            ) STRICT;
+
+            CREATE INDEX local_history_path ON local_history(path);
+            CREATE INDEX local_history_timestamp ON local_history(timestamp);
+        )];
+);
+
+impl LocalHistoryDb {
+    // Returns the id, timestamp and size in bytes of the snapshots of a file,
+    // newest first.
+    query! {
+        pub fn snapshots(path: &Path) -> Result<Vec<(i64, i64, usize)>> {
+            SELECT snapshot_id, timestamp, length(CAST(contents AS BLOB))
+            FROM local_history
+            WHERE path = ?
+            ORDER BY snapshot_id DESC
+        }
+    }
+
+    query! {
+        pub fn snapshot_contents(snapshot_id: i64) -> Result<Option<String>> {
+            SELECT contents
+            FROM local_history
+            WHERE snapshot_id = ?
+        }
+    }
+
+    /// Stores a snapshot of the file at `path`, unless its contents match the
+    /// file's latest snapshot. Snapshots of any file taken before
+    /// `min_timestamp`, snapshots of this file beyond the `max_snapshots`
+    /// newest ones, and the oldest snapshots of any file once their total size
+    /// exceeds `max_total_size` bytes, are deleted.
+    pub async fn save_snapshot(
+        &self,
+        path: PathBuf,
+        timestamp: i64,
+        contents: String,
+        max_snapshots: usize,
+        min_timestamp: i64,
+        max_total_size: usize,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_snapshot", || {
+                let latest_contents = conn.select_row_bound::<&Path, String>(sql!(
+                    SELECT contents FROM local_history
+                    WHERE path = ?
+                    ORDER BY snapshot_id DESC
+                    LIMIT 1
+                ))?(&path)?;
+                if latest_contents.as_ref() != Some(&contents) {
+                    conn.exec_bound(sql!(
+                        INSERT INTO local_history(path, timestamp, contents)
+                        VALUES (?, ?, ?)
+                    ))?((path.as_path(), timestamp, contents))?;
+                }
+
+                conn.exec_bound(sql!(DELETE FROM local_history WHERE timestamp < ?))?(
+                    min_timestamp,
+                )?;
+                conn.exec_bound(sql!(
+                    DELETE FROM local_history
+                    WHERE path = ?1 AND snapshot_id NOT IN (
+                        SELECT snapshot_id FROM local_history
+                        WHERE path = ?1
+                        ORDER BY snapshot_id DESC
+                        LIMIT ?2
+                    )
+                ))?((path.as_path(), max_snapshots))?;
+                conn.exec_bound(sql!(
+                    DELETE FROM local_history
+                    WHERE snapshot_id IN (
+                        SELECT snapshot_id FROM (
+                            SELECT
+                                snapshot_id,
+                                SUM(length(CAST(contents AS BLOB)))
+                                    OVER (ORDER BY snapshot_id DESC) AS total_size
+                            FROM local_history
+                        )
+                        WHERE total_size > ?
+                    )
+                ))?(max_total_size)?;
+                Ok(())
+            })
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_save_snapshot() {
+        let db = LocalHistoryDb(db::open_test_db("test_save_snapshot").await);
+        let path_a = PathBuf::from("/root/a.txt");
+        let path_b = PathBuf::from("/root/b.txt");
+        let timestamps = |path: &Path| {
+            db.snapshots(path)
+                .unwrap()
+                .into_iter()
+                .map(|(_, timestamp, _)| timestamp)
+                .collect::<Vec<_>>()
+        };
+
+        db.save_snapshot(path_a.clone(), 10, "one".into(), 2, 0, 1024)
+            .await
+            .unwrap();
+        db.save_snapshot(path_b.clone(), 15, "bee".into(), 2, 0, 1024)
+            .await
+            .unwrap();
+        // Unchanged contents aren't stored again.
+        db.save_snapshot(path_a.clone(), 20, "one".into(), 2, 0, 1024)
+            .await
+            .unwrap();
+        assert_eq!(timestamps(&path_a), [10]);
+
+        // Only the newest snapshots of a file are kept.
+        db.save_snapshot(path_a.clone(), 30, "two".into(), 2, 0, 1024)
+            .await
+            .unwrap();
+        db.save_snapshot(path_a.clone(), 40, "three".into(), 2, 0, 1024)
+            .await
+            .unwrap();
+        assert_eq!(timestamps(&path_a), [40, 30]);
+        assert_eq!(timestamps(&path_b), [15]);
+
+        // Old snapshots of every file are deleted.
+        db.save_snapshot(path_a.clone(), 50, "four".into(), 3, 35, 1024)
+            .await
+            .unwrap();
+        assert_eq!(timestamps(&path_a), [50, 40]);
+        assert_eq!(timestamps(&path_b), Vec::<i64>::new());
+
+        let (snapshot_id, _, size) = db.snapshots(&path_a).unwrap()[1];
+        assert_eq!(size, 5);
+        assert_eq!(
+            db.snapshot_contents(snapshot_id).unwrap().as_deref(),
+            Some("three")
+        );
+
+        // The oldest snapshots of any file are deleted once their total size
+        // exceeds the limit.
+        db.save_snapshot(path_b.clone(), 60, "bee".into(), 3, 0, 1024)
+            .await
+            .unwrap();
+        db.save_snapshot(path_a.clone(), 70, "five".into(), 3, 0, 12)
+            .await
+            .unwrap();
+        assert_eq!(timestamps(&path_a), [70, 50]);
+        assert_eq!(timestamps(&path_b), [60]);
+    }
+}
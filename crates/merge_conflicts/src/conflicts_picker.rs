@@ -0,0 +1,337 @@
+use std::sync::Arc;
+
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use git::conflict::parse_conflicts;
+use gpui::{
+    Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, SharedString, Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use project::{Item as _, ProjectPath};
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace};
+
+use crate::{mark_buffer_resolved, open_conflicted_file, ShowConflicts};
+
+/// Lists the files with merge conflicts, to open them or to mark them as
+/// resolved.
+pub(crate) struct ConflictsPicker {
+    picker: View<Picker<ConflictsPickerDelegate>>,
+}
+
+impl ConflictsPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &ShowConflicts,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let delegate = ConflictsPickerDelegate::new(cx.view().downgrade(), workspace_handle);
+            let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+            picker.update(cx, |picker, cx| picker.delegate.load_files(cx));
+            Self { picker }
+        });
+    }
+}
+
+impl Render for ConflictsPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for ConflictsPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for ConflictsPicker {}
+impl ModalView for ConflictsPicker {}
+
+struct ConflictedFile {
+    project_path: ProjectPath,
+    buffer: Model<Buffer>,
+    /// The file's path relative to its worktree.
+    path: String,
+    /// The number of conflict regions left in the file.
+    conflicts: usize,
+    /// Whether git reports the file as conflicted, rather than it only
+    /// containing conflict markers.
+    is_unmerged: bool,
+}
+
+pub(crate) struct ConflictsPickerDelegate {
+    conflicts_picker: WeakView<ConflictsPicker>,
+    workspace: WeakView<Workspace>,
+    files: Vec<ConflictedFile>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    load_task: Task<()>,
+}
+
+impl ConflictsPickerDelegate {
+    fn new(conflicts_picker: WeakView<ConflictsPicker>, workspace: WeakView<Workspace>) -> Self {
+        Self {
+            conflicts_picker,
+            workspace,
+            files: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            load_task: Task::ready(()),
+        }
+    }
+
+    /// Opens the files git reports as conflicted, and looks for conflict
+    /// markers in them and in the other open files.
+    fn load_files(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        let unmerged_paths = project.read(cx).conflicted_paths(cx).collect::<Vec<_>>();
+        let open_tasks = unmerged_paths
+            .iter()
+            .map(|path| project.update(cx, |project, cx| project.open_buffer(path.clone(), cx)))
+            .collect::<Vec<_>>();
+
+        self.load_task = cx.spawn(|picker, mut cx| async move {
+            let mut buffers = Vec::new();
+            for open_task in open_tasks {
+                if let Some(buffer) = open_task.await.log_err() {
+                    buffers.push((buffer, true));
+                }
+            }
+            picker
+                .update(&mut cx, |picker, cx| {
+                    for buffer in project.read(cx).opened_buffers() {
+                        if !buffers.iter().any(|(opened, _)| opened == &buffer) {
+                            buffers.push((buffer, false));
+                        }
+                    }
+
+                    let mut files = buffers
+                        .into_iter()
+                        .filter_map(|(buffer, is_unmerged)| {
+                            let project_path = buffer.read(cx).project_path(cx)?;
+                            let conflicts = parse_conflicts(&buffer.read(cx).text()).len();
+                            if conflicts == 0 && !is_unmerged {
+                                return None;
+                            }
+                            Some(ConflictedFile {
+                                path: project_path.path.to_string_lossy().to_string(),
+                                project_path,
+                                buffer,
+                                conflicts,
+                                is_unmerged,
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    files.sort_by(|a, b| a.path.cmp(&b.path));
+                    picker.delegate.files = files;
+                    picker.refresh(cx);
+                })
+                .log_err();
+        });
+    }
+}
+
+impl PickerDelegate for ConflictsPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search conflicted files...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.files.is_empty() {
+            "No conflicts".into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(mat) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let file = &self.files[mat.candidate_id];
+
+        if secondary {
+            // Files that merely contain markers aren't tracked as conflicted.
+            if !file.is_unmerged {
+                return;
+            }
+            let Some(workspace) = self.workspace.upgrade() else {
+                return;
+            };
+            let project = workspace.read(cx).project().clone();
+            let project_path = file.project_path.clone();
+            let mark_task = mark_buffer_resolved(project, file.buffer.clone(), cx);
+            // Keep the picker open, so that several files can be marked.
+            cx.spawn(|picker, mut cx| async move {
+                mark_task.await?;
+                picker.update(&mut cx, |picker, cx| {
+                    picker
+                        .delegate
+                        .files
+                        .retain(|file| file.project_path != project_path);
+                    picker.refresh(cx);
+                })
+            })
+            .detach_and_prompt_err(
+                "Failed to mark conflicts as resolved",
+                cx,
+                |error, _| Some(error.to_string()),
+            );
+        } else {
+            let project_path = file.project_path.clone();
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_conflicted_file(workspace, project_path, cx)
+                })
+                .log_err();
+            self.dismissed(cx);
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.conflicts_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(id, file)| StringMatchCandidate::new(id, file.path.clone()))
+            .collect::<Vec<_>>();
+        let max_results = candidates.len();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    max_results,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let file = &self.files[mat.candidate_id];
+        let status = match (file.conflicts, file.is_unmerged) {
+            (0, _) => "No conflicts left".to_string(),
+            (1, true) => "1 conflict".to_string(),
+            (1, false) => "1 conflict, not tracked by git".to_string(),
+            (count, true) => format!("{count} conflicts"),
+            (count, false) => format!("{count} conflicts, not tracked by git"),
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            file.path.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(status)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let file = &self.files[self.matches.get(self.selected_index)?.candidate_id];
+        Some(
+            h_flex()
+                .w_full()
+                .h_8()
+                .p_2()
+                .gap_2()
+                .justify_end()
+                .rounded_b_md()
+                .bg(cx.theme().colors().ghost_element_selected)
+                .child(
+                    Button::new("open-conflicted-file", "Open")
+                        .label_size(LabelSize::Small)
+                        .when_some(
+                            KeyBinding::for_action(&menu::Confirm, cx),
+                            |this, keybind| this.key_binding(keybind),
+                        )
+                        .on_click(|_, cx| cx.dispatch_action(menu::Confirm.boxed_clone())),
+                )
+                .child(
+                    Button::new("mark-conflict-resolved", "Mark Resolved")
+                        .label_size(LabelSize::Small)
+                        .disabled(!file.is_unmerged || file.conflicts > 0)
+                        .when_some(
+                            KeyBinding::for_action(&menu::SecondaryConfirm, cx),
+                            |this, keybind| this.key_binding(keybind),
+                        )
+                        .on_click(|_, cx| cx.dispatch_action(menu::SecondaryConfirm.boxed_clone())),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -0,0 +1,148 @@
+mod conflicts_picker;
+
+use anyhow::{anyhow, Context as _, Result};
+use conflicts_picker::ConflictsPicker;
+use diff_view::DiffView;
+use editor::{actions::CompareConflict, scroll::Autoscroll, Editor};
+use git::conflict::parse_conflicts;
+use gpui::{actions, AppContext, Model, SharedString, Task, ViewContext, WindowContext};
+use language::Buffer;
+use project::{Item as _, Project, ProjectPath};
+use workspace::{notifications::DetachAndPromptErr, Workspace};
+
+actions!(merge_conflicts, [ShowConflicts, MarkConflictResolved]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(register).detach();
+}
+
+fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(ConflictsPicker::toggle);
+    workspace.register_action(compare_conflict);
+    workspace.register_action(|workspace, _: &MarkConflictResolved, cx| {
+        let Some(buffer) = active_buffer(workspace, cx) else {
+            return;
+        };
+        mark_buffer_resolved(workspace.project().clone(), buffer, cx).detach_and_prompt_err(
+            "Failed to mark conflicts as resolved",
+            cx,
+            |error, _| Some(error.to_string()),
+        );
+    });
+}
+
+fn active_buffer(workspace: &Workspace, cx: &AppContext) -> Option<Model<Buffer>> {
+    workspace
+        .active_item(cx)?
+        .act_as::<Editor>(cx)?
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+}
+
+/// Opens our and their side of the conflict at the cursor side by side.
+fn compare_conflict(
+    workspace: &mut Workspace,
+    _: &CompareConflict,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(editor) = workspace
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+    else {
+        return;
+    };
+    let editor = editor.read(cx);
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(region) = editor.conflict_at_cursor(cx) else {
+        return;
+    };
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let ours = snapshot.text_for_range(region.ours.clone()).collect();
+    let theirs = snapshot.text_for_range(region.theirs.clone()).collect();
+    let title = |side: &str, label: &str| -> SharedString {
+        if label.is_empty() {
+            side.to_string().into()
+        } else {
+            format!("{side} ({label})").into()
+        }
+    };
+    let ours_title = title("Ours", region.ours_label.as_ref());
+    let theirs_title = title("Theirs", region.theirs_label.as_ref());
+    DiffView::open_for_texts(
+        ours,
+        ours_title,
+        theirs,
+        theirs_title,
+        &buffer,
+        workspace,
+        cx,
+    );
+}
+
+/// Saves the buffer and marks its file's conflicts as resolved, unless it
+/// still contains conflict markers.
+fn mark_buffer_resolved(
+    project: Model<Project>,
+    buffer: Model<Buffer>,
+    cx: &mut WindowContext,
+) -> Task<Result<()>> {
+    let remaining = parse_conflicts(&buffer.read(cx).text()).len();
+    let project_path = buffer.read(cx).project_path(cx);
+    let Some(project_path) = project_path.filter(|_| remaining == 0) else {
+        let file_name = buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        return Task::ready(Err(match remaining {
+            0 => anyhow!("{file_name} isn't a file in the project"),
+            1 => anyhow!("{file_name} still has a conflict"),
+            _ => anyhow!("{file_name} still has {remaining} conflicts"),
+        }));
+    };
+
+    let save = buffer
+        .read(cx)
+        .is_dirty()
+        .then(|| project.update(cx, |project, cx| project.save_buffer(buffer, cx)));
+    cx.spawn(|mut cx| async move {
+        if let Some(save) = save {
+            save.await.context("failed to save the file")?;
+        }
+        project
+            .update(&mut cx, |project, cx| {
+                project.mark_conflict_resolved(&project_path, cx)
+            })?
+            .await
+    })
+}
+
+/// Opens the file at the given path, with the cursor on its first conflict.
+fn open_conflicted_file(
+    workspace: &mut Workspace,
+    project_path: ProjectPath,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let open_task = workspace.open_path(project_path, None, true, cx);
+    cx.spawn(|_, mut cx| async move {
+        let item = open_task.await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let Some(conflict) = parse_conflicts(&snapshot.text()).into_iter().next() else {
+                    return;
+                };
+                let point = snapshot.offset_to_point(conflict.range.start);
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([point..point])
+                });
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
@@ -4239,6 +4239,11 @@ impl<'a> MultiBufferExcerpt<'a> {
             ..self.map_offset_from_buffer(buffer_range.end)
     }
 
+    /// The range of the [`Buffer`] that the excerpt shows
+    pub fn buffer_range(&self) -> Range<usize> {
+        self.excerpt.buffer_start_offset()..self.excerpt.buffer_end_offset()
+    }
+
     /// Returns true if the entirety of the given range is in the buffer's excerpt
     pub fn contains_buffer_range(&self, range: Range<usize>) -> bool {
         range.start >= self.excerpt.buffer_start_offset()
@@ -134,6 +134,12 @@ pub fn keymap_file() -> &'static PathBuf {
     KEYMAP_FILE.get_or_init(|| config_dir().join("keymap.json"))
 }
 
+/// Returns the path to the `vimrc` file.
+pub fn vimrc_file() -> &'static PathBuf {
+    static VIMRC_FILE: OnceLock<PathBuf> = OnceLock::new();
+    VIMRC_FILE.get_or_init(|| config_dir().join("vimrc"))
+}
+
 /// Returns the path to the `tasks.json` file.
 pub fn tasks_file() -> &'static PathBuf {
     static TASKS_FILE: OnceLock<PathBuf> = OnceLock::new();
@@ -242,6 +248,18 @@ pub fn local_tasks_file_relative_path() -> &'static Path {
     LOCAL_TASKS_FILE_RELATIVE_PATH.get_or_init(|| Path::new(".zed/tasks.json"))
 }
 
+/// Returns the relative path to a `vimrc` file within a project.
+pub fn local_vimrc_file_relative_path() -> &'static Path {
+    static LOCAL_VIMRC_FILE_RELATIVE_PATH: OnceLock<&Path> = OnceLock::new();
+    LOCAL_VIMRC_FILE_RELATIVE_PATH.get_or_init(|| Path::new(".zed/vimrc"))
+}
+
+/// Returns the relative path to the folder of project-specific prompts within a project.
+pub fn local_prompts_dir_relative_path() -> &'static Path {
+    static LOCAL_PROMPTS_DIR_RELATIVE_PATH: OnceLock<&Path> = OnceLock::new();
+    LOCAL_PROMPTS_DIR_RELATIVE_PATH.get_or_init(|| Path::new(".zed/prompts"))
+}
+
 /// Returns the relative path to a `.vscode/tasks.json` file within a project.
 pub fn local_vscode_tasks_file_relative_path() -> &'static Path {
     static LOCAL_VSCODE_TASKS_FILE_RELATIVE_PATH: OnceLock<&Path> = OnceLock::new();
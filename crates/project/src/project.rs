@@ -11,6 +11,7 @@ pub mod terminals;
 #[cfg(test)]
 mod project_tests;
 pub mod search_history;
+pub mod structural_search;
 
 use anyhow::{anyhow, bail, Context as _, Result};
 use async_trait::async_trait;
@@ -33,7 +34,10 @@ use futures::{
     AsyncWriteExt, Future, FutureExt, StreamExt, TryFutureExt,
 };
 use fuzzy::CharBag;
-use git::{blame::Blame, repository::GitRepository};
+use git::{
+    blame::Blame,
+    repository::{GitFileStatus, GitRepository},
+};
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use gpui::{
     AnyModel, AppContext, AsyncAppContext, BackgroundExecutor, BorrowAppContext, Context, Entity,
@@ -43,12 +47,14 @@ use http::{HttpClient, Url};
 use itertools::Itertools;
 use language::{
     language_settings::{
-        language_settings, AllLanguageSettings, FormatOnSave, Formatter, InlayHintKind,
+        editorconfig_settings, language_settings, AllLanguageSettings, FormatOnSave, Formatter,
+        InlayHintKind,
     },
     markdown, point_to_lsp, prepare_completion_documentation,
     proto::{
-        deserialize_anchor, deserialize_line_ending, deserialize_version, serialize_anchor,
-        serialize_line_ending, serialize_version, split_operations,
+        deserialize_anchor, deserialize_encoding, deserialize_line_ending, deserialize_version,
+        serialize_anchor, serialize_encoding, serialize_line_ending, serialize_version,
+        split_operations,
     },
     range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CodeLabel,
     ContextProvider, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation,
@@ -78,7 +84,10 @@ use rpc::{ErrorCode, ErrorExt as _};
 use search::SearchQuery;
 use search_history::SearchHistory;
 use serde::Serialize;
-use settings::{watch_config_file, Settings, SettingsLocation, SettingsStore};
+use settings::{
+    editorconfig_properties, watch_config_file, EditorConfig, Settings, SettingsLocation,
+    SettingsStore, EDITORCONFIG_FILE_NAME,
+};
 use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use smol::channel::{Receiver, Sender};
@@ -212,6 +221,14 @@ pub struct Project {
     opened_buffers: HashMap<BufferId, OpenBuffer>,
     local_buffer_ids_by_path: HashMap<ProjectPath, BufferId>,
     local_buffer_ids_by_entry_id: HashMap<ProjectEntryId, BufferId>,
+    /// The `.editorconfig` files of each local worktree, keyed by the
+    /// directory containing them.
+    editorconfigs: HashMap<WorktreeId, BTreeMap<Arc<Path>, EditorConfig>>,
+    /// The `.editorconfig` files above each local worktree, from the outermost
+    /// to the innermost, keyed by the absolute path of the directory containing them.
+    ancestor_editorconfigs: HashMap<WorktreeId, Vec<(PathBuf, EditorConfig)>>,
+    /// The settings store keys of the buffers with EditorConfig settings.
+    editorconfig_buffer_paths: HashMap<BufferId, (usize, Arc<Path>)>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
     buffers_being_formatted: HashSet<BufferId>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
@@ -726,6 +743,9 @@ impl Project {
                 buffer_ordered_messages_tx: tx,
                 collaborators: Default::default(),
                 opened_buffers: Default::default(),
+                editorconfigs: Default::default(),
+                ancestor_editorconfigs: Default::default(),
+                editorconfig_buffer_paths: Default::default(),
                 shared_buffers: Default::default(),
                 loading_buffers_by_path: Default::default(),
                 loading_local_worktrees: Default::default(),
@@ -919,6 +939,9 @@ impl Project {
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
                 opened_buffers: Default::default(),
+                editorconfigs: Default::default(),
+                ancestor_editorconfigs: Default::default(),
+                editorconfig_buffer_paths: Default::default(),
                 buffers_being_formatted: Default::default(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
@@ -2065,17 +2088,31 @@ impl Project {
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
             cx.spawn(move |_, mut cx| async move {
                 let loaded = load_file.await?;
+                let is_large = loaded.is_large;
                 let text_buffer = cx
                     .background_executor()
-                    .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
+                    .spawn(async move {
+                        let mut text_buffer = text::Buffer::new_normalized(
+                            0,
+                            buffer_id,
+                            loaded.line_ending,
+                            loaded.text,
+                        );
+                        text_buffer.set_encoding(loaded.encoding);
+                        text_buffer
+                    })
                     .await;
-                cx.insert_model(reservation, |_| {
-                    Buffer::build(
+                cx.insert_model(reservation, |cx| {
+                    let mut buffer = Buffer::build(
                         text_buffer,
                         loaded.diff_base,
                         Some(loaded.file),
                         Capability::ReadWrite,
-                    )
+                    );
+                    if is_large {
+                        buffer.set_large_file_mode(true, cx);
+                    }
+                    buffer
                 })
             })
         });
@@ -2281,13 +2318,14 @@ impl Project {
         let buffer_id = buffer.remote_id();
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
+        let encoding = buffer.encoding();
         let version = buffer.version();
         if buffer.file().is_some_and(|file| !file.is_created()) {
             has_changed_file = true;
         }
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
         let client = self.client.clone();
@@ -2436,9 +2474,19 @@ impl Project {
             }
         }
 
+        self.update_editorconfig_settings(buffer, cx);
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
         cx.observe_release(buffer, |this, buffer, cx| {
+            if let Some((root_id, path)) =
+                this.editorconfig_buffer_paths.remove(&buffer.remote_id())
+            {
+                cx.update_global::<SettingsStore, _>(|store, cx| {
+                    store
+                        .set_editorconfig_settings(root_id, path, None, cx)
+                        .log_err();
+                });
+            }
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
@@ -2472,6 +2520,9 @@ impl Project {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.large_file_mode() {
+            return;
+        }
 
         if let Some(file) = File::from_dyn(buffer.file()) {
             if !file.is_local() {
@@ -2729,6 +2780,7 @@ impl Project {
                                 version: serialize_version(&buffer.version()),
                                 mtime: buffer.saved_mtime().map(|t| t.into()),
                                 line_ending: serialize_line_ending(buffer.line_ending()) as i32,
+                                encoding: Some(serialize_encoding(buffer.encoding())),
                             })
                             .log_err();
                     }
@@ -2855,6 +2907,7 @@ impl Project {
                 }
             }
             BufferEvent::FileHandleChanged => {
+                self.update_editorconfig_settings(&buffer, cx);
                 let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
                     return None;
                 };
@@ -3197,7 +3250,7 @@ impl Project {
         };
         if let Some(file) = buffer_file {
             let worktree = file.worktree.clone();
-            if worktree.read(cx).is_local() {
+            if worktree.read(cx).is_local() && !buffer.read(cx).large_file_mode() {
                 self.start_language_servers(&worktree, new_language, cx);
             }
         }
@@ -3836,6 +3889,9 @@ impl Project {
         for buffer in self.opened_buffers.values() {
             if let Some(buffer_handle) = buffer.upgrade() {
                 let buffer = buffer_handle.read(cx);
+                if buffer.large_file_mode() {
+                    continue;
+                }
                 let file = match File::from_dyn(buffer.file()) {
                     Some(file) => file,
                     None => continue,
@@ -5087,6 +5143,7 @@ impl Project {
 
             let remove_trailing_whitespace = settings.remove_trailing_whitespace_on_save;
             let ensure_final_newline = settings.ensure_final_newline_on_save;
+            let line_ending = settings.line_ending.line_ending();
             let tab_size = settings.tab_size;
 
             // First, format buffer's whitespace according to the settings.
@@ -5108,6 +5165,9 @@ impl Project {
                 if ensure_final_newline {
                     buffer.ensure_final_newline(cx);
                 }
+                if let Some(line_ending) = line_ending {
+                    buffer.set_line_ending(line_ending, cx);
+                }
                 buffer.end_transaction(cx)
             })?;
 
@@ -7236,8 +7296,13 @@ impl Project {
                         })?,
                     };
 
-                    chunk_results.push(cx.spawn(|cx| async move {
+                    chunk_results.push(cx.spawn(|mut cx| async move {
                         let buffer = buffer.await?;
+                        if query.requires_syntax_tree() {
+                            buffer
+                                .update(&mut cx, |buffer, _| buffer.parsing_idle())?
+                                .await;
+                        }
                         let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
                         let ranges = cx
                             .background_executor()
@@ -7260,6 +7325,11 @@ impl Project {
                 let chunk_results = futures::future::join_all(chunk_results).await;
                 for result in chunk_results {
                     if let Some((buffer, ranges)) = result.log_err() {
+                        // Files are only pre-filtered by their text, which
+                        // isn't enough for queries depending on syntax.
+                        if ranges.is_empty() {
+                            continue;
+                        }
                         range_count += ranges.len();
                         result_tx
                             .send(SearchResult::Buffer { buffer, ranges })
@@ -7780,6 +7850,9 @@ impl Project {
 
     fn add_worktree(&mut self, worktree: &Model<Worktree>, cx: &mut ModelContext<Self>) {
         cx.observe(worktree, |_, _, cx| cx.notify()).detach();
+        if worktree.read(cx).is_local() {
+            self.load_ancestor_editorconfigs(worktree, cx);
+        }
         cx.subscribe(worktree, |this, worktree, event, cx| {
             let is_local = worktree.read(cx).is_local();
             match event {
@@ -7840,6 +7913,8 @@ impl Project {
         let handle_id = worktree.entity_id();
         cx.observe_release(worktree, move |this, worktree, cx| {
             let _ = this.remove_worktree(worktree.id(), cx);
+            this.editorconfigs.remove(&worktree.id());
+            this.ancestor_editorconfigs.remove(&worktree.id());
             cx.update_global::<SettingsStore, _>(|store, cx| {
                 store
                     .clear_local_settings(handle_id.as_u64() as usize, cx)
@@ -8182,6 +8257,7 @@ impl Project {
         let remote_worktree_id = worktree.read(cx).id();
 
         let mut settings_contents = Vec::new();
+        let mut editorconfig_contents = Vec::new();
         for (path, _, change) in changes.iter() {
             let removed = change == &PathChange::Removed;
             let abs_path = match worktree.read(cx).absolutize(path) {
@@ -8255,9 +8331,42 @@ impl Project {
                         );
                     }
                 })
+            } else if path.file_name() == Some(OsStr::new(EDITORCONFIG_FILE_NAME)) {
+                let directory: Arc<Path> = Arc::from(path.parent().unwrap_or(Path::new("")));
+                let fs = self.fs.clone();
+                editorconfig_contents.push(async move {
+                    (
+                        directory,
+                        if removed {
+                            None
+                        } else {
+                            fs.load(&abs_path).await.log_err()
+                        },
+                    )
+                });
             }
         }
 
+        if !editorconfig_contents.is_empty() {
+            cx.spawn(move |this, mut cx| async move {
+                let editorconfig_contents: Vec<(Arc<Path>, _)> =
+                    futures::future::join_all(editorconfig_contents).await;
+                this.update(&mut cx, |this, cx| {
+                    let editorconfigs = this.editorconfigs.entry(remote_worktree_id).or_default();
+                    for (directory, content) in editorconfig_contents {
+                        if let Some(content) = content {
+                            editorconfigs.insert(directory, EditorConfig::parse(&content));
+                        } else {
+                            editorconfigs.remove(&directory);
+                        }
+                    }
+                    this.refresh_editorconfig_settings(remote_worktree_id, cx);
+                })
+                .ok();
+            })
+            .detach();
+        }
+
         if settings_contents.is_empty() {
             return;
         }
@@ -8296,6 +8405,121 @@ impl Project {
         .detach();
     }
 
+    /// Reapplies the `.editorconfig` files of a worktree to all of its open buffers.
+    fn refresh_editorconfig_settings(
+        &mut self,
+        worktree_id: WorktreeId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffers = self
+            .opened_buffers
+            .values()
+            .filter_map(|buffer| buffer.upgrade())
+            .filter(|buffer| {
+                File::from_dyn(buffer.read(cx).file())
+                    .map_or(false, |file| file.worktree_id(cx) == worktree_id)
+            })
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.update_editorconfig_settings(&buffer, cx);
+        }
+    }
+
+    /// Loads the `.editorconfig` files of the directories above a local
+    /// worktree, stopping at the first one marked as `root`.
+    fn load_ancestor_editorconfigs(&self, worktree: &Model<Worktree>, cx: &mut ModelContext<Self>) {
+        let worktree_id = worktree.read(cx).id();
+        let abs_path = worktree.read(cx).abs_path();
+        let fs = self.fs.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let mut configs = Vec::new();
+            for directory in abs_path.ancestors().skip(1) {
+                let Ok(content) = fs.load(&directory.join(EDITORCONFIG_FILE_NAME)).await else {
+                    continue;
+                };
+                let config = EditorConfig::parse(&content);
+                let root = config.root;
+                configs.push((directory.to_path_buf(), config));
+                if root {
+                    break;
+                }
+            }
+            if configs.is_empty() {
+                return;
+            }
+            configs.reverse();
+            this.update(&mut cx, |this, cx| {
+                this.ancestor_editorconfigs.insert(worktree_id, configs);
+                this.refresh_editorconfig_settings(worktree_id, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Registers the settings from the `.editorconfig` files that apply to a
+    /// local buffer's file, which take precedence over the user's settings but
+    /// not over the project's `.zed/settings.json` files.
+    fn update_editorconfig_settings(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let settings = File::from_dyn(buffer.read(cx).file())
+            .filter(|file| file.is_local)
+            .and_then(|file| {
+                let worktree_id = file.worktree_id(cx);
+                let worktree_abs_path = file.worktree.read(cx).abs_path();
+                let configs = self
+                    .ancestor_editorconfigs
+                    .get(&worktree_id)
+                    .into_iter()
+                    .flatten()
+                    .map(|(directory, config)| (directory.clone(), config))
+                    .chain(
+                        self.editorconfigs
+                            .get(&worktree_id)
+                            .into_iter()
+                            .flatten()
+                            .filter(|(directory, _)| file.path.starts_with(directory))
+                            .map(|(directory, config)| (worktree_abs_path.join(directory), config)),
+                    )
+                    .collect::<Vec<_>>();
+                let properties = editorconfig_properties(
+                    configs
+                        .iter()
+                        .map(|(directory, config)| (directory.as_path(), *config)),
+                    &file.abs_path(cx),
+                );
+                let root_id = file.worktree.entity_id().as_u64() as usize;
+                Some((
+                    (root_id, file.path.clone()),
+                    editorconfig_settings(&properties)?,
+                ))
+            });
+
+        let previous_key = self.editorconfig_buffer_paths.remove(&buffer_id);
+        if let Some((key, _)) = &settings {
+            self.editorconfig_buffer_paths
+                .insert(buffer_id, key.clone());
+        }
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            if let Some((root_id, path)) = previous_key {
+                if settings.as_ref().map(|(key, _)| key) != Some(&(root_id, path.clone())) {
+                    store
+                        .set_editorconfig_settings(root_id, path, None, cx)
+                        .log_err();
+                }
+            }
+            if let Some(((root_id, path), settings)) = settings {
+                store
+                    .set_editorconfig_settings(root_id, path, Some(settings), cx)
+                    .log_err();
+            }
+        });
+    }
+
     pub fn set_active_path(&mut self, entry: Option<ProjectPath>, cx: &mut ModelContext<Self>) {
         let new_active_entry = entry.and_then(|project_path| {
             let worktree = self.worktree_for_id(project_path.worktree_id, cx)?;
@@ -8555,6 +8779,108 @@ impl Project {
         }
     }
 
+    /// Loads the contents of the buffer's file as of the given git revision.
+    /// Returns `None` if the file didn't exist at that revision.
+    pub fn load_buffer_text_at_revision(
+        &self,
+        buffer: &Model<Buffer>,
+        revision: String,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "Comparing with git revisions is only supported in local projects"
+            )));
+        }
+
+        let params = maybe!({
+            let buffer_project_path = buffer
+                .read(cx)
+                .project_path(cx)
+                .context("failed to get buffer project path")?;
+            let worktree = self
+                .worktree_for_id(buffer_project_path.worktree_id, cx)
+                .context("failed to get worktree")?
+                .read(cx)
+                .as_local()
+                .context("worktree was not local")?
+                .snapshot();
+            let (repo_entry, local_repo_entry) =
+                match worktree.repo_for_path(&buffer_project_path.path) {
+                    Some(repo_for_path) => repo_for_path,
+                    None => anyhow::bail!(NoRepositoryError {}),
+                };
+            let relative_path = repo_entry
+                .relativize(&worktree, &buffer_project_path.path)
+                .context("failed to relativize buffer path")?;
+            anyhow::Ok((local_repo_entry.repo().clone(), relative_path))
+        });
+
+        cx.background_executor().spawn(async move {
+            let (repo, relative_path) = params?;
+            repo.load_text_at_revision(&relative_path, &revision)
+                .with_context(|| format!("Failed to load {:?} at {revision}", relative_path.0))
+        })
+    }
+
+    /// Stages the file at the given path, marking its merge conflicts as
+    /// resolved.
+    pub fn mark_conflict_resolved(
+        &self,
+        project_path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "Resolving conflicts is only supported in local projects"
+            )));
+        }
+
+        let params = maybe!({
+            let worktree = self
+                .worktree_for_id(project_path.worktree_id, cx)
+                .context("failed to get worktree")?
+                .read(cx)
+                .as_local()
+                .context("worktree was not local")?
+                .snapshot();
+            let (repo_entry, local_repo_entry) = match worktree.repo_for_path(&project_path.path) {
+                Some(repo_for_path) => repo_for_path,
+                None => anyhow::bail!(NoRepositoryError {}),
+            };
+            let relative_path = repo_entry
+                .relativize(&worktree, &project_path.path)
+                .context("failed to relativize path")?;
+            anyhow::Ok((local_repo_entry.repo().clone(), relative_path))
+        });
+
+        cx.background_executor().spawn(async move {
+            let (repo, relative_path) = params?;
+            repo.stage_paths(&[relative_path.clone()])
+                .with_context(|| format!("Failed to stage {:?}", relative_path.0))
+        })
+    }
+
+    /// Returns the paths of the files with merge conflicts, across all
+    /// worktrees.
+    pub fn conflicted_paths<'a>(
+        &'a self,
+        cx: &'a AppContext,
+    ) -> impl Iterator<Item = ProjectPath> + 'a {
+        self.visible_worktrees(cx).flat_map(|worktree| {
+            let worktree = worktree.read(cx);
+            let worktree_id = worktree.id();
+            worktree
+                .entries(false, 0)
+                .filter(|entry| entry.git_status == Some(GitFileStatus::Conflict))
+                .map(move |entry| ProjectPath {
+                    worktree_id,
+                    path: entry.path.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+    }
+
     // RPC message handlers
 
     async fn handle_blame_buffer(
@@ -9369,6 +9695,7 @@ impl Project {
                             line_ending: language::proto::serialize_line_ending(
                                 buffer.line_ending(),
                             ) as i32,
+                            encoding: Some(serialize_encoding(buffer.encoding())),
                         })
                         .log_err();
 
@@ -10537,6 +10864,7 @@ impl Project {
             proto::LineEnding::from_i32(payload.line_ending)
                 .ok_or_else(|| anyhow!("missing line ending"))?,
         );
+        let encoding = deserialize_encoding(payload.encoding);
         let mtime = payload.mtime.map(|time| time.into());
         let buffer_id = BufferId::new(payload.buffer_id)?;
         this.update(&mut cx, |this, cx| {
@@ -10547,7 +10875,7 @@ impl Project {
                 .or_else(|| this.incomplete_remote_buffers.get(&buffer_id).cloned());
             if let Some(buffer) = buffer {
                 buffer.update(cx, |buffer, cx| {
-                    buffer.did_reload(version, line_ending, mtime, cx);
+                    buffer.did_reload(version, line_ending, encoding, mtime, cx);
                 });
             }
             Ok(())
@@ -10774,6 +11102,10 @@ impl Project {
         buffer: &Buffer,
         cx: &AppContext,
     ) -> Vec<LanguageServerId> {
+        // Large files aren't opened in language servers.
+        if buffer.large_file_mode() {
+            return Vec::new();
+        }
         if let Some((file, language)) = File::from_dyn(buffer.file()).zip(buffer.language()) {
             let worktree_id = file.worktree_id(cx);
             self.languages
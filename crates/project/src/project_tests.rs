@@ -1,4 +1,4 @@
-use crate::{Event, *};
+use crate::{search::SyntaxContext, Event, *};
 use fs::FakeFs;
 use futures::{future, StreamExt};
 use gpui::{AppContext, SemanticVersion, UpdateGlobal};
@@ -294,6 +294,211 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_editorconfig_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".editorconfig": "root = true\n[*]\nindent_size = 4\n[*.md]\nmax_line_length = 120\n",
+            "a": {
+                "a.rs": "fn a() {}",
+                "a.md": "# A",
+            },
+            "b": {
+                ".editorconfig": "[*]\nindent_style = tab\ntab_width = 8\n",
+                ".zed": {
+                    "settings.json": r#"{ "tab_size": 2 }"#,
+                },
+                "b.rs": "fn b() {}",
+            }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let mut buffers = Vec::new();
+    for path in ["/the-root/a/a.rs", "/the-root/a/a.md", "/the-root/b/b.rs"] {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        buffers.push(buffer);
+    }
+    let [a_rs, a_md, b_rs] = buffers.try_into().unwrap();
+    cx.executor().run_until_parked();
+
+    let settings = |buffer: &Model<Buffer>, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            let buffer = buffer.read(cx);
+            let settings = language_settings(buffer.language(), buffer.file(), cx);
+            (
+                settings.tab_size.get(),
+                settings.hard_tabs,
+                settings.preferred_line_length,
+            )
+        })
+    };
+    assert_eq!(settings(&a_rs, cx), (4, false, 80));
+    assert_eq!(settings(&a_md, cx), (4, false, 120));
+    // `.zed/settings.json` takes precedence over `.editorconfig`.
+    assert_eq!(settings(&b_rs, cx), (2, true, 80));
+
+    fs.save(
+        "/the-root/.editorconfig".as_ref(),
+        &"root = true\n[*]\nindent_size = 3\n".into(),
+        Default::default(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    fs.remove_file("/the-root/b/.editorconfig".as_ref(), Default::default())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    assert_eq!(settings(&a_rs, cx), (3, false, 80));
+    assert_eq!(settings(&a_md, cx), (3, false, 80));
+    assert_eq!(settings(&b_rs, cx), (2, false, 80));
+}
+
+#[gpui::test]
+async fn test_editorconfig_settings_above_worktree(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/top",
+        json!({
+            ".editorconfig": "[*]\nindent_style = tab\n",
+            "outer": {
+                ".editorconfig": "root = true\n[*]\nindent_size = 8\n[*.md]\nmax_line_length = 100\n",
+                "middle": {
+                    ".editorconfig": "[*.rs]\nindent_size = 4\n",
+                    "project": {
+                        "a.rs": "fn a() {}",
+                        "b.md": "# B",
+                    },
+                },
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/top/outer/middle/project".as_ref()], cx).await;
+    let a_rs = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/top/outer/middle/project/a.rs", cx)
+        })
+        .await
+        .unwrap();
+    let b_md = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/top/outer/middle/project/b.md", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let settings = |buffer: &Model<Buffer>, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            let buffer = buffer.read(cx);
+            let settings = language_settings(buffer.language(), buffer.file(), cx);
+            (
+                settings.tab_size.get(),
+                settings.hard_tabs,
+                settings.preferred_line_length,
+            )
+        })
+    };
+    // The `.editorconfig` above the one marked as `root` is ignored.
+    assert_eq!(settings(&a_rs, cx), (4, false, 80));
+    assert_eq!(settings(&b_md, cx), (8, false, 100));
+
+    // Renamed buffers pick up the properties of their new path.
+    fs.rename(
+        "/top/outer/middle/project/a.rs".as_ref(),
+        "/top/outer/middle/project/a.md".as_ref(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(settings(&a_rs, cx), (8, false, 100));
+}
+
+#[gpui::test]
+async fn test_opening_large_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.large_file_threshold_mb = Some(0);
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "large.rs": "fn main() {}",
+            "empty.rs": "",
+        }),
+    )
+    .await;
+    fs.set_index_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("large.rs"), "fn old() {}".into())],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    let mut fake_rust_servers =
+        language_registry.register_fake_lsp_adapter("Rust", FakeLspAdapter::default());
+    language_registry.add(rust_lang());
+
+    // Files larger than the threshold aren't parsed, diffed or opened in language servers.
+    let large_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/large.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    large_buffer.update(cx, |buffer, _| {
+        assert!(buffer.large_file_mode());
+        assert_eq!(buffer.text(), "fn main() {}");
+        assert!(buffer.diff_base().is_none());
+        assert!(buffer.snapshot().syntax_layers().next().is_none());
+    });
+    assert!(fake_rust_servers.try_next().is_err());
+
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/empty.rs", cx)
+        })
+        .await
+        .unwrap();
+    small_buffer.update(cx, |buffer, _| assert!(!buffer.large_file_mode()));
+
+    // The language server started for the smaller file isn't told about the large one,
+    // even though it was opened first.
+    let mut fake_rust_server = fake_rust_servers.next().await.unwrap();
+    assert_eq!(
+        fake_rust_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri
+            .as_str(),
+        "file:///dir/empty.rs"
+    );
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2795,6 +3000,67 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_save_file_in_original_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    fs.insert_file("/dir/latin1.c", b"// caf\xE9\n".to_vec())
+        .await;
+    fs.insert_file("/dir/utf16.txt", b"\xFF\xFEh\0i\0".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/latin1.c", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "// café\n");
+        assert_eq!(buffer.encoding().name(), "windows-1252");
+        buffer.edit([(3..3, "naïve ")], None, cx);
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.read_file_sync("/dir/latin1.c").unwrap(),
+        b"// na\xEFve caf\xE9\n"
+    );
+
+    // Text that can't be represented in the encoding fails to save, leaving
+    // the file untouched.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "日本")], None, cx));
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        fs.read_file_sync("/dir/latin1.c").unwrap(),
+        b"// na\xEFve caf\xE9\n"
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/utf16.txt", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "hi");
+        assert_eq!(buffer.encoding().to_string(), "UTF-16LE with BOM");
+        buffer.edit([(2..2, "!")], None, cx);
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.read_file_sync("/dir/utf16.txt").unwrap(),
+        b"\xFF\xFEh\0i\0!\0"
+    );
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2825,6 +3091,7 @@ async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext)
         "/dir/file1".as_ref(),
         &"the first contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -2836,6 +3103,7 @@ async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext)
         "/dir/file1".as_ref(),
         &"the second contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -2880,6 +3148,7 @@ async fn test_edit_buffer_while_it_reloads(cx: &mut gpui::TestAppContext) {
         "/dir/file1".as_ref(),
         &"the first contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3425,6 +3694,7 @@ async fn test_buffer_file_changes_on_disk(cx: &mut gpui::TestAppContext) {
         "/dir/the-file".as_ref(),
         &new_contents.into(),
         LineEnding::Unix,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3460,6 +3730,7 @@ async fn test_buffer_file_changes_on_disk(cx: &mut gpui::TestAppContext) {
         "/dir/the-file".as_ref(),
         &"\n\n\nAAAA\naaa\nBB\nbbbbb\n".into(),
         LineEnding::Unix,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3511,6 +3782,7 @@ async fn test_buffer_line_endings(cx: &mut gpui::TestAppContext) {
         "/dir/file1".as_ref(),
         &"aaa\nb\nc\n".into(),
         LineEnding::Windows,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3983,6 +4255,219 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let one = "
+        fn one() {
+            let a = foo.unwrap();
+            let b = bar(a, baz(qux.unwrap())).unwrap().len();
+            assert_eq!(a, a);
+            assert_eq!(a, b);
+        }
+    "
+    .unindent();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": one,
+            // Contains the pattern's words, but not its code.
+            "two.rs": "fn unwrap() { unwrap(); }",
+            // Has no syntax tree.
+            "three.txt": "foo.unwrap()",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    let range_of = |text: &str| {
+        let start = one.find(text).unwrap();
+        start..start + text.len()
+    };
+    let query =
+        SearchQuery::structural("$X.unwrap()", false, Default::default(), Default::default())
+            .unwrap();
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([(
+            "dir/one.rs".to_string(),
+            vec![
+                range_of("foo.unwrap()"),
+                range_of("bar(a, baz(qux.unwrap())).unwrap()"),
+            ]
+        )])
+    );
+
+    let query = query.with_replacement("$X.expect(\"$X\")".to_string());
+    assert_eq!(
+        query.replacement_for("bar(a, baz(qux.unwrap())).unwrap()"),
+        Some("bar(a, baz(qux.unwrap())).expect(\"bar(a, baz(qux.unwrap()))\")".into())
+    );
+
+    // Repeated metavariables have to match the same code.
+    let query = SearchQuery::structural(
+        "assert_eq!($A, $A)",
+        false,
+        Default::default(),
+        Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query, cx).await.unwrap(),
+        HashMap::from_iter([("dir/one.rs".to_string(), vec![range_of("assert_eq!(a, a)")])])
+    );
+
+    assert!(SearchQuery::structural("$A", false, Default::default(), Default::default()).is_err());
+}
+
+#[gpui::test]
+async fn test_structural_search_in_buffer(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let text = "fn f() { a + b + c; }\nfn g() { d + e; }\n";
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "one.rs": text })).await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/one.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+
+    let range_of = |substring: &str| {
+        let start = text.find(substring).unwrap();
+        start..start + substring.len()
+    };
+    let query = SearchQuery::structural("$A + $B", false, Default::default(), Default::default())
+        .unwrap()
+        .with_replacement("$B + $A".to_string());
+
+    // Nested binary expressions only match once, at the outermost one.
+    assert_eq!(
+        query.search(&snapshot, None).await,
+        vec![range_of("a + b + c"), range_of("d + e")]
+    );
+    // Within a subrange, the nodes inside the outer match are searched too.
+    let subrange = range_of("a + b");
+    assert_eq!(
+        query.search(&snapshot, Some(subrange.clone())).await,
+        vec![0..subrange.len()]
+    );
+
+    // `+` is left associative, so `$A` matched `a + b` in the syntax tree.
+    assert_eq!(
+        query.replacement_for_range(&snapshot, range_of("a + b + c")),
+        Some("c + a + b".to_string())
+    );
+    assert_eq!(
+        query.replacement_for_range(&snapshot, range_of("d + e")),
+        Some("e + d".to_string())
+    );
+}
+
+#[gpui::test]
+async fn test_search_in_syntax_contexts(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let text = "
+        // TODO: rename todo
+        fn todo() {
+            let message = \"todo\";
+            todo();
+        }
+    "
+    .unindent();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "one.rs": text.clone() }))
+        .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_override_query("(string_literal) @string [(line_comment) (block_comment)] @comment")
+        .unwrap(),
+    );
+    // Without override scopes for comments and strings, everything is code.
+    assert!(SyntaxContext::is_supported_by(&language));
+    assert!(!SyntaxContext::is_supported_by(&rust_lang()));
+    project.read_with(cx, |project, _| project.languages().add(language));
+
+    let ranges_of = |needle: &str, nth: &[usize]| {
+        let starts = text
+            .match_indices(needle)
+            .map(|(start, _)| start)
+            .collect::<Vec<_>>();
+        nth.iter()
+            .map(|ix| starts[*ix]..starts[*ix] + needle.len())
+            .collect::<Vec<_>>()
+    };
+    let query = |text: &str, syntax_context| {
+        SearchQuery::text(
+            text,
+            false,
+            true,
+            false,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap()
+        .with_syntax_context(syntax_context)
+    };
+
+    assert_eq!(
+        search(&project, query("todo", Some(SyntaxContext::Code)), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([("dir/one.rs".to_string(), ranges_of("todo", &[1, 3]))])
+    );
+    assert_eq!(
+        search(&project, query("todo", Some(SyntaxContext::Comments)), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([("dir/one.rs".to_string(), ranges_of("todo", &[0]))])
+    );
+    assert_eq!(
+        search(&project, query("todo", Some(SyntaxContext::Strings)), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([("dir/one.rs".to_string(), ranges_of("todo", &[2]))])
+    );
+
+    // Matches at the very start of a comment or string belong to it.
+    assert_eq!(
+        search(
+            &project,
+            query("// TODO", Some(SyntaxContext::Comments)),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/one.rs".to_string(), ranges_of("// TODO", &[0]))])
+    );
+    assert_eq!(
+        search(&project, query("\"todo\"", Some(SyntaxContext::Code)), cx)
+            .await
+            .unwrap(),
+        HashMap::default()
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
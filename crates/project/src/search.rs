@@ -1,7 +1,9 @@
+use crate::structural_search::StructuralPattern;
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::Result;
 use client::proto;
-use language::{char_kind, BufferSnapshot};
+use convert_case::{Case, Casing};
+use language::{char_kind, BufferSnapshot, Language};
 use regex::{Captures, Regex, RegexBuilder};
 use smol::future::yield_now;
 use std::{
@@ -20,6 +22,8 @@ pub struct SearchInputs {
     query: Arc<str>,
     files_to_include: PathMatcher,
     files_to_exclude: PathMatcher,
+    syntax_context: Option<SyntaxContext>,
+    preserve_case: bool,
 }
 
 impl SearchInputs {
@@ -32,6 +36,61 @@ impl SearchInputs {
     pub fn files_to_exclude(&self) -> &PathMatcher {
         &self.files_to_exclude
     }
+    pub fn syntax_context(&self) -> Option<SyntaxContext> {
+        self.syntax_context
+    }
+    pub fn preserve_case(&self) -> bool {
+        self.preserve_case
+    }
+}
+
+/// The kind of text that search matches can be restricted to, according to
+/// the language's override scope at each match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyntaxContext {
+    /// Anything outside of comments and strings.
+    Code,
+    Comments,
+    Strings,
+}
+
+impl SyntaxContext {
+    /// Returns whether the match at `range` lies in this context.
+    pub fn contains(&self, buffer: &BufferSnapshot, range: Range<usize>) -> bool {
+        let mut context = Self::at(buffer, range.start);
+        // Scopes only cover the offsets strictly inside their nodes, so a match
+        // starting a comment or a string is classified by its second character.
+        if context == Self::Code && range.start < range.end {
+            let next = buffer
+                .chars_at(range.start)
+                .next()
+                .map_or(range.start + 1, |c| range.start + c.len_utf8());
+            context = Self::at(buffer, next);
+        }
+        context == *self
+    }
+
+    /// Returns whether code, comments and strings can be told apart in the
+    /// language. Without `comment` and `string` override scopes, everything
+    /// in it is classified as code.
+    pub fn is_supported_by(language: &Language) -> bool {
+        language
+            .override_names()
+            .any(|name| Self::from_override_name(Some(name)) != Self::Code)
+    }
+
+    fn at(buffer: &BufferSnapshot, offset: usize) -> Self {
+        let scope = buffer.language_scope_at(offset);
+        Self::from_override_name(scope.as_ref().and_then(|scope| scope.override_name()))
+    }
+
+    fn from_override_name(name: Option<&str>) -> Self {
+        match name.and_then(|name| name.split('.').next()) {
+            Some("comment") => Self::Comments,
+            Some("string") => Self::Strings,
+            _ => Self::Code,
+        }
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
@@ -53,6 +112,14 @@ pub enum SearchQuery {
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    /// Code with metavariables, matched against the syntax trees of buffers.
+    Structural {
+        pattern: Arc<StructuralPattern>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 impl SearchQuery {
@@ -72,6 +139,8 @@ impl SearchQuery {
             query: query.into(),
             files_to_exclude,
             files_to_include,
+            syntax_context: None,
+            preserve_case: false,
         };
         Ok(Self::Text {
             search: Arc::new(search),
@@ -91,25 +160,16 @@ impl SearchQuery {
         files_to_include: PathMatcher,
         files_to_exclude: PathMatcher,
     ) -> Result<Self> {
-        let mut query = query.to_string();
+        let query = query.to_string();
         let initial_query = Arc::from(query.as_str());
-        if whole_word {
-            let mut word_query = String::new();
-            word_query.push_str("\\b");
-            word_query.push_str(&query);
-            word_query.push_str("\\b");
-            query = word_query
-        }
-
         let multiline = query.contains('\n') || query.contains("\\n");
-        let regex = RegexBuilder::new(&query)
-            .case_insensitive(!case_sensitive)
-            .multi_line(multiline)
-            .build()?;
+        let regex = build_regex(&query, whole_word, case_sensitive, multiline)?;
         let inner = SearchInputs {
             query: initial_query,
             files_to_exclude,
             files_to_include,
+            syntax_context: None,
+            preserve_case: false,
         };
         Ok(Self::Regex {
             regex,
@@ -122,8 +182,47 @@ impl SearchQuery {
         })
     }
 
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            syntax_context: None,
+            preserve_case: false,
+        };
+        Ok(Self::Structural {
+            pattern: Arc::new(pattern),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
-        if message.regex {
+        let syntax_context =
+            match proto::search_project::SyntaxContext::from_i32(message.syntax_context) {
+                Some(proto::search_project::SyntaxContext::Code) => Some(SyntaxContext::Code),
+                Some(proto::search_project::SyntaxContext::Comments) => {
+                    Some(SyntaxContext::Comments)
+                }
+                Some(proto::search_project::SyntaxContext::Strings) => Some(SyntaxContext::Strings),
+                Some(proto::search_project::SyntaxContext::Any) | None => None,
+            };
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -141,8 +240,12 @@ impl SearchQuery {
                 deserialize_path_matches(&message.files_to_include)?,
                 deserialize_path_matches(&message.files_to_exclude)?,
             )
-        }
+        }?;
+        query
+            .with_syntax_context(syntax_context)
+            .with_preserve_case(message.preserve_case)
     }
+
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
         match self {
             Self::Text {
@@ -152,17 +255,91 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
             }
         }
     }
+    /// Restricts the matches to comments, strings, or the code outside them.
+    pub fn with_syntax_context(mut self, syntax_context: Option<SyntaxContext>) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Structural { ref mut inner, .. } => inner.syntax_context = syntax_context,
+        }
+        self
+    }
+
+    /// Makes replacements take the case form of the text they replace, such
+    /// as camel, Pascal, snake, kebab or upper case. Text queries, and regex
+    /// queries made of a single identifier, then also match the query in each
+    /// of those forms.
+    pub fn with_preserve_case(mut self, preserve_case: bool) -> Result<Self> {
+        match self {
+            Self::Text {
+                ref mut search,
+                case_sensitive,
+                ref mut inner,
+                ..
+            } => {
+                let patterns = if preserve_case {
+                    case_variants(&inner.query)
+                } else {
+                    vec![inner.query.to_string()]
+                };
+                *search = Arc::new(
+                    AhoCorasickBuilder::new()
+                        .ascii_case_insensitive(!case_sensitive)
+                        .build(&patterns)?,
+                );
+                inner.preserve_case = preserve_case;
+            }
+            Self::Regex {
+                ref mut regex,
+                multiline,
+                whole_word,
+                case_sensitive,
+                ref mut inner,
+                ..
+            } => {
+                let variants = if preserve_case {
+                    case_variants(&inner.query)
+                } else {
+                    Vec::new()
+                };
+                let query = if variants.len() > 1 {
+                    let variants = variants.iter().map(|variant| regex::escape(variant));
+                    format!("(?:{})", variants.collect::<Vec<_>>().join("|"))
+                } else {
+                    inner.query.to_string()
+                };
+                *regex = build_regex(&query, whole_word, case_sensitive, multiline)?;
+                inner.preserve_case = preserve_case;
+            }
+            // Structural replacements are code rather than a single name.
+            Self::Structural { .. } => {}
+        }
+        Ok(self)
+    }
+
     pub fn to_proto(&self, project_id: u64) -> proto::SearchProject {
         proto::SearchProject {
             project_id,
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
+            syntax_context: match self.syntax_context() {
+                None => proto::search_project::SyntaxContext::Any,
+                Some(SyntaxContext::Code) => proto::search_project::SyntaxContext::Code,
+                Some(SyntaxContext::Comments) => proto::search_project::SyntaxContext::Comments,
+                Some(SyntaxContext::Strings) => proto::search_project::SyntaxContext::Strings,
+            } as i32,
+            preserve_case: self.preserve_case(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -206,18 +383,32 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let mut text = String::new();
+                BufReader::new(stream).read_to_string(&mut text)?;
+                Ok(pattern.words().all(|word| text.contains(word)))
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
+        let replacement = self.replacement_ignoring_case(text)?;
+        if self.preserve_case() {
+            Some(Cow::Owned(convert_to_case_of(text, &replacement)))
+        } else {
+            Some(replacement)
+        }
+    }
+
+    fn replacement_ignoring_case<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
             SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
             SearchQuery::Regex {
@@ -239,9 +430,39 @@ impl SearchQuery {
                     None
                 }
             }
+            SearchQuery::Structural {
+                pattern,
+                replacement,
+                ..
+            } => replacement
+                .as_ref()
+                .and_then(|replacement| pattern.replace(text, replacement))
+                .map(Cow::from),
         }
     }
 
+    /// Returns the replacement for the match of this `SearchQuery` at `range` in the buffer.
+    /// Unlike [`Self::replacement_for`], structural queries replace their metavariables with the
+    /// code they matched in the buffer's syntax tree.
+    pub fn replacement_for_range(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        if let SearchQuery::Structural {
+            pattern,
+            replacement: Some(replacement),
+            ..
+        } = self
+        {
+            if let Some(replacement) = pattern.replace_match(buffer, range.clone(), replacement) {
+                return Some(replacement);
+            }
+        }
+        let text = buffer.text_for_range(range).collect::<String>();
+        self.replacement_for(&text).map(Cow::into_owned)
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -254,10 +475,12 @@ impl SearchQuery {
         }
 
         let range_offset = subrange.as_ref().map(|r| r.start).unwrap_or(0);
-        let rope = if let Some(range) = subrange {
-            buffer.as_rope().slice(range)
-        } else {
-            buffer.as_rope().clone()
+        let rope = || {
+            if let Some(range) = subrange.clone() {
+                buffer.as_rope().slice(range)
+            } else {
+                buffer.as_rope().clone()
+            }
         };
 
         let mut matches = Vec::new();
@@ -265,6 +488,7 @@ impl SearchQuery {
             Self::Text {
                 search, whole_word, ..
             } => {
+                let rope = rope();
                 for (ix, mat) in search
                     .stream_find_iter(rope.bytes_in_range(0..rope.len()))
                     .enumerate()
@@ -293,6 +517,7 @@ impl SearchQuery {
             Self::Regex {
                 regex, multiline, ..
             } => {
+                let rope = rope();
                 if *multiline {
                     let text = rope.to_string();
                     for (ix, mat) in regex.find_iter(&text).enumerate() {
@@ -326,9 +551,45 @@ impl SearchQuery {
                     }
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let range = subrange.unwrap_or(0..buffer.len());
+                matches.extend(
+                    pattern
+                        .find_matches(buffer, range)
+                        .into_iter()
+                        .map(|mat| mat.start - range_offset..mat.end - range_offset),
+                );
+            }
         }
 
-        matches
+        self.filter_by_syntax_context(buffer, range_offset, matches)
+            .await
+    }
+
+    /// Drops the matches outside of the query's syntax context, if it has one.
+    async fn filter_by_syntax_context(
+        &self,
+        buffer: &BufferSnapshot,
+        range_offset: usize,
+        matches: Vec<Range<usize>>,
+    ) -> Vec<Range<usize>> {
+        const YIELD_INTERVAL: usize = 1000;
+
+        let Some(syntax_context) = self.syntax_context() else {
+            return matches;
+        };
+        let mut filtered = Vec::new();
+        for (ix, mat) in matches.into_iter().enumerate() {
+            if (ix + 1) % YIELD_INTERVAL == 0 {
+                yield_now().await;
+            }
+
+            let range = range_offset + mat.start..range_offset + mat.end;
+            if syntax_context.contains(buffer, range) {
+                filtered.push(mat);
+            }
+        }
+        filtered
     }
 
     pub fn is_empty(&self) -> bool {
@@ -343,6 +604,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -350,6 +612,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -361,6 +624,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -368,6 +634,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -376,6 +646,19 @@ impl SearchQuery {
         self.as_inner().files_to_exclude()
     }
 
+    pub fn syntax_context(&self) -> Option<SyntaxContext> {
+        self.as_inner().syntax_context()
+    }
+
+    pub fn preserve_case(&self) -> bool {
+        self.as_inner().preserve_case()
+    }
+
+    /// Whether matching needs buffers' syntax trees, rather than only their text.
+    pub fn requires_syntax_tree(&self) -> bool {
+        self.is_structural() || self.syntax_context().is_some()
+    }
+
     pub fn file_matches(&self, file_path: Option<&Path>) -> bool {
         match file_path {
             Some(file_path) => {
@@ -397,11 +680,105 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 }
 
+fn build_regex(
+    query: &str,
+    whole_word: bool,
+    case_sensitive: bool,
+    multiline: bool,
+) -> Result<Regex> {
+    let query = if whole_word {
+        format!("\\b{query}\\b")
+    } else {
+        query.to_string()
+    };
+    Ok(RegexBuilder::new(&query)
+        .case_insensitive(!case_sensitive)
+        .multi_line(multiline)
+        .build()?)
+}
+
+/// Returns whether the text is made of words that can be recased.
+fn is_identifier_like(text: &str) -> bool {
+    text.chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | ' '))
+}
+
+/// Returns the text in each case form recognized when preserving case, if
+/// it is made of words that can be recased.
+fn case_variants(text: &str) -> Vec<String> {
+    let mut variants = vec![text.to_string()];
+    if is_identifier_like(text) {
+        for case in [
+            Case::Camel,
+            Case::Pascal,
+            Case::Snake,
+            Case::UpperSnake,
+            Case::Kebab,
+        ] {
+            let variant = text.to_case(case);
+            if !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+    }
+    variants
+}
+
+/// Converts the replacement to the case form of the text it replaces. Other
+/// replacements, such as code, are kept as is.
+fn convert_to_case_of(matched: &str, replacement: &str) -> String {
+    if !is_identifier_like(matched) || !is_identifier_like(replacement) {
+        return replacement.to_string();
+    }
+
+    let has_lowercase = matched.chars().any(char::is_lowercase);
+    let has_uppercase = matched.chars().any(char::is_uppercase);
+    if !has_lowercase && !has_uppercase {
+        return replacement.to_string();
+    }
+
+    let case = if matched.contains('_') {
+        if has_lowercase {
+            Case::Snake
+        } else {
+            Case::UpperSnake
+        }
+    } else if matched.contains('-') {
+        if has_lowercase {
+            Case::Kebab
+        } else {
+            Case::Cobol
+        }
+    } else if !has_lowercase {
+        Case::UpperSnake
+    } else if matched.chars().skip(1).any(char::is_uppercase) {
+        if matched.starts_with(char::is_uppercase) {
+            Case::Pascal
+        } else {
+            Case::Camel
+        }
+    } else {
+        // A single word only says how the replacement should start.
+        let mut chars = replacement.chars();
+        let Some(first) = chars.next() else {
+            return String::new();
+        };
+        return if matched.starts_with(char::is_uppercase) {
+            first.to_uppercase().chain(chars).collect()
+        } else {
+            first.to_lowercase().chain(chars).collect()
+        };
+    };
+    replacement.to_case(case)
+}
+
 fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<PathMatcher> {
     let globs = glob_set
         .split(',')
@@ -415,6 +792,80 @@ fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<PathMatcher> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_preserving_case() {
+        assert_eq!(
+            case_variants("fooBar"),
+            ["fooBar", "FooBar", "foo_bar", "FOO_BAR", "foo-bar"]
+        );
+        assert_eq!(case_variants("foo.bar()"), ["foo.bar()"]);
+
+        for (matched, replaced) in [
+            ("fooBar", "bazQux"),
+            ("FooBar", "BazQux"),
+            ("foo_bar", "baz_qux"),
+            ("FOO_BAR", "BAZ_QUX"),
+            ("foo-bar", "baz-qux"),
+            ("foo", "bazQux"),
+            ("Foo", "BazQux"),
+            ("123", "bazQux"),
+        ] {
+            assert_eq!(convert_to_case_of(matched, "bazQux"), replaced);
+        }
+        // Only identifiers are recased.
+        assert_eq!(convert_to_case_of("foo_bar", "self.baz"), "self.baz");
+        assert_eq!(convert_to_case_of("foo_bar()", "bazQux"), "bazQux");
+
+        let query = SearchQuery::text(
+            "fooBar",
+            false,
+            true,
+            false,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap()
+        .with_preserve_case(true)
+        .unwrap()
+        .with_replacement("bazQux".to_string());
+        assert!(query.detect("let FOO_BAR = 1;".as_bytes()).unwrap());
+        assert!(!query.detect("let foobar = 1;".as_bytes()).unwrap());
+        assert_eq!(query.replacement_for("foo_bar").as_deref(), Some("baz_qux"));
+
+        let query = SearchQuery::regex(
+            "fooBar",
+            true,
+            true,
+            false,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap()
+        .with_preserve_case(true)
+        .unwrap()
+        .with_replacement("bazQux".to_string());
+        assert!(query.detect("let FOO_BAR = 1;".as_bytes()).unwrap());
+        assert!(!query.detect("let foo_bar_baz = 1;".as_bytes()).unwrap());
+        assert_eq!(query.replacement_for("foo-bar").as_deref(), Some("baz-qux"));
+
+        // Other regexes are kept, and only their replacements are recased.
+        let query = SearchQuery::regex(
+            "foo(\\w+)",
+            false,
+            true,
+            false,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap()
+        .with_preserve_case(true)
+        .unwrap()
+        .with_replacement("baz$1".to_string());
+        assert!(!query.detect("let FOO_BAR = 1;".as_bytes()).unwrap());
+        assert_eq!(query.replacement_for("foo_bar").as_deref(), Some("baz_bar"));
+        assert_eq!(query.replacement_for("fooBar").as_deref(), Some("bazBar"));
+    }
+
     #[test]
     fn path_matcher_creation_for_valid_paths() {
         for valid_path in [
@@ -0,0 +1,619 @@
+use anyhow::{bail, Result};
+use collections::HashMap;
+use language::{BufferSnapshot, Node};
+use std::{iter, ops::Range};
+
+/// A search pattern made of code and metavariables, matched against syntax
+/// trees rather than text.
+///
+/// `$NAME` matches any single syntax node and `$$$NAME` matches any number
+/// of sibling nodes. A name can be used several times to require the same
+/// code in each place, and `$_` and `$$$` match without capturing anything.
+/// Whitespace in the pattern matches any whitespace in the code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralPattern {
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    /// Code to match exactly, with whitespace collapsed. Spaces are only kept
+    /// between two word characters, where they separate tokens.
+    Literal(String),
+    Metavariable {
+        name: Option<String>,
+        multiple: bool,
+    },
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Metavariable {
+        name: Option<&'a str>,
+        multiple: bool,
+    },
+}
+
+type Bindings<'a> = HashMap<&'a str, &'a str>;
+
+impl StructuralPattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        for token in tokenize(pattern) {
+            match token {
+                Token::Text(text) => {
+                    let text = collapse_whitespace(text);
+                    if text.is_empty() {
+                        continue;
+                    }
+                    if let Some(Segment::Literal(literal)) = segments.last_mut() {
+                        literal.push_str(&text);
+                    } else {
+                        segments.push(Segment::Literal(text));
+                    }
+                }
+                Token::Metavariable { name, multiple } => segments.push(Segment::Metavariable {
+                    name: name.map(str::to_string),
+                    multiple,
+                }),
+            }
+        }
+
+        if !segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Literal(_)))
+        {
+            bail!("a structural pattern must contain code besides metavariables");
+        }
+        Ok(Self { segments })
+    }
+
+    /// Returns the words of code in the pattern, which every matching file
+    /// has to contain.
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Literal(literal) => Some(literal.as_str()),
+                Segment::Metavariable { .. } => None,
+            })
+            .flat_map(|literal| literal.split(|c: char| !is_word_char(c)))
+            .filter(|word| !word.is_empty())
+    }
+
+    /// Returns the byte ranges of the syntax nodes within `range` in the
+    /// buffer matching the pattern, in order. Matches nested in other matches
+    /// are skipped.
+    pub fn find_matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+        let text = buffer.text_for_range(range.clone()).collect::<String>();
+        let source = Source {
+            text: &text,
+            start: range.start,
+        };
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let mut cursor = layer.node().walk();
+            'nodes: loop {
+                let node = cursor.node();
+                // Only the nodes intersecting the range are walked.
+                if node.start_byte() < range.end && node.end_byte() > range.start {
+                    if node.is_named()
+                        && node.start_byte() >= range.start
+                        && node.end_byte() <= range.end
+                        && self.match_node(node, source).is_some()
+                    {
+                        matches.push(node.byte_range());
+                    }
+                    if cursor.goto_first_child() {
+                        continue;
+                    }
+                }
+
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() {
+                        break 'nodes;
+                    }
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+        matches.dedup();
+        let mut last_end = 0;
+        matches.retain(|range| {
+            if range.start >= last_end {
+                last_end = range.end;
+                true
+            } else {
+                false
+            }
+        });
+        matches
+    }
+
+    /// Replaces the metavariables in `replacement` with the code they matched
+    /// in the match of the pattern at `range` in the buffer, as returned by
+    /// [`Self::find_matches`]. Returns `None` if no syntax node at `range`
+    /// matches the pattern.
+    pub fn replace_match(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+        replacement: &str,
+    ) -> Option<String> {
+        let text = buffer.text_for_range(range.clone()).collect::<String>();
+        let source = Source {
+            text: &text,
+            start: range.start,
+        };
+        let bindings = buffer.syntax_layers().find_map(|layer| {
+            let node = layer
+                .node()
+                .descendant_for_byte_range(range.start, range.end);
+            iter::successors(node, |node| node.parent())
+                .take_while(|node| node.byte_range() == range)
+                .filter(|node| node.is_named())
+                .find_map(|node| self.match_node(node, source))
+        })?;
+        Some(substitute(&bindings, replacement))
+    }
+
+    /// Matches the pattern against a whole node, returning what its
+    /// metavariables matched.
+    fn match_node<'a>(&'a self, node: Node, source: Source<'a>) -> Option<Bindings<'a>> {
+        let mut bindings = Bindings::default();
+        self.match_node_segments(0, node.start_byte(), node, source, &mut bindings)
+            .then_some(bindings)
+    }
+
+    fn match_node_segments<'a>(
+        &'a self,
+        ix: usize,
+        offset: usize,
+        root: Node,
+        source: Source<'a>,
+        bindings: &mut Bindings<'a>,
+    ) -> bool {
+        let end = root.end_byte();
+        let Some(segment) = self.segments.get(ix) else {
+            return source.slice(offset..end).trim().is_empty();
+        };
+
+        match segment {
+            Segment::Literal(literal) => match_literal(literal, source.slice(offset..end))
+                .map_or(false, |len| {
+                    self.match_node_segments(ix + 1, offset + len, root, source, bindings)
+                }),
+            Segment::Metavariable { name, multiple } => {
+                let start = offset + leading_whitespace_len(source.slice(offset..end));
+                if *multiple
+                    && bind(bindings, name.as_deref(), "", |bindings| {
+                        self.match_node_segments(ix + 1, offset, root, source, bindings)
+                    })
+                {
+                    return true;
+                }
+
+                for first in nodes_starting_at(root, start) {
+                    if !*multiple && !first.is_named() {
+                        continue;
+                    }
+
+                    let mut last = first;
+                    loop {
+                        let captured = source.slice(start..last.end_byte());
+                        if bind(bindings, name.as_deref(), captured, |bindings| {
+                            self.match_node_segments(
+                                ix + 1,
+                                last.end_byte(),
+                                root,
+                                source,
+                                bindings,
+                            )
+                        }) {
+                            return true;
+                        }
+
+                        if !*multiple {
+                            break;
+                        }
+                        match last.next_sibling() {
+                            Some(next) if next.end_byte() <= end => last = next,
+                            _ => break,
+                        }
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    /// Replaces the metavariables in `replacement` with the code they matched
+    /// in `text`, which is assumed to be a whole match of the pattern.
+    ///
+    /// Without a syntax tree, the captures are recovered from the text alone:
+    /// each metavariable takes the shortest run of code with balanced brackets
+    /// that lets the rest of the pattern match. Prefer [`Self::replace_match`]
+    /// when the buffer containing the match is available.
+    pub fn replace(&self, text: &str, replacement: &str) -> Option<String> {
+        let mut bindings = Bindings::default();
+        if !self.match_text_segments(0, 0, text, &mut bindings) {
+            return None;
+        }
+        Some(substitute(&bindings, replacement))
+    }
+
+    fn match_text_segments<'a>(
+        &'a self,
+        ix: usize,
+        offset: usize,
+        text: &'a str,
+        bindings: &mut Bindings<'a>,
+    ) -> bool {
+        let Some(segment) = self.segments.get(ix) else {
+            return text[offset..].trim().is_empty();
+        };
+
+        match segment {
+            Segment::Literal(literal) => match_literal(literal, &text[offset..])
+                .map_or(false, |len| {
+                    self.match_text_segments(ix + 1, offset + len, text, bindings)
+                }),
+            Segment::Metavariable { name, multiple } => {
+                let start = offset + leading_whitespace_len(&text[offset..]);
+                for end in start..=text.len() {
+                    if !text.is_char_boundary(end) {
+                        continue;
+                    }
+                    let captured = text[start..end].trim_end();
+                    if (captured.is_empty() && !*multiple)
+                        || captured.len() < end - start
+                        || !has_balanced_brackets(captured)
+                    {
+                        continue;
+                    }
+                    if bind(bindings, name.as_deref(), captured, |bindings| {
+                        self.match_text_segments(ix + 1, end, text, bindings)
+                    }) {
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+    }
+}
+
+/// The text of a range of a buffer, sliced by buffer offsets.
+#[derive(Clone, Copy)]
+struct Source<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+impl<'a> Source<'a> {
+    fn slice(&self, range: Range<usize>) -> &'a str {
+        &self.text[range.start - self.start..range.end - self.start]
+    }
+}
+
+/// Replaces the metavariables in `replacement` with the code bound to them.
+/// Metavariables that weren't bound are kept as is.
+fn substitute(bindings: &Bindings, replacement: &str) -> String {
+    let mut result = String::new();
+    for token in tokenize(replacement) {
+        match token {
+            Token::Text(text) => result.push_str(text),
+            Token::Metavariable {
+                name: Some(name),
+                multiple,
+            } => match bindings.get(name) {
+                Some(captured) => result.push_str(captured),
+                None => {
+                    result.push_str(if multiple { "$$$" } else { "$" });
+                    result.push_str(name);
+                }
+            },
+            Token::Metavariable {
+                name: None,
+                multiple,
+            } => result.push_str(if multiple { "$$$" } else { "$_" }),
+        }
+    }
+    result
+}
+
+/// Splits a pattern or replacement into text and metavariables. A `$` that
+/// doesn't start a metavariable is kept as text.
+fn tokenize(pattern: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut offset = 0;
+    while let Some(dollar) = pattern[offset..].find('$').map(|ix| offset + ix) {
+        let multiple = pattern[dollar..].starts_with("$$$");
+        let name_start = dollar + if multiple { 3 } else { 1 };
+        let name_len = metavariable_name_len(&pattern[name_start..]);
+        if !multiple && name_len == 0 {
+            offset = dollar + 1;
+            continue;
+        }
+
+        if text_start < dollar {
+            tokens.push(Token::Text(&pattern[text_start..dollar]));
+        }
+        let name = &pattern[name_start..name_start + name_len];
+        tokens.push(Token::Metavariable {
+            name: Some(name).filter(|name| !name.is_empty() && *name != "_"),
+            multiple,
+        });
+        offset = name_start + name_len;
+        text_start = offset;
+    }
+    if text_start < pattern.len() {
+        tokens.push(Token::Text(&pattern[text_start..]));
+    }
+    tokens
+}
+
+/// Returns the length of the metavariable name at the start of `text`, made
+/// of uppercase letters, digits and underscores.
+fn metavariable_name_len(text: &str) -> usize {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_uppercase() || c == '_' => {}
+        _ => return 0,
+    }
+    1 + chars
+        .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || *c == '_')
+        .count()
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::new();
+    let mut pending_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space && result.chars().last().map_or(false, is_word_char) && is_word_char(c) {
+            result.push(' ');
+        }
+        pending_space = false;
+        result.push(c);
+    }
+    result
+}
+
+/// Matches a collapsed literal against the start of `text`, skipping
+/// whitespace, and returns the length of the text it matched.
+fn match_literal(literal: &str, text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+    let mut len = 0;
+    for expected in literal.chars() {
+        let mut skipped_whitespace = false;
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {
+            skipped_whitespace = true;
+        }
+        if expected == ' ' {
+            if !skipped_whitespace {
+                return None;
+            }
+            continue;
+        }
+
+        let (ix, c) = chars.next()?;
+        if c != expected {
+            return None;
+        }
+        len = ix + c.len_utf8();
+    }
+    Some(len)
+}
+
+fn leading_whitespace_len(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+/// Binds the metavariable to the captured code while `f` runs, or checks the
+/// code against an earlier capture of the same name.
+fn bind<'a>(
+    bindings: &mut Bindings<'a>,
+    name: Option<&'a str>,
+    captured: &'a str,
+    f: impl FnOnce(&mut Bindings<'a>) -> bool,
+) -> bool {
+    let Some(name) = name else {
+        return f(bindings);
+    };
+    if let Some(bound) = bindings.get(name) {
+        let strip = |text: &str| text.split_whitespace().collect::<String>();
+        return strip(bound) == strip(captured) && f(bindings);
+    }
+
+    bindings.insert(name, captured);
+    let matched = f(bindings);
+    if !matched {
+        bindings.remove(name);
+    }
+    matched
+}
+
+/// Returns the nodes within `root` that start at `offset`, from the smallest.
+fn nodes_starting_at<'a>(root: Node<'a>, offset: usize) -> Vec<Node<'a>> {
+    let mut nodes = Vec::new();
+    if offset >= root.end_byte() {
+        return nodes;
+    }
+
+    let mut node = root.descendant_for_byte_range(offset, offset + 1);
+    while let Some(current) = node {
+        if current.start_byte() != offset {
+            break;
+        }
+        nodes.push(current);
+        if current == root {
+            break;
+        }
+        node = current.parent();
+    }
+    nodes
+}
+
+/// Returns whether the brackets in `text` are balanced, ignoring those in
+/// string and character literals. A single quote followed by a word that
+/// isn't closed right after it, such as a Rust lifetime, doesn't start a
+/// literal.
+fn has_balanced_brackets(text: &str) -> bool {
+    let mut stack = Vec::new();
+    let mut offset = 0;
+    while let Some(c) = text[offset..].chars().next() {
+        offset += c.len_utf8();
+        match c {
+            '"' => match quoted_literal_len(&text[offset..], '"') {
+                Some(len) => offset += len,
+                None => return false,
+            },
+            '\'' => {
+                let rest = &text[offset..];
+                let word_len = rest.len() - rest.trim_start_matches(is_word_char).len();
+                let is_lifetime = word_len > 0 && !rest[word_len..].starts_with('\'');
+                if !is_lifetime {
+                    offset += quoted_literal_len(rest, '\'').unwrap_or(0);
+                }
+            }
+            '(' => stack.push(')'),
+            '[' => stack.push(']'),
+            '{' => stack.push('}'),
+            ')' | ']' | '}' => {
+                if stack.pop() != Some(c) {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    stack.is_empty()
+}
+
+/// Returns the length of the rest of a literal opened by `quote`, up to and
+/// including the closing quote. Single quoted literals end with their line.
+fn quoted_literal_len(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (ix, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\n' if quote == '\'' => return None,
+            _ if c == quote => return Some(ix + c.len_utf8()),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_structural_pattern_parsing() {
+        assert_eq!(
+            StructuralPattern::new("$X.unwrap( )").unwrap().segments,
+            [
+                Segment::Metavariable {
+                    name: Some("X".into()),
+                    multiple: false
+                },
+                Segment::Literal(".unwrap()".into()),
+            ]
+        );
+        assert_eq!(
+            StructuralPattern::new("let  mut $_ = foo($$$ARGS)")
+                .unwrap()
+                .segments,
+            [
+                Segment::Literal("let mut".into()),
+                Segment::Metavariable {
+                    name: None,
+                    multiple: false
+                },
+                Segment::Literal("=foo(".into()),
+                Segment::Metavariable {
+                    name: Some("ARGS".into()),
+                    multiple: true
+                },
+                Segment::Literal(")".into()),
+            ]
+        );
+        assert_eq!(
+            StructuralPattern::new("$x + $").unwrap().segments,
+            [Segment::Literal("$x+$".into())]
+        );
+        assert!(StructuralPattern::new("$A $$$B").is_err());
+        assert_eq!(
+            StructuralPattern::new("foo($A, bar($B))")
+                .unwrap()
+                .words()
+                .collect::<Vec<_>>(),
+            ["foo", "bar"]
+        );
+    }
+
+    #[test]
+    fn test_structural_replacement() {
+        let pattern = StructuralPattern::new("$X.unwrap()").unwrap();
+        assert_eq!(
+            pattern
+                .replace("foo(a, b).unwrap()", "$X.expect(\"$X\")")
+                .as_deref(),
+            Some("foo(a, b).expect(\"foo(a, b)\")")
+        );
+        assert_eq!(
+            pattern.replace("foo(a).bar()", "$X"),
+            None,
+            "text that doesn't match the pattern"
+        );
+
+        let pattern = StructuralPattern::new("assert_eq!($A, $B)").unwrap();
+        assert_eq!(
+            pattern
+                .replace("assert_eq!(f(x, y),\n    [1, 2])", "assert_eq!($B, $A)")
+                .as_deref(),
+            Some("assert_eq!([1, 2], f(x, y))")
+        );
+
+        let pattern = StructuralPattern::new("call($$$ARGS)").unwrap();
+        assert_eq!(
+            pattern.replace("call()", "call(cx, $$$ARGS)").as_deref(),
+            Some("call(cx, )")
+        );
+        assert_eq!(
+            pattern
+                .replace("call(\"(\", b)", "call($$$ARGS, $UNKNOWN)")
+                .as_deref(),
+            Some("call(\"(\", b, $UNKNOWN)")
+        );
+    }
+
+    #[test]
+    fn test_balanced_brackets() {
+        assert!(has_balanced_brackets("f(a, [b, {c}])"));
+        assert!(has_balanced_brackets("f(')')"));
+        assert!(has_balanced_brackets("'('"));
+        assert!(has_balanced_brackets("'\\''"));
+        assert!(has_balanced_brackets("x: &'a (u8)"));
+        assert!(has_balanced_brackets("'outer: loop { break 'outer; }"));
+        assert!(has_balanced_brackets("['[', \"]\"]"));
+        assert!(has_balanced_brackets("\"\\\"(\""));
+
+        assert!(!has_balanced_brackets("f("));
+        assert!(!has_balanced_brackets("f(]"));
+        assert!(!has_balanced_brackets("\"(\\\")"));
+    }
+}
@@ -146,6 +146,7 @@ actions!(
         UnfoldDirectory,
         FoldDirectory,
         SelectParent,
+        CompareMarkedFiles,
     ]
 );
 
@@ -460,6 +461,7 @@ impl ProjectPanel {
             let is_local = project.is_local();
             let is_read_only = project.is_read_only();
             let is_remote = project.is_remote();
+            let can_compare_files = self.marked_file_pair(cx).is_some();
 
             let context_menu = ContextMenu::build(cx, |menu, cx| {
                 menu.context(self.focus_handle.clone()).when_else(
@@ -476,6 +478,9 @@ impl ProjectPanel {
                             .separator()
                             .action("Reveal in Finder", Box::new(RevealInFinder))
                             .action("Open in Terminal", Box::new(OpenInTerminal))
+                            .when(can_compare_files, |menu| {
+                                menu.action("Compare Marked Files", Box::new(CompareMarkedFiles))
+                            })
                             .when(is_dir, |menu| {
                                 menu.separator()
                                     .action("Find in Folder…", Box::new(NewSearchInDirectory))
@@ -1402,6 +1407,38 @@ impl ProjectPanel {
         }
     }
 
+    fn compare_marked_files(&mut self, _: &CompareMarkedFiles, cx: &mut ViewContext<Self>) {
+        let Some([old_path, new_path]) = self.marked_file_pair(cx) else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                diff_view::DiffView::open_for_paths(old_path, new_path, workspace, cx)
+                    .detach_and_prompt_err("Failed to compare files", cx, |error, _| {
+                        Some(error.to_string())
+                    });
+            })
+            .ok();
+    }
+
+    /// Returns the paths of the marked entries if exactly two files are marked.
+    fn marked_file_pair(&self, cx: &AppContext) -> Option<[ProjectPath; 2]> {
+        let marked_entries = self.marked_entries();
+        if marked_entries.len() != 2 {
+            return None;
+        }
+        let project = self.project.read(cx);
+        let mut paths = marked_entries.iter().map(|selection| {
+            let worktree = project.worktree_for_id(selection.worktree_id, cx)?;
+            let entry = worktree.read(cx).entry_for_id(selection.entry_id)?;
+            entry.is_file().then(|| ProjectPath {
+                worktree_id: selection.worktree_id,
+                path: entry.path.clone(),
+            })
+        });
+        Some([paths.next()??, paths.next()??])
+    }
+
     fn move_entry(
         &mut self,
         entry_to_move: ProjectEntryId,
@@ -2391,6 +2428,7 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::new_search_in_directory))
                 .on_action(cx.listener(Self::unfold_directory))
                 .on_action(cx.listener(Self::fold_directory))
+                .on_action(cx.listener(Self::compare_marked_files))
                 .when(!project.is_read_only(), |el| {
                     el.on_action(cx.listener(Self::new_file))
                         .on_action(cx.listener(Self::new_directory))
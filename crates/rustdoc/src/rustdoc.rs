@@ -1,9 +0,0 @@
-mod indexer;
-mod item;
-mod store;
-mod to_markdown;
-
-pub use crate::indexer::{DocsDotRsProvider, LocalProvider, RustdocSource};
-pub use crate::item::*;
-pub use crate::store::*;
-pub use crate::to_markdown::convert_rustdoc_to_markdown;
@@ -3,7 +3,8 @@ mod registrar;
 use crate::{
     search_bar::render_nav_button, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
     ReplaceNext, SearchOptions, SelectAllMatches, SelectNextMatch, SelectPrevMatch,
-    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
+    ToggleCaseSensitive, ToggleCodeOnly, ToggleCommentsOnly, ToggleRegex, ToggleReplace,
+    ToggleSelection, ToggleStringsOnly, ToggleStructural, ToggleWholeWord,
 };
 use any_vec::AnyVec;
 use collections::HashMap;
@@ -19,7 +20,7 @@ use gpui::{
     ViewContext, VisualContext as _, WhiteSpace, WindowContext,
 };
 use project::{
-    search::SearchQuery,
+    search::{SearchQuery, SyntaxContext},
     search_history::{SearchHistory, SearchHistoryCursor},
 };
 use serde::Deserialize;
@@ -151,6 +152,7 @@ impl Render for BufferSearchBar {
             > self.editor_scroll_handle.bounds().size.width - cx.rem_size() * 6.;
 
         let supported_options = self.supported_options();
+        let supports_syntax_context = self.supports_syntax_context(cx);
 
         if self.query_editor.update(cx, |query_editor, cx| {
             query_editor.placeholder_text(cx).is_none()
@@ -238,6 +240,34 @@ impl Render for BufferSearchBar {
                                 cx.listener(|this, _, cx| this.toggle_regex(&ToggleRegex, cx)),
                             )
                         }))
+                        .children(supported_options.structural.then(|| {
+                            self.render_search_option_button(
+                                SearchOptions::STRUCTURAL,
+                                cx.listener(|this, _, cx| {
+                                    this.toggle_structural(&ToggleStructural, cx)
+                                }),
+                            )
+                        }))
+                        .when(supports_syntax_context, |div| {
+                            div.child(self.render_search_option_button(
+                                SearchOptions::CODE_ONLY,
+                                cx.listener(|this, _, cx| {
+                                    this.toggle_code_only(&ToggleCodeOnly, cx)
+                                }),
+                            ))
+                            .child(self.render_search_option_button(
+                                SearchOptions::COMMENTS_ONLY,
+                                cx.listener(|this, _, cx| {
+                                    this.toggle_comments_only(&ToggleCommentsOnly, cx)
+                                }),
+                            ))
+                            .child(self.render_search_option_button(
+                                SearchOptions::STRINGS_ONLY,
+                                cx.listener(|this, _, cx| {
+                                    this.toggle_strings_only(&ToggleStringsOnly, cx)
+                                }),
+                            ))
+                        })
                     }),
             )
             .when(supported_options.replacement, |this| {
@@ -383,6 +413,14 @@ impl Render for BufferSearchBar {
             .when(self.supported_options().regex, |this| {
                 this.on_action(cx.listener(Self::toggle_regex))
             })
+            .when(self.supported_options().structural, |this| {
+                this.on_action(cx.listener(Self::toggle_structural))
+            })
+            .when(self.supported_options().syntax_context, |this| {
+                this.on_action(cx.listener(Self::toggle_code_only))
+                    .on_action(cx.listener(Self::toggle_comments_only))
+                    .on_action(cx.listener(Self::toggle_strings_only))
+            })
             .when(self.supported_options().selection, |this| {
                 this.on_action(cx.listener(Self::toggle_selection))
             })
@@ -621,6 +659,23 @@ impl BufferSearchBar {
             .map(SearchableItemHandle::supported_options)
             .unwrap_or_default()
     }
+
+    /// The syntax context toggles are hidden and ignored while the active
+    /// item can't tell code, comments and strings apart, such as in a plain
+    /// text buffer, where every match would be classified as code.
+    fn supports_syntax_context(&self, cx: &AppContext) -> bool {
+        self.supported_options().syntax_context
+            && self
+                .active_searchable_item
+                .as_ref()
+                .map_or(false, |item| item.supports_syntax_context(cx))
+    }
+
+    fn syntax_context(&self, cx: &AppContext) -> Option<SyntaxContext> {
+        self.search_options
+            .syntax_context()
+            .filter(|_| self.supports_syntax_context(cx))
+    }
     pub fn search_suggested(&mut self, cx: &mut ViewContext<Self>) {
         let search = self
             .query_suggestion(cx)
@@ -705,6 +760,10 @@ impl BufferSearchBar {
         self.update_matches(cx)
     }
 
+    pub fn has_search_option(&self, option: SearchOptions) -> bool {
+        self.search_options.contains(option)
+    }
+
     fn render_search_option_button(
         &self,
         option: SearchOptions,
@@ -722,7 +781,7 @@ impl BufferSearchBar {
     }
 
     fn toggle_search_option(&mut self, search_option: SearchOptions, cx: &mut ViewContext<Self>) {
-        self.search_options.toggle(search_option);
+        self.search_options.toggle_exclusive(search_option);
         self.default_options = self.search_options;
         let _ = self.update_matches(cx);
         cx.notify();
@@ -879,6 +938,22 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, cx)
     }
 
+    fn toggle_structural(&mut self, _: &ToggleStructural, cx: &mut ViewContext<Self>) {
+        self.toggle_search_option(SearchOptions::STRUCTURAL, cx)
+    }
+
+    fn toggle_code_only(&mut self, _: &ToggleCodeOnly, cx: &mut ViewContext<Self>) {
+        self.toggle_search_option(SearchOptions::CODE_ONLY, cx)
+    }
+
+    fn toggle_comments_only(&mut self, _: &ToggleCommentsOnly, cx: &mut ViewContext<Self>) {
+        self.toggle_search_option(SearchOptions::COMMENTS_ONLY, cx)
+    }
+
+    fn toggle_strings_only(&mut self, _: &ToggleStringsOnly, cx: &mut ViewContext<Self>) {
+        self.toggle_search_option(SearchOptions::STRINGS_ONLY, cx)
+    }
+
     fn clear_active_searchable_item_matches(&mut self, cx: &mut WindowContext) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -922,7 +997,24 @@ impl BufferSearchBar {
                 let _ = done_tx.send(());
                 cx.notify();
             } else {
-                let query: Arc<_> = if self.search_options.contains(SearchOptions::REGEX) {
+                let query: Arc<_> = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+                    match SearchQuery::structural(
+                        query,
+                        false,
+                        Default::default(),
+                        Default::default(),
+                    ) {
+                        Ok(query) => query
+                            .with_replacement(self.replacement(cx))
+                            .with_syntax_context(self.syntax_context(cx)),
+                        Err(_) => {
+                            self.query_contains_error = true;
+                            self.clear_active_searchable_item_matches(cx);
+                            cx.notify();
+                            return done_rx;
+                        }
+                    }
+                } else if self.search_options.contains(SearchOptions::REGEX) {
                     match SearchQuery::regex(
                         query,
                         self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -931,7 +1023,9 @@ impl BufferSearchBar {
                         Default::default(),
                         Default::default(),
                     ) {
-                        Ok(query) => query.with_replacement(self.replacement(cx)),
+                        Ok(query) => query
+                            .with_replacement(self.replacement(cx))
+                            .with_syntax_context(self.syntax_context(cx)),
                         Err(_) => {
                             self.query_contains_error = true;
                             self.clear_active_searchable_item_matches(cx);
@@ -948,7 +1042,9 @@ impl BufferSearchBar {
                         Default::default(),
                         Default::default(),
                     ) {
-                        Ok(query) => query.with_replacement(self.replacement(cx)),
+                        Ok(query) => query
+                            .with_replacement(self.replacement(cx))
+                            .with_syntax_context(self.syntax_context(cx)),
                         Err(_) => {
                             self.query_contains_error = true;
                             self.clear_active_searchable_item_matches(cx);
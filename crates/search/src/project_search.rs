@@ -1,14 +1,16 @@
 use crate::{
     FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
-    ToggleReplace, ToggleWholeWord,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleCodeOnly, ToggleCommentsOnly,
+    ToggleIncludeIgnored, TogglePreserveCase, ToggleRegex, ToggleReplace, ToggleStringsOnly,
+    ToggleStructural, ToggleWholeWord,
 };
 use collections::{HashMap, HashSet};
 use editor::{
     actions::SelectAll,
     items::active_match_index,
     scroll::{Autoscroll, Axis},
-    Anchor, Editor, EditorElement, EditorEvent, EditorStyle, MultiBuffer, MAX_TAB_TITLE_LEN,
+    Anchor, AnchorRangeExt as _, Editor, EditorElement, EditorEvent, EditorStyle, MultiBuffer,
+    MAX_TAB_TITLE_LEN,
 };
 use gpui::{
     actions, div, Action, AnyElement, AnyView, AppContext, Context as _, Element, EntityId,
@@ -26,6 +28,7 @@ use std::{
     mem,
     ops::{Not, Range},
     path::Path,
+    time::Duration,
 };
 use theme::ThemeSettings;
 use ui::{
@@ -43,6 +46,11 @@ use workspace::{
 const MIN_INPUT_WIDTH_REMS: f32 = 15.;
 const MAX_INPUT_WIDTH_REMS: f32 = 30.;
 
+/// The most replacements previewed in the results, so that typing the
+/// replacement stays responsive when there are many matches.
+const MAX_PREVIEWED_REPLACEMENTS: usize = 1000;
+const REPLACEMENT_PREVIEWS_DEBOUNCE: Duration = Duration::from_millis(100);
+
 actions!(
     project_search,
     [SearchInNew, ToggleFocus, NextField, ToggleFilters]
@@ -71,6 +79,21 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleStructural, cx| {
+            search_bar.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleCodeOnly, cx| {
+            search_bar.toggle_search_option(SearchOptions::CODE_ONLY, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleCommentsOnly, cx| {
+            search_bar.toggle_search_option(SearchOptions::COMMENTS_ONLY, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleStringsOnly, cx| {
+            search_bar.toggle_search_option(SearchOptions::STRINGS_ONLY, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &TogglePreserveCase, cx| {
+            search_bar.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+        });
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
@@ -148,6 +171,7 @@ pub struct ProjectSearchView {
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    replacement_previews_task: Task<()>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -541,7 +565,7 @@ impl ProjectSearchView {
     }
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut ViewContext<Self>) {
-        self.search_options.toggle(option);
+        self.search_options.toggle_exclusive(option);
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.model.read(cx).project.downgrade(),
@@ -567,6 +591,7 @@ impl ProjectSearchView {
             self.results_editor.update(cx, |editor, cx| {
                 editor.replace(&mat, &query, cx);
             });
+            self.update_replacement_previews(cx);
             self.select_match(Direction::Next, cx)
         }
     }
@@ -599,6 +624,72 @@ impl ProjectSearchView {
         self.model.update(cx, |model, _cx| {
             model.match_ranges = match_ranges;
         });
+        self.update_replacement_previews(cx);
+    }
+
+    /// Shows what each match would be replaced with, while replacing is
+    /// enabled. Matches that the replacement leaves unchanged, such as those
+    /// already replaced, aren't previewed. The previews are computed in the
+    /// background once the replacement stops changing.
+    fn update_replacement_previews(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self
+            .model
+            .read(cx)
+            .active_query
+            .clone()
+            .filter(|_| self.replace_enabled);
+        let Some(query) = query else {
+            self.replacement_previews_task = Task::ready(());
+            self.results_editor
+                .update(cx, |editor, cx| editor.clear_replacement_previews(cx));
+            return;
+        };
+
+        let query = query.with_replacement(self.replacement(cx));
+        let snapshot = self.results_editor.read(cx).buffer().read(cx).snapshot(cx);
+        let match_ranges = self
+            .model
+            .read(cx)
+            .match_ranges
+            .iter()
+            .take(MAX_PREVIEWED_REPLACEMENTS)
+            .cloned()
+            .collect::<Vec<_>>();
+        self.replacement_previews_task = cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(REPLACEMENT_PREVIEWS_DEBOUNCE)
+                .await;
+            let replacements = cx
+                .background_executor()
+                .spawn(async move {
+                    match_ranges
+                        .into_iter()
+                        .filter_map(|range| {
+                            let text = snapshot.text_for_range(range.clone()).collect::<String>();
+                            let replacement = if query.is_structural() {
+                                let offsets = range.to_offset(&snapshot);
+                                let (buffer, start) =
+                                    snapshot.point_to_buffer_offset(offsets.start)?;
+                                query.replacement_for_range(buffer, start..start + offsets.len())?
+                            } else {
+                                query.replacement_for(&text)?.into_owned()
+                            };
+                            (replacement != text).then_some((range, replacement))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.results_editor.update(cx, |editor, cx| {
+                    if replacements.is_empty() {
+                        editor.clear_replacement_previews(cx);
+                    } else {
+                        editor.preview_replacements(replacements, cx);
+                    }
+                });
+            })
+            .ok();
+        });
     }
 
     fn new(
@@ -651,6 +742,14 @@ impl ProjectSearchView {
             }
             editor
         });
+        subscriptions.push(cx.subscribe(
+            &replacement_editor,
+            |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.update_replacement_previews(cx);
+                }
+            },
+        ));
         let results_editor = cx.new_view(|cx| {
             let mut editor = Editor::for_multibuffer(excerpts, Some(project.clone()), true, cx);
             editor.set_searchable(false);
@@ -721,6 +820,7 @@ impl ProjectSearchView {
             excluded_files_editor,
             filters_enabled,
             replace_enabled: false,
+            replacement_previews_task: Task::ready(()),
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -845,6 +945,7 @@ impl ProjectSearchView {
 
         search.update(cx, |search, cx| {
             search.replace_enabled = action.replace_enabled;
+            search.update_replacement_previews(cx);
             if let Some(query) = query {
                 search.set_query(&query, cx);
             }
@@ -897,7 +998,31 @@ impl ProjectSearchView {
                 }
             };
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query.with_syntax_context(self.search_options.syntax_context()))
+                }
+                Err(_e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -912,7 +1037,7 @@ impl ProjectSearchView {
                         cx.notify();
                     }
 
-                    Some(query)
+                    Some(query.with_syntax_context(self.search_options.syntax_context()))
                 }
                 Err(_e) => {
                     let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
@@ -938,7 +1063,7 @@ impl ProjectSearchView {
                         cx.notify();
                     }
 
-                    Some(query)
+                    Some(query.with_syntax_context(self.search_options.syntax_context()))
                 }
                 Err(_e) => {
                     let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
@@ -950,6 +1075,21 @@ impl ProjectSearchView {
                 }
             }
         };
+        let preserve_case = self.search_options.contains(SearchOptions::PRESERVE_CASE);
+        let query = match query
+            .map(|query| query.with_preserve_case(preserve_case))
+            .transpose()
+        {
+            Ok(query) => query,
+            Err(_e) => {
+                let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                if should_mark_error {
+                    cx.notify();
+                }
+
+                None
+            }
+        };
         if !self.panels_with_errors.is_empty() {
             return None;
         }
@@ -1041,6 +1181,7 @@ impl ProjectSearchView {
             }
         }
 
+        self.update_replacement_previews(cx);
         cx.emit(ViewEvent::UpdateTab);
         cx.notify();
     }
@@ -1181,6 +1322,7 @@ impl ProjectSearchBar {
         if let Some(search) = &self.active_project_search {
             search.update(cx, |this, cx| {
                 this.replace_enabled = !this.replace_enabled;
+                this.update_replacement_previews(cx);
                 let editor_to_focus = if this.replace_enabled {
                     this.replacement_editor.focus_handle(cx)
                 } else {
@@ -1371,6 +1513,30 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+                        }),
+                    ))
+                    .child(SearchOptions::CODE_ONLY.as_button(
+                        self.is_option_enabled(SearchOptions::CODE_ONLY, cx),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::CODE_ONLY, cx);
+                        }),
+                    ))
+                    .child(SearchOptions::COMMENTS_ONLY.as_button(
+                        self.is_option_enabled(SearchOptions::COMMENTS_ONLY, cx),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::COMMENTS_ONLY, cx);
+                        }),
+                    ))
+                    .child(SearchOptions::STRINGS_ONLY.as_button(
+                        self.is_option_enabled(SearchOptions::STRINGS_ONLY, cx),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::STRINGS_ONLY, cx);
+                        }),
                     )),
             );
 
@@ -1483,7 +1649,13 @@ impl Render for ProjectSearchBar {
                 .border_1()
                 .border_color(cx.theme().colors().border)
                 .rounded_lg()
-                .child(self.render_text_input(&search.replacement_editor, cx));
+                .child(self.render_text_input(&search.replacement_editor, cx))
+                .child(SearchOptions::PRESERVE_CASE.as_button(
+                    self.is_option_enabled(SearchOptions::PRESERVE_CASE, cx),
+                    cx.listener(|this, _, cx| {
+                        this.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+                    }),
+                ));
             let replace_actions = h_flex().when(search.replace_enabled, |this| {
                 this.child(
                     IconButton::new("project-search-replace-next", IconName::ReplaceNext)
@@ -3223,6 +3395,79 @@ pub mod tests {
             .expect("unable to update search view");
     }
 
+    #[gpui::test]
+    async fn test_replacement_previews_preserving_case(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "fn foo_bar() { FooBar::new(); }",
+                "two.rs": "const FOO_BAR: usize = 1;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.search_options = SearchOptions::PRESERVE_CASE;
+                search_view.replace_enabled = true;
+                search_view
+                    .replacement_editor
+                    .update(cx, |editor, cx| editor.set_text("bazQux", cx));
+            })
+            .unwrap();
+        perform_search(search_view, "fooBar", cx);
+        cx.background_executor
+            .advance_clock(REPLACEMENT_PREVIEWS_DEBOUNCE);
+        cx.background_executor.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                let mut previews = search_view.results_editor.read(cx).replacement_previews(cx);
+                previews.sort();
+                assert_eq!(previews, ["BAZ_QUX", "BazQux", "baz_qux"]);
+
+                search_view
+                    .replacement_editor
+                    .update(cx, |editor, cx| editor.set_text("quxBaz", cx));
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                let mut previews = search_view.results_editor.read(cx).replacement_previews(cx);
+                previews.sort();
+                assert_eq!(
+                    previews,
+                    ["BAZ_QUX", "BazQux", "baz_qux"],
+                    "previews are only updated once the replacement stops changing"
+                );
+            })
+            .unwrap();
+        cx.background_executor
+            .advance_clock(REPLACEMENT_PREVIEWS_DEBOUNCE);
+        cx.background_executor.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                let mut previews = search_view.results_editor.read(cx).replacement_previews(cx);
+                previews.sort();
+                assert_eq!(previews, ["QUX_BAZ", "QuxBaz", "qux_baz"]);
+
+                search_view.replace_enabled = false;
+                search_view.update_replacement_previews(cx);
+                assert!(search_view
+                    .results_editor
+                    .read(cx)
+                    .replacement_previews(cx)
+                    .is_empty());
+            })
+            .unwrap();
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
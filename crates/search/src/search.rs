@@ -1,7 +1,7 @@
 use bitflags::bitflags;
 pub use buffer_search::BufferSearchBar;
 use gpui::{actions, Action, AppContext, IntoElement};
-use project::search::SearchQuery;
+use project::search::{SearchQuery, SyntaxContext};
 pub use project_search::ProjectSearchView;
 use ui::{prelude::*, Tooltip};
 use ui::{ButtonStyle, IconButton};
@@ -24,6 +24,11 @@ actions!(
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
         ToggleRegex,
+        ToggleStructural,
+        ToggleCodeOnly,
+        ToggleCommentsOnly,
+        ToggleStringsOnly,
+        TogglePreserveCase,
         ToggleReplace,
         ToggleSelection,
         SelectNextMatch,
@@ -38,12 +43,17 @@ actions!(
 
 bitflags! {
     #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
-    pub struct SearchOptions: u8 {
+    pub struct SearchOptions: u16 {
         const NONE = 0b000;
         const WHOLE_WORD = 0b001;
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
         const REGEX = 0b1000;
+        const STRUCTURAL = 0b10000;
+        const CODE_ONLY = 0b100000;
+        const COMMENTS_ONLY = 0b1000000;
+        const STRINGS_ONLY = 0b10000000;
+        const PRESERVE_CASE = 0b100000000;
     }
 }
 
@@ -54,6 +64,11 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "match case",
             SearchOptions::INCLUDE_IGNORED => "include Ignored",
             SearchOptions::REGEX => "regular expression",
+            SearchOptions::STRUCTURAL => "structural pattern",
+            SearchOptions::CODE_ONLY => "code only",
+            SearchOptions::COMMENTS_ONLY => "comments only",
+            SearchOptions::STRINGS_ONLY => "strings only",
+            SearchOptions::PRESERVE_CASE => "preserve case",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -64,6 +79,11 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::FileGit,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::STRUCTURAL => ui::IconName::ListTree,
+            SearchOptions::CODE_ONLY => ui::IconName::Code,
+            SearchOptions::COMMENTS_ONLY => ui::IconName::MessageBubbles,
+            SearchOptions::STRINGS_ONLY => ui::IconName::Quote,
+            SearchOptions::PRESERVE_CASE => ui::IconName::CaseSensitive,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -74,6 +94,11 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::STRUCTURAL => Box::new(ToggleStructural),
+            SearchOptions::CODE_ONLY => Box::new(ToggleCodeOnly),
+            SearchOptions::COMMENTS_ONLY => Box::new(ToggleCommentsOnly),
+            SearchOptions::STRINGS_ONLY => Box::new(ToggleStringsOnly),
+            SearchOptions::PRESERVE_CASE => Box::new(TogglePreserveCase),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -88,9 +113,55 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        options.set(SearchOptions::PRESERVE_CASE, query.preserve_case());
+        options.set(
+            SearchOptions::CODE_ONLY,
+            query.syntax_context() == Some(SyntaxContext::Code),
+        );
+        options.set(
+            SearchOptions::COMMENTS_ONLY,
+            query.syntax_context() == Some(SyntaxContext::Comments),
+        );
+        options.set(
+            SearchOptions::STRINGS_ONLY,
+            query.syntax_context() == Some(SyntaxContext::Strings),
+        );
         options
     }
 
+    /// Returns the syntax context these options restrict matches to.
+    pub fn syntax_context(&self) -> Option<SyntaxContext> {
+        if self.contains(SearchOptions::CODE_ONLY) {
+            Some(SyntaxContext::Code)
+        } else if self.contains(SearchOptions::COMMENTS_ONLY) {
+            Some(SyntaxContext::Comments)
+        } else if self.contains(SearchOptions::STRINGS_ONLY) {
+            Some(SyntaxContext::Strings)
+        } else {
+            None
+        }
+    }
+
+    /// Toggles the option, turning off the ones it can't be combined with: a
+    /// query is either a regex or a structural pattern, and matches can only
+    /// be restricted to one syntax context.
+    pub fn toggle_exclusive(&mut self, option: SearchOptions) {
+        self.toggle(option);
+        if self.contains(option) {
+            for group in [
+                SearchOptions::REGEX | SearchOptions::STRUCTURAL,
+                SearchOptions::CODE_ONLY
+                    | SearchOptions::COMMENTS_ONLY
+                    | SearchOptions::STRINGS_ONLY,
+            ] {
+                if group.contains(option) {
+                    self.remove(group.difference(option));
+                }
+            }
+        }
+    }
+
     pub fn as_button(
         &self,
         active: bool,
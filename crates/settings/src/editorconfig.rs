@@ -0,0 +1,225 @@
+use std::path::Path;
+
+use collections::BTreeMap;
+use globset::{GlobBuilder, GlobMatcher};
+
+/// The name of the files from which EditorConfig properties are read.
+pub const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// A parsed [EditorConfig](https://editorconfig.org) file.
+#[derive(Debug, Default, Clone)]
+pub struct EditorConfig {
+    /// Whether the search for EditorConfig files stops at this one.
+    pub root: bool,
+    sections: Vec<EditorConfigSection>,
+}
+
+#[derive(Debug, Clone)]
+struct EditorConfigSection {
+    matcher: GlobMatcher,
+    properties: Vec<(String, String)>,
+}
+
+/// The EditorConfig properties that apply to a file, keyed by their lowercase
+/// names, e.g. `indent_style`.
+pub type EditorConfigProperties = BTreeMap<String, String>;
+
+impl EditorConfig {
+    /// Parses the contents of an `.editorconfig` file. Sections whose glob
+    /// can't be understood are skipped, as are malformed lines.
+    pub fn parse(content: &str) -> Self {
+        let mut config = Self::default();
+        let mut section = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                config.sections.extend(section.take());
+                section = section_matcher(glob).map(|matcher| EditorConfigSection {
+                    matcher,
+                    properties: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            // The values of all of the supported properties are case-insensitive.
+            let value = value.trim().to_lowercase();
+            if let Some(section) = &mut section {
+                section.properties.push((key, value));
+            } else if key == "root" {
+                config.root = value == "true";
+            }
+        }
+        config.sections.extend(section);
+        config
+    }
+
+    /// Applies the properties of every section matching the given path, which
+    /// is relative to the directory containing this file.
+    fn apply(&self, path: &Path, properties: &mut EditorConfigProperties) {
+        for section in &self.sections {
+            if section.matcher.is_match(path) {
+                for (key, value) in &section.properties {
+                    if value == "unset" {
+                        properties.remove(key);
+                    } else {
+                        properties.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the EditorConfig properties for a file, given the `.editorconfig`
+/// files of the directories containing it, from the outermost to the innermost.
+///
+/// Files closer to the path take precedence, and files outside of the
+/// innermost one marked as `root` are ignored.
+pub fn editorconfig_properties<'a>(
+    configs: impl IntoIterator<Item = (&'a Path, &'a EditorConfig)>,
+    path: &Path,
+) -> EditorConfigProperties {
+    let mut configs = configs.into_iter().collect::<Vec<_>>();
+    if let Some(root_ix) = configs.iter().rposition(|(_, config)| config.root) {
+        configs.drain(..root_ix);
+    }
+
+    let mut properties = EditorConfigProperties::default();
+    for (directory, config) in configs {
+        if let Ok(relative_path) = path.strip_prefix(directory) {
+            config.apply(relative_path, &mut properties);
+        }
+    }
+    properties
+}
+
+/// Globs without a slash match files in any directory below the
+/// `.editorconfig` file, while those with one are relative to it.
+fn section_matcher(glob: &str) -> Option<GlobMatcher> {
+    let glob = if glob.contains('/') {
+        glob.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{glob}")
+    };
+    // EditorConfig's `**` can be followed by more of a file name, as in
+    // `lib/**.js`, but globset's must be a whole path component.
+    let glob = glob
+        .replace("**/", "\0")
+        .replace("**", "**/*")
+        .replace('\0', "**/");
+    GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .ok()
+        .map(|glob| glob.compile_matcher())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_editorconfig_properties() {
+        let root = EditorConfig::parse(indoc! {"
+            # top-most EditorConfig file
+            root = true
+
+            [*]
+            indent_style = space
+            indent_size = 4
+            end_of_line = LF
+
+            [*.{js,ts}]
+            indent_size = 2
+
+            [Makefile]
+            indent_style = tab
+
+            [lib/**.c]
+            max_line_length = 80
+        "});
+        let nested = EditorConfig::parse(indoc! {"
+            [*.js]
+            indent_size = unset
+            trim_trailing_whitespace = true
+        "});
+        assert!(root.root);
+        assert!(!nested.root);
+
+        let configs = [(Path::new(""), &root), (Path::new("web"), &nested)];
+        let properties = |path: &str| {
+            editorconfig_properties(configs, Path::new(path))
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        let owned = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            properties("src/main.rs"),
+            owned(&[
+                ("end_of_line", "lf"),
+                ("indent_size", "4"),
+                ("indent_style", "space")
+            ])
+        );
+        assert_eq!(
+            properties("src/app.ts"),
+            owned(&[
+                ("end_of_line", "lf"),
+                ("indent_size", "2"),
+                ("indent_style", "space")
+            ])
+        );
+        assert_eq!(
+            properties("src/Makefile"),
+            owned(&[
+                ("end_of_line", "lf"),
+                ("indent_size", "4"),
+                ("indent_style", "tab")
+            ])
+        );
+        assert_eq!(
+            properties("lib/a/b.c"),
+            owned(&[
+                ("end_of_line", "lf"),
+                ("indent_size", "4"),
+                ("indent_style", "space"),
+                ("max_line_length", "80")
+            ])
+        );
+        assert_eq!(
+            properties("web/app.js"),
+            owned(&[
+                ("end_of_line", "lf"),
+                ("indent_style", "space"),
+                ("trim_trailing_whitespace", "true")
+            ])
+        );
+
+        // Files above the innermost root are ignored.
+        let configs = [(Path::new(""), &nested), (Path::new("web"), &root)];
+        assert_eq!(
+            editorconfig_properties(configs, Path::new("web/app.js")).get("indent_size"),
+            Some(&"2".to_string())
+        );
+        assert_eq!(
+            editorconfig_properties(configs, Path::new("web/app.js"))
+                .get("trim_trailing_whitespace"),
+            None
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{settings_store::parse_json_with_comments, SettingsAssets};
+use crate::{settings_store::parse_json_with_comments, SettingsAssets, VimMapping};
 use anyhow::{anyhow, Context, Result};
 use collections::BTreeMap;
 use gpui::{Action, AppContext, KeyBinding, SharedString};
@@ -53,6 +53,32 @@ impl KeymapFile {
         parse_json_with_comments::<Self>(content)
     }
 
+    /// Builds the bindings for the mappings of a vimrc, with one block per
+    /// mode. Each mapping is bound to `vim::SendKeys`, which types the
+    /// right-hand side of the mapping.
+    pub fn from_vim_mappings(mappings: &[VimMapping]) -> Self {
+        let mut blocks = BTreeMap::<_, BTreeMap<String, KeymapAction>>::default();
+        for mapping in mappings {
+            let action = serde_json::json!([
+                "vim::SendKeys",
+                { "keys": mapping.keys, "remap": mapping.remap }
+            ]);
+            blocks
+                .entry(mapping.mode)
+                .or_default()
+                .insert(mapping.keystrokes.clone(), KeymapAction(action));
+        }
+        Self(
+            blocks
+                .into_iter()
+                .map(|(mode, bindings)| KeymapBlock {
+                    context: Some(mode.context().to_string()),
+                    bindings,
+                })
+                .collect(),
+        )
+    }
+
     pub fn add_to_cx(self, cx: &mut AppContext) -> Result<()> {
         for KeymapBlock { context, bindings } in self.0 {
             let bindings = bindings
@@ -146,7 +172,7 @@ fn no_action() -> Box<dyn gpui::Action> {
 
 #[cfg(test)]
 mod tests {
-    use crate::KeymapFile;
+    use crate::{KeymapFile, VimMapMode, Vimrc};
 
     #[test]
     fn can_deserialize_keymap_with_trailing_comma() {
@@ -163,4 +189,23 @@ mod tests {
         };
         KeymapFile::parse(json).unwrap();
     }
+
+    #[test]
+    fn can_build_keymap_from_vim_mappings() {
+        let vimrc = Vimrc::parse("nnoremap <C-s> :w<CR>\nnmap <C-s> :wa<CR>\nimap jk <Esc>");
+        let keymap = KeymapFile::from_vim_mappings(&vimrc.mappings);
+        assert_eq!(keymap.0.len(), 2);
+        assert_eq!(
+            keymap.0[0].context.as_deref(),
+            Some(VimMapMode::Normal.context())
+        );
+        assert_eq!(
+            keymap.0[0].bindings["ctrl-s"].0,
+            serde_json::json!(["vim::SendKeys", { "keys": ":wa<CR>", "remap": true }])
+        );
+        assert_eq!(
+            keymap.0[1].context.as_deref(),
+            Some(VimMapMode::Insert.context())
+        );
+    }
 }
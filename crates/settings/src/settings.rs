@@ -1,17 +1,23 @@
+mod editorconfig;
 mod keymap_file;
 mod settings_file;
 mod settings_store;
+mod vimrc;
 
 use gpui::AppContext;
 use rust_embed::RustEmbed;
 use std::{borrow::Cow, str};
 use util::asset_str;
 
+pub use editorconfig::{
+    editorconfig_properties, EditorConfig, EditorConfigProperties, EDITORCONFIG_FILE_NAME,
+};
 pub use keymap_file::KeymapFile;
 pub use settings_file::*;
 pub use settings_store::{
     Settings, SettingsJsonSchemaParams, SettingsLocation, SettingsSources, SettingsStore,
 };
+pub use vimrc::{VimMapMode, VimMapping, Vimrc, VimrcError};
 
 #[derive(RustEmbed)]
 #[folder = "../../assets"]
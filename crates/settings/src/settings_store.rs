@@ -7,6 +7,7 @@ use serde::{de::DeserializeOwned, Deserialize as _, Serialize};
 use smallvec::SmallVec;
 use std::{
     any::{type_name, Any, TypeId},
+    borrow::Cow,
     fmt::Debug,
     ops::Range,
     path::Path,
@@ -155,8 +156,11 @@ pub struct SettingsStore {
     setting_values: HashMap<TypeId, Box<dyn AnySettingValue>>,
     raw_default_settings: serde_json::Value,
     raw_user_settings: serde_json::Value,
+    raw_vimrc_settings: serde_json::Value,
     raw_extension_settings: serde_json::Value,
     raw_local_settings: BTreeMap<(usize, Arc<Path>), serde_json::Value>,
+    raw_local_vimrc_settings: BTreeMap<usize, serde_json::Value>,
+    raw_editorconfig_settings: BTreeMap<(usize, Arc<Path>), serde_json::Value>,
     tab_size_callback: Option<(
         TypeId,
         Box<dyn Fn(&dyn Any) -> Option<usize> + Send + Sync + 'static>,
@@ -171,8 +175,11 @@ impl Default for SettingsStore {
             setting_values: Default::default(),
             raw_default_settings: serde_json::json!({}),
             raw_user_settings: serde_json::json!({}),
+            raw_vimrc_settings: serde_json::json!({}),
             raw_extension_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
+            raw_local_vimrc_settings: Default::default(),
+            raw_editorconfig_settings: Default::default(),
             tab_size_callback: Default::default(),
         }
     }
@@ -196,6 +203,7 @@ trait AnySettingValue: 'static + Send + Sync {
     fn value_for_path(&self, path: Option<SettingsLocation>) -> &dyn Any;
     fn set_global_value(&mut self, value: Box<dyn Any>);
     fn set_local_value(&mut self, root_id: usize, path: Arc<Path>, value: Box<dyn Any>);
+    fn clear_local_values(&mut self, changed_local_path: Option<(usize, &Path)>);
     fn json_schema(
         &self,
         generator: &mut SchemaGenerator,
@@ -232,7 +240,10 @@ impl SettingsStore {
             .log_err()
         {
             let user_value = setting_value
-                .deserialize_setting(&self.raw_user_settings)
+                .deserialize_setting(&user_settings_with_vimrc(
+                    &self.raw_user_settings,
+                    &self.raw_vimrc_settings,
+                ))
                 .log_err();
 
             let mut release_channel_value = None;
@@ -444,6 +455,45 @@ impl SettingsStore {
         }
     }
 
+    /// Sets the settings that correspond to the options of the user's vimrc.
+    /// They apply beneath the user settings, which take precedence.
+    pub fn set_vimrc_settings(
+        &mut self,
+        settings: serde_json::Value,
+        cx: &mut AppContext,
+    ) -> Result<()> {
+        if settings.is_object() {
+            self.raw_vimrc_settings = settings;
+            self.recompute_values(None, cx)?;
+            Ok(())
+        } else {
+            Err(anyhow!("settings must be an object"))
+        }
+    }
+
+    /// Add or remove the settings that correspond to the options of a worktree's
+    /// vimrc. They apply to the worktree's files, beneath its local settings.
+    pub fn set_local_vimrc_settings(
+        &mut self,
+        root_id: usize,
+        settings: Option<serde_json::Value>,
+        cx: &mut AppContext,
+    ) -> Result<()> {
+        match settings {
+            Some(settings) if !settings.is_object() => {
+                return Err(anyhow!("settings must be an object"));
+            }
+            Some(settings) => {
+                self.raw_local_vimrc_settings.insert(root_id, settings);
+            }
+            None => {
+                self.raw_local_vimrc_settings.remove(&root_id);
+            }
+        }
+        self.recompute_values(Some((root_id, "".as_ref())), cx)?;
+        Ok(())
+    }
+
     /// Add or remove a set of local settings via a JSON string.
     pub fn set_local_settings(
         &mut self,
@@ -464,6 +514,33 @@ impl SettingsStore {
         Ok(())
     }
 
+    /// Add or remove the settings derived from the EditorConfig properties of
+    /// a file. These take precedence over the user's settings, but not over
+    /// local settings files.
+    pub fn set_editorconfig_settings(
+        &mut self,
+        root_id: usize,
+        path: Arc<Path>,
+        settings: Option<serde_json::Value>,
+        cx: &mut AppContext,
+    ) -> Result<()> {
+        match settings {
+            Some(settings) if !settings.is_object() => {
+                return Err(anyhow!("settings must be an object"));
+            }
+            Some(settings) => {
+                self.raw_editorconfig_settings
+                    .insert((root_id, path.clone()), settings);
+            }
+            None => {
+                self.raw_editorconfig_settings
+                    .remove(&(root_id, path.clone()));
+            }
+        }
+        self.recompute_values(Some((root_id, &path)), cx)?;
+        Ok(())
+    }
+
     pub fn set_extension_settings<T: Serialize>(
         &mut self,
         content: T,
@@ -482,6 +559,8 @@ impl SettingsStore {
     /// Add or remove a set of local settings via a JSON string.
     pub fn clear_local_settings(&mut self, root_id: usize, cx: &mut AppContext) -> Result<()> {
         self.raw_local_settings.retain(|k, _| k.0 != root_id);
+        self.raw_local_vimrc_settings.remove(&root_id);
+        self.raw_editorconfig_settings.retain(|k, _| k.0 != root_id);
         self.recompute_values(Some((root_id, "".as_ref())), cx)?;
         Ok(())
     }
@@ -607,6 +686,10 @@ impl SettingsStore {
         // Reload the global and local values for every setting.
         let mut project_settings_stack = Vec::<DeserializedSetting>::new();
         let mut paths_stack = Vec::<Option<(usize, &Path)>>::new();
+        let user_settings_value =
+            user_settings_with_vimrc(&self.raw_user_settings, &self.raw_vimrc_settings);
+        let local_settings_values =
+            local_settings_with_vimrc(&self.raw_local_settings, &self.raw_local_vimrc_settings);
         for setting_value in self.setting_values.values_mut() {
             let default_settings = setting_value.deserialize_setting(&self.raw_default_settings)?;
 
@@ -615,7 +698,7 @@ impl SettingsStore {
                 .log_err();
 
             let user_settings = setting_value
-                .deserialize_setting(&self.raw_user_settings)
+                .deserialize_setting(&user_settings_value)
                 .log_err();
 
             let mut release_channel_settings = None;
@@ -651,9 +734,10 @@ impl SettingsStore {
             }
 
             // Reload the local values for the setting.
+            setting_value.clear_local_values(changed_local_path);
             paths_stack.clear();
             project_settings_stack.clear();
-            for ((root_id, path), local_settings) in &self.raw_local_settings {
+            for ((root_id, path), local_settings) in &local_settings_values {
                 // Build a stack of all of the local values for that setting.
                 while let Some(prev_entry) = paths_stack.last() {
                     if let Some((prev_root_id, prev_path)) = prev_entry {
@@ -697,6 +781,50 @@ impl SettingsStore {
                     }
                 }
             }
+
+            // Reload the values for files with EditorConfig properties, which
+            // apply beneath the local settings of every directory containing them.
+            for ((root_id, path), editorconfig_settings) in &self.raw_editorconfig_settings {
+                if changed_local_path.map_or(false, |(changed_root_id, changed_local_path)| {
+                    *root_id != changed_root_id || !path.starts_with(changed_local_path)
+                }) {
+                    continue;
+                }
+
+                let Some(editorconfig_settings) = setting_value
+                    .deserialize_setting(editorconfig_settings)
+                    .log_err()
+                else {
+                    continue;
+                };
+                let local_settings = local_settings_values
+                    .range((*root_id, Path::new("").into())..(root_id + 1, Path::new("").into()))
+                    .filter(|((_, settings_path), _)| path.starts_with(settings_path))
+                    .filter_map(|(_, local_settings)| {
+                        setting_value.deserialize_setting(local_settings).log_err()
+                    })
+                    .collect::<Vec<_>>();
+                let project_settings = [&editorconfig_settings]
+                    .into_iter()
+                    .chain(&local_settings)
+                    .collect::<Vec<_>>();
+
+                if let Some(value) = setting_value
+                    .load_setting(
+                        SettingsSources {
+                            default: &default_settings,
+                            extensions: extension_settings.as_ref(),
+                            user: user_settings.as_ref(),
+                            release_channel: release_channel_settings.as_ref(),
+                            project: &project_settings,
+                        },
+                        cx,
+                    )
+                    .log_err()
+                {
+                    setting_value.set_local_value(*root_id, path.clone(), value);
+                }
+            }
         }
         Ok(())
     }
@@ -798,6 +926,14 @@ impl<T: Settings> AnySettingValue for SettingValue<T> {
         }
     }
 
+    fn clear_local_values(&mut self, changed_local_path: Option<(usize, &Path)>) {
+        self.local_values.retain(|(root_id, path, _)| {
+            changed_local_path.map_or(false, |(changed_root_id, changed_local_path)| {
+                *root_id != changed_root_id || !path.starts_with(changed_local_path)
+            })
+        });
+    }
+
     fn json_schema(
         &self,
         generator: &mut SchemaGenerator,
@@ -808,6 +944,43 @@ impl<T: Settings> AnySettingValue for SettingValue<T> {
     }
 }
 
+/// Merges the user settings over the options set in the vimrc.
+fn user_settings_with_vimrc<'a>(
+    user_settings: &'a serde_json::Value,
+    vimrc_settings: &serde_json::Value,
+) -> Cow<'a, serde_json::Value> {
+    if vimrc_settings
+        .as_object()
+        .map_or(true, |settings| settings.is_empty())
+    {
+        return Cow::Borrowed(user_settings);
+    }
+    let mut settings = vimrc_settings.clone();
+    merge_non_null_json_value_into(user_settings.clone(), &mut settings);
+    Cow::Owned(settings)
+}
+
+/// Merges the local settings of each worktree's root over the options set in
+/// the worktree's vimrc.
+fn local_settings_with_vimrc<'a>(
+    local_settings: &'a BTreeMap<(usize, Arc<Path>), serde_json::Value>,
+    vimrc_settings: &BTreeMap<usize, serde_json::Value>,
+) -> BTreeMap<(usize, Arc<Path>), Cow<'a, serde_json::Value>> {
+    let mut settings = local_settings
+        .iter()
+        .map(|(key, value)| (key.clone(), Cow::Borrowed(value)))
+        .collect::<BTreeMap<_, _>>();
+    for (root_id, vimrc_settings) in vimrc_settings {
+        let key = (*root_id, Arc::from(Path::new("")));
+        let mut root_settings = vimrc_settings.clone();
+        if let Some(local_settings) = settings.get(&key) {
+            merge_non_null_json_value_into(local_settings.as_ref().clone(), &mut root_settings);
+        }
+        settings.insert(key, Cow::Owned(root_settings));
+    }
+    settings
+}
+
 fn update_value_in_json_text<'a>(
     text: &mut String,
     key_path: &mut Vec<&'a str>,
@@ -1144,6 +1317,90 @@ mod tests {
                 key2: "b".to_string(),
             }
         );
+
+        // EditorConfig settings override the user's settings, but not local ones.
+        store
+            .set_editorconfig_settings(
+                1,
+                Path::new("/root1/subdir/file.c").into(),
+                Some(serde_json::json!({ "user": { "name": "Editor Config", "age": 7 } })),
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            store.get::<UserSettings>(Some(SettingsLocation {
+                worktree_id: 1,
+                path: Path::new("/root1/subdir/file.c")
+            })),
+            &UserSettings {
+                name: "Jane Doe".to_string(),
+                age: 7,
+                staff: true
+            }
+        );
+        assert_eq!(
+            store
+                .get::<UserSettings>(Some(SettingsLocation {
+                    worktree_id: 1,
+                    path: Path::new("/root1/subdir/other.c")
+                }))
+                .age,
+            31
+        );
+
+        store
+            .set_editorconfig_settings(1, Path::new("/root1/subdir/file.c").into(), None, cx)
+            .unwrap();
+        assert_eq!(
+            store
+                .get::<UserSettings>(Some(SettingsLocation {
+                    worktree_id: 1,
+                    path: Path::new("/root1/subdir/file.c")
+                }))
+                .age,
+            31
+        );
+
+        // The options of a worktree's vimrc apply beneath its local settings.
+        store
+            .set_local_vimrc_settings(
+                1,
+                Some(serde_json::json!({ "user": { "age": 50, "staff": false } })),
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            store.get::<UserSettings>(Some(SettingsLocation {
+                worktree_id: 1,
+                path: Path::new("/root1/something"),
+            })),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 50,
+                staff: true
+            }
+        );
+        assert_eq!(
+            store
+                .get::<UserSettings>(Some(SettingsLocation {
+                    worktree_id: 1,
+                    path: Path::new("/root2/something"),
+                }))
+                .age,
+            42
+        );
+        assert_eq!(store.get::<UserSettings>(None).age, 31);
+
+        store.set_local_vimrc_settings(1, None, cx).unwrap();
+        assert_eq!(
+            store
+                .get::<UserSettings>(Some(SettingsLocation {
+                    worktree_id: 1,
+                    path: Path::new("/root1/something"),
+                }))
+                .age,
+            31
+        );
     }
 
     #[gpui::test]
@@ -0,0 +1,530 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+/// The mappings and options read from a Vim-style init file, such as
+/// `~/.config/zed/vimrc`.
+#[derive(Debug, Default, Clone)]
+pub struct Vimrc {
+    /// The mappings, in the order they were defined.
+    pub mappings: Vec<VimMapping>,
+    /// The settings set with `set`, in the format of the settings file.
+    pub settings: Value,
+    /// The lines that could not be understood.
+    pub errors: Vec<VimrcError>,
+}
+
+/// A mapping defined with one of the `map` or `noremap` commands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VimMapping {
+    pub mode: VimMapMode,
+    /// The keys of the left-hand side, in the keystroke format of the keymap
+    /// file, e.g. `\ ctrl-f`.
+    pub keystrokes: String,
+    /// The right-hand side, in Vim's key notation, e.g. `:w<CR>`.
+    pub keys: String,
+    /// Whether the right-hand side can trigger other mappings.
+    pub remap: bool,
+}
+
+/// The modes a mapping can apply to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VimMapMode {
+    Normal,
+    Visual,
+    OperatorPending,
+    Insert,
+}
+
+impl VimMapMode {
+    /// The keymap context in which the mappings of this mode apply.
+    pub fn context(&self) -> &'static str {
+        match self {
+            VimMapMode::Normal => {
+                "Editor && vim_mode == normal && vim_operator == none && !VimWaiting && !VimNoremap"
+            }
+            VimMapMode::Visual => {
+                "Editor && vim_mode == visual && vim_operator == none && !VimWaiting && !VimNoremap"
+            }
+            VimMapMode::OperatorPending => {
+                "Editor && vim_operator != none && !VimWaiting && !VimObject && !VimNoremap"
+            }
+            VimMapMode::Insert => "Editor && vim_mode == insert && !VimNoremap",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VimrcError {
+    /// The line of the error, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for VimrcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Keys that Vim's key notation writes with a name in angle brackets, and the
+/// name of the key in a keystroke.
+const NAMED_KEYS: &[(&str, &str)] = &[
+    ("esc", "escape"),
+    ("cr", "enter"),
+    ("enter", "enter"),
+    ("return", "enter"),
+    ("bs", "backspace"),
+    ("tab", "tab"),
+    ("space", "space"),
+    ("del", "delete"),
+    ("up", "up"),
+    ("down", "down"),
+    ("left", "left"),
+    ("right", "right"),
+    ("home", "home"),
+    ("end", "end"),
+    ("pageup", "pageup"),
+    ("pagedown", "pagedown"),
+    ("lt", "<"),
+    ("bar", "|"),
+    ("bslash", "\\"),
+];
+
+impl Vimrc {
+    /// Parses the content of a vimrc. Lines that can't be parsed are skipped
+    /// and reported in `errors`, so one mistake doesn't discard the whole file.
+    pub fn parse(content: &str) -> Self {
+        let mut vimrc = Vimrc {
+            settings: json!({}),
+            ..Default::default()
+        };
+        let mut leader = "\\".to_string();
+        let mut local_leader = "\\".to_string();
+
+        for (ix, line) in content.lines().enumerate() {
+            let line = line.trim_start().trim_start_matches(':').trim();
+            if line.is_empty() || line.starts_with('"') {
+                continue;
+            }
+            let (command, args) = line
+                .split_once(char::is_whitespace)
+                .map_or((line, ""), |(command, args)| (command, args.trim_start()));
+
+            let result = if command == "let" {
+                parse_let(args, &mut leader, &mut local_leader)
+            } else if command == "set" || command == "se" {
+                parse_set(args, &mut vimrc.settings)
+            } else if let Some((modes, remap)) = map_command(command) {
+                parse_map(args, &leader, &local_leader).map(|(keystrokes, keys)| {
+                    for mode in modes {
+                        vimrc.mappings.push(VimMapping {
+                            mode: *mode,
+                            keystrokes: keystrokes.clone(),
+                            keys: keys.clone(),
+                            remap,
+                        });
+                    }
+                })
+            } else if let Some(modes) = unmap_command(command) {
+                parse_keys(args.trim(), &leader, &local_leader).map(|keystrokes| {
+                    vimrc.mappings.retain(|mapping| {
+                        !(modes.contains(&mapping.mode) && mapping.keystrokes == keystrokes)
+                    })
+                })
+            } else {
+                Err(format!("unsupported command `{command}`"))
+            };
+
+            if let Err(message) = result {
+                vimrc.errors.push(VimrcError {
+                    line: ix + 1,
+                    message,
+                });
+            }
+        }
+        vimrc
+    }
+}
+
+const ALL_MODES: &[VimMapMode] = &[
+    VimMapMode::Normal,
+    VimMapMode::Visual,
+    VimMapMode::OperatorPending,
+];
+
+/// Returns the modes of a `map` command, and whether it allows remapping.
+fn map_command(command: &str) -> Option<(&'static [VimMapMode], bool)> {
+    Some(match command {
+        "map" => (ALL_MODES, true),
+        "nm" | "nmap" => (&[VimMapMode::Normal], true),
+        "vm" | "vmap" | "xm" | "xmap" => (&[VimMapMode::Visual], true),
+        "om" | "omap" => (&[VimMapMode::OperatorPending], true),
+        "im" | "imap" => (&[VimMapMode::Insert], true),
+        "no" | "noremap" => (ALL_MODES, false),
+        "nn" | "nnoremap" => (&[VimMapMode::Normal], false),
+        "vn" | "vnoremap" | "xn" | "xnoremap" => (&[VimMapMode::Visual], false),
+        "ono" | "onoremap" => (&[VimMapMode::OperatorPending], false),
+        "ino" | "inoremap" => (&[VimMapMode::Insert], false),
+        _ => return None,
+    })
+}
+
+fn unmap_command(command: &str) -> Option<&'static [VimMapMode]> {
+    Some(match command {
+        "unm" | "unmap" => ALL_MODES,
+        "nun" | "nunmap" => &[VimMapMode::Normal],
+        "vu" | "vunmap" | "xu" | "xunmap" => &[VimMapMode::Visual],
+        "ou" | "ounmap" => &[VimMapMode::OperatorPending],
+        "iu" | "iunmap" => &[VimMapMode::Insert],
+        _ => return None,
+    })
+}
+
+/// Parses the arguments of a `map` command into the keystrokes of the
+/// left-hand side and the right-hand side.
+fn parse_map(args: &str, leader: &str, local_leader: &str) -> Result<(String, String), String> {
+    let mut args = args;
+    loop {
+        let lower = args.to_ascii_lowercase();
+        let Some(modifier) = ["<silent>", "<nowait>", "<unique>", "<buffer>", "<expr>"]
+            .into_iter()
+            .find(|modifier| lower.starts_with(modifier))
+        else {
+            break;
+        };
+        if modifier == "<expr>" {
+            return Err("`<expr>` mappings are not supported".to_string());
+        }
+        args = args[modifier.len()..].trim_start();
+    }
+
+    let (lhs, rhs) = args
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| "expected a mapping of the form `{lhs} {rhs}`".to_string())?;
+    let rhs = rhs.trim_start();
+    if rhs.is_empty() {
+        return Err("expected a mapping of the form `{lhs} {rhs}`".to_string());
+    }
+    let keystrokes = parse_keys(lhs, leader, local_leader)?;
+    Ok((keystrokes, expand_leader(rhs, leader, local_leader)))
+}
+
+/// Replaces `<Leader>` and `<LocalLeader>` with the keys they stand for.
+fn expand_leader(keys: &str, leader: &str, local_leader: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = keys;
+    while let Some(start) = rest.find('<') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let lower = rest.to_ascii_lowercase();
+        if lower.starts_with("<leader>") {
+            expanded.push_str(leader);
+            rest = &rest["<leader>".len()..];
+        } else if lower.starts_with("<localleader>") {
+            expanded.push_str(local_leader);
+            rest = &rest["<localleader>".len()..];
+        } else {
+            expanded.push('<');
+            rest = &rest[1..];
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Converts keys written in Vim's key notation into keystrokes in the format
+/// of the keymap file.
+fn parse_keys(keys: &str, leader: &str, local_leader: &str) -> Result<String, String> {
+    let keys = expand_leader(keys, leader, local_leader);
+    let mut keystrokes = Vec::new();
+    let mut rest = keys.as_str();
+    while let Some(char) = rest.chars().next() {
+        if char == '<' {
+            if let Some((name, _)) = rest[1..].split_once('>') {
+                if let Some(keystroke) = parse_key_notation(name) {
+                    keystrokes.push(keystroke);
+                    rest = &rest[name.len() + 2..];
+                    continue;
+                }
+            }
+        }
+        keystrokes.push(char_keystroke(char));
+        rest = &rest[char.len_utf8()..];
+    }
+    if keystrokes.is_empty() {
+        return Err("expected keys to map".to_string());
+    }
+    Ok(keystrokes.join(" "))
+}
+
+/// Parses the inside of a key written like `<C-x>`, returning `None` if it
+/// isn't a key name, in which case Vim treats the `<` literally.
+fn parse_key_notation(name: &str) -> Option<String> {
+    let mut modifiers = String::new();
+    let mut rest = name;
+    while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+        let modifier = match rest.as_bytes()[0].to_ascii_lowercase() {
+            b'c' => "ctrl-",
+            b's' => "shift-",
+            b'a' | b'm' => "alt-",
+            b'd' => "cmd-",
+            _ => return None,
+        };
+        modifiers.push_str(modifier);
+        rest = &rest[2..];
+    }
+
+    let lower = rest.to_ascii_lowercase();
+    let key = if let Some((_, key)) = NAMED_KEYS.iter().find(|(name, _)| *name == lower) {
+        key.to_string()
+    } else if lower.len() > 1
+        && lower.starts_with('f')
+        && lower[1..]
+            .parse::<u8>()
+            .is_ok_and(|n| (1..=12).contains(&n))
+    {
+        lower
+    } else if rest.chars().count() == 1 && !modifiers.is_empty() {
+        let char = rest.chars().next()?;
+        if modifiers.contains("ctrl-") || modifiers.contains("shift-") {
+            char.to_ascii_lowercase().to_string()
+        } else {
+            return Some(format!("{modifiers}{}", char_keystroke(char)));
+        }
+    } else {
+        return None;
+    };
+    Some(format!("{modifiers}{key}"))
+}
+
+fn char_keystroke(char: char) -> String {
+    match char {
+        ' ' => "space".to_string(),
+        char if char.is_ascii_uppercase() => format!("shift-{}", char.to_ascii_lowercase()),
+        char => char.to_string(),
+    }
+}
+
+/// Parses `let mapleader = ...` and `let maplocalleader = ...`, the only
+/// variables that affect mappings.
+fn parse_let(args: &str, leader: &mut String, local_leader: &mut String) -> Result<(), String> {
+    let (name, value) = args
+        .split_once('=')
+        .ok_or_else(|| "expected `let {name} = {value}`".to_string())?;
+    let target = match name.trim().trim_start_matches("g:") {
+        "mapleader" => leader,
+        "maplocalleader" => local_leader,
+        name => return Err(format!("unsupported variable `{name}`")),
+    };
+    *target = parse_string(value.trim())?;
+    Ok(())
+}
+
+/// Parses a Vim string literal into keys in Vim's key notation.
+fn parse_string(value: &str) -> Result<String, String> {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return Ok(value[1..value.len() - 1]
+            .replace("''", "'")
+            .replace(' ', "<Space>"));
+    }
+    if !(value.len() >= 2 && value.starts_with('"') && value.ends_with('"')) {
+        return Err(format!("expected a string, got `{value}`"));
+    }
+    let mut string = String::new();
+    let mut chars = value[1..value.len() - 1].chars();
+    while let Some(char) = chars.next() {
+        match char {
+            // `"\<Space>"` is the key itself, which we keep in key notation.
+            '\\' => match chars.next() {
+                Some('<') => string.push('<'),
+                Some(escaped) => string.push(escaped),
+                None => string.push('\\'),
+            },
+            ' ' => string.push_str("<Space>"),
+            char => string.push(char),
+        }
+    }
+    Ok(string)
+}
+
+/// Parses the options of a `set` command into the settings they correspond to.
+fn parse_set(args: &str, settings: &mut Value) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for arg in args.split_whitespace() {
+        if arg.starts_with('"') {
+            break;
+        }
+        if let Err(error) = parse_option(arg, settings) {
+            errors.push(error);
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join(", "))
+    }
+}
+
+fn parse_option(arg: &str, settings: &mut Value) -> Result<(), String> {
+    let (name, value) = match arg.split_once(['=', ':']) {
+        Some((name, value)) => (name, Some(value)),
+        None => (arg, None),
+    };
+    if name.ends_with(['+', '-', '^', '!']) || name.starts_with("inv") {
+        return Err(format!(
+            "`{arg}` is not supported, set the option to a value"
+        ));
+    }
+
+    let (name, enabled) = match name.strip_prefix("no") {
+        Some(name) if value.is_none() && option_setting(name).is_some() => (name, false),
+        _ => (name, true),
+    };
+    let (path, kind) =
+        option_setting(name).ok_or_else(|| format!("unsupported option `{name}`"))?;
+
+    let setting = match (kind, value) {
+        (OptionKind::Bool, None) => Value::Bool(enabled),
+        (OptionKind::InvertedBool, None) => Value::Bool(!enabled),
+        (OptionKind::Wrap, None) => json!(if enabled { "editor_width" } else { "none" }),
+        (OptionKind::Number, Some(value)) => {
+            let value = value
+                .parse::<u32>()
+                .map_err(|_| format!("expected a number for `{name}`, got `{value}`"))?;
+            // `shiftwidth=0` means that the `tabstop` is used.
+            if value == 0 && matches!(name, "sw" | "shiftwidth") {
+                return Ok(());
+            }
+            json!(value)
+        }
+        (OptionKind::Clipboard, Some(value)) => {
+            let unnamed = value
+                .split(',')
+                .any(|value| value == "unnamed" || value == "unnamedplus");
+            json!(if unnamed { "always" } else { "never" })
+        }
+        (OptionKind::Number | OptionKind::Clipboard, None) => {
+            return Err(format!("expected a value for `{name}`"))
+        }
+        (_, Some(_)) => return Err(format!("`{name}` doesn't take a value")),
+    };
+
+    let mut target = &mut *settings;
+    for key in &path[..path.len() - 1] {
+        target = target
+            .as_object_mut()
+            .unwrap()
+            .entry(*key)
+            .or_insert_with(|| json!({}));
+    }
+    target
+        .as_object_mut()
+        .unwrap()
+        .insert(path[path.len() - 1].to_string(), setting);
+    Ok(())
+}
+
+enum OptionKind {
+    Bool,
+    /// A boolean option that is the opposite of the setting.
+    InvertedBool,
+    Number,
+    Wrap,
+    Clipboard,
+}
+
+/// The path of the setting that a Vim option corresponds to.
+fn option_setting(name: &str) -> Option<(&'static [&'static str], OptionKind)> {
+    Some(match name {
+        "nu" | "number" => (&["gutter", "line_numbers"], OptionKind::Bool),
+        "rnu" | "relativenumber" => (&["relative_line_numbers"], OptionKind::Bool),
+        "so" | "scrolloff" => (&["vertical_scroll_margin"], OptionKind::Number),
+        "ts" | "tabstop" | "sw" | "shiftwidth" => (&["tab_size"], OptionKind::Number),
+        "et" | "expandtab" => (&["hard_tabs"], OptionKind::InvertedBool),
+        "wrap" => (&["soft_wrap"], OptionKind::Wrap),
+        "scs" | "smartcase" => (&["vim", "use_smartcase_find"], OptionKind::Bool),
+        "cb" | "clipboard" => (&["vim", "use_system_clipboard"], OptionKind::Clipboard),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vimrc() {
+        let vimrc = Vimrc::parse(indoc::indoc! {r#"
+            " comment
+            let mapleader = " "
+            nnoremap <leader>f :w<CR>
+            nmap <silent> <C-j> 5j
+            inoremap jk <Esc>
+            map Q gq
+            unmap Q
+            set relativenumber nowrap scrolloff=8
+            set noexpandtab ts=2 clipboard=unnamedplus
+            set hlsearch
+            colorscheme desert
+        "#});
+
+        assert_eq!(
+            vimrc.mappings,
+            vec![
+                VimMapping {
+                    mode: VimMapMode::Normal,
+                    keystrokes: "space f".into(),
+                    keys: ":w<CR>".into(),
+                    remap: false,
+                },
+                VimMapping {
+                    mode: VimMapMode::Normal,
+                    keystrokes: "ctrl-j".into(),
+                    keys: "5j".into(),
+                    remap: true,
+                },
+                VimMapping {
+                    mode: VimMapMode::Insert,
+                    keystrokes: "j k".into(),
+                    keys: "<Esc>".into(),
+                    remap: false,
+                },
+            ]
+        );
+        assert_eq!(
+            vimrc.settings,
+            json!({
+                "relative_line_numbers": true,
+                "soft_wrap": "none",
+                "vertical_scroll_margin": 8,
+                "hard_tabs": true,
+                "tab_size": 2,
+                "vim": { "use_system_clipboard": "always" },
+            })
+        );
+        assert_eq!(
+            vimrc
+                .errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "line 10: unsupported option `hlsearch`",
+                "line 11: unsupported command `colorscheme`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(parse_keys("<leader>G", "\\", "\\").unwrap(), "\\ shift-g");
+        assert_eq!(
+            parse_keys("<C-S-x><F5>", "\\", "\\").unwrap(),
+            "ctrl-shift-x f5"
+        );
+        assert_eq!(parse_keys("<lt>a<Bar>", "\\", "\\").unwrap(), "< a |");
+        assert_eq!(parse_keys("<M-K>", "\\", "\\").unwrap(), "alt-shift-k");
+        assert_eq!(parse_keys("<x>", "\\", "\\").unwrap(), "< x >");
+    }
+}
@@ -971,6 +971,8 @@ impl SearchableItem for TerminalView {
             case: false,
             word: false,
             regex: true,
+            structural: false,
+            syntax_context: false,
             replacement: false,
             selection: false,
         }
@@ -1030,6 +1032,8 @@ impl SearchableItem for TerminalView {
                 .unwrap()),
             ),
             SearchQuery::Regex { .. } => regex_search_for_query(&query),
+            // Terminal output has no syntax tree to match against.
+            SearchQuery::Structural { .. } => None,
         };
 
         if let Some(s) = searcher {
@@ -0,0 +1,294 @@
+use crate::LineEnding;
+use anyhow::{anyhow, Context as _, Result};
+use encoding_rs::DecoderResult;
+use rope::Rope;
+use std::{borrow::Cow, fmt};
+
+/// The encodings that can be chosen when reopening or saving a file.
+const SELECTABLE_ENCODINGS: &[&encoding_rs::Encoding] = &[
+    encoding_rs::UTF_8,
+    encoding_rs::UTF_16LE,
+    encoding_rs::UTF_16BE,
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::ISO_8859_2,
+    encoding_rs::ISO_8859_15,
+    encoding_rs::WINDOWS_1250,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::KOI8_R,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::EUC_JP,
+    encoding_rs::ISO_2022_JP,
+    encoding_rs::GBK,
+    encoding_rs::GB18030,
+    encoding_rs::BIG5,
+    encoding_rs::EUC_KR,
+];
+
+/// The character encoding of a file on disk, and whether the file starts with
+/// a byte order mark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::UTF8
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())?;
+        if self.bom {
+            f.write_str(" with BOM")?;
+        }
+        Ok(())
+    }
+}
+
+impl Encoding {
+    pub const UTF8: Self = Self {
+        encoding: encoding_rs::UTF_8,
+        bom: false,
+    };
+
+    /// Returns the encoding with the given WHATWG label, such as `"shift_jis"`
+    /// or `"latin1"`.
+    pub fn for_label(label: &str) -> Option<Self> {
+        let encoding = encoding_rs::Encoding::for_label(label.trim().as_bytes())?;
+        // `replacement` and `x-user-defined` can't round-trip arbitrary files.
+        if encoding == encoding_rs::REPLACEMENT || encoding == encoding_rs::X_USER_DEFINED {
+            return None;
+        }
+        Some(Self {
+            encoding,
+            bom: false,
+        })
+    }
+
+    /// Every encoding that can be chosen for a buffer, without a byte order mark.
+    pub fn all() -> impl Iterator<Item = Self> {
+        SELECTABLE_ENCODINGS.iter().map(|encoding| Self {
+            encoding,
+            bom: false,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    pub fn with_bom(self, bom: bool) -> Self {
+        Self {
+            encoding: self.encoding,
+            bom: bom && self.supports_bom(),
+        }
+    }
+
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    pub fn supports_bom(&self) -> bool {
+        self.is_utf8() || self.is_utf16()
+    }
+
+    fn is_utf16(&self) -> bool {
+        self.encoding == encoding_rs::UTF_16LE || self.encoding == encoding_rs::UTF_16BE
+    }
+
+    /// The byte order mark that precedes the encoded text, if any.
+    pub fn bom_bytes(&self) -> &'static [u8] {
+        if !self.bom {
+            &[]
+        } else if self.encoding == encoding_rs::UTF_8 {
+            b"\xEF\xBB\xBF"
+        } else if self.encoding == encoding_rs::UTF_16LE {
+            b"\xFF\xFE"
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            b"\xFE\xFF"
+        } else {
+            &[]
+        }
+    }
+
+    /// Decodes the contents of a file.
+    ///
+    /// A byte order mark always takes precedence. Otherwise, the contents are
+    /// decoded with the given encoding, or when none is given, as UTF-8 if they
+    /// are valid UTF-8 and with the encoding detected from their bytes otherwise.
+    /// Without an encoding, contents that aren't valid UTF-8 are refused as
+    /// binary if they contain a NUL byte, or if they aren't valid in the
+    /// detected encoding.
+    pub fn decode(bytes: &[u8], encoding: Option<Self>) -> Result<(String, Self)> {
+        let (encoding, bom_len) = Self::choose(bytes, encoding, true)?;
+        let text = encoding.decode_without_bom(&bytes[bom_len..])?;
+        Ok((text, encoding))
+    }
+
+    /// Chooses the encoding to decode the given contents with, returning it
+    /// along with the length of its byte order mark. When the contents aren't
+    /// `complete`, a UTF-8 sequence cut off at their end is still valid UTF-8.
+    fn choose(bytes: &[u8], encoding: Option<Self>, complete: bool) -> Result<(Self, usize)> {
+        if let Some((encoding, bom_len)) = encoding_rs::Encoding::for_bom(bytes) {
+            let encoding = Self {
+                encoding,
+                bom: true,
+            };
+            return Ok((encoding, bom_len));
+        }
+
+        let is_utf8 = match std::str::from_utf8(bytes) {
+            Ok(_) => true,
+            Err(error) => !complete && error.error_len().is_none(),
+        };
+        let encoding = match encoding {
+            Some(encoding) => encoding.with_bom(false),
+            None if is_utf8 => Self::UTF8,
+            None if bytes.contains(&0) => {
+                return Err(anyhow!("file contents appear to be binary"));
+            }
+            None => Self::detect(bytes),
+        };
+        Ok((encoding, 0))
+    }
+
+    /// Guesses the legacy encoding of contents that aren't valid UTF-8.
+    fn detect(bytes: &[u8]) -> Self {
+        let mut detector = chardetng::EncodingDetector::new();
+        detector.feed(bytes, true);
+        Self {
+            encoding: detector.guess(None, false),
+            bom: false,
+        }
+    }
+
+    fn decode_without_bom(&self, bytes: &[u8]) -> Result<String> {
+        self.encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(Cow::into_owned)
+            .ok_or_else(|| anyhow!("file contents are not valid {}", self.name()))
+    }
+
+    /// Encodes the given text, not including the byte order mark. Fails if the
+    /// text contains characters that can't be represented in this encoding.
+    pub fn encode<'a>(&self, text: &'a str) -> Result<Cow<'a, [u8]>> {
+        if self.encoding == encoding_rs::UTF_16LE {
+            return Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect());
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            return Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect());
+        }
+
+        let (bytes, _, unmappable) = self.encoding.encode(text);
+        if unmappable {
+            let ch = text
+                .chars()
+                .find(|ch| {
+                    let mut buf = [0; 4];
+                    self.encoding.encode(ch.encode_utf8(&mut buf)).2
+                })
+                .unwrap_or(char::REPLACEMENT_CHARACTER);
+            return Err(anyhow!("{ch:?} can't be represented in {}", self.name()));
+        }
+        Ok(bytes)
+    }
+}
+
+/// Decodes the contents of a file as they're read in chunks, appending the
+/// decoded text to a rope with its line endings normalized, so the raw chunks
+/// can be dropped as soon as they're decoded.
+///
+/// The encoding is chosen from the first chunk like [`Encoding::decode`]
+/// chooses it from the whole contents, so contents that are only invalid
+/// after the first chunk are refused instead of being detected again.
+#[derive(Default)]
+pub struct ChunkedDecoder {
+    decoder: Option<(Encoding, encoding_rs::Decoder)>,
+    line_ending: Option<LineEnding>,
+    text: Rope,
+    pending: String,
+}
+
+impl ChunkedDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes the next chunk of the contents.
+    pub fn push(&mut self, mut chunk: &[u8]) -> Result<()> {
+        if self.decoder.is_none() {
+            let (encoding, bom_len) = Encoding::choose(chunk, None, false)?;
+            let decoder = encoding.encoding.new_decoder_without_bom_handling();
+            self.decoder = Some((encoding, decoder));
+            chunk = &chunk[bom_len..];
+        }
+        self.decode(chunk, false)
+    }
+
+    /// Decodes whatever is left of the contents, returning their text with its
+    /// line endings normalized, the line ending it used and its encoding.
+    pub fn finish(mut self) -> Result<(Rope, LineEnding, Encoding)> {
+        if self.decoder.is_none() {
+            self.push(&[])?;
+        }
+        self.decode(&[], true)?;
+        let encoding = self
+            .decoder
+            .map_or(Encoding::UTF8, |(encoding, _)| encoding);
+        Ok((self.text, self.line_ending.unwrap_or_default(), encoding))
+    }
+
+    fn decode(&mut self, mut chunk: &[u8], last: bool) -> Result<()> {
+        let Some((encoding, decoder)) = self.decoder.as_mut() else {
+            return Ok(());
+        };
+        loop {
+            let capacity = decoder
+                .max_utf8_buffer_length_without_replacement(chunk.len())
+                .context("file chunk is too large to decode")?;
+            self.pending.reserve(capacity);
+            let (result, read) =
+                decoder.decode_to_string_without_replacement(chunk, &mut self.pending, last);
+            chunk = &chunk[read..];
+            match result {
+                DecoderResult::InputEmpty => break,
+                DecoderResult::OutputFull => continue,
+                DecoderResult::Malformed(..) => {
+                    return Err(anyhow!("file contents are not valid {}", encoding.name()));
+                }
+            }
+        }
+
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        if self.line_ending.is_none() {
+            if let Some(ix) = self.pending.find('\n') {
+                self.line_ending = Some(if self.pending[..ix].ends_with('\r') {
+                    LineEnding::Windows
+                } else {
+                    LineEnding::Unix
+                });
+            }
+        }
+        // A carriage return at the end of a chunk may start a line ending that
+        // the next chunk finishes.
+        let split_line_ending = !last && self.pending.ends_with('\r');
+        if split_line_ending {
+            self.pending.pop();
+        }
+        LineEnding::normalize(&mut self.pending);
+        self.text.push(&self.pending);
+        self.pending.clear();
+        if split_line_ending {
+            self.pending.push('\r');
+        }
+        Ok(())
+    }
+}
@@ -189,6 +189,107 @@ fn test_line_endings() {
     buffer.check_invariants();
 }
 
+#[test]
+fn test_encodings() {
+    let (text, encoding) = Encoding::decode(b"caf\xE9\n", None).unwrap();
+    assert_eq!(text, "café\n");
+    assert_eq!(encoding.name(), "windows-1252");
+    assert_eq!(encoding.encode(&text).unwrap().as_ref(), b"caf\xE9\n");
+
+    let (text, encoding) = Encoding::decode("café".as_bytes(), None).unwrap();
+    assert_eq!(text, "café");
+    assert_eq!(encoding, Encoding::UTF8);
+
+    let (text, encoding) = Encoding::decode(b"\xFF\xFEh\0i\0", None).unwrap();
+    assert_eq!(text, "hi");
+    assert_eq!(encoding.to_string(), "UTF-16LE with BOM");
+    assert_eq!(encoding.bom_bytes(), b"\xFF\xFE");
+    assert_eq!(encoding.encode(&text).unwrap().as_ref(), b"h\0i\0");
+
+    let shift_jis = Encoding::for_label("shift_jis").unwrap();
+    let (text, encoding) = Encoding::decode(b"\x93\xfa\x96\x7b", Some(shift_jis)).unwrap();
+    assert_eq!(text, "日本");
+    assert_eq!(encoding, shift_jis);
+    assert_eq!(
+        encoding.encode(&text).unwrap().as_ref(),
+        b"\x93\xfa\x96\x7b"
+    );
+    assert!(Encoding::decode(b"\x93", Some(shift_jis)).is_err());
+    assert!(!shift_jis.with_bom(true).has_bom());
+
+    // Without an encoding, legacy encodings are detected from the contents.
+    let (text, encoding) = Encoding::decode(
+        &shift_jis
+            .encode("// 日本語のテキストです。\nfn main() {}\n")
+            .unwrap(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(text, "// 日本語のテキストです。\nfn main() {}\n");
+    assert_eq!(encoding, shift_jis);
+    let gbk = Encoding::for_label("gbk").unwrap();
+    let (text, encoding) = Encoding::decode(
+        &gbk.encode("// 这是一个中文注释，用于测试编码。\n").unwrap(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(text, "// 这是一个中文注释，用于测试编码。\n");
+    assert_eq!(encoding, gbk);
+
+    // Contents with a NUL byte are binary, unless they're valid UTF-8 or an
+    // encoding is given.
+    assert!(Encoding::decode(b"ELF\0\x01\xFF", None).is_err());
+    let (text, encoding) = Encoding::decode(b"a\0b", None).unwrap();
+    assert_eq!(text, "a\0b");
+    assert_eq!(encoding, Encoding::UTF8);
+    assert!(Encoding::decode(b"h\0i\0", Some(Encoding::for_label("utf-16le").unwrap())).is_ok());
+
+    let latin1 = Encoding::for_label("latin1").unwrap();
+    assert_eq!(
+        latin1.encode("日本").unwrap_err().to_string(),
+        "'日' can't be represented in windows-1252"
+    );
+}
+
+#[test]
+fn test_chunked_decoder() {
+    // Chunks can split multi-byte characters and line endings.
+    let mut decoder = ChunkedDecoder::new();
+    for chunk in [&b"one\r"[..], b"\ncaf\xC3", b"\xA9\r", b"\ntwo\r"] {
+        decoder.push(chunk).unwrap();
+    }
+    let (text, line_ending, encoding) = decoder.finish().unwrap();
+    assert_eq!(text.to_string(), "one\ncafé\ntwo\n");
+    assert_eq!(line_ending, LineEnding::Windows);
+    assert_eq!(encoding, Encoding::UTF8);
+
+    let mut decoder = ChunkedDecoder::new();
+    decoder.push(b"\xFF\xFEh\0").unwrap();
+    decoder.push(b"i\0\r\0").unwrap();
+    let (text, _, encoding) = decoder.finish().unwrap();
+    assert_eq!(text.to_string(), "hi\n");
+    assert_eq!(encoding.to_string(), "UTF-16LE with BOM");
+
+    let mut decoder = ChunkedDecoder::new();
+    decoder.push(b"caf\xE9\n").unwrap();
+    let (text, _, encoding) = decoder.finish().unwrap();
+    assert_eq!(text.to_string(), "café\n");
+    assert_eq!(encoding.name(), "windows-1252");
+
+    let (text, _, encoding) = ChunkedDecoder::new().finish().unwrap();
+    assert_eq!(text.to_string(), "");
+    assert_eq!(encoding, Encoding::UTF8);
+
+    // The encoding is chosen from the first chunk.
+    assert!(ChunkedDecoder::new().push(b"ELF\0\x01\xFF").is_err());
+    let mut decoder = ChunkedDecoder::new();
+    decoder.push(b"caf").unwrap();
+    assert!(decoder.push(b"\xFF").is_err());
+    let mut decoder = ChunkedDecoder::new();
+    decoder.push(b"caf\xC3").unwrap();
+    assert!(decoder.finish().is_err());
+}
+
 #[test]
 fn test_line_len() {
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
@@ -1,4 +1,5 @@
 mod anchor;
+mod encoding;
 pub mod locator;
 #[cfg(any(test, feature = "test-support"))]
 pub mod network;
@@ -14,6 +15,7 @@ pub use anchor::*;
 use anyhow::{anyhow, Context as _, Result};
 pub use clock::ReplicaId;
 use collections::{HashMap, HashSet};
+pub use encoding::*;
 use locator::Locator;
 use operation_queue::OperationQueue;
 pub use patch::Patch;
@@ -109,6 +111,7 @@ pub struct BufferSnapshot {
     visible_text: Rope,
     deleted_text: Rope,
     line_ending: LineEnding,
+    encoding: Encoding,
     undo_map: UndoMap,
     fragments: SumTree<Fragment>,
     insertions: SumTree<InsertionFragment>,
@@ -661,6 +664,7 @@ impl Buffer {
                 visible_text,
                 deleted_text: Rope::new(),
                 line_ending,
+                encoding: Encoding::default(),
                 fragments,
                 insertions,
                 version,
@@ -895,6 +899,10 @@ impl Buffer {
         self.snapshot.line_ending = line_ending;
     }
 
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.snapshot.encoding = encoding;
+    }
+
     pub fn apply_ops<I: IntoIterator<Item = Operation>>(&mut self, ops: I) -> Result<()> {
         let mut deferred_ops = Vec::new();
         for op in ops {
@@ -1871,6 +1879,10 @@ impl BufferSnapshot {
         self.line_ending
     }
 
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
     pub fn deleted_text(&self) -> String {
         self.deleted_text.to_string()
     }
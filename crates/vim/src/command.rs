@@ -1,46 +1,896 @@
+use std::ops::{Range, RangeInclusive};
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use command_palette::CommandPalette;
 use command_palette_hooks::CommandInterceptResult;
-use editor::actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive};
-use gpui::{impl_actions, Action, AppContext, ViewContext};
+use editor::{
+    actions::{Indent, JoinLines, Outdent, SortLinesCaseInsensitive, SortLinesCaseSensitive},
+    scroll::Autoscroll,
+    Anchor, Editor, ToPoint,
+};
+use gpui::{actions, impl_actions, Action, AppContext, View, ViewContext, WindowContext};
+use language::Point;
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
+use regex::{Regex, RegexBuilder};
 use serde_derive::Deserialize;
-use workspace::{SaveIntent, Workspace};
+use workspace::{notifications::NotificationId, SaveIntent, Toast, Workspace};
 
 use crate::{
-    motion::{EndOfDocument, Motion, StartOfDocument},
+    insert::NormalBefore,
+    marks_picker::{DeleteMarks, ShowJumps, ShowMarks},
     normal::{
-        move_cursor,
-        search::{range_regex, FindCommand, ReplaceCommand},
-        JoinLines,
+        macros::{literal_keystrokes, replay},
+        mark::create_visual_marks,
+        search::{replace_with_search_bar, FindCommand, Replacement},
+        yank::{copy_selections_content, yank_selections_content},
     },
-    state::Mode,
+    state::{Mode, ReplayStep, ReplayableAction, SubstituteState},
     Vim,
 };
 
+/// A line address in the range of an Ex command.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Address {
+    /// `.`
+    CurrentLine,
+    /// `$`
+    LastLine,
+    /// `{number}`, where line 0 is above the first line.
+    Line(u32),
+    /// `'x`
+    Mark(char),
+    /// `/pattern/`, the next line that matches.
+    SearchForward(String),
+    /// `?pattern?`, the previous line that matches.
+    SearchBackward(String),
+}
+
+/// An address followed by any number of `+N` and `-N` offsets.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Position {
+    pub address: Address,
+    pub offset: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CommandRange {
+    pub start: Position,
+    pub end: Option<Position>,
+}
+
+/// The arguments of `:s/pattern/replacement/flags`, in Vim's syntax.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Substitution {
+    pub pattern: String,
+    pub replacement: String,
+    pub flags: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum LineCommand {
+    /// `:{range}` moves to the last line of the range.
+    GoTo,
+    Delete {
+        register: Option<char>,
+        count: Option<u32>,
+    },
+    Yank {
+        register: Option<char>,
+        count: Option<u32>,
+    },
+    Join {
+        count: Option<u32>,
+    },
+    Move {
+        destination: Position,
+    },
+    Copy {
+        destination: Position,
+    },
+    Indent {
+        shifts: usize,
+    },
+    Outdent {
+        shifts: usize,
+    },
+    Substitute(Substitution),
+    /// `:&`, or `:s` without a pattern, repeats the last substitution.
+    RepeatSubstitute {
+        flags: String,
+    },
+    /// `:g/pattern/command` runs `command` on each matching line (or each
+    /// line that does not match, for `:g!` and `:v`).
+    Global {
+        pattern: String,
+        invert: bool,
+        command: String,
+    },
+    Normal {
+        keys: String,
+    },
+    Sort {
+        case_insensitive: bool,
+    },
+}
+
+/// A command typed after `:`, run against the lines of the active editor.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
-pub struct GoToLine {
-    pub line: u32,
+pub struct ExCommand {
+    pub range: Option<CommandRange>,
+    pub command: LineCommand,
 }
 
-impl_actions!(vim, [GoToLine]);
+impl_actions!(vim, [ExCommand]);
+
+actions!(vim, [EndNormalCommand, VisualCommand, CountCommand]);
 
 pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
-    workspace.register_action(|_: &mut Workspace, action: &GoToLine, cx| {
+    workspace.register_action(|workspace: &mut Workspace, _: &VisualCommand, cx| {
         Vim::update(cx, |vim, cx| {
-            vim.switch_mode(Mode::Normal, false, cx);
-            move_cursor(vim, Motion::StartOfDocument, Some(action.line as usize), cx);
+            let mode = vim.state().mode;
+            create_visual_marks(vim, mode, cx);
+        });
+        CommandPalette::toggle(workspace, "'<,'>", cx);
+    });
+    workspace.register_action(|workspace: &mut Workspace, _: &CountCommand, cx| {
+        let count = Vim::update(cx, |vim, cx| vim.take_count(cx)).unwrap_or(1);
+        let query = if count > 1 {
+            format!(".,.+{}", count - 1)
+        } else {
+            ".".to_string()
+        };
+        CommandPalette::toggle(workspace, &query, cx);
+    });
+    workspace.register_action(|workspace: &mut Workspace, action: &ExCommand, cx| {
+        if let Err(err) = ex_command(workspace, action, cx) {
+            workspace.show_error(&err, cx);
+        }
+    });
+    workspace.register_action(|_: &mut Workspace, _: &EndNormalCommand, cx| {
+        // an incomplete command is aborted as though <Esc> was typed.
+        let mode = Vim::update(cx, |vim, cx| {
+            vim.clear_operator(cx);
+            vim.state().mode
         });
+        if matches!(mode, Mode::Insert | Mode::Replace) {
+            cx.dispatch_action(NormalBefore.boxed_clone());
+        }
+    });
+}
+
+/// Work left over once a command has run in the editor.
+enum Outcome {
+    Done,
+    /// Replay keystrokes, as `:normal` does. Their edits are grouped into
+    /// one undo step by the first and last steps.
+    Replay(Vec<ReplayStep>),
+    /// Step through the matches of `:s///c` in the buffer search bar.
+    ReplaceWithSearchBar(Replacement),
+    Report(String),
+}
+
+fn ex_command(
+    workspace: &mut Workspace,
+    action: &ExCommand,
+    cx: &mut ViewContext<Workspace>,
+) -> Result<()> {
+    let outcome = Vim::update(cx, |vim, cx| {
+        vim.switch_mode(Mode::Normal, false, cx);
+        vim.update_active_editor(cx, |vim, editor, cx| {
+            let mut result = Ok(Outcome::Done);
+            editor.transact(cx, |editor, cx| {
+                let addresses = AddressContext::new(vim, editor, cx);
+                result = run_line_command(vim, editor, &addresses, action, cx);
+            });
+            result
+        })
+    });
+
+    match outcome.transpose()? {
+        None | Some(Outcome::Done) => {}
+        Some(Outcome::Replay(steps)) => replay(steps, cx),
+        Some(Outcome::ReplaceWithSearchBar(replacement)) => {
+            replace_with_search_bar(workspace, replacement, cx)
+        }
+        Some(Outcome::Report(message)) => workspace.show_toast(
+            Toast::new(NotificationId::unique::<ExCommand>(), message),
+            cx,
+        ),
+    }
+    Ok(())
+}
+
+/// The most replacements shown by the preview of a `:s` command, so that
+/// typing stays responsive in large buffers.
+const MAX_PREVIEWED_REPLACEMENTS: usize = 1000;
+
+/// Previews the replacements of the `:s` command being typed into the
+/// command palette, or removes the preview when `None` is given.
+pub fn command_preview(query: Option<&str>, cx: &mut WindowContext) {
+    let command = query.and_then(|query| parse_ex_command(query.trim_start_matches(':')));
+    Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |vim, editor, cx| {
+            let replacements = command
+                .and_then(|command| substitution_preview(vim, editor, &command, cx))
+                .unwrap_or_default();
+            if replacements.is_empty() {
+                editor.clear_replacement_previews(cx);
+            } else {
+                editor.preview_replacements(replacements, cx);
+            }
+        });
+    });
+}
+
+/// The ranges a `:s` command would replace, with the text replacing each.
+fn substitution_preview(
+    vim: &Vim,
+    editor: &mut Editor,
+    command: &ExCommand,
+    cx: &mut ViewContext<Editor>,
+) -> Option<Vec<(Range<Anchor>, String)>> {
+    let LineCommand::Substitute(substitution) = &command.command else {
+        return None;
+    };
+    let last = vim.workspace_state.last_substitute.as_ref();
+    let addresses = AddressContext::new(vim, editor, cx);
+    let rows = addresses.rows(command.range.as_ref(), false).ok()?;
+    let pattern = addresses.pattern(&substitution.pattern).ok()?;
+    let flags = SubstituteFlags::parse(&expand_previous_flags(&substitution.flags, last)).ok()?;
+    let replacement = vim_replacement_to_zed(&expand_previous_replacement(
+        &substitution.replacement,
+        last.map(|last| last.replacement.as_str()),
+    ));
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(flags.ignore_case)
+        .build()
+        .ok()?;
+
+    let snapshot = &addresses.snapshot;
+    let mut replacements = Vec::new();
+    for row in rows {
+        let line = line_text(snapshot, row);
+        let limit = if flags.global { usize::MAX } else { 1 };
+        for captures in regex.captures_iter(&line).take(limit) {
+            let Some(found) = captures.get(0) else {
+                continue;
+            };
+            let mut text = String::new();
+            captures.expand(&replacement, &mut text);
+            let start = snapshot.anchor_before(Point::new(row, found.start() as u32));
+            let end = snapshot.anchor_after(Point::new(row, found.end() as u32));
+            replacements.push((start..end, text));
+            if replacements.len() == MAX_PREVIEWED_REPLACEMENTS {
+                return Some(replacements);
+            }
+        }
+    }
+    Some(replacements)
+}
+
+/// What the addresses in a range are resolved against.
+struct AddressContext {
+    snapshot: MultiBufferSnapshot,
+    current_row: u32,
+    marks: HashMap<String, Vec<Anchor>>,
+    last_pattern: Option<String>,
+}
+
+impl AddressContext {
+    fn new(vim: &Vim, editor: &mut Editor, cx: &mut ViewContext<Editor>) -> Self {
+        Self {
+            snapshot: editor.buffer().read(cx).snapshot(cx),
+            current_row: editor.selections.newest::<Point>(cx).head().row,
+            marks: vim.state().marks.clone(),
+            last_pattern: vim.workspace_state.last_search_pattern.clone(),
+        }
+    }
+
+    fn max_row(&self) -> u32 {
+        self.snapshot.max_buffer_row().0
+    }
+
+    /// Resolves a position to a 1-based line number, where 0 is above the first line.
+    fn line(&self, position: &Position) -> Result<u32> {
+        let line = match &position.address {
+            Address::CurrentLine => self.current_row + 1,
+            Address::LastLine => self.max_row() + 1,
+            Address::Line(line) => *line,
+            Address::Mark(mark) => {
+                let anchor = self
+                    .marks
+                    .get(&mark.to_string())
+                    .and_then(|anchors| anchors.first())
+                    .ok_or_else(|| anyhow!("E20: Mark not set"))?;
+                anchor.to_point(&self.snapshot).row + 1
+            }
+            Address::SearchForward(pattern) => self.search(pattern, false)? + 1,
+            Address::SearchBackward(pattern) => self.search(pattern, true)? + 1,
+        };
+        let line = line as i64 + position.offset as i64;
+        if line < 0 {
+            return Err(anyhow!("E16: Invalid range"));
+        }
+        Ok((line as u32).min(self.max_row() + 1))
+    }
+
+    /// Resolves a range to the rows it covers, defaulting to the current line
+    /// or to the whole buffer.
+    fn rows(
+        &self,
+        range: Option<&CommandRange>,
+        whole_buffer: bool,
+    ) -> Result<RangeInclusive<u32>> {
+        let Some(range) = range else {
+            return Ok(if whole_buffer {
+                0..=self.max_row()
+            } else {
+                self.current_row..=self.current_row
+            });
+        };
+        let start = self.line(&range.start)?;
+        let end = match &range.end {
+            Some(end) => self.line(end)?,
+            None => start,
+        };
+        let (start, end) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        Ok(start.saturating_sub(1)..=end.saturating_sub(1))
+    }
+
+    /// Converts a pattern to Zed's regex syntax, with an empty pattern
+    /// standing for the last one searched for.
+    fn pattern(&self, pattern: &str) -> Result<String> {
+        if pattern.is_empty() {
+            self.last_pattern
+                .clone()
+                .ok_or_else(|| anyhow!("E35: No previous regular expression"))
+        } else {
+            Ok(vim_pattern_to_zed(pattern))
+        }
+    }
+
+    fn search(&self, pattern: &str, backwards: bool) -> Result<u32> {
+        let regex = Regex::new(&self.pattern(pattern)?)?;
+        let max_row = self.max_row();
+        let matches = |row: &u32| regex.is_match(&line_text(&self.snapshot, *row));
+        let row = if backwards {
+            (0..self.current_row)
+                .rev()
+                .chain((self.current_row..=max_row).rev())
+                .find(matches)
+        } else {
+            (self.current_row + 1..=max_row)
+                .chain(0..=self.current_row)
+                .find(matches)
+        };
+        row.ok_or_else(|| anyhow!("E486: Pattern not found: {pattern}"))
+    }
+}
+
+fn run_line_command(
+    vim: &mut Vim,
+    editor: &mut Editor,
+    addresses: &AddressContext,
+    command: &ExCommand,
+    cx: &mut ViewContext<Editor>,
+) -> Result<Outcome> {
+    let range = command.range.as_ref();
+    match &command.command {
+        LineCommand::GoTo => {
+            let rows = addresses.rows(range, false)?;
+            move_to_line(editor, *rows.end(), cx);
+        }
+        LineCommand::Delete { register, count } | LineCommand::Yank { register, count } => {
+            let rows = with_count(addresses.rows(range, false)?, *count, addresses.max_row());
+            let yank = matches!(command.command, LineCommand::Yank { .. });
+            delete_lines(vim, editor, rows, *register, yank, cx);
+        }
+        LineCommand::Join { count } => {
+            let rows = with_count(addresses.rows(range, false)?, *count, addresses.max_row());
+            let start = *rows.start();
+            let end = if start == *rows.end() {
+                (start + 1).min(addresses.max_row())
+            } else {
+                *rows.end()
+            };
+            if start < end {
+                editor.change_selections(None, cx, |s| {
+                    s.select_ranges([Point::new(start, 0)..Point::new(end, 0)])
+                });
+                editor.join_lines(&JoinLines, cx);
+            }
+        }
+        LineCommand::Move { destination } | LineCommand::Copy { destination } => {
+            let rows = addresses.rows(range, false)?;
+            let destination = addresses.line(destination)?;
+            let is_move = matches!(command.command, LineCommand::Move { .. });
+            transfer_lines(editor, rows, destination, is_move, cx)?;
+        }
+        LineCommand::Indent { shifts } | LineCommand::Outdent { shifts } => {
+            let rows = addresses.rows(range, false)?;
+            select_lines(editor, &rows, cx);
+            for _ in 0..*shifts {
+                if matches!(command.command, LineCommand::Indent { .. }) {
+                    editor.indent(&Indent, cx);
+                } else {
+                    editor.outdent(&Outdent, cx);
+                }
+            }
+            move_to_line(editor, *rows.end(), cx);
+        }
+        LineCommand::Sort { case_insensitive } => {
+            let rows = addresses.rows(range, true)?;
+            select_lines(editor, &rows, cx);
+            if *case_insensitive {
+                editor.sort_lines_case_insensitive(&SortLinesCaseInsensitive, cx);
+            } else {
+                editor.sort_lines_case_sensitive(&SortLinesCaseSensitive, cx);
+            }
+            move_to_line(editor, *rows.start(), cx);
+        }
+        LineCommand::Substitute(substitution) => {
+            let rows = addresses.rows(range, false)?;
+            let last = vim.workspace_state.last_substitute.clone();
+            let substitute = SubstituteState {
+                pattern: addresses.pattern(&substitution.pattern)?,
+                replacement: expand_previous_replacement(
+                    &substitution.replacement,
+                    last.as_ref().map(|last| last.replacement.as_str()),
+                ),
+                flags: expand_previous_flags(&substitution.flags, last.as_ref()),
+            };
+            return substitute_lines(vim, editor, addresses, rows, substitute, cx);
+        }
+        LineCommand::RepeatSubstitute { flags } => {
+            let rows = addresses.rows(range, false)?;
+            let last = vim
+                .workspace_state
+                .last_substitute
+                .clone()
+                .ok_or_else(|| anyhow!("E35: No previous regular expression"))?;
+            let substitute = SubstituteState {
+                flags: expand_previous_flags(flags, Some(&last)),
+                ..last
+            };
+            return substitute_lines(vim, editor, addresses, rows, substitute, cx);
+        }
+        LineCommand::Global {
+            pattern,
+            invert,
+            command,
+        } => {
+            let rows = addresses.rows(range, true)?;
+            return global(vim, editor, addresses, rows, pattern, *invert, command, cx);
+        }
+        LineCommand::Normal { keys } => {
+            let lines = range
+                .map(|range| addresses.rows(Some(range), false))
+                .transpose()?
+                .map(|rows| {
+                    rows.map(|row| addresses.snapshot.anchor_after(Point::new(row, 0)))
+                        .collect()
+                });
+            return Ok(Outcome::Replay(normal_steps(
+                lines,
+                keys,
+                cx.view().clone(),
+            )));
+        }
+    }
+    Ok(Outcome::Done)
+}
+
+/// Applies a `:d 3` style count, which counts from the last line of the range.
+fn with_count(rows: RangeInclusive<u32>, count: Option<u32>, max_row: u32) -> RangeInclusive<u32> {
+    match count {
+        Some(count) => *rows.end()..=(rows.end() + count.max(1) - 1).min(max_row),
+        None => rows,
+    }
+}
+
+/// The range to delete to remove the given rows, including a newline.
+fn line_range(snapshot: &MultiBufferSnapshot, rows: &RangeInclusive<u32>) -> Range<Point> {
+    let (start, end) = (*rows.start(), *rows.end());
+    if end < snapshot.max_buffer_row().0 {
+        Point::new(start, 0)..Point::new(end + 1, 0)
+    } else if start > 0 {
+        let previous_row = MultiBufferRow(start - 1);
+        Point::new(start - 1, snapshot.line_len(previous_row))..snapshot.max_point()
+    } else {
+        Point::new(0, 0)..snapshot.max_point()
+    }
+}
+
+fn line_text(snapshot: &MultiBufferSnapshot, row: u32) -> String {
+    let end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+    snapshot.text_for_range(Point::new(row, 0)..end).collect()
+}
+
+fn select_lines(editor: &mut Editor, rows: &RangeInclusive<u32>, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let end = Point::new(*rows.end(), snapshot.line_len(MultiBufferRow(*rows.end())));
+    editor.change_selections(None, cx, |s| {
+        s.select_ranges([Point::new(*rows.start(), 0)..end])
+    });
+}
+
+/// Moves the cursor to the first non-blank character of the row.
+fn move_to_line(editor: &mut Editor, row: u32, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let row = row.min(snapshot.max_buffer_row().0);
+    let indent = snapshot.indent_size_for_line(MultiBufferRow(row));
+    let point = Point::new(row, indent.len);
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_ranges([point..point])
+    });
+}
+
+fn delete_lines(
+    vim: &mut Vim,
+    editor: &mut Editor,
+    rows: RangeInclusive<u32>,
+    register: Option<char>,
+    yank: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let head = editor.selections.newest_anchor().head();
+    editor.set_clip_at_line_ends(false, cx);
+    editor.change_selections(None, cx, |s| {
+        s.select_ranges([line_range(&snapshot, &rows)])
     });
+    vim.update_state(|state| state.selected_register = register);
+    if yank {
+        yank_selections_content(vim, editor, true, cx);
+        editor.change_selections(None, cx, |s| s.select_anchor_ranges([head..head]));
+    } else {
+        copy_selections_content(vim, editor, true, cx);
+        editor.insert("", cx);
+        move_to_line(editor, *rows.start(), cx);
+    }
+    editor.set_clip_at_line_ends(true, cx);
+}
+
+/// Moves or copies the rows to below the 1-based `destination` line.
+fn transfer_lines(
+    editor: &mut Editor,
+    rows: RangeInclusive<u32>,
+    destination: u32,
+    is_move: bool,
+    cx: &mut ViewContext<Editor>,
+) -> Result<()> {
+    let (start, end) = (*rows.start(), *rows.end());
+    let line_count = end - start + 1;
+    if is_move && destination > start && destination <= end {
+        return Err(anyhow!("E134: Cannot move a range of lines into itself"));
+    }
+    if is_move && (destination == start || destination == end + 1) {
+        move_to_line(editor, end, cx);
+        return Ok(());
+    }
+
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let mut text = line_text(&snapshot, start);
+    for row in start + 1..=end {
+        text.push('\n');
+        text.push_str(&line_text(&snapshot, row));
+    }
+    let insertion = if destination > snapshot.max_buffer_row().0 {
+        text.insert(0, '\n');
+        snapshot.max_point()
+    } else {
+        text.push('\n');
+        Point::new(destination, 0)
+    };
+
+    let mut edits = vec![(insertion..insertion, text)];
+    if is_move {
+        edits.push((line_range(&snapshot, &rows), String::new()));
+    }
+    editor.edit(edits, cx);
+
+    let last_row = if is_move && destination > end {
+        destination - 1
+    } else {
+        destination + line_count - 1
+    };
+    move_to_line(editor, last_row, cx);
+    Ok(())
+}
+
+#[derive(Default)]
+struct SubstituteFlags {
+    global: bool,
+    confirm: bool,
+    ignore_case: bool,
+    report_only: bool,
+    no_error: bool,
+}
+
+impl SubstituteFlags {
+    fn parse(flags: &str) -> Result<Self> {
+        let mut result = Self::default();
+        for c in flags.chars() {
+            match c {
+                '&' => {}
+                'g' => result.global = true,
+                'c' => result.confirm = true,
+                'i' => result.ignore_case = true,
+                'I' => result.ignore_case = false,
+                'n' => result.report_only = true,
+                'e' => result.no_error = true,
+                _ => return Err(anyhow!("E488: Trailing characters: {flags}")),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// `&` as the first flag keeps the flags of the last substitution.
+fn expand_previous_flags(flags: &str, last: Option<&SubstituteState>) -> String {
+    match (flags.strip_prefix('&'), last) {
+        (Some(rest), Some(last)) => last.flags.clone() + rest,
+        (Some(rest), None) => rest.to_string(),
+        (None, _) => flags.to_string(),
+    }
+}
+
+fn substitute_lines(
+    vim: &mut Vim,
+    editor: &mut Editor,
+    addresses: &AddressContext,
+    rows: RangeInclusive<u32>,
+    substitute: SubstituteState,
+    cx: &mut ViewContext<Editor>,
+) -> Result<Outcome> {
+    vim.workspace_state.last_substitute = Some(substitute.clone());
+    vim.workspace_state.last_search_pattern = Some(substitute.pattern.clone());
+    let flags = SubstituteFlags::parse(&substitute.flags)?;
+    let replacement = vim_replacement_to_zed(&substitute.replacement);
+
+    if flags.confirm {
+        return Ok(Outcome::ReplaceWithSearchBar(Replacement {
+            search: substitute.pattern,
+            replacement,
+            should_replace_all: false,
+            is_case_sensitive: !flags.ignore_case,
+            range: Some(*rows.start() as usize + 1..*rows.end() as usize + 1),
+        }));
+    }
+
+    let regex = RegexBuilder::new(&substitute.pattern)
+        .case_insensitive(flags.ignore_case)
+        .build()?;
+    let mut match_count = 0;
+    let mut edits = Vec::new();
+    for row in rows {
+        let line = line_text(&addresses.snapshot, row);
+        let count = if flags.global {
+            regex.find_iter(&line).count()
+        } else {
+            regex.is_match(&line) as usize
+        };
+        if count == 0 {
+            continue;
+        }
+        match_count += count;
+        let new_line = if flags.global {
+            regex.replace_all(&line, replacement.as_str())
+        } else {
+            regex.replace(&line, replacement.as_str())
+        };
+        let range = Point::new(row, 0)..Point::new(row, line.len() as u32);
+        edits.push((range, new_line.into_owned()));
+    }
+
+    let Some((last_edit, _)) = edits.last() else {
+        if flags.no_error {
+            return Ok(Outcome::Done);
+        }
+        return Err(anyhow!("E486: Pattern not found: {}", substitute.pattern));
+    };
+    if flags.report_only {
+        return Ok(Outcome::Report(format!(
+            "{match_count} matches on {} lines",
+            edits.len()
+        )));
+    }
+
+    let last_line = addresses.snapshot.anchor_before(last_edit.start);
+    editor.edit(edits, cx);
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    move_to_line(editor, last_line.to_point(&snapshot).row, cx);
+    Ok(Outcome::Done)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn global(
+    vim: &mut Vim,
+    editor: &mut Editor,
+    addresses: &AddressContext,
+    rows: RangeInclusive<u32>,
+    pattern: &str,
+    invert: bool,
+    command: &str,
+    cx: &mut ViewContext<Editor>,
+) -> Result<Outcome> {
+    let pattern = addresses.pattern(pattern)?;
+    let regex = Regex::new(&pattern)?;
+    vim.workspace_state.last_search_pattern = Some(pattern.clone());
+    let is_selected = |snapshot: &MultiBufferSnapshot, row: u32| {
+        regex.is_match(&line_text(snapshot, row)) != invert
+    };
+    let rows = rows
+        .filter(|row| is_selected(&addresses.snapshot, *row))
+        .collect::<Vec<_>>();
+    if rows.is_empty() {
+        return Err(anyhow!("E486: Pattern not found: {pattern}"));
+    }
+
+    // without a command, leave a cursor on each line.
+    if command.is_empty() {
+        let points = rows.into_iter().map(|row| {
+            let indent = addresses.snapshot.indent_size_for_line(MultiBufferRow(row));
+            let point = Point::new(row, indent.len);
+            point..point
+        });
+        editor.change_selections(Some(Autoscroll::fit()), cx, |s| s.select_ranges(points));
+        return Ok(Outcome::Done);
+    }
+
+    let command = parse_ex_command(command)
+        .ok_or_else(|| anyhow!("E492: Not an editor command: {command}"))?;
+    let lines = rows
+        .into_iter()
+        .map(|row| addresses.snapshot.anchor_after(Point::new(row, 0)))
+        .collect::<Vec<_>>();
+    match &command.command {
+        LineCommand::Global { .. } => return Err(anyhow!("E147: Cannot do :global recursive")),
+        LineCommand::Normal { keys } if command.range.is_none() => {
+            return Ok(Outcome::Replay(normal_steps(
+                Some(lines),
+                keys,
+                cx.view().clone(),
+            )));
+        }
+        LineCommand::Normal { .. } => {
+            return Err(anyhow!(":normal with a range is not supported in :global"))
+        }
+        _ => {}
+    }
+
+    for line in lines {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let row = line.to_point(&snapshot).row;
+        // the line may have been removed by an earlier command.
+        if !is_selected(&snapshot, row) {
+            continue;
+        }
+        move_to_line(editor, row, cx);
+        // an empty pattern in the command refers to the pattern of the :g,
+        // unless an earlier command substituted another one.
+        let addresses = AddressContext::new(vim, editor, cx);
+        match run_line_command(vim, editor, &addresses, &command, cx)? {
+            Outcome::ReplaceWithSearchBar(_) => {
+                return Err(anyhow!(":s with the c flag is not supported in :global"))
+            }
+            Outcome::Done | Outcome::Report(_) | Outcome::Replay(_) => {}
+        }
+    }
+    Ok(Outcome::Done)
+}
+
+/// The steps that run `keys` in normal mode, on each of `lines` if given.
+fn normal_steps(lines: Option<Vec<Anchor>>, keys: &str, editor: View<Editor>) -> Vec<ReplayStep> {
+    let keystrokes = literal_keystrokes(keys);
+    let run_keys = || {
+        keystrokes
+            .iter()
+            .cloned()
+            .map(ReplayStep::Keystroke)
+            .chain([ReplayStep::Action(ReplayableAction::Action(
+                EndNormalCommand.boxed_clone(),
+            ))])
+    };
+    let mut steps = vec![ReplayStep::StartUndoGroup];
+    match lines {
+        Some(lines) => {
+            for line in lines {
+                steps.push(ReplayStep::MoveToLine(line));
+                steps.extend(run_keys());
+            }
+        }
+        None => steps.extend(run_keys()),
+    }
+    steps.push(ReplayStep::EndUndoGroup(editor));
+    steps
+}
+
+// we don't attempt to fully convert between the two regex syntaxes,
+// but we do flip \( and \) to ( and ) (and vice-versa), and turn \< and \>
+// into word boundaries so that common idioms work.
+fn vim_pattern_to_zed(pattern: &str) -> String {
+    let mut result = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ ('(' | ')')) => result.push(c),
+                Some('<' | '>') => result.push_str("\\b"),
+                Some(c) => {
+                    result.push('\\');
+                    result.push(c);
+                }
+                None => result.push_str("\\\\"),
+            },
+            '(' | ')' => {
+                result.push('\\');
+                result.push(c);
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Replaces `~` in a replacement with the previous replacement.
+fn expand_previous_replacement(replacement: &str, previous: Option<&str>) -> String {
+    let mut result = String::new();
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                result.push(c);
+                if let Some(c) = chars.next() {
+                    result.push(c);
+                }
+            }
+            '~' => result.push_str(previous.unwrap_or_default()),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+// convert \0..\9 and & to capture groups, and \r, \n and \t to the
+// characters they stand for.
+fn vim_replacement_to_zed(replacement: &str) -> String {
+    let mut result = String::new();
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(digit @ '0'..='9') => {
+                    result.push_str("${");
+                    result.push(digit);
+                    result.push('}');
+                }
+                Some('r' | 'n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('$') => result.push_str("$$"),
+                Some(c) => result.push(c),
+                None => result.push('\\'),
+            },
+            '&' => result.push_str("${0}"),
+            '$' => result.push_str("$$"),
+            _ => result.push(c),
+        }
+    }
+    result
 }
 
 pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandInterceptResult> {
-    // Note: this is a very poor simulation of vim's command palette.
-    // In the future we should adjust it to handle parsing range syntax,
-    // and then calling the appropriate commands with/without ranges.
+    // Note: this is still a partial simulation of vim's command line.
+    // Commands that operate on lines are parsed into an ExCommand (see
+    // parse_ex_command), the rest are matched by name below.
     //
     // We also need to support passing arguments to commands like :w
     // (ideally with filename autocompletion).
-    //
-    // For now, you can only do a replace on the % range, and you can
-    // only use a specific line number range to "go to line"
     while query.starts_with(':') {
         query = &query[1..];
     }
@@ -255,14 +1105,9 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
             ("lNext", editor::actions::GoToPrevDiagnostic.boxed_clone())
         }
 
-        // modify the buffer (should accept [range])
-        "j" | "jo" | "joi" | "join" => ("join", JoinLines.boxed_clone()),
-        "d" | "de" | "del" | "dele" | "delet" | "delete" | "dl" | "dell" | "delel" | "deletl"
-        | "deletel" | "dp" | "dep" | "delp" | "delep" | "deletp" | "deletep" => {
-            ("delete", editor::actions::DeleteLine.boxed_clone())
-        }
-        "sor" | "sor " | "sort" | "sort " => ("sort", SortLinesCaseSensitive.boxed_clone()),
-        "sor i" | "sort i" => ("sort i", SortLinesCaseInsensitive.boxed_clone()),
+        // marks and jumps
+        "marks" => ("marks", ShowMarks.boxed_clone()),
+        "ju" | "jum" | "jump" | "jumps" => ("jumps", ShowJumps.boxed_clone()),
 
         // Explore, etc.
         "E" | "Ex" | "Exp" | "Expl" | "Explo" | "Explor" | "Explore" => (
@@ -315,43 +1160,16 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
             cx.build_action("assistant::ToggleFocus", None).unwrap(),
         ),
 
-        // goto (other ranges handled under _ => )
-        "$" => ("$", EndOfDocument.boxed_clone()),
-        "%" => ("%", EndOfDocument.boxed_clone()),
-        "0" => ("0", StartOfDocument.boxed_clone()),
-
-        _ => {
-            if query.starts_with('/') || query.starts_with('?') {
-                (
-                    query,
-                    FindCommand {
-                        query: query[1..].to_string(),
-                        backwards: query.starts_with('?'),
-                    }
-                    .boxed_clone(),
-                )
-            } else if query.starts_with('%') {
-                (
-                    query,
-                    ReplaceCommand {
-                        query: query.to_string(),
-                    }
-                    .boxed_clone(),
-                )
-            } else if let Ok(line) = query.parse::<u32>() {
-                (query, GoToLine { line }.boxed_clone())
-            } else if range_regex().is_match(query) {
-                (
-                    query,
-                    ReplaceCommand {
-                        query: query.to_string(),
-                    }
-                    .boxed_clone(),
-                )
-            } else {
-                return None;
+        _ => match parse_delete_marks(query) {
+            Some(delete_marks) => (query, delete_marks.boxed_clone()),
+            None => {
+                let command = parse_ex_command(query)?;
+                match find_command(&command) {
+                    Some(find) => (query, find.boxed_clone()),
+                    None => (query, command.boxed_clone()),
+                }
             }
-        }
+        },
     };
 
     let string = ":".to_owned() + name;
@@ -364,6 +1182,331 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
     })
 }
 
+/// A lone `:/pattern` or `:?pattern` searches with the buffer search bar,
+/// so that `n` and `N` repeat it.
+fn find_command(command: &ExCommand) -> Option<FindCommand> {
+    let range = command.range.as_ref()?;
+    if command.command != LineCommand::GoTo || range.end.is_some() || range.start.offset != 0 {
+        return None;
+    }
+    match &range.start.address {
+        Address::SearchForward(query) => Some(FindCommand {
+            query: vim_pattern_to_zed(query),
+            backwards: false,
+        }),
+        Address::SearchBackward(query) => Some(FindCommand {
+            query: vim_pattern_to_zed(query),
+            backwards: true,
+        }),
+        _ => None,
+    }
+}
+
+/// Parses `:delm[arks] {marks}`, where the marks can include ranges like
+/// `a-d`, and `:delm[arks]!`.
+fn parse_delete_marks(query: &str) -> Option<DeleteMarks> {
+    let name_len = query
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(query.len());
+    let (name, rest) = query.split_at(name_len);
+    if !abbreviates(name, 4, "delmarks") {
+        return None;
+    }
+    if let Some(rest) = rest.strip_prefix('!') {
+        return rest.trim().is_empty().then(|| DeleteMarks {
+            marks: String::new(),
+            lowercase: true,
+        });
+    }
+
+    let mut marks = String::new();
+    let mut chars = rest.chars().filter(|c| !c.is_whitespace()).peekable();
+    while let Some(first) = chars.next() {
+        if chars.next_if_eq(&'-').is_some() {
+            let last = chars.next()?;
+            let same_kind = (first.is_ascii_lowercase() && last.is_ascii_lowercase())
+                || (first.is_ascii_uppercase() && last.is_ascii_uppercase())
+                || (first.is_ascii_digit() && last.is_ascii_digit());
+            if !same_kind || first > last {
+                return None;
+            }
+            marks.extend(first..=last);
+        } else if first.is_ascii_alphanumeric() || "\"^.[]<>'`".contains(first) {
+            marks.push(first);
+        } else {
+            return None;
+        }
+    }
+    Some(DeleteMarks {
+        marks,
+        lowercase: false,
+    })
+}
+
+/// Parses a command line such as `'<,'>s/a/b/g` or `g/pattern/d` into a
+/// range and the command to run on it.
+fn parse_ex_command(query: &str) -> Option<ExCommand> {
+    let (range, rest) = parse_range(query);
+    let command = parse_line_command(rest.trim_start())?;
+    if range.is_none() && command == LineCommand::GoTo {
+        return None;
+    }
+    Some(ExCommand { range, command })
+}
+
+fn parse_range(input: &str) -> (Option<CommandRange>, &str) {
+    if let Some(rest) = input.strip_prefix('%') {
+        let range = CommandRange {
+            start: Position {
+                address: Address::Line(1),
+                offset: 0,
+            },
+            end: Some(Position {
+                address: Address::LastLine,
+                offset: 0,
+            }),
+        };
+        return (Some(range), rest);
+    }
+
+    let (start, rest) = match parse_position(input) {
+        Some((start, rest)) => (Some(start), rest),
+        None => (None, input),
+    };
+    let current_line = Position {
+        address: Address::CurrentLine,
+        offset: 0,
+    };
+    // we don't move the cursor between addresses, so `;` behaves like `,`.
+    let Some(rest) = rest.strip_prefix(|c: char| c == ',' || c == ';') else {
+        return (start.map(|start| CommandRange { start, end: None }), rest);
+    };
+    let (end, rest) = parse_position(rest).unwrap_or((current_line.clone(), rest));
+    let range = CommandRange {
+        start: start.unwrap_or(current_line),
+        end: Some(end),
+    };
+    (Some(range), rest)
+}
+
+fn parse_position(input: &str) -> Option<(Position, &str)> {
+    let (address, rest) = match parse_address(input) {
+        Some((address, rest)) => (Some(address), rest),
+        None => (None, input),
+    };
+    let (offset, rest) = parse_offset(rest);
+    if address.is_none() && rest.len() == input.len() {
+        return None;
+    }
+    let address = address.unwrap_or(Address::CurrentLine);
+    Some((Position { address, offset }, rest))
+}
+
+fn parse_address(input: &str) -> Option<(Address, &str)> {
+    let mut chars = input.chars();
+    let address = match chars.next()? {
+        '.' => Address::CurrentLine,
+        '$' => Address::LastLine,
+        '\'' => Address::Mark(chars.next()?),
+        delimiter @ ('/' | '?') => {
+            let (pattern, rest) = split_pattern(chars.as_str(), delimiter);
+            let address = if delimiter == '/' {
+                Address::SearchForward(pattern)
+            } else {
+                Address::SearchBackward(pattern)
+            };
+            return Some((address, rest));
+        }
+        c if c.is_ascii_digit() => {
+            let (digits, rest) = split_digits(input);
+            return Some((Address::Line(digits.parse().ok()?), rest));
+        }
+        _ => return None,
+    };
+    Some((address, chars.as_str()))
+}
+
+fn parse_offset(mut input: &str) -> (i32, &str) {
+    let mut offset = 0;
+    loop {
+        let sign = match input.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => return (offset, input),
+        };
+        let (digits, rest) = split_digits(&input[1..]);
+        offset += sign * digits.parse::<i32>().unwrap_or(1);
+        input = rest;
+    }
+}
+
+fn split_digits(input: &str) -> (&str, &str) {
+    let end = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    input.split_at(end)
+}
+
+/// Splits `input` at the first unescaped `delimiter`, unescaping any escaped
+/// delimiters before it. If there is no delimiter, everything is the pattern.
+fn split_pattern(input: &str, delimiter: char) -> (String, &str) {
+    let mut pattern = String::new();
+    let mut chars = input.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        if c == delimiter {
+            return (pattern, &input[ix + c.len_utf8()..]);
+        }
+        if c == '\\' {
+            match chars.next() {
+                Some((_, c)) if c == delimiter => pattern.push(c),
+                Some((_, c)) => {
+                    pattern.push('\\');
+                    pattern.push(c);
+                }
+                None => pattern.push('\\'),
+            }
+        } else {
+            pattern.push(c);
+        }
+    }
+    (pattern, "")
+}
+
+fn is_pattern_delimiter(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace() && !matches!(c, '\\' | '"' | '|')
+}
+
+/// Whether `name` is an abbreviation of `command` at least `min` characters long.
+fn abbreviates(name: &str, min: usize, command: &str) -> bool {
+    name.len() >= min && command.starts_with(name)
+}
+
+fn parse_line_command(input: &str) -> Option<LineCommand> {
+    if input.is_empty() {
+        return Some(LineCommand::GoTo);
+    }
+    if let Some(flags) = input.strip_prefix('&') {
+        return Some(LineCommand::RepeatSubstitute {
+            flags: flags.trim().to_string(),
+        });
+    }
+    if input.starts_with(|c: char| c == '>' || c == '<') {
+        let shift = input.chars().next()?;
+        let shifts = input.chars().take_while(|c| *c == shift).count();
+        if !input[shifts..].trim().is_empty() {
+            return None;
+        }
+        return Some(if shift == '>' {
+            LineCommand::Indent { shifts }
+        } else {
+            LineCommand::Outdent { shifts }
+        });
+    }
+
+    let name_len = input
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(input.len());
+    let (name, rest) = input.split_at(name_len);
+    let (bang, rest) = match rest.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let args = rest.trim_start();
+
+    // :dl and :dp delete, then list or print the line.
+    let delete_name = name
+        .strip_suffix(|c: char| c == 'l' || c == 'p')
+        .filter(|name| !name.is_empty())
+        .unwrap_or(name);
+    let command = if abbreviates(name, 1, "delete") || abbreviates(delete_name, 1, "delete") {
+        let (register, count) = parse_register_and_count(args.trim_end())?;
+        LineCommand::Delete { register, count }
+    } else if abbreviates(name, 1, "yank") {
+        let (register, count) = parse_register_and_count(args.trim_end())?;
+        LineCommand::Yank { register, count }
+    } else if abbreviates(name, 1, "join") {
+        LineCommand::Join {
+            count: parse_count(args.trim_end())?,
+        }
+    } else if abbreviates(name, 1, "move") {
+        LineCommand::Move {
+            destination: parse_destination(args)?,
+        }
+    } else if name == "t" || abbreviates(name, 2, "copy") {
+        LineCommand::Copy {
+            destination: parse_destination(args)?,
+        }
+    } else if abbreviates(name, 4, "normal") && !args.is_empty() {
+        LineCommand::Normal {
+            keys: args.to_string(),
+        }
+    } else if abbreviates(name, 3, "sort") && !bang {
+        match args.trim_end() {
+            "" => LineCommand::Sort {
+                case_insensitive: false,
+            },
+            "i" => LineCommand::Sort {
+                case_insensitive: true,
+            },
+            _ => return None,
+        }
+    } else if abbreviates(name, 1, "substitute") {
+        parse_substitute(args)
+    } else if abbreviates(name, 1, "global") || abbreviates(name, 1, "vglobal") {
+        let mut chars = args.chars();
+        let delimiter = chars.next().filter(|c| is_pattern_delimiter(*c))?;
+        let (pattern, command) = split_pattern(chars.as_str(), delimiter);
+        LineCommand::Global {
+            pattern,
+            invert: bang || name.starts_with('v'),
+            command: command.trim().to_string(),
+        }
+    } else {
+        return None;
+    };
+    Some(command)
+}
+
+fn parse_register_and_count(args: &str) -> Option<(Option<char>, Option<u32>)> {
+    match args.chars().next() {
+        Some(register) if !register.is_ascii_digit() => {
+            let count = parse_count(args[register.len_utf8()..].trim_start())?;
+            Some((Some(register), count))
+        }
+        _ => Some((None, parse_count(args)?)),
+    }
+}
+
+fn parse_count(args: &str) -> Option<Option<u32>> {
+    if args.is_empty() {
+        return Some(None);
+    }
+    args.parse().ok().filter(|count| *count > 0).map(Some)
+}
+
+fn parse_destination(args: &str) -> Option<Position> {
+    let (destination, rest) = parse_position(args.trim_end())?;
+    rest.is_empty().then_some(destination)
+}
+
+fn parse_substitute(args: &str) -> LineCommand {
+    let mut chars = args.chars();
+    match chars.next() {
+        Some(delimiter) if is_pattern_delimiter(delimiter) && delimiter != '&' => {
+            let (pattern, rest) = split_pattern(chars.as_str(), delimiter);
+            let (replacement, flags) = split_pattern(rest, delimiter);
+            LineCommand::Substitute(Substitution {
+                pattern,
+                replacement,
+                flags: flags.trim().to_string(),
+            })
+        }
+        _ => LineCommand::RepeatSubstitute {
+            flags: args.trim().to_string(),
+        },
+    }
+}
+
 fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     let mut positions = Vec::new();
     let mut chars = query.chars();
@@ -390,9 +1533,14 @@ fn generate_positions(string: &str, query: &str) -> Vec<usize> {
 mod test {
     use std::path::Path;
 
-    use crate::test::{NeovimBackedTestContext, VimTestContext};
+    use super::{parse_ex_command, Address, CommandRange, ExCommand, LineCommand, Position};
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
     use gpui::TestAppContext;
     use indoc::indoc;
+    use search::{BufferSearchBar, SearchOptions};
 
     #[gpui::test]
     async fn test_command_basics(cx: &mut TestAppContext) {
@@ -516,4 +1664,387 @@ mod test {
         cx.simulate_keystrokes(": q a enter");
         cx.workspace(|workspace, cx| assert_eq!(workspace.items(cx).count(), 0));
     }
+
+    #[gpui::test]
+    async fn test_command_normal(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two", Mode::Normal);
+        cx.simulate_keystrokes(": n o r m space shift-a ! enter");
+        cx.assert_state("one twoˇ!", Mode::Normal);
+
+        cx.simulate_keystrokes("q a 0 x q");
+        cx.simulate_keystrokes(": n o r m a l space @ a enter");
+        cx.assert_state("ˇe two!", Mode::Normal);
+    }
+
+    #[test]
+    fn test_parse_ex_command() {
+        fn position(address: Address, offset: i32) -> Position {
+            Position { address, offset }
+        }
+
+        assert_eq!(
+            parse_ex_command("5,20m 30"),
+            Some(ExCommand {
+                range: Some(CommandRange {
+                    start: position(Address::Line(5), 0),
+                    end: Some(position(Address::Line(20), 0)),
+                }),
+                command: LineCommand::Move {
+                    destination: position(Address::Line(30), 0)
+                },
+            })
+        );
+        assert_eq!(
+            parse_ex_command("t."),
+            Some(ExCommand {
+                range: None,
+                command: LineCommand::Copy {
+                    destination: position(Address::CurrentLine, 0)
+                },
+            })
+        );
+        assert_eq!(
+            parse_ex_command("/foo/+1,'a-2d x 3"),
+            Some(ExCommand {
+                range: Some(CommandRange {
+                    start: position(Address::SearchForward("foo".into()), 1),
+                    end: Some(position(Address::Mark('a'), -2)),
+                }),
+                command: LineCommand::Delete {
+                    register: Some('x'),
+                    count: Some(3)
+                },
+            })
+        );
+        assert_eq!(
+            parse_ex_command("g!/a\\/b/s/x/y/g").map(|command| command.command),
+            Some(LineCommand::Global {
+                pattern: "a/b".into(),
+                invert: true,
+                command: "s/x/y/g".into()
+            })
+        );
+        assert_eq!(
+            parse_ex_command(".,$s//~/gc").map(|command| command.command),
+            Some(LineCommand::Substitute(super::Substitution {
+                pattern: "".into(),
+                replacement: "~".into(),
+                flags: "gc".into()
+            }))
+        );
+        assert_eq!(
+            parse_ex_command(">>").map(|command| command.command),
+            Some(LineCommand::Indent { shifts: 2 })
+        );
+        assert_eq!(
+            parse_ex_command("&&").map(|command| command.command),
+            Some(LineCommand::RepeatSubstitute { flags: "&".into() })
+        );
+        for query in ["", "e", "ma", "norm", "d 0", "m", "sort x"] {
+            assert_eq!(parse_ex_command(query), None, "{query}");
+        }
+    }
+
+    #[test]
+    fn test_parse_delete_marks() {
+        fn delete_marks(marks: &str) -> Option<DeleteMarks> {
+            Some(DeleteMarks {
+                marks: marks.into(),
+                lowercase: false,
+            })
+        }
+
+        assert_eq!(parse_delete_marks("delm a-c F"), delete_marks("abcF"));
+        assert_eq!(parse_delete_marks("delmarks 0-2<"), delete_marks("012<"));
+        assert_eq!(parse_delete_marks("delm"), delete_marks(""));
+        assert_eq!(
+            parse_delete_marks("delm!"),
+            Some(DeleteMarks {
+                marks: String::new(),
+                lowercase: true,
+            })
+        );
+        assert_eq!(parse_delete_marks("delm a-C"), None);
+        assert_eq!(parse_delete_marks("delm! a"), None);
+        assert_eq!(parse_delete_marks("del a"), None);
+    }
+
+    #[gpui::test]
+    async fn test_command_ranges(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            b
+            c
+            d"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": 2 , 3 d enter");
+        cx.assert_state(
+            indoc! {"
+            a
+            ˇd"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("p");
+        cx.assert_state(
+            indoc! {"
+            a
+            d
+            ˇb
+            c"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(": $ m 0 enter");
+        cx.assert_state(
+            indoc! {"
+            ˇc
+            a
+            d
+            b"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": t $ enter");
+        cx.assert_state(
+            indoc! {"
+            c
+            a
+            d
+            b
+            ˇc"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": 2 , . - 2 m $ enter");
+        cx.assert_state(
+            indoc! {"
+            c
+            b
+            c
+            a
+            ˇd"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("g g shift-v j : d enter");
+        cx.assert_state(
+            indoc! {"
+            ˇc
+            a
+            d"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 : > enter");
+        cx.assert_state(
+            indoc! {"
+                c
+                ˇa
+            d"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_global(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa1
+            b
+            a2
+            c"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": g / a / d enter");
+        cx.assert_state(
+            indoc! {"
+            b
+            ˇc"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": v / b / s / c / x enter");
+        cx.assert_state(
+            indoc! {"
+            b
+            ˇx"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": g / . / m 0 enter");
+        cx.assert_state(
+            indoc! {"
+            ˇx
+            b"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": g / [ a b ] / enter");
+        cx.assert_state(
+            indoc! {"
+            x
+            ˇb"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_substitute(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa a
+            a a"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": s / a / b enter");
+        cx.assert_state(
+            indoc! {"
+            ˇb a
+            a a"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": % s / a / ~ c / g enter");
+        cx.assert_state(
+            indoc! {"
+            b bc
+            ˇbc bc"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": 1 s / \\ < b c \\ > / ( & ) enter");
+        cx.assert_state(
+            indoc! {"
+            ˇb (bc)
+            bc bc"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("j : & & enter");
+        cx.assert_state(
+            indoc! {"
+            b (bc)
+            ˇ(bc) bc"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_substitute_last_literal_search(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇaxb a.b", Mode::Normal);
+        cx.simulate_keystrokes("/");
+        let search_bar = cx.workspace(|workspace, cx| {
+            workspace
+                .active_pane()
+                .read(cx)
+                .toolbar()
+                .read(cx)
+                .item_of_type::<BufferSearchBar>()
+                .expect("Buffer search bar should be deployed")
+        });
+        cx.update_view(search_bar, |bar, cx| {
+            bar.search("a.b", Some(SearchOptions::NONE), cx)
+        })
+        .await
+        .unwrap();
+        cx.simulate_keystrokes("enter");
+
+        // without the regex option, the last search matches its query literally.
+        cx.simulate_keystrokes(": s / / X / g enter");
+        assert_eq!(cx.buffer_text(), "axb X");
+    }
+
+    #[gpui::test]
+    async fn test_command_substitute_last_pattern(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇfoo bar foo bar", Mode::Normal);
+        cx.simulate_keystrokes("/ f o o enter");
+        cx.simulate_keystrokes(": s / b a r / b a z / enter");
+        assert_eq!(cx.buffer_text(), "foo baz foo bar");
+
+        // an empty pattern refers to the last substitution, not the earlier search.
+        cx.simulate_keystrokes(": s / / q u x / enter");
+        assert_eq!(cx.buffer_text(), "foo baz foo qux");
+
+        cx.simulate_keystrokes("/ b a z enter");
+        cx.simulate_keystrokes(": s / / x / enter");
+        assert_eq!(cx.buffer_text(), "foo x foo qux");
+    }
+
+    #[gpui::test]
+    async fn test_command_substitute_preview(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa a
+            a a"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": s / a / b");
+        cx.update_editor(|editor, cx| assert_eq!(editor.replacement_previews(cx), ["b"]));
+        cx.simulate_keystrokes("/ g");
+        cx.update_editor(|editor, cx| assert_eq!(editor.replacement_previews(cx), ["b", "b"]));
+        cx.simulate_keystrokes("backspace backspace backspace c");
+        cx.update_editor(|editor, cx| assert_eq!(editor.replacement_previews(cx), ["c"]));
+        cx.simulate_keystrokes("escape");
+        cx.update_editor(|editor, cx| assert!(editor.replacement_previews(cx).is_empty()));
+        cx.assert_state(
+            indoc! {"
+            ˇa a
+            a a"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(": % s / a / & & / g");
+        cx.update_editor(|editor, cx| {
+            assert_eq!(editor.replacement_previews(cx), ["aa", "aa", "aa", "aa"])
+        });
+        cx.simulate_keystrokes("enter");
+        cx.update_editor(|editor, cx| assert!(editor.replacement_previews(cx).is_empty()));
+        cx.assert_state(
+            indoc! {"
+            aa aa
+            ˇaa aa"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_normal_range(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            b
+            c"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": % n o r m space A ; enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+            a;
+            b;
+            cˇ;"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("u");
+        assert_eq!(cx.buffer_text(), "a\nb\nc");
+
+        cx.simulate_keystrokes(": g / b / n o r m space x enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+            a
+            ˇ
+            c"},
+            Mode::Normal,
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{insert::NormalBefore, Vim, VimModeSetting};
+use crate::{insert::NormalBefore, normal, Vim, VimModeSetting};
 use editor::{Editor, EditorEvent};
 use gpui::{Action, AppContext, Entity, EntityId, UpdateGlobal, View, ViewContext, WindowContext};
 use settings::{Settings, SettingsStore};
@@ -52,6 +52,7 @@ fn blurred(editor: View<Editor>, cx: &mut WindowContext) {
                 vim.clear_operator(cx);
             }
         }
+        normal::mark::leave_file(vim, &editor, cx);
         editor.update(cx, |editor, cx| {
             if editor.use_modal_editing() {
                 editor.set_cursor_shape(language::CursorShape::Hollow, cx);
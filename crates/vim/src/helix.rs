@@ -0,0 +1,500 @@
+//! A Helix-style mode, where motions select text and operators act on the
+//! selections.
+
+use std::ops::Range;
+
+use editor::{
+    display_map::DisplaySnapshot, movement, scroll::Autoscroll, Anchor, DisplayPoint, Editor,
+    ToOffset,
+};
+use gpui::{actions, ViewContext, WindowContext};
+use language::{char_kind, CharKind, Point, Selection, SelectionGoal};
+use regex::RegexBuilder;
+use search::{buffer_search, BufferSearchBar, SearchOptions};
+use workspace::{searchable::Direction, Workspace};
+
+use crate::{
+    motion::{coerce_punctuation, Motion},
+    normal::{paste, yank::yank_selections_content},
+    state::{Mode, SearchState, SelectionSearch},
+    Vim,
+};
+
+actions!(
+    vim,
+    [
+        HelixInsert,
+        HelixAppend,
+        HelixYank,
+        HelixPaste,
+        HelixPasteBefore,
+        HelixSelectLine,
+        HelixCollapseSelection,
+        HelixKeepPrimarySelection,
+        HelixSelectRegex,
+        HelixSplitSelection,
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_: &mut Workspace, _: &HelixInsert, cx| {
+        insert(false, cx);
+    });
+    workspace.register_action(|_: &mut Workspace, _: &HelixAppend, cx| {
+        insert(true, cx);
+    });
+    workspace.register_action(|_: &mut Workspace, _: &HelixYank, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.update_active_editor(cx, |vim, editor, cx| {
+                yank_selections_content(vim, editor, false, cx);
+            });
+        });
+    });
+    workspace.register_action(|workspace: &mut Workspace, _: &HelixPaste, cx| {
+        paste_at_selections(workspace, false, cx);
+    });
+    workspace.register_action(|workspace: &mut Workspace, _: &HelixPasteBefore, cx| {
+        paste_at_selections(workspace, true, cx);
+    });
+    workspace.register_action(|_: &mut Workspace, _: &HelixSelectLine, cx| {
+        Vim::update(cx, |vim, cx| {
+            let times = vim.take_count(cx).unwrap_or(1) as u32;
+            vim.update_active_editor(cx, |_, editor, cx| select_lines(editor, times, cx));
+        });
+    });
+    workspace.register_action(|_: &mut Workspace, _: &HelixCollapseSelection, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.update_active_editor(cx, |_, editor, cx| {
+                editor.change_selections(None, cx, |s| {
+                    s.move_with(|map, selection| {
+                        let cursor = cursor_position(map, selection);
+                        selection.collapse_to(cursor, selection.goal);
+                        select_cursor_char(map, selection);
+                    });
+                });
+            });
+        });
+    });
+    workspace.register_action(|_: &mut Workspace, _: &HelixKeepPrimarySelection, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.update_active_editor(cx, |_, editor, cx| {
+                editor.change_selections(None, cx, |s| {
+                    let newest = s.newest_anchor().clone();
+                    s.select_anchors(vec![newest]);
+                });
+            });
+        });
+    });
+    workspace.register_action(|workspace: &mut Workspace, _: &HelixSelectRegex, cx| {
+        search_within_selections(workspace, SelectionSearch::Select, cx);
+    });
+    workspace.register_action(|workspace: &mut Workspace, _: &HelixSplitSelection, cx| {
+        search_within_selections(workspace, SelectionSearch::Split, cx);
+    });
+}
+
+/// The position of the cursor within a selection: the last selected
+/// character, or the first when the selection is reversed.
+fn cursor_position(map: &DisplaySnapshot, selection: &Selection<DisplayPoint>) -> DisplayPoint {
+    if selection.is_empty() || selection.reversed {
+        selection.head()
+    } else {
+        movement::left(map, selection.head())
+    }
+}
+
+/// Selects the character under an empty selection, as Helix shows its
+/// cursor as a one-character selection.
+pub(crate) fn select_cursor_char(map: &DisplaySnapshot, selection: &mut Selection<DisplayPoint>) {
+    if selection.is_empty() {
+        selection.end = movement::right(map, selection.start);
+        selection.reversed = false;
+    }
+}
+
+fn char_kind_at(map: &DisplaySnapshot, point: DisplayPoint, ignore_punctuation: bool) -> CharKind {
+    let point = point.to_point(map);
+    let scope = map.buffer_snapshot.language_scope_at(point);
+    let c = map.buffer_snapshot.chars_at(point).next().unwrap_or('\n');
+    coerce_punctuation(char_kind(&scope, c), ignore_punctuation)
+}
+
+/// Moves the selections with a motion. Word motions and finds select the
+/// text moved over, other motions move the cursor.
+pub(crate) fn helix_motion(motion: Motion, times: Option<usize>, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.move_with(|map, selection| {
+                    let cursor = cursor_position(map, selection);
+                    let goal = selection.goal;
+                    let move_point =
+                        |point| motion.move_point(map, point, goal, times, &text_layout_details);
+                    match &motion {
+                        Motion::NextWordStart { ignore_punctuation }
+                        | Motion::NextWordEnd { ignore_punctuation } => {
+                            // a cursor at the end of a word selects the next one.
+                            let next = movement::right(map, cursor);
+                            let start = if char_kind_at(map, cursor, *ignore_punctuation)
+                                != char_kind_at(map, next, *ignore_punctuation)
+                            {
+                                next
+                            } else {
+                                cursor
+                            };
+                            let Some((mut end, _)) = move_point(start) else {
+                                return;
+                            };
+                            if matches!(motion, Motion::NextWordEnd { .. }) {
+                                end = movement::right(map, end);
+                            }
+                            if end > start {
+                                selection.start = start;
+                                selection.end = end;
+                                selection.reversed = false;
+                                selection.goal = SelectionGoal::None;
+                            }
+                        }
+                        Motion::PreviousWordStart { ignore_punctuation } => {
+                            // a cursor at the start of a word selects the previous one.
+                            let previous = movement::left(map, cursor);
+                            let end = if previous != cursor
+                                && char_kind_at(map, cursor, *ignore_punctuation)
+                                    != char_kind_at(map, previous, *ignore_punctuation)
+                            {
+                                previous
+                            } else {
+                                cursor
+                            };
+                            let Some((start, _)) = move_point(end) else {
+                                return;
+                            };
+                            if start <= end {
+                                selection.start = start;
+                                selection.end = movement::right(map, end);
+                                selection.reversed = true;
+                                selection.goal = SelectionGoal::None;
+                            }
+                        }
+                        Motion::FindForward { .. } => {
+                            if let Some((end, _)) = move_point(cursor) {
+                                selection.start = cursor;
+                                selection.end = movement::right(map, end);
+                                selection.reversed = false;
+                                selection.goal = SelectionGoal::None;
+                            }
+                        }
+                        Motion::FindBackward { .. } => {
+                            if let Some((start, _)) = move_point(cursor) {
+                                selection.start = start;
+                                selection.end = movement::right(map, cursor);
+                                selection.reversed = true;
+                                selection.goal = SelectionGoal::None;
+                            }
+                        }
+                        _ => {
+                            if let Some((point, goal)) = move_point(cursor) {
+                                selection.collapse_to(point, goal);
+                                select_cursor_char(map, selection);
+                            }
+                        }
+                    }
+                });
+            });
+        });
+    });
+}
+
+/// Collapses the selections to their start, or to their end when
+/// appending, and starts inserting.
+fn insert(append: bool, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, cx| {
+        vim.start_recording(cx);
+        vim.update_active_editor(cx, |_, editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.move_with(|_, selection| {
+                    let point = if append {
+                        selection.end
+                    } else {
+                        selection.start
+                    };
+                    selection.collapse_to(point, SelectionGoal::None);
+                });
+            });
+        });
+        vim.switch_mode(Mode::Insert, false, cx);
+    });
+}
+
+/// Pastes after the selections, or before them.
+fn paste_at_selections(workspace: &mut Workspace, before: bool, cx: &mut ViewContext<Workspace>) {
+    Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |_, editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.move_with(|map, selection| {
+                    // Vim pastes after the character under the cursor.
+                    let point = if before || selection.is_empty() {
+                        selection.start
+                    } else {
+                        movement::left(map, selection.end)
+                    };
+                    selection.collapse_to(point, SelectionGoal::None);
+                });
+            });
+        });
+    });
+    paste::paste(
+        workspace,
+        &paste::Paste {
+            before,
+            preserve_clipboard: false,
+        },
+        cx,
+    );
+}
+
+/// Selects the lines of each selection, or the next lines when whole lines
+/// are already selected.
+fn select_lines(editor: &mut Editor, times: u32, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let max_row = snapshot.max_buffer_row().0;
+    let line_end = |row: u32| {
+        if row < max_row {
+            Point::new(row + 1, 0)
+        } else {
+            snapshot.max_point()
+        }
+    };
+    let selections = editor
+        .selections
+        .all::<Point>(cx)
+        .into_iter()
+        .map(|mut selection| {
+            let start = Point::new(selection.start.row, 0);
+            let mut end_row = selection.end.row;
+            // a selection ending at the start of a line doesn't include it.
+            if selection.end.column == 0 && end_row > selection.start.row {
+                end_row -= 1;
+            }
+            let is_whole_lines = selection.start == start
+                && selection.end == line_end(end_row)
+                && !selection.is_empty();
+            end_row += if is_whole_lines { times } else { times - 1 };
+            selection.start = start;
+            selection.end = line_end(end_row.min(max_row));
+            selection.reversed = false;
+            selection.goal = SelectionGoal::None;
+            selection
+        })
+        .collect::<Vec<_>>();
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+}
+
+/// Opens the search bar to search within the selections. Submitting the
+/// search selects the matches, or splits the selections on them.
+fn search_within_selections(
+    workspace: &mut Workspace,
+    search: SelectionSearch,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let pane = workspace.active_pane().clone();
+    Vim::update(cx, |vim, cx| {
+        let prior_selections = vim.editor_selections(cx);
+        pane.update(cx, |pane, cx| {
+            let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() else {
+                return;
+            };
+            search_bar.update(cx, |search_bar, cx| {
+                let deploy = buffer_search::Deploy {
+                    focus: true,
+                    replace_enabled: false,
+                    selection_search_enabled: true,
+                };
+                if !search_bar.deploy(&deploy, cx) {
+                    return;
+                }
+                search_bar.set_replacement(None, cx);
+                search_bar.set_search_options(SearchOptions::REGEX, cx);
+                vim.workspace_state.search = SearchState {
+                    direction: Direction::Next,
+                    count: 1,
+                    initial_query: search_bar.query(cx),
+                    prior_selections,
+                    prior_operator: None,
+                    prior_mode: vim.state().mode,
+                    within_selections: Some(search),
+                };
+            });
+        });
+    });
+}
+
+/// Selects the matches of `query` within `ranges`, or the text between them
+/// when splitting. The selections are left alone if nothing matches.
+pub(crate) fn select_within_selections(
+    vim: &mut Vim,
+    search: SelectionSearch,
+    query: &str,
+    ranges: Vec<Range<Anchor>>,
+    cx: &mut WindowContext,
+) {
+    // like Helix, the search ignores case unless the query has capitals.
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let Ok(regex) = RegexBuilder::new(query)
+        .case_insensitive(ignore_case)
+        .build()
+    else {
+        return;
+    };
+    vim.update_active_editor(cx, |_, editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let mut new_ranges = Vec::new();
+        for range in ranges {
+            let (start, end) = (
+                range.start.to_offset(&snapshot),
+                range.end.to_offset(&snapshot),
+            );
+            let range = start.min(end)..start.max(end);
+            let text = snapshot.text_for_range(range.clone()).collect::<String>();
+            match search {
+                SelectionSearch::Select => {
+                    new_ranges.extend(
+                        regex
+                            .find_iter(&text)
+                            .filter(|found| !found.is_empty())
+                            .map(|found| range.start + found.start()..range.start + found.end()),
+                    );
+                }
+                SelectionSearch::Split => {
+                    let mut start = 0;
+                    for found in regex.find_iter(&text) {
+                        if found.start() > start {
+                            new_ranges.push(range.start + start..range.start + found.start());
+                        }
+                        start = found.end();
+                    }
+                    if text.len() > start {
+                        new_ranges.push(range.start + start..range.end);
+                    }
+                }
+            }
+        }
+        if !new_ranges.is_empty() {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| s.select_ranges(new_ranges));
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use settings::SettingsStore;
+
+    use crate::{state::Mode, test::VimTestContext, VimSettings};
+
+    async fn helix_test_context(cx: &mut gpui::TestAppContext) -> VimTestContext {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| s.helix_mode = Some(true));
+        });
+        cx
+    }
+
+    #[gpui::test]
+    async fn test_helix_word_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = helix_test_context(cx).await;
+
+        cx.set_state("ˇfoo bar baz", Mode::HelixNormal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("«foo ˇ»bar baz", Mode::HelixNormal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("foo «bar ˇ»baz", Mode::HelixNormal);
+        cx.simulate_keystrokes("e");
+        cx.assert_state("foo bar «bazˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("b");
+        cx.assert_state("foo bar «ˇbaz»", Mode::HelixNormal);
+        cx.simulate_keystrokes("b");
+        cx.assert_state("foo «ˇbar »baz", Mode::HelixNormal);
+        cx.simulate_keystrokes("h");
+        cx.assert_state("foo« ˇ»bar baz", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_helix_operators(cx: &mut gpui::TestAppContext) {
+        let mut cx = helix_test_context(cx).await;
+
+        cx.set_state("ˇfoo bar baz", Mode::HelixNormal);
+        cx.simulate_keystrokes("w d");
+        cx.assert_state("«bˇ»ar baz", Mode::HelixNormal);
+        cx.simulate_keystrokes("w c q u x space escape");
+        cx.assert_state("qux« ˇ»baz", Mode::HelixNormal);
+        cx.simulate_keystrokes("e y");
+        cx.assert_state("qux «bazˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("; p");
+        cx.assert_state("qux bazba«zˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("a !");
+        cx.assert_state("qux bazbaz!ˇ", Mode::Insert);
+    }
+
+    #[gpui::test]
+    async fn test_helix_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = helix_test_context(cx).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa,b,c
+            d,e"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("x");
+        cx.assert_state(
+            indoc! {"
+            «a,b,c
+            ˇ»d,e"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("shift-s , enter");
+        cx.assert_state(
+            indoc! {"
+            «aˇ»,«bˇ»,«c
+            ˇ»d,e"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("alt-;");
+        cx.assert_state(
+            indoc! {"
+            «ˇa»,«ˇb»,«ˇc
+            »d,e"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes(",");
+        cx.assert_state(
+            indoc! {"
+            a,b,«ˇc
+            »d,e"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("% s [ a - z ] enter");
+        cx.assert_state(
+            indoc! {"
+            «aˇ»,«bˇ»,«cˇ»
+            «dˇ»,«eˇ»"},
+            Mode::HelixNormal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            ˇab
+            cd"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("shift-c");
+        cx.assert_state(
+            indoc! {"
+            «aˇ»b
+            «cˇ»d"},
+            Mode::HelixNormal,
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use std::sync::Arc;
+
+use editor::{scroll::Autoscroll, Anchor, Editor, MultiBufferSnapshot, ToPoint};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, impl_actions, AppContext, DismissEvent, EntityId, EventEmitter, FocusHandle,
+    FocusableView, ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+    WindowContext,
+};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+use picker::{Picker, PickerDelegate};
+use serde_derive::Deserialize;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    normal::mark::{delete_marks, open_file_mark, record_jump, workspace_marks},
+    state::FileMark,
+    Vim,
+};
+
+actions!(vim, [ShowMarks, ShowJumps]);
+
+/// Deletes the marks listed in `marks`, which can include ranges like `a-d`.
+/// With `lowercase`, deletes every lowercase mark of the active editor
+/// instead, and with neither opens a picker to choose the marks to delete.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DeleteMarks {
+    pub marks: String,
+    pub lowercase: bool,
+}
+
+impl_actions!(vim, [DeleteMarks]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, _: &ShowMarks, cx| {
+        MarksPicker::toggle(workspace, MarksPickerMode::Marks, cx)
+    });
+    workspace.register_action(|workspace, _: &ShowJumps, cx| {
+        MarksPicker::toggle(workspace, MarksPickerMode::Jumps, cx)
+    });
+    workspace.register_action(|workspace, action: &DeleteMarks, cx| {
+        if action.lowercase {
+            Vim::update(cx, |vim, _| {
+                vim.update_state(|state| {
+                    state
+                        .marks
+                        .retain(|name, _| !name.chars().all(|c| c.is_ascii_lowercase()))
+                })
+            })
+        } else if action.marks.is_empty() {
+            MarksPicker::toggle(workspace, MarksPickerMode::Delete, cx)
+        } else {
+            Vim::update(cx, |vim, cx| delete_marks(vim, &action.marks, cx))
+        }
+    });
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MarksPickerMode {
+    /// `:marks`, which jumps to the chosen mark.
+    Marks,
+    /// `:jumps`, which jumps to the chosen position.
+    Jumps,
+    /// `:delmarks`, which deletes the chosen marks.
+    Delete,
+}
+
+pub struct MarksPicker {
+    picker: View<Picker<MarksPickerDelegate>>,
+}
+
+impl MarksPicker {
+    fn toggle(workspace: &mut Workspace, mode: MarksPickerMode, cx: &mut ViewContext<Workspace>) {
+        let workspace_handle = cx.view().clone();
+        let entries = Vim::update(cx, |vim, cx| {
+            mark_entries(vim, workspace_handle.entity_id(), mode, cx)
+        });
+        let workspace_handle = workspace_handle.downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let delegate =
+                MarksPickerDelegate::new(cx.view().downgrade(), workspace_handle, mode, entries);
+            let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+            Self { picker }
+        });
+    }
+}
+
+impl ModalView for MarksPicker {}
+
+impl EventEmitter<DismissEvent> for MarksPicker {}
+
+impl FocusableView for MarksPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for MarksPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+#[derive(Clone)]
+enum MarkLocation {
+    /// A lowercase or special mark, which is local to the active editor.
+    Editor(WeakView<Editor>, Anchor),
+    /// A global mark or a jump list entry.
+    File(FileMark),
+}
+
+struct MarkEntry {
+    /// The name of the mark, or how many jumps back the position is.
+    name: String,
+    location: MarkLocation,
+    point: Point,
+    /// The text of the line for marks in the active editor, and the path of
+    /// the file for the others.
+    detail: String,
+}
+
+impl MarkEntry {
+    fn label(&self) -> String {
+        format!("{} {}", self.name, self.detail)
+    }
+}
+
+fn mark_entries(
+    vim: &mut Vim,
+    workspace: EntityId,
+    mode: MarksPickerMode,
+    cx: &mut WindowContext,
+) -> Vec<MarkEntry> {
+    let mut entries = Vec::new();
+    if mode != MarksPickerMode::Jumps {
+        if let Some(editor) = vim
+            .active_editor
+            .clone()
+            .and_then(|editor| editor.upgrade())
+        {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            for (name, anchors) in &vim.state().marks {
+                let Some(anchor) = anchors.first() else {
+                    continue;
+                };
+                let point = anchor.to_point(&snapshot);
+                entries.push(MarkEntry {
+                    name: name.clone(),
+                    location: MarkLocation::Editor(editor.downgrade(), *anchor),
+                    point,
+                    detail: line_text(&snapshot, point.row),
+                });
+            }
+        }
+    }
+
+    let Some(marks) = workspace_marks(vim, workspace, cx) else {
+        return entries;
+    };
+    if mode == MarksPickerMode::Jumps {
+        for (ix, jump) in marks.jumps.iter().rev().enumerate() {
+            entries.push(MarkEntry {
+                name: (ix + 1).to_string(),
+                location: MarkLocation::File(jump.clone()),
+                point: jump.point(cx),
+                detail: jump.path.path.to_string_lossy().into_owned(),
+            });
+        }
+        return entries;
+    }
+    for (name, mark) in &marks.marks {
+        entries.push(MarkEntry {
+            name: name.to_string(),
+            location: MarkLocation::File(mark.clone()),
+            point: mark.point(cx),
+            detail: mark.path.path.to_string_lossy().into_owned(),
+        });
+    }
+
+    // List marks in the same order as Vim: lowercase, uppercase, numbered,
+    // then the rest.
+    entries.sort_by_key(|entry| {
+        let first = entry.name.chars().next().unwrap_or_default();
+        let group = if first.is_ascii_lowercase() {
+            0
+        } else if first.is_ascii_uppercase() {
+            1
+        } else if first.is_ascii_digit() {
+            2
+        } else {
+            3
+        };
+        (group, entry.name.clone())
+    });
+    entries
+}
+
+fn line_text(snapshot: &MultiBufferSnapshot, row: u32) -> String {
+    let end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+    snapshot
+        .text_for_range(Point::new(row, 0)..end)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+pub struct MarksPickerDelegate {
+    view: WeakView<MarksPicker>,
+    workspace: WeakView<Workspace>,
+    mode: MarksPickerMode,
+    entries: Vec<MarkEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl MarksPickerDelegate {
+    fn new(
+        view: WeakView<MarksPicker>,
+        workspace: WeakView<Workspace>,
+        mode: MarksPickerMode,
+        entries: Vec<MarkEntry>,
+    ) -> Self {
+        Self {
+            view,
+            workspace,
+            mode,
+            entries,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn jump_to(&self, entry: &MarkEntry, cx: &mut WindowContext) {
+        match entry.location.clone() {
+            MarkLocation::Editor(editor, anchor) => {
+                let Some(editor) = editor.upgrade() else {
+                    return;
+                };
+                Vim::update(cx, |vim, cx| record_jump(vim, cx));
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_anchor_ranges([anchor..anchor])
+                    })
+                });
+            }
+            MarkLocation::File(mark) => {
+                let Some(workspace) = self.workspace.upgrade() else {
+                    return;
+                };
+                Vim::update(cx, |vim, cx| record_jump(vim, cx));
+                open_file_mark(workspace, mark, false, cx);
+            }
+        }
+    }
+}
+
+impl PickerDelegate for MarksPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match self.mode {
+            MarksPickerMode::Marks => "Jump to mark...".into(),
+            MarksPickerMode::Jumps => "Jump to position...".into(),
+            MarksPickerMode::Delete => "Delete mark...".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background_executor = cx.background_executor().clone();
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| {
+                let label = entry.label();
+                StringMatchCandidate {
+                    id,
+                    char_bag: label.as_str().into(),
+                    string: label,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background_executor,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, _| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = this
+                    .delegate
+                    .selected_index
+                    .min(this.delegate.matches.len().saturating_sub(1));
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(candidate_id) = self
+            .matches
+            .get(self.selected_index)
+            .map(|mat| mat.candidate_id)
+        else {
+            self.dismissed(cx);
+            return;
+        };
+
+        if self.mode == MarksPickerMode::Delete {
+            let entry = self.entries.remove(candidate_id);
+            Vim::update(cx, |vim, cx| delete_marks(vim, &entry.name, cx));
+            self.matches.remove(self.selected_index);
+            for mat in &mut self.matches {
+                if mat.candidate_id > candidate_id {
+                    mat.candidate_id -= 1;
+                }
+            }
+            self.selected_index = self
+                .selected_index
+                .min(self.matches.len().saturating_sub(1));
+            cx.notify();
+            return;
+        }
+
+        self.dismissed(cx);
+        let entry = &self.entries[candidate_id];
+        self.jump_to(entry, cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(
+                    Label::new(format!(
+                        "{}:{}",
+                        entry.point.row + 1,
+                        entry.point.column + 1
+                    ))
+                    .color(Color::Muted),
+                ),
+        )
+    }
+}
@@ -8,6 +8,7 @@ use crate::{state::Mode, Vim};
 pub struct ModeIndicator {
     pub(crate) mode: Option<Mode>,
     pub(crate) operators: String,
+    recording_register: Option<char>,
     pending_keys: Option<String>,
     _subscriptions: Vec<Subscription>,
 }
@@ -26,6 +27,7 @@ impl ModeIndicator {
         let mut this = Self {
             mode: None,
             operators: "".to_string(),
+            recording_register: None,
             pending_keys: None,
             _subscriptions,
         };
@@ -37,8 +39,10 @@ impl ModeIndicator {
         if let Some(vim) = self.vim(cx) {
             self.mode = Some(vim.state().mode);
             self.operators = self.current_operators_description(&vim);
+            self.recording_register = vim.workspace_state.recording_register;
         } else {
             self.mode = None;
+            self.recording_register = None;
         }
     }
 
@@ -85,8 +89,12 @@ impl Render for ModeIndicator {
         };
 
         let pending = self.pending_keys.as_ref().unwrap_or(&self.operators);
+        let recording = self
+            .recording_register
+            .map(|register| format!("recording @{register} "))
+            .unwrap_or_default();
 
-        Label::new(format!("{} -- {} --", pending, mode))
+        Label::new(format!("{}{} -- {} --", recording, pending, mode))
             .size(LabelSize::Small)
             .line_height_style(LineHeightStyle::UiLabel)
             .into_any_element()
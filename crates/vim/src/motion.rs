@@ -7,13 +7,14 @@ use editor::{
     Anchor, Bias, DisplayPoint, RowExt, ToOffset,
 };
 use gpui::{actions, impl_actions, px, ViewContext, WindowContext};
-use language::{char_kind, CharKind, Point, Selection, SelectionGoal};
+use language::{char_kind, CharKind, Point, Selection, SelectionGoal, TextObject};
 use multi_buffer::MultiBufferRow;
 use serde::Deserialize;
 use std::ops::Range;
 use workspace::Workspace;
 
 use crate::{
+    helix::helix_motion,
     normal::{mark, normal_motion},
     state::{Mode, Operator},
     surrounds::SurroundsType,
@@ -97,6 +98,16 @@ pub enum Motion {
     WindowTop,
     WindowMiddle,
     WindowBottom,
+    NextMethodStart,
+    NextMethodEnd,
+    PreviousMethodStart,
+    PreviousMethodEnd,
+    NextSectionStart,
+    NextSectionEnd,
+    PreviousSectionStart,
+    PreviousSectionEnd,
+    NextComment,
+    PreviousComment,
 
     // we don't have a good way to run a search syncronously, so
     // we handle search motions by running the search async and then
@@ -243,6 +254,16 @@ actions!(
         WindowTop,
         WindowMiddle,
         WindowBottom,
+        NextMethodStart,
+        NextMethodEnd,
+        PreviousMethodStart,
+        PreviousMethodEnd,
+        NextSectionStart,
+        NextSectionEnd,
+        PreviousSectionStart,
+        PreviousSectionEnd,
+        NextComment,
+        PreviousComment,
     ]
 );
 
@@ -390,6 +411,35 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(|_: &mut Workspace, &WindowBottom, cx: _| {
         motion(Motion::WindowBottom, cx)
     });
+    workspace.register_action(|_: &mut Workspace, &NextMethodStart, cx: _| {
+        motion(Motion::NextMethodStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &NextMethodEnd, cx: _| {
+        motion(Motion::NextMethodEnd, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &PreviousMethodStart, cx: _| {
+        motion(Motion::PreviousMethodStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &PreviousMethodEnd, cx: _| {
+        motion(Motion::PreviousMethodEnd, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &NextSectionStart, cx: _| {
+        motion(Motion::NextSectionStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &NextSectionEnd, cx: _| {
+        motion(Motion::NextSectionEnd, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &PreviousSectionStart, cx: _| {
+        motion(Motion::PreviousSectionStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &PreviousSectionEnd, cx: _| {
+        motion(Motion::PreviousSectionEnd, cx)
+    });
+    workspace
+        .register_action(|_: &mut Workspace, &NextComment, cx: _| motion(Motion::NextComment, cx));
+    workspace.register_action(|_: &mut Workspace, &PreviousComment, cx: _| {
+        motion(Motion::PreviousComment, cx)
+    });
 }
 
 pub(crate) fn search_motion(m: Motion, cx: &mut WindowContext) {
@@ -409,7 +459,7 @@ pub(crate) fn search_motion(m: Motion, cx: &mut WindowContext) {
                     });
                 }
             }
-            Mode::Normal | Mode::Replace | Mode::Insert => {
+            Mode::Normal | Mode::Replace | Mode::Insert | Mode::HelixNormal => {
                 if Vim::read(cx).active_operator().is_none() {
                     return;
                 }
@@ -429,6 +479,9 @@ pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
 
     let count = Vim::update(cx, |vim, cx| vim.take_count(cx));
     let active_operator = Vim::read(cx).active_operator();
+    if motion.is_jump() && active_operator.is_none() {
+        Vim::update(cx, |vim, cx| mark::record_jump(vim, cx));
+    }
     let mut waiting_operator: Option<Operator> = None;
     match Vim::read(cx).state().mode {
         Mode::Normal | Mode::Replace => {
@@ -443,6 +496,7 @@ pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
         Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
             visual_motion(motion.clone(), count, cx)
         }
+        Mode::HelixNormal => helix_motion(motion.clone(), count, cx),
         Mode::Insert => {
             // Shouldn't execute a motion in insert mode. Ignoring
         }
@@ -459,6 +513,27 @@ pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
 // Motion handling is specified here:
 // https://github.com/vim/vim/blob/master/runtime/doc/motion.txt
 impl Motion {
+    /// Whether the position before the motion is added to the jump list.
+    /// Searches and marks add to the jump list themselves.
+    pub fn is_jump(&self) -> bool {
+        use Motion::*;
+        matches!(
+            self,
+            StartOfDocument
+                | EndOfDocument
+                | Matching
+                | StartOfParagraph
+                | EndOfParagraph
+                | WindowTop
+                | WindowMiddle
+                | WindowBottom
+                | NextSectionStart
+                | NextSectionEnd
+                | PreviousSectionStart
+                | PreviousSectionEnd
+        )
+    }
+
     pub fn linewise(&self) -> bool {
         use Motion::*;
         match self {
@@ -498,6 +573,16 @@ impl Motion {
             | RepeatFind { .. }
             | RepeatFindReversed { .. }
             | Jump { line: false, .. }
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd
+            | NextComment
+            | PreviousComment
             | ZedSearchResult { .. } => false,
         }
     }
@@ -537,6 +622,16 @@ impl Motion {
             | WindowMiddle
             | WindowBottom
             | NextLineStart
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd
+            | NextComment
+            | PreviousComment
             | ZedSearchResult { .. }
             | Jump { .. } => false,
         }
@@ -561,6 +656,10 @@ impl Motion {
             | PreviousWordEnd { .. }
             | NextSubwordEnd { .. }
             | PreviousSubwordEnd { .. }
+            | NextMethodEnd
+            | PreviousMethodEnd
+            | NextSectionEnd
+            | PreviousSectionEnd
             | NextLineStart => true,
             Left
             | Backspace
@@ -577,6 +676,12 @@ impl Motion {
             | PreviousSubwordStart { .. }
             | FirstNonWhitespace { .. }
             | FindBackward { .. }
+            | NextMethodStart
+            | PreviousMethodStart
+            | NextSectionStart
+            | PreviousSectionStart
+            | NextComment
+            | PreviousComment
             | Jump { .. }
             | ZedSearchResult { .. } => false,
             RepeatFind { last_find: motion } | RepeatFindReversed { last_find: motion } => {
@@ -770,6 +875,49 @@ impl Motion {
             WindowMiddle => window_middle(map, point, &text_layout_details),
             WindowBottom => window_bottom(map, point, &text_layout_details, times - 1),
             Jump { line, anchor } => mark::jump_motion(map, *anchor, *line),
+            // ]m ]M [m [M
+            NextMethodStart => (
+                text_object_motion(map, point, times, TextObject::AroundFunction, true, true),
+                SelectionGoal::None,
+            ),
+            NextMethodEnd => (
+                text_object_motion(map, point, times, TextObject::AroundFunction, true, false),
+                SelectionGoal::None,
+            ),
+            PreviousMethodStart => (
+                text_object_motion(map, point, times, TextObject::AroundFunction, false, true),
+                SelectionGoal::None,
+            ),
+            PreviousMethodEnd => (
+                text_object_motion(map, point, times, TextObject::AroundFunction, false, false),
+                SelectionGoal::None,
+            ),
+            // ]] ][ [[ []
+            NextSectionStart => (
+                text_object_motion(map, point, times, TextObject::AroundClass, true, true),
+                SelectionGoal::None,
+            ),
+            NextSectionEnd => (
+                text_object_motion(map, point, times, TextObject::AroundClass, true, false),
+                SelectionGoal::None,
+            ),
+            PreviousSectionStart => (
+                text_object_motion(map, point, times, TextObject::AroundClass, false, true),
+                SelectionGoal::None,
+            ),
+            PreviousSectionEnd => (
+                text_object_motion(map, point, times, TextObject::AroundClass, false, false),
+                SelectionGoal::None,
+            ),
+            // ]/ [/
+            NextComment => (
+                text_object_motion(map, point, times, TextObject::AroundComment, true, true),
+                SelectionGoal::None,
+            ),
+            PreviousComment => (
+                text_object_motion(map, point, times, TextObject::AroundComment, false, true),
+                SelectionGoal::None,
+            ),
             ZedSearchResult { new_selections, .. } => {
                 // There will be only one selection, as
                 // Search::SelectNextMatch selects a single match.
@@ -924,6 +1072,46 @@ impl Motion {
     }
 }
 
+/// Moves to the start or the end of the next or previous syntax-aware text object of the given
+/// kind, as captured by the language's `textobjects.scm` query.
+fn text_object_motion(
+    map: &DisplaySnapshot,
+    mut display_point: DisplayPoint,
+    times: usize,
+    object: TextObject,
+    forward: bool,
+    to_start: bool,
+) -> DisplayPoint {
+    for _ in 0..times {
+        let offset = display_point.to_offset(map, Bias::Left);
+        let Some(excerpt) = map.buffer_snapshot.excerpt_containing(offset..offset) else {
+            break;
+        };
+        let buffer = excerpt.buffer();
+        let buffer_offset = excerpt.map_offset_to_buffer(offset);
+        let positions = buffer
+            .text_object_ranges(excerpt.buffer_range())
+            .filter(|(_, text_object)| *text_object == object)
+            .map(|(range, _)| {
+                if to_start {
+                    range.start
+                } else {
+                    buffer.clip_offset(range.end.saturating_sub(1), Bias::Left)
+                }
+            });
+        let target = if forward {
+            positions.filter(|position| *position > buffer_offset).min()
+        } else {
+            positions.filter(|position| *position < buffer_offset).max()
+        };
+        let Some(target) = target else {
+            break;
+        };
+        display_point = excerpt.map_offset_from_buffer(target).to_display_point(map);
+    }
+    display_point
+}
+
 fn left(map: &DisplaySnapshot, mut point: DisplayPoint, times: usize) -> DisplayPoint {
     for _ in 0..times {
         point = movement::saturating_left(map, point);
@@ -1776,7 +1964,10 @@ pub fn coerce_punctuation(kind: CharKind, treat_punctuation_as_word: bool) -> Ch
 #[cfg(test)]
 mod test {
 
-    use crate::test::NeovimBackedTestContext;
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
     use indoc::indoc;
 
     #[gpui::test]
@@ -2291,4 +2482,40 @@ mod test {
             }ˇ»
         "});
     }
+
+    #[gpui::test]
+    async fn test_syntax_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        const CODE: &str = indoc! {"
+            struct Foo;
+
+            impl Foo {
+                // Returns 1
+                fn a() -> u32 { 1 }
+
+                fn b() -> u32 { 2 }
+            }
+        "};
+
+        cx.set_state(&format!("ˇ{CODE}"), Mode::Normal);
+        cx.simulate_keystrokes("] m");
+        cx.assert_state(&CODE.replace("fn a", "ˇfn a"), Mode::Normal);
+        cx.simulate_keystrokes("] m");
+        cx.assert_state(&CODE.replace("fn b", "ˇfn b"), Mode::Normal);
+        cx.simulate_keystrokes("[ m");
+        cx.assert_state(&CODE.replace("fn a", "ˇfn a"), Mode::Normal);
+        cx.simulate_keystrokes("] shift-m");
+        cx.assert_state(&CODE.replace("{ 1 }", "{ 1 ˇ}"), Mode::Normal);
+        cx.simulate_keystrokes("[ [");
+        cx.assert_state(&CODE.replace("impl", "ˇimpl"), Mode::Normal);
+        cx.simulate_keystrokes("] /");
+        cx.assert_state(&CODE.replace("// Returns", "ˇ// Returns"), Mode::Normal);
+
+        cx.set_state(&format!("ˇ{CODE}"), Mode::Normal);
+        cx.simulate_keystrokes("] ]");
+        cx.assert_state(&CODE.replace("impl", "ˇimpl"), Mode::Normal);
+        cx.simulate_keystrokes("] [");
+        cx.assert_state(&CODE.replace("}\n}\n", "}\nˇ}\n"), Mode::Normal);
+    }
 }
@@ -3,8 +3,9 @@ mod change;
 mod delete;
 mod increment;
 mod indent;
+pub(crate) mod macros;
 pub(crate) mod mark;
-mod paste;
+pub(crate) mod paste;
 pub(crate) mod repeat;
 mod scroll;
 pub(crate) mod search;
@@ -180,6 +181,8 @@ pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace
 
     paste::register(workspace, cx);
     repeat::register(workspace, cx);
+    macros::register(workspace, cx);
+    mark::register(workspace, cx);
     scroll::register(workspace, cx);
     search::register(workspace, cx);
     substitute::register(workspace, cx);
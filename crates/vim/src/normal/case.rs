@@ -141,7 +141,7 @@ where
                         ranges.push(start..end);
                         cursor_positions.push(start..start);
                     }
-                    Mode::Visual => {
+                    Mode::Visual | Mode::HelixNormal => {
                         ranges.push(selection.start..selection.end);
                         cursor_positions.push(selection.start..selection.start);
                     }
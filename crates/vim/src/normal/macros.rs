@@ -0,0 +1,646 @@
+use std::iter;
+
+use anyhow::Result;
+use editor::{scroll::Autoscroll, ToPoint};
+use gpui::{
+    actions, impl_actions, Action, AnyWindowHandle, AsyncAppContext, Keystroke, Modifiers,
+    ViewContext, WindowContext,
+};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+use serde_derive::Deserialize;
+use workspace::Workspace;
+
+use crate::{
+    state::{Operator, Register, ReplayStep, ReplayableAction},
+    Vim,
+};
+
+actions!(vim, [ToggleRecord]);
+
+/// Types `keys`, written in Vim's key notation, as though they were typed by
+/// the user. This is the action that mappings from a vimrc are bound to.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SendKeys {
+    pub keys: String,
+    /// Whether the keys can trigger other mappings, as with `map` rather
+    /// than `noremap`.
+    #[serde(default)]
+    pub remap: bool,
+}
+
+impl_actions!(vim, [SendKeys]);
+
+/// How many mappings can expand into each other before the mapping is
+/// considered recursive, as with Vim's `maxmapdepth`.
+const MAX_MAPPING_EXPANSIONS: usize = 1000;
+
+/// Keys that Vim writes with a name in angle brackets, e.g. `<Esc>`.
+const NAMED_KEYS: &[(&str, &str)] = &[
+    ("escape", "Esc"),
+    ("enter", "CR"),
+    ("backspace", "BS"),
+    ("tab", "Tab"),
+    ("space", "Space"),
+    ("delete", "Del"),
+    ("up", "Up"),
+    ("down", "Down"),
+    ("left", "Left"),
+    ("right", "Right"),
+    ("home", "Home"),
+    ("end", "End"),
+    ("pageup", "PageUp"),
+    ("pagedown", "PageDown"),
+];
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_: &mut Workspace, action: &SendKeys, cx| send_keys(action, cx));
+    workspace.register_action(|_: &mut Workspace, _: &ToggleRecord, cx| {
+        Vim::update(cx, |vim, cx| {
+            if vim.workspace_state.recording_register.is_some() {
+                stop_recording_register(vim);
+            } else {
+                vim.push_operator(Operator::RecordRegister, cx);
+            }
+        })
+    });
+}
+
+/// Starts recording into `register`, after `q{register}`.
+pub(crate) fn start_recording_register(vim: &mut Vim, register: char, cx: &mut WindowContext) {
+    vim.clear_operator(cx);
+    if !(register.is_ascii_alphanumeric() || register == '"') {
+        return;
+    }
+    let state = &mut vim.workspace_state;
+    state.recording_register = Some(register);
+    state.recorded_register_text.clear();
+    state.recorded_register_actions.clear();
+    // the register name is reported as an insertion once we return.
+    state.ignore_current_insertion = true;
+}
+
+/// Stops recording and stores what was recorded in the register, appending
+/// to it if the register was given in uppercase.
+pub(crate) fn stop_recording_register(vim: &mut Vim) {
+    let state = &mut vim.workspace_state;
+    let Some(register) = state.recording_register.take() else {
+        return;
+    };
+    let text = std::mem::take(&mut state.recorded_register_text);
+    let actions = std::mem::take(&mut state.recorded_register_actions);
+
+    let lower = register.to_ascii_lowercase();
+    if lower != register {
+        let current = state.registers.entry(lower).or_default();
+        current.recorded_actions = if current.text.is_empty() {
+            Some(actions.into())
+        } else {
+            current
+                .recorded_actions
+                .take()
+                .map(|recorded| recorded.iter().cloned().chain(actions).collect())
+        };
+        current.text = (current.text.to_string() + &text).into();
+        current.clipboard_selections.take();
+    } else {
+        state.registers.insert(
+            register,
+            Register {
+                text: text.into(),
+                clipboard_selections: None,
+                recorded_actions: Some(actions.into()),
+            },
+        );
+    }
+}
+
+/// Records an action dispatched by `keystroke` into the register being recorded.
+pub(crate) fn record_action(
+    vim: &mut Vim,
+    action: &dyn Action,
+    keystroke: Option<&Keystroke>,
+    cx: &mut WindowContext,
+) {
+    if vim.workspace_state.recording_register.is_none() || vim.workspace_state.replaying_register {
+        return;
+    }
+    if let Some(keystroke) = keystroke {
+        // the observed keystroke is only the last one of a multi-key binding.
+        let bindings = cx.bindings_for_action(action);
+        let keystrokes = bindings
+            .iter()
+            .map(|binding| binding.keystrokes())
+            .find(|keystrokes| {
+                keystrokes.last().is_some_and(|last| {
+                    last.key == keystroke.key && last.modifiers == keystroke.modifiers
+                })
+            })
+            .unwrap_or(std::slice::from_ref(keystroke));
+        let text = keystrokes_to_text(keystrokes);
+        vim.workspace_state.recorded_register_text.push_str(&text);
+    }
+    vim.workspace_state
+        .recorded_register_actions
+        .push(ReplayableAction::Action(action.boxed_clone()));
+}
+
+/// Records text typed by the user into the register being recorded.
+pub(crate) fn record_insertion(vim: &mut Vim, action: ReplayableAction) {
+    let state = &mut vim.workspace_state;
+    if std::mem::take(&mut state.ignore_current_insertion)
+        || state.recording_register.is_none()
+        || state.replaying_register
+    {
+        return;
+    }
+    if let ReplayableAction::Insertion { text, .. } = &action {
+        for char in text.chars() {
+            push_char(char, &mut state.recorded_register_text);
+        }
+    }
+    state.recorded_register_actions.push(action);
+}
+
+/// Replays the contents of `register` as though they were typed, after `@{register}`.
+/// `@@` replays the register that was replayed last.
+pub(crate) fn replay_register(register: char, cx: &mut WindowContext) {
+    let Some(steps) = Vim::update(cx, |vim, cx| {
+        let count = vim.take_count(cx).unwrap_or(1);
+        vim.clear_operator(cx);
+        let register = if register == '@' {
+            vim.workspace_state.last_replayed_register?
+        } else {
+            register
+        };
+        let content = vim.read_register(Some(register), None, cx)?;
+        vim.workspace_state.last_replayed_register = Some(register);
+
+        let steps: Vec<ReplayStep> = match content.recorded_actions {
+            Some(actions) => actions.iter().cloned().map(ReplayStep::Action).collect(),
+            None => text_to_keystrokes(&content.text)
+                .into_iter()
+                .map(ReplayStep::Keystroke)
+                .collect(),
+        };
+        Some(
+            iter::repeat(steps)
+                .take(count)
+                .flatten()
+                .collect::<Vec<_>>(),
+        )
+    }) else {
+        return;
+    };
+    replay(steps, cx);
+}
+
+/// Types the right-hand side of a vimrc mapping, after its left-hand side was
+/// typed. Unless the mapping allows remapping, the keystrokes are replayed with
+/// the `VimNoremap` context set, which disables the bindings of other mappings.
+fn send_keys(action: &SendKeys, cx: &mut WindowContext) {
+    let Some(keystrokes) = Vim::update(cx, |vim, cx| {
+        let state = &mut vim.workspace_state;
+        state.mapping_expansions += 1;
+        if state.mapping_expansions > MAX_MAPPING_EXPANSIONS {
+            // like Vim's "E223: recursive mapping", abandon the rest of the replay.
+            log::error!("recursive mapping: {}", action.keys);
+            state.register_replay_queue.clear();
+            vim.clear_operator(cx);
+            return None;
+        }
+        Some(text_to_keystrokes(&action.keys))
+    }) else {
+        return;
+    };
+    let noremap = Vim::read(cx).workspace_state.noremap;
+    let steps = iter::once(ReplayStep::SetRemap(action.remap))
+        .chain(keystrokes.into_iter().map(ReplayStep::Keystroke))
+        .chain(iter::once(ReplayStep::SetRemap(!noremap)))
+        .collect();
+    replay(steps, cx);
+}
+
+/// Runs the given steps in order. If a replay is already in progress (because a
+/// macro invokes another one), the steps run before the rest of that replay.
+pub(crate) fn replay(steps: Vec<ReplayStep>, cx: &mut WindowContext) {
+    let already_replaying = Vim::update(cx, |vim, _| {
+        let queue = &mut vim.workspace_state.register_replay_queue;
+        for step in steps.into_iter().rev() {
+            queue.push_front(step);
+        }
+        vim.workspace_state.replaying_register
+    });
+    if already_replaying {
+        return;
+    }
+
+    let window = cx.window_handle();
+    cx.spawn(move |mut cx| async move {
+        cx.update(|cx| Vim::update(cx, |vim, _| vim.workspace_state.replaying_register = true))?;
+        let result = replay_steps(window, &mut cx).await;
+        // reset the replay state even if a step failed, so that later replays
+        // don't group their edits into the abandoned one's undo step.
+        cx.update(|cx| {
+            Vim::update(cx, |vim, cx| {
+                let state = &mut vim.workspace_state;
+                state.replaying_register = false;
+                state.mapping_expansions = 0;
+                state.replay_undo_group = None;
+                state.register_replay_queue.clear();
+                if std::mem::take(&mut state.noremap) {
+                    vim.sync_vim_settings(cx);
+                }
+            })
+        })?;
+        result
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Runs the steps in the replay queue until it's empty.
+async fn replay_steps(window: AnyWindowHandle, cx: &mut AsyncAppContext) -> Result<()> {
+    while let Some(step) = cx.update(|cx| {
+        Vim::update(cx, |vim, _| {
+            vim.workspace_state.register_replay_queue.pop_front()
+        })
+    })? {
+        match step {
+            ReplayStep::Action(ReplayableAction::Action(action)) => {
+                window.update(cx, |_, cx| cx.dispatch_action(action.boxed_clone()))?;
+                // observe the action once it has run, as for one dispatched by a keystroke.
+                window.update(cx, |_, cx| {
+                    Vim::update(cx, |vim, cx| vim.observe_action(action, None, cx))
+                })?
+            }
+            ReplayStep::Action(ReplayableAction::Insertion {
+                text,
+                utf16_range_to_replace,
+            }) => window
+                .update(cx, |_, cx| {
+                    let editor = Vim::read(cx).active_editor.clone()?.upgrade()?;
+                    editor.update(cx, |editor, cx| {
+                        editor.replay_insert_event(&text, utf16_range_to_replace, cx)
+                    });
+                    Some(())
+                })
+                .map(|_| ())?,
+            ReplayStep::Keystroke(keystroke) => window.update(cx, |_, cx| {
+                let focused = cx.focused();
+                cx.dispatch_keystroke(keystroke);
+                if cx.focused() != focused {
+                    // drawing schedules the FocusChanged events, which must be
+                    // handled before the next keystroke (e.g. after `:`).
+                    cx.draw();
+                }
+            })?,
+            ReplayStep::MoveToLine(anchor) => window.update(cx, |_, cx| {
+                Vim::update(cx, |vim, cx| {
+                    vim.update_active_editor(cx, |_, editor, cx| {
+                        let snapshot = editor.buffer().read(cx).snapshot(cx);
+                        let row = anchor.to_point(&snapshot).row;
+                        let indent = snapshot.indent_size_for_line(MultiBufferRow(row));
+                        let point = Point::new(row, indent.len);
+                        editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                            s.select_ranges([point..point])
+                        });
+                    });
+                });
+            })?,
+            ReplayStep::StartUndoGroup => cx.update(|cx| {
+                Vim::update(cx, |vim, _| {
+                    vim.workspace_state.replay_undo_group = Some(Vec::new())
+                })
+            })?,
+            ReplayStep::EndUndoGroup(editor) => window.update(cx, |_, cx| {
+                let transaction_ids =
+                    Vim::update(cx, |vim, _| vim.workspace_state.replay_undo_group.take());
+                // transactions without edits aren't in the undo history, so group
+                // from the first one that is. Grouping from the later ones is a no-op.
+                editor.update(cx, |editor, cx| {
+                    for transaction_id in transaction_ids.into_iter().flatten() {
+                        editor.group_until_transaction(transaction_id, cx);
+                    }
+                });
+            })?,
+            ReplayStep::SetRemap(remap) => cx.update(|cx| {
+                Vim::update(cx, |vim, cx| {
+                    if vim.workspace_state.noremap == remap {
+                        vim.workspace_state.noremap = !remap;
+                        vim.sync_vim_settings(cx);
+                    }
+                })
+            })?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes keystrokes the way Vim shows them in a register, e.g. `d$<Esc>`.
+pub(crate) fn keystrokes_to_text(keystrokes: &[Keystroke]) -> String {
+    let mut text = String::new();
+    for keystroke in keystrokes {
+        let modifiers = &keystroke.modifiers;
+        let modified =
+            modifiers.control || modifiers.alt || modifiers.platform || modifiers.function;
+        let is_char = keystroke.key.chars().count() == 1;
+
+        if !modified && (is_char || keystroke.key == "space") {
+            match &keystroke.ime_key {
+                Some(ime_key) => ime_key.chars().for_each(|char| push_char(char, &mut text)),
+                None if keystroke.key == "space" => text.push(' '),
+                None if modifiers.shift => keystroke
+                    .key
+                    .to_uppercase()
+                    .chars()
+                    .for_each(|char| push_char(char, &mut text)),
+                None => keystroke
+                    .key
+                    .chars()
+                    .for_each(|char| push_char(char, &mut text)),
+            }
+            continue;
+        }
+
+        text.push('<');
+        if modifiers.control {
+            text.push_str("C-");
+        }
+        if modifiers.alt {
+            text.push_str("A-");
+        }
+        if modifiers.platform {
+            text.push_str("D-");
+        }
+        if modifiers.shift && !is_char {
+            text.push_str("S-");
+        }
+        match NAMED_KEYS.iter().find(|(key, _)| *key == keystroke.key) {
+            Some((_, name)) => text.push_str(name),
+            None if keystroke.key == "<" => text.push_str("lt"),
+            None if is_char && modifiers.shift => text.push_str(&keystroke.key.to_uppercase()),
+            None if is_char => text.push_str(&keystroke.key),
+            None => text.push_str(&capitalize(&keystroke.key)),
+        }
+        text.push('>');
+    }
+    text
+}
+
+/// Parses the text of a register into the keystrokes it represents,
+/// understanding Vim's key notation such as `<Esc>` and `<C-v>`.
+pub(crate) fn text_to_keystrokes(text: &str) -> Vec<Keystroke> {
+    let mut keystrokes = Vec::new();
+    let mut rest = text;
+    while let Some(char) = rest.chars().next() {
+        if char == '<' {
+            if let Some(keystroke) = rest[1..]
+                .split_once('>')
+                .and_then(|(name, _)| parse_key_notation(name))
+            {
+                keystrokes.push(keystroke);
+                rest = &rest[rest.find('>').unwrap() + 1..];
+                continue;
+            }
+        }
+        keystrokes.push(char_keystroke(char));
+        rest = &rest[char.len_utf8()..];
+    }
+    keystrokes
+}
+
+/// Returns the keystrokes that type `text` literally, as `:normal` does.
+pub(crate) fn literal_keystrokes(text: &str) -> Vec<Keystroke> {
+    text.chars().map(char_keystroke).collect()
+}
+
+fn push_char(char: char, text: &mut String) {
+    match char {
+        '<' => text.push_str("<lt>"),
+        '\n' => text.push_str("<CR>"),
+        '\t' => text.push_str("<Tab>"),
+        '\x1b' => text.push_str("<Esc>"),
+        char => text.push(char),
+    }
+}
+
+fn char_keystroke(char: char) -> Keystroke {
+    match char {
+        ' ' => named_keystroke("space"),
+        '\n' | '\r' => named_keystroke("enter"),
+        '\t' => named_keystroke("tab"),
+        '\x1b' => named_keystroke("escape"),
+        char => Keystroke {
+            modifiers: Modifiers {
+                shift: char.is_uppercase(),
+                ..Default::default()
+            },
+            key: char.to_lowercase().collect(),
+            ime_key: Some(char.to_string()),
+        },
+    }
+}
+
+fn named_keystroke(key: &str) -> Keystroke {
+    Keystroke {
+        modifiers: Modifiers::default(),
+        key: key.to_string(),
+        ime_key: None,
+    }
+}
+
+fn parse_key_notation(name: &str) -> Option<Keystroke> {
+    let mut modifiers = Modifiers::default();
+    let mut name = name;
+    while name.len() > 2 && name.as_bytes()[1] == b'-' {
+        match name.as_bytes()[0].to_ascii_uppercase() {
+            b'C' => modifiers.control = true,
+            b'A' | b'M' => modifiers.alt = true,
+            b'D' => modifiers.platform = true,
+            b'S' => modifiers.shift = true,
+            _ => return None,
+        }
+        name = &name[2..];
+    }
+
+    let key = if name.eq_ignore_ascii_case("lt") {
+        "<".to_string()
+    } else if let Some((key, _)) = NAMED_KEYS
+        .iter()
+        .find(|(_, named)| named.eq_ignore_ascii_case(name))
+    {
+        key.to_string()
+    } else if name.eq_ignore_ascii_case("Enter") || name.eq_ignore_ascii_case("Return") {
+        "enter".to_string()
+    } else if name.len() > 1
+        && name[..1].eq_ignore_ascii_case("f")
+        && name[1..].parse::<u8>().is_ok()
+    {
+        name.to_ascii_lowercase()
+    } else if name.chars().count() == 1 && modifiers != Modifiers::default() {
+        let char = name.chars().next()?;
+        modifiers.shift |= char.is_uppercase();
+        char.to_lowercase().collect()
+    } else {
+        return None;
+    };
+
+    if modifiers == Modifiers::default() && key.chars().count() == 1 {
+        return key.chars().next().map(char_keystroke);
+    }
+    Some(Keystroke {
+        modifiers,
+        key,
+        ime_key: None,
+    })
+}
+
+fn capitalize(key: &str) -> String {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::Keystroke;
+    use indoc::indoc;
+
+    use super::{keystrokes_to_text, literal_keystrokes, text_to_keystrokes};
+    use crate::{state::Mode, test::VimTestContext, Vim};
+
+    #[test]
+    fn test_key_notation() {
+        let keystrokes = [
+            "d",
+            "w",
+            "shift-a",
+            "<",
+            "space",
+            "escape",
+            "ctrl-v",
+            "shift-tab",
+        ]
+        .into_iter()
+        .map(|keystroke| Keystroke::parse(keystroke).unwrap())
+        .collect::<Vec<_>>();
+        let text = keystrokes_to_text(&keystrokes);
+        assert_eq!(text, "dwA<lt> <Esc><C-v><S-Tab>");
+
+        let parsed = text_to_keystrokes(&text);
+        assert_eq!(keystrokes_to_text(&parsed), text);
+        assert_eq!(parsed[2].key, "a");
+        assert!(parsed[2].modifiers.shift);
+        assert_eq!(parsed[3].key, "<");
+        assert_eq!(parsed[5].key, "escape");
+        assert!(parsed[6].modifiers.control);
+
+        // unknown names and stray brackets are typed literally
+        assert_eq!(
+            keystrokes_to_text(&text_to_keystrokes("<nope>x<")),
+            "<lt>nope>x<lt>"
+        );
+        assert_eq!(literal_keystrokes("<Esc>").len(), 5);
+    }
+
+    #[gpui::test]
+    async fn test_record_and_replay(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three four five", Mode::Normal);
+        cx.simulate_keystrokes("q a d w q");
+        cx.assert_state("ˇtwo three four five", Mode::Normal);
+        cx.simulate_keystrokes("@ a");
+        cx.assert_state("ˇthree four five", Mode::Normal);
+        cx.simulate_keystrokes("@ @");
+        cx.assert_state("ˇfour five", Mode::Normal);
+        cx.simulate_keystrokes("2 @ a");
+        cx.assert_state("ˇ", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_replay_insert_mode(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            b
+            c
+            d"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("q b shift-a ! escape j q");
+        cx.simulate_keystrokes("2 @ b");
+        cx.assert_state(
+            indoc! {"
+            a!
+            b!
+            c!
+            ˇd"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("\" b p");
+        cx.assert_state(
+            indoc! {"
+            a!
+            b!
+            c!
+            dA!<Esc>ˇj"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_replay_edited_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇA?<Esc>j
+            one
+            two"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("\" c y $ j @ c");
+        cx.assert_state(
+            indoc! {"
+            A?<Esc>j
+            one?
+            twˇo"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_append_to_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇabcdef", Mode::Normal);
+        cx.simulate_keystrokes("q d x q q shift-d l q");
+        cx.assert_state("bˇcdef", Mode::Normal);
+        let text = cx.read(|cx| {
+            cx.global::<Vim>()
+                .workspace_state
+                .registers
+                .get(&'d')
+                .map(|register| register.text.clone())
+        });
+        assert_eq!(text, Some("xl".into()));
+        cx.simulate_keystrokes("@ d");
+        cx.assert_state("bdˇef", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_replay_multiple_cursors(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇfoo bar", Mode::Normal);
+        cx.simulate_keystrokes("q a i x escape q");
+        cx.set_state("ˇfoo ˇbar", Mode::Normal);
+        cx.simulate_keystrokes("@ a");
+        cx.assert_state("ˇxfoo ˇxbar", Mode::Normal);
+    }
+}
@@ -1,21 +1,68 @@
-use std::{ops::Range, sync::Arc};
+use std::{ops::Range, path::PathBuf, sync::Arc, time::Duration};
 
 use editor::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement,
     scroll::Autoscroll,
-    Anchor, Bias, DisplayPoint,
+    Anchor, Bias, DisplayPoint, Editor,
 };
-use gpui::WindowContext;
-use language::SelectionGoal;
+use gpui::{AppContext, Entity, EntityId, UpdateGlobal, View, ViewContext, WindowContext};
+use language::{Point, SelectionGoal, ToPoint as _};
+use project::Item as _;
+use util::ResultExt;
+use workspace::Workspace;
 
 use crate::{
     motion::{self, Motion},
-    state::Mode,
+    persistence::DB,
+    state::{FileMark, Mode, WorkspaceMarks},
     Vim,
 };
 
+/// The number of positions kept in the jump list.
+const MAX_JUMPS: usize = 100;
+
+/// How long changes to the global marks and jump list wait before they're
+/// saved, so that a burst of jumps is written once.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let workspace_marks = WorkspaceMarks {
+        workspace_id: workspace.database_id(),
+        project: workspace.project().downgrade(),
+        loaded: false,
+        marks: Default::default(),
+        jumps: Default::default(),
+        saved_marks: Default::default(),
+        saved_jumps: Default::default(),
+        save_scheduled: false,
+    };
+    let entity_id = cx.view().entity_id();
+    Vim::update_global(cx, |vim, _| {
+        vim.workspace_state
+            .workspace_marks
+            .insert(entity_id, workspace_marks);
+    });
+    cx.on_release(move |_, _, cx| {
+        Vim::update_global(cx, |vim, cx| {
+            if let Some(mut marks) = vim.workspace_state.workspace_marks.remove(&entity_id) {
+                save_changes(&mut marks, cx);
+            }
+        })
+    })
+    .detach();
+}
+
 pub fn create_mark(vim: &mut Vim, text: Arc<str>, tail: bool, cx: &mut WindowContext) {
+    if let Some(name) = global_mark_name(&text) {
+        // The numbered marks are set when leaving a file, not with `m`.
+        if name.is_ascii_uppercase() {
+            create_global_mark(vim, name, tail, cx);
+        }
+        vim.clear_operator(cx);
+        return;
+    }
+
     let Some(anchors) = vim.update_active_editor(cx, |_, editor, _| {
         editor
             .selections
@@ -30,6 +77,317 @@ pub fn create_mark(vim: &mut Vim, text: Arc<str>, tail: bool, cx: &mut WindowCon
     vim.clear_operator(cx);
 }
 
+fn create_global_mark(vim: &mut Vim, name: char, tail: bool, cx: &mut WindowContext) {
+    let Some((workspace, mark)) = vim
+        .update_active_editor(cx, |_, editor, cx| {
+            let selection = editor.selections.newest_anchor();
+            let anchor = if tail {
+                selection.tail()
+            } else {
+                selection.head()
+            };
+            Some((
+                editor.workspace()?.entity_id(),
+                file_mark(editor, anchor, cx)?,
+            ))
+        })
+        .flatten()
+    else {
+        return;
+    };
+    let Some(marks) = workspace_marks(vim, workspace, cx) else {
+        return;
+    };
+    marks.marks.insert(name, mark);
+    schedule_save(marks, workspace, cx);
+}
+
+/// Returns the name of a mark that is shared by all the files of the
+/// workspace: `A`-`Z`, or `0`-`9`.
+pub(crate) fn global_mark_name(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    let name = chars.next()?;
+    (chars.next().is_none() && (name.is_ascii_uppercase() || name.is_ascii_digit())).then_some(name)
+}
+
+/// Returns the global marks and jump list of a workspace, reading the ones
+/// saved by a previous session the first time they're needed.
+pub(crate) fn workspace_marks<'a>(
+    vim: &'a mut Vim,
+    workspace: EntityId,
+    cx: &AppContext,
+) -> Option<&'a mut WorkspaceMarks> {
+    let marks = vim.workspace_state.workspace_marks.get_mut(&workspace)?;
+    if !marks.loaded {
+        marks.loaded = true;
+        load_saved_marks(marks, cx);
+    }
+    Some(marks)
+}
+
+fn load_saved_marks(marks: &mut WorkspaceMarks, cx: &AppContext) {
+    let (Some(workspace_id), Some(project)) = (marks.workspace_id, marks.project.upgrade()) else {
+        return;
+    };
+    let project = project.read(cx);
+    let file_mark = |path: PathBuf, line: u32, col: u32| {
+        Some(FileMark {
+            path: project.project_path_for_absolute_path(&path, cx)?,
+            anchor: None,
+            point: Point::new(line, col),
+        })
+    };
+
+    for (name, path, line, col) in DB.get_marks(workspace_id).log_err().unwrap_or_default() {
+        let Some(name) = name.chars().next() else {
+            continue;
+        };
+        if let Some(mark) = file_mark(path.clone(), line, col) {
+            marks.marks.insert(name, mark);
+        }
+        marks.saved_marks.insert(name, (path, line, col));
+    }
+    marks.saved_jumps = DB.get_jumps(workspace_id).log_err().unwrap_or_default();
+    marks.jumps = marks
+        .saved_jumps
+        .iter()
+        .filter_map(|(path, line, col)| file_mark(path.clone(), *line, *col))
+        .collect();
+}
+
+/// Returns a mark at the given position of the editor, if the editor is
+/// showing a file of the project there.
+fn file_mark(editor: &Editor, anchor: Anchor, cx: &AppContext) -> Option<FileMark> {
+    let (buffer, anchor) = editor
+        .buffer()
+        .read(cx)
+        .text_anchor_for_position(anchor, cx)?;
+    let path = buffer.read(cx).project_path(cx)?;
+    let point = anchor.to_point(buffer.read(cx));
+    Some(FileMark {
+        path,
+        anchor: Some((buffer.downgrade(), anchor)),
+        point,
+    })
+}
+
+/// Returns the position of the mark in the editor, if the editor is showing
+/// the marked file.
+pub(crate) fn anchor_in_editor(
+    editor: &Editor,
+    mark: &FileMark,
+    cx: &AppContext,
+) -> Option<Anchor> {
+    let multi_buffer = editor.buffer().read(cx);
+    if multi_buffer.as_singleton()?.read(cx).project_path(cx)? != mark.path {
+        return None;
+    }
+    let snapshot = multi_buffer.snapshot(cx);
+    Some(snapshot.anchor_before(snapshot.clip_point(mark.point(cx), Bias::Left)))
+}
+
+fn absolute_path(marks: &WorkspaceMarks, mark: &FileMark, cx: &AppContext) -> Option<PathBuf> {
+    marks
+        .project
+        .upgrade()?
+        .read(cx)
+        .absolute_path(&mark.path, cx)
+}
+
+/// Saves the changes to the workspace's global marks and jump list once they
+/// haven't changed for a while.
+fn schedule_save(marks: &mut WorkspaceMarks, workspace: EntityId, cx: &AppContext) {
+    if marks.workspace_id.is_none() || marks.save_scheduled {
+        return;
+    }
+    marks.save_scheduled = true;
+    cx.spawn(|mut cx| async move {
+        cx.background_executor().timer(SAVE_DEBOUNCE).await;
+        cx.update(|cx| {
+            Vim::update_global(cx, |vim, cx| {
+                if let Some(marks) = vim.workspace_state.workspace_marks.get_mut(&workspace) {
+                    marks.save_scheduled = false;
+                    save_changes(marks, cx);
+                }
+            })
+        })
+        .log_err();
+    })
+    .detach();
+}
+
+/// Writes the global marks that moved since they were last saved, and the jump
+/// list if it changed.
+fn save_changes(marks: &mut WorkspaceMarks, cx: &AppContext) {
+    let Some(workspace_id) = marks.workspace_id else {
+        return;
+    };
+    let row = |mark: &FileMark| {
+        let point = mark.point(cx);
+        Some((absolute_path(marks, mark, cx)?, point.row, point.column))
+    };
+    let changed_marks = marks
+        .marks
+        .iter()
+        .filter_map(|(name, mark)| Some((*name, row(mark)?)))
+        .filter(|(name, row)| marks.saved_marks.get(name) != Some(row))
+        .collect::<Vec<_>>();
+    let jumps = marks.jumps.iter().filter_map(row).collect::<Vec<_>>();
+    let jumps_changed = jumps != marks.saved_jumps;
+    if changed_marks.is_empty() && !jumps_changed {
+        return;
+    }
+
+    marks.saved_marks.extend(changed_marks.iter().cloned());
+    if jumps_changed {
+        marks.saved_jumps = jumps.clone();
+    }
+    cx.background_executor()
+        .spawn(async move {
+            for (name, (path, line, col)) in changed_marks {
+                DB.save_mark(workspace_id, name.to_string(), path, line, col)
+                    .await
+                    .log_err();
+            }
+            if jumps_changed {
+                DB.save_jumps(workspace_id, jumps).await.log_err();
+            }
+        })
+        .detach();
+}
+
+/// Deletes marks by name: lowercase marks from the active editor, and global
+/// marks from its workspace.
+pub(crate) fn delete_marks(vim: &mut Vim, names: &str, cx: &mut WindowContext) {
+    let workspace = vim
+        .update_active_editor(cx, |_, editor, _| editor.workspace())
+        .flatten();
+    for name in names.chars() {
+        if global_mark_name(&name.to_string()).is_some() {
+            let Some(workspace) = workspace.as_ref() else {
+                continue;
+            };
+            let Some(marks) = workspace_marks(vim, workspace.entity_id(), cx) else {
+                continue;
+            };
+            if marks.marks.remove(&name).is_some() {
+                marks.saved_marks.remove(&name);
+                if let Some(workspace_id) = marks.workspace_id {
+                    cx.background_executor()
+                        .spawn(async move {
+                            DB.delete_mark(workspace_id, name.to_string())
+                                .await
+                                .log_err()
+                        })
+                        .detach();
+                }
+            }
+        } else {
+            vim.update_state(|state| state.marks.remove(&name.to_string()));
+        }
+    }
+}
+
+/// Adds the position of the newest cursor to the jump list, before a jump
+/// moves it somewhere else.
+pub(crate) fn record_jump(vim: &mut Vim, cx: &mut WindowContext) {
+    let Some((workspace, jump)) = vim
+        .update_active_editor(cx, |_, editor, cx| {
+            let head = editor.selections.newest_anchor().head();
+            Some((
+                editor.workspace()?.entity_id(),
+                file_mark(editor, head, cx)?,
+            ))
+        })
+        .flatten()
+    else {
+        return;
+    };
+    let Some(marks) = workspace_marks(vim, workspace, cx) else {
+        return;
+    };
+    marks
+        .jumps
+        .retain(|existing| existing.path != jump.path || existing.point(cx).row != jump.point.row);
+    marks.jumps.push(jump);
+    if marks.jumps.len() > MAX_JUMPS {
+        marks.jumps.remove(0);
+    }
+    schedule_save(marks, workspace, cx);
+}
+
+/// Called when an editor loses focus. Sets `'0` to the position the file was
+/// left at, shifting the older numbered marks along, and schedules saving the
+/// marks that changed, including the file's global marks that moved with edits.
+pub(crate) fn leave_file(vim: &mut Vim, editor: &View<Editor>, cx: &mut WindowContext) {
+    let Some((workspace, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        Some((
+            editor.workspace()?.entity_id(),
+            file_mark(editor, head, cx)?,
+        ))
+    }) else {
+        return;
+    };
+    let Some(marks) = workspace_marks(vim, workspace, cx) else {
+        return;
+    };
+
+    let path = position.path.clone();
+    let mut numbered = vec![position];
+    numbered.extend(
+        ('0'..='9')
+            .filter_map(|name| marks.marks.remove(&name))
+            .filter(|mark| mark.path != path),
+    );
+    for (name, mark) in ('0'..='9').zip(numbered) {
+        marks.marks.insert(name, mark);
+    }
+
+    // the marks in the file may have moved with edits too.
+    schedule_save(marks, workspace, cx);
+}
+
+/// Opens the file of a global mark or jump, and moves the cursor to it (or to
+/// the first non-blank character of its line).
+pub(crate) fn open_file_mark(
+    workspace: View<Workspace>,
+    mark: FileMark,
+    line: bool,
+    cx: &mut WindowContext,
+) {
+    cx.defer(move |cx| {
+        let point = mark.point(cx);
+        let open_path = workspace.update(cx, |workspace, cx| {
+            workspace.open_path(mark.path.clone(), None, true, cx)
+        });
+        cx.spawn(|mut cx| async move {
+            let item = open_path.await?;
+            cx.update(|cx| {
+                let Some(editor) = item.act_as::<Editor>(cx) else {
+                    return;
+                };
+                editor.update(cx, |editor, cx| {
+                    let map = editor.snapshot(cx);
+                    let point = map
+                        .display_snapshot
+                        .buffer_snapshot
+                        .clip_point(point, Bias::Left);
+                    let mut point = point.to_display_point(&map.display_snapshot);
+                    if line {
+                        point = motion::first_non_whitespace(&map.display_snapshot, false, point);
+                    }
+                    let point = point.to_point(&map.display_snapshot);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([point..point])
+                    });
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    });
+}
+
 pub fn create_visual_marks(vim: &mut Vim, mode: Mode, cx: &mut WindowContext) {
     let mut starts = vec![];
     let mut ends = vec![];
@@ -60,6 +418,7 @@ pub fn create_visual_marks(vim: &mut Vim, mode: Mode, cx: &mut WindowContext) {
 }
 
 pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
+    let mut other_file = None;
     let anchors = Vim::update(cx, |vim, cx| {
         vim.pop_operator(cx);
 
@@ -80,10 +439,44 @@ pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
                     .collect::<Vec<Anchor>>()
             }),
             "." => vim.state().change_list.last().cloned(),
-            _ => vim.state().marks.get(&*text).cloned(),
+            _ => match global_mark_name(&text) {
+                Some(name) => {
+                    let workspace = vim
+                        .update_active_editor(cx, |_, editor, _| editor.workspace())
+                        .flatten()?;
+                    let mark = workspace_marks(vim, workspace.entity_id(), cx)?
+                        .marks
+                        .get(&name)?
+                        .clone();
+                    let anchor = vim
+                        .update_active_editor(cx, |_, editor, cx| {
+                            anchor_in_editor(editor, &mark, cx)
+                        })
+                        .flatten();
+                    if anchor.is_none() {
+                        other_file = Some((workspace, mark));
+                    }
+                    anchor.map(|anchor| vec![anchor])
+                }
+                None => vim.state().marks.get(&*text).cloned(),
+            },
         }
     });
 
+    if let Some((workspace, mark)) = other_file {
+        Vim::update(cx, |vim, cx| {
+            // Operators can't span files, so `d'A` does nothing if `A` is
+            // in another file.
+            if vim.active_operator().is_some() {
+                vim.clear_operator(cx);
+            } else {
+                record_jump(vim, cx);
+                open_file_mark(workspace, mark, line, cx);
+            }
+        });
+        return;
+    }
+
     let Some(anchors) = anchors else { return };
 
     let is_active_operator = Vim::read(cx).state().active_operator().is_some();
@@ -100,6 +493,7 @@ pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
         return;
     } else {
         Vim::update(cx, |vim, cx| {
+            record_jump(vim, cx);
             vim.update_active_editor(cx, |_, editor, cx| {
                 let map = editor.snapshot(cx);
                 let mut ranges: Vec<Range<Anchor>> = Vec::new();
@@ -1,15 +1,15 @@
-use std::{ops::Range, sync::OnceLock, time::Duration};
+use std::{mem, ops::Range, time::Duration};
 
-use gpui::{actions, impl_actions, ViewContext};
+use gpui::{actions, impl_actions, ViewContext, WindowContext};
 use language::Point;
-use regex::Regex;
 use search::{buffer_search, BufferSearchBar, SearchOptions};
 use serde_derive::Deserialize;
 use workspace::{searchable::Direction, Workspace};
 
 use crate::{
+    helix::select_within_selections,
     motion::{search_motion, Motion},
-    normal::move_cursor,
+    normal::{mark::record_jump, move_cursor},
     state::{Mode, SearchState},
     Vim,
 };
@@ -40,30 +40,21 @@ pub struct FindCommand {
     pub backwards: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-pub struct ReplaceCommand {
-    pub query: String,
-}
-
+/// A replacement run through the buffer search bar.
 #[derive(Debug, Default)]
-struct Replacement {
-    search: String,
-    replacement: String,
-    should_replace_all: bool,
-    is_case_sensitive: bool,
-    range: Option<Range<usize>>,
+pub(crate) struct Replacement {
+    /// The pattern in Zed's regex syntax, or empty to reuse the current query.
+    pub search: String,
+    pub replacement: String,
+    /// Whether to replace every match at once, rather than stepping through them.
+    pub should_replace_all: bool,
+    pub is_case_sensitive: bool,
+    /// The 1-based lines to search within.
+    pub range: Option<Range<usize>>,
 }
 
 actions!(vim, [SearchSubmit, MoveToNextMatch, MoveToPrevMatch]);
-impl_actions!(
-    vim,
-    [FindCommand, ReplaceCommand, Search, MoveToPrev, MoveToNext]
-);
-
-static RANGE_REGEX: OnceLock<Regex> = OnceLock::new();
-pub(crate) fn range_regex() -> &'static Regex {
-    RANGE_REGEX.get_or_init(|| Regex::new(r"^(\d+),(\d+)s(.*)").unwrap())
-}
+impl_actions!(vim, [FindCommand, Search, MoveToPrev, MoveToNext]);
 
 pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(move_to_next);
@@ -75,7 +66,6 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
     workspace.register_action(search_deploy);
 
     workspace.register_action(find_command);
-    workspace.register_action(replace_command);
 }
 
 fn move_to_next(workspace: &mut Workspace, action: &MoveToNext, cx: &mut ViewContext<Workspace>) {
@@ -112,6 +102,9 @@ fn search(workspace: &mut Workspace, action: &Search, cx: &mut ViewContext<Works
     Vim::update(cx, |vim, cx| {
         let count = vim.take_count(cx).unwrap_or(1);
         let prior_selections = vim.editor_selections(cx);
+        if vim.active_operator().is_none() {
+            record_jump(vim, cx);
+        }
         pane.update(cx, |pane, cx| {
             if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
                 search_bar.update(cx, |search_bar, cx| {
@@ -134,6 +127,7 @@ fn search(workspace: &mut Workspace, action: &Search, cx: &mut ViewContext<Works
                         prior_selections,
                         prior_operator: vim.active_operator(),
                         prior_mode: vim.state().mode,
+                        within_selections: None,
                     };
                 });
             }
@@ -151,10 +145,18 @@ fn search_submit(workspace: &mut Workspace, _: &SearchSubmit, cx: &mut ViewConte
     let mut motion = None;
     Vim::update(cx, |vim, cx| {
         let pane = workspace.active_pane().clone();
+        let mut selection_search = None;
         pane.update(cx, |pane, cx| {
             if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
                 search_bar.update(cx, |search_bar, cx| {
                     let state = &mut vim.workspace_state.search;
+                    if let Some(search) = state.within_selections.take() {
+                        let prior_selections = mem::take(&mut state.prior_selections);
+                        selection_search = Some((search, search_bar.query(cx), prior_selections));
+                        search_bar.dismiss(&buffer_search::Dismiss, cx);
+                        return;
+                    }
+
                     let mut count = state.count;
                     let direction = state.direction;
 
@@ -168,6 +170,8 @@ fn search_submit(workspace: &mut Workspace, _: &SearchSubmit, cx: &mut ViewConte
                     vim.workspace_state
                         .registers
                         .insert('/', search_bar.query(cx).into());
+                    vim.workspace_state.last_search_pattern =
+                        Some(search_bar_pattern(search_bar, cx));
                     state.count = 1;
                     search_bar.select_match(direction, count, cx);
                     search_bar.focus_editor(&Default::default(), cx);
@@ -200,6 +204,9 @@ fn search_submit(workspace: &mut Workspace, _: &SearchSubmit, cx: &mut ViewConte
                 });
             }
         });
+        if let Some((search, query, prior_selections)) = selection_search {
+            select_within_selections(vim, search, &query, prior_selections, cx);
+        }
     });
 
     if let Some(motion) = motion {
@@ -207,6 +214,16 @@ fn search_submit(workspace: &mut Workspace, _: &SearchSubmit, cx: &mut ViewConte
     }
 }
 
+/// The search bar's query as a regex, escaped unless the regex option is on.
+fn search_bar_pattern(search_bar: &BufferSearchBar, cx: &WindowContext) -> String {
+    let query = search_bar.query(cx);
+    if search_bar.has_search_option(SearchOptions::REGEX) {
+        query
+    } else {
+        regex::escape(&query)
+    }
+}
+
 pub fn move_to_match_internal(
     workspace: &mut Workspace,
     direction: Direction,
@@ -217,6 +234,9 @@ pub fn move_to_match_internal(
         let pane = workspace.active_pane().clone();
         let count = vim.take_count(cx).unwrap_or(1);
         let prior_selections = vim.editor_selections(cx);
+        if vim.active_operator().is_none() {
+            record_jump(vim, cx);
+        }
 
         pane.update(cx, |pane, cx| {
             if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
@@ -250,6 +270,9 @@ pub fn move_to_internal(
         let pane = workspace.active_pane().clone();
         let count = vim.take_count(cx).unwrap_or(1);
         let prior_selections = vim.editor_selections(cx);
+        if vim.active_operator().is_none() {
+            record_jump(vim, cx);
+        }
 
         pane.update(cx, |pane, cx| {
             if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
@@ -267,6 +290,7 @@ pub fn move_to_internal(
                     if whole_word {
                         query = format!(r"\<{}\>", query);
                     }
+                    vim.workspace_state.last_search_pattern = Some(query.clone());
                     Some(search_bar.search(&query, Some(options), cx))
                 });
 
@@ -308,10 +332,15 @@ fn find_command(workspace: &mut Workspace, action: &FindCommand, cx: &mut ViewCo
                 if !search_bar.show(cx) {
                     return None;
                 }
-                let mut query = action.query.clone();
-                if query == "" {
-                    query = search_bar.query(cx);
-                };
+                // an empty query repeats the last search.
+                let query = Vim::update(cx, |vim, cx| {
+                    let query = Some(action.query.clone())
+                        .filter(|query| !query.is_empty())
+                        .or_else(|| vim.workspace_state.last_search_pattern.clone())
+                        .unwrap_or_else(|| search_bar.query(cx));
+                    vim.workspace_state.last_search_pattern = Some(query.clone());
+                    query
+                });
 
                 Some(search_bar.search(
                     &query,
@@ -338,12 +367,11 @@ fn find_command(workspace: &mut Workspace, action: &FindCommand, cx: &mut ViewCo
     })
 }
 
-fn replace_command(
+pub(crate) fn replace_with_search_bar(
     workspace: &mut Workspace,
-    action: &ReplaceCommand,
+    replacement: Replacement,
     cx: &mut ViewContext<Workspace>,
 ) {
-    let replacement = parse_replace_all(&action.query);
     let pane = workspace.active_pane().clone();
     let mut editor = Vim::read(cx)
         .active_editor
@@ -419,97 +447,6 @@ fn replace_command(
     })
 }
 
-// convert a vim query into something more usable by zed.
-// we don't attempt to fully convert between the two regex syntaxes,
-// but we do flip \( and \) to ( and ) (and vice-versa) in the pattern,
-// and convert \0..\9 to $0..$9 in the replacement so that common idioms work.
-fn parse_replace_all(query: &str) -> Replacement {
-    let mut chars = query.chars();
-    let mut range = None;
-    let maybe_line_range_and_rest: Option<(Range<usize>, &str)> =
-        range_regex().captures(query).map(|captures| {
-            (
-                captures.get(1).unwrap().as_str().parse().unwrap()
-                    ..captures.get(2).unwrap().as_str().parse().unwrap(),
-                captures.get(3).unwrap().as_str(),
-            )
-        });
-    if maybe_line_range_and_rest.is_some() {
-        let (line_range, rest) = maybe_line_range_and_rest.unwrap();
-        range = Some(line_range);
-        chars = rest.chars();
-    } else if Some('%') != chars.next() || Some('s') != chars.next() {
-        return Replacement::default();
-    }
-
-    let Some(delimiter) = chars.next() else {
-        return Replacement::default();
-    };
-
-    let mut search = String::new();
-    let mut replacement = String::new();
-    let mut flags = String::new();
-
-    let mut buffer = &mut search;
-
-    let mut escaped = false;
-    // 0 - parsing search
-    // 1 - parsing replacement
-    // 2 - parsing flags
-    let mut phase = 0;
-
-    for c in chars {
-        if escaped {
-            escaped = false;
-            if phase == 1 && c.is_digit(10) {
-                buffer.push('$')
-            // unescape escaped parens
-            } else if phase == 0 && c == '(' || c == ')' {
-            } else if c != delimiter {
-                buffer.push('\\')
-            }
-            buffer.push(c)
-        } else if c == '\\' {
-            escaped = true;
-        } else if c == delimiter {
-            if phase == 0 {
-                buffer = &mut replacement;
-                phase = 1;
-            } else if phase == 1 {
-                buffer = &mut flags;
-                phase = 2;
-            } else {
-                break;
-            }
-        } else {
-            // escape unescaped parens
-            if phase == 0 && c == '(' || c == ')' {
-                buffer.push('\\')
-            }
-            buffer.push(c)
-        }
-    }
-
-    let mut replacement = Replacement {
-        search,
-        replacement,
-        should_replace_all: true,
-        is_case_sensitive: true,
-        range,
-    };
-
-    for c in flags.chars() {
-        match c {
-            'g' | 'I' => {}
-            'c' | 'n' => replacement.should_replace_all = false,
-            'i' => replacement.is_case_sensitive = false,
-            _ => {}
-        }
-    }
-
-    replacement
-}
-
 #[cfg(test)]
 mod test {
     use std::time::Duration;
@@ -159,6 +159,7 @@ fn copy_selections_content_internal(
         Register {
             text: text.into(),
             clipboard_selections: Some(clipboard_selections),
+            recorded_actions: None,
         },
         selected_register,
         is_yank,
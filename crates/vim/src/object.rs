@@ -16,7 +16,7 @@ use editor::{
 use itertools::Itertools;
 
 use gpui::{actions, impl_actions, ViewContext, WindowContext};
-use language::{char_kind, BufferSnapshot, CharKind, Point, Selection};
+use language::{char_kind, BufferSnapshot, CharKind, Point, Selection, TextObject};
 use multi_buffer::MultiBufferRow;
 use serde::Deserialize;
 use workspace::Workspace;
@@ -36,6 +36,9 @@ pub enum Object {
     AngleBrackets,
     Argument,
     Tag,
+    Method,
+    Class,
+    Comment,
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -61,7 +64,10 @@ actions!(
         CurlyBrackets,
         AngleBrackets,
         Argument,
-        Tag
+        Tag,
+        Method,
+        Class,
+        Comment
     ]
 );
 
@@ -99,12 +105,17 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     });
     workspace
         .register_action(|_: &mut Workspace, _: &Argument, cx: _| object(Object::Argument, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Method, cx: _| object(Object::Method, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Class, cx: _| object(Object::Class, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Comment, cx: _| object(Object::Comment, cx));
 }
 
 fn object(object: Object, cx: &mut WindowContext) {
     match Vim::read(cx).state().mode {
         Mode::Normal => normal_object(object, cx),
-        Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_object(object, cx),
+        Mode::Visual | Mode::VisualLine | Mode::VisualBlock | Mode::HelixNormal => {
+            visual_object(object, cx)
+        }
         Mode::Insert | Mode::Replace => {
             // Shouldn't execute a text object in insert mode. Ignoring
         }
@@ -126,13 +137,22 @@ impl Object {
             | Object::AngleBrackets
             | Object::CurlyBrackets
             | Object::SquareBrackets
-            | Object::Argument => true,
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => true,
         }
     }
 
     pub fn always_expands_both_ways(self) -> bool {
         match self {
-            Object::Word { .. } | Object::Sentence | Object::Paragraph | Object::Argument => false,
+            Object::Word { .. }
+            | Object::Sentence
+            | Object::Paragraph
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => false,
             Object::Quotes
             | Object::BackQuotes
             | Object::DoubleQuotes
@@ -146,6 +166,9 @@ impl Object {
     }
 
     pub fn target_visual_mode(self, current_mode: Mode) -> Mode {
+        if current_mode == Mode::HelixNormal {
+            return Mode::HelixNormal;
+        }
         match self {
             Object::Word { .. }
             | Object::Sentence
@@ -164,7 +187,10 @@ impl Object {
             | Object::AngleBrackets
             | Object::VerticalBars
             | Object::Tag
-            | Object::Argument => Mode::Visual,
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => Mode::Visual,
             Object::Paragraph => Mode::VisualLine,
         }
     }
@@ -212,6 +238,33 @@ impl Object {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '<', '>')
             }
             Object::Argument => argument(map, relative_to, around),
+            Object::Method => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundFunction
+                } else {
+                    TextObject::InsideFunction
+                },
+            ),
+            Object::Class => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundClass
+                } else {
+                    TextObject::InsideClass
+                },
+            ),
+            Object::Comment => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundComment
+                } else {
+                    TextObject::InsideComment
+                },
+            ),
         }
     }
 
@@ -414,6 +467,164 @@ fn around_next_word(
     Some(start..end)
 }
 
+/// Returns the range of the syntax-aware text object that contains `relative_to`, as captured by
+/// the `textobjects.scm` query of the language at that point.
+///
+/// Inside objects are looked up within the smallest surrounding object, so that e.g. `if` selects
+/// the body of the function even when the cursor is on the function's signature.
+fn text_object(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    target: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let snapshot = &map.buffer_snapshot;
+    let offset = relative_to.to_offset(map, Bias::Left);
+    let excerpt = snapshot.excerpt_containing(offset..offset)?;
+    let buffer = excerpt.buffer();
+    let offset = excerpt.map_offset_to_buffer(offset);
+
+    let around_object = target.around().unwrap_or(target);
+    let around_range = buffer
+        .text_object_ranges(offset..(offset + 1).min(buffer.len()))
+        .filter(|(range, object)| *object == around_object && range.contains(&offset))
+        .min_by_key(|(range, _)| range.len())?
+        .0;
+
+    let range = if target == around_object {
+        expand_to_whole_lines(buffer, around_range)
+    } else {
+        let inside_range = buffer
+            .text_object_ranges(around_range.clone())
+            .filter(|(range, object)| {
+                *object == target
+                    && range.start >= around_range.start
+                    && range.end <= around_range.end
+            })
+            .max_by_key(|(range, _)| range.len())
+            .map(|(range, _)| range);
+        match inside_range {
+            Some(range) => range,
+            None if target == TextObject::InsideComment => comment_contents(buffer, around_range)?,
+            None => return None,
+        }
+    };
+
+    if excerpt.contains_buffer_range(range.clone()) {
+        let result = excerpt.map_range_from_buffer(range);
+        Some(result.start.to_display_point(map)..result.end.to_display_point(map))
+    } else {
+        None
+    }
+}
+
+/// Expands a range that's alone on its lines to cover those lines entirely, including the
+/// trailing newline, so that e.g. deleting a function doesn't leave an empty line behind.
+fn expand_to_whole_lines(buffer: &BufferSnapshot, range: Range<usize>) -> Range<usize> {
+    let start = buffer.offset_to_point(range.start);
+    let mut end = buffer.offset_to_point(range.end);
+    // Some grammars include the trailing newline in nodes such as line comments
+    if end.column == 0 && end.row > start.row {
+        end = Point::new(end.row - 1, buffer.line_len(end.row - 1));
+    }
+    let line_start = Point::new(start.row, 0);
+    let line_end = Point::new(end.row, buffer.line_len(end.row));
+    if !buffer
+        .chars_for_range(line_start..start)
+        .chain(buffer.chars_for_range(end..line_end))
+        .all(char::is_whitespace)
+    {
+        return range;
+    }
+
+    let end = if end.row < buffer.max_point().row {
+        Point::new(end.row + 1, 0)
+    } else {
+        line_end
+    };
+    buffer.point_to_offset(line_start)..buffer.point_to_offset(end)
+}
+
+/// Returns the text of a comment without its delimiters, for languages whose text object query
+/// doesn't capture the inside of comments.
+fn comment_contents(buffer: &BufferSnapshot, range: Range<usize>) -> Option<Range<usize>> {
+    let scope = buffer.language_scope_at(range.start)?;
+    let text = buffer.text_for_range(range.clone()).collect::<String>();
+
+    let mut start = range.start;
+    let mut end = range.end;
+    if let Some((open, close)) = scope.block_comment_delimiters().filter(|(open, close)| {
+        text.len() >= open.len() + close.len()
+            && text.starts_with(open.as_ref())
+            && text.ends_with(close.as_ref())
+    }) {
+        start += open.len();
+        end -= close.len();
+    } else if let Some(prefix) = scope
+        .line_comment_prefixes()
+        .iter()
+        .map(|prefix| prefix.trim_end())
+        .filter(|prefix| text.starts_with(prefix))
+        .max_by_key(|prefix| prefix.len())
+    {
+        start += prefix.len();
+    }
+
+    start += buffer
+        .chars_at(start)
+        .take_while(|c| c.is_whitespace())
+        .map(char::len_utf8)
+        .sum::<usize>();
+    end -= buffer
+        .reversed_chars_at(end)
+        .take_while(|c| c.is_whitespace())
+        .map(char::len_utf8)
+        .sum::<usize>();
+    Some(start..end.max(start))
+}
+
+/// Returns the parameter or argument containing `offset`, as captured by the language's text
+/// object query. The around range includes the comma separating it from the next parameter,
+/// or from the previous one for the last parameter.
+fn parameter_range(buffer: &BufferSnapshot, offset: usize, around: bool) -> Option<Range<usize>> {
+    let inside_range = buffer
+        .text_object_ranges(offset..(offset + 1).min(buffer.len()))
+        .filter(|(range, object)| *object == TextObject::InsideParameter && range.contains(&offset))
+        .min_by_key(|(range, _)| range.len())?
+        .0;
+    if !around {
+        return Some(inside_range);
+    }
+
+    let around_range = buffer
+        .text_object_ranges(inside_range.clone())
+        .filter(|(range, object)| {
+            *object == TextObject::AroundParameter
+                && range.start <= inside_range.start
+                && range.end >= inside_range.end
+        })
+        .min_by_key(|(range, _)| range.len());
+    if let Some((range, _)) = around_range {
+        return Some(range);
+    }
+
+    fn whitespace_len(chars: impl Iterator<Item = char>) -> usize {
+        chars
+            .take_while(|c| c.is_whitespace())
+            .map(char::len_utf8)
+            .sum()
+    }
+    let comma = inside_range.end + whitespace_len(buffer.chars_at(inside_range.end));
+    if buffer.chars_at(comma).next() == Some(',') {
+        let next = comma + 1;
+        return Some(inside_range.start..next + whitespace_len(buffer.chars_at(next)));
+    }
+    let comma = inside_range.start - whitespace_len(buffer.reversed_chars_at(inside_range.start));
+    if buffer.reversed_chars_at(comma).next() == Some(',') {
+        return Some(comma - 1..inside_range.end);
+    }
+    Some(inside_range)
+}
+
 fn argument(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
@@ -555,7 +766,11 @@ fn argument(
         Some(start..end)
     }
 
-    let result = comma_delimited_range_at(buffer, excerpt.map_offset_to_buffer(offset), around)?;
+    // Prefer the parameters captured by the language's text object query, falling back to the
+    // comma-delimited range around the cursor
+    let offset = excerpt.map_offset_to_buffer(offset);
+    let result = parameter_range(buffer, offset, around)
+        .or_else(|| comma_delimited_range_at(buffer, offset, around))?;
 
     if excerpt.contains_buffer_range(result.clone()) {
         let result = excerpt.map_range_from_buffer(result);
@@ -1437,6 +1652,74 @@ mod test {
         cx.assert_state("let a = [«test::call(first_arg)ˇ»]", Mode::Visual);
     }
 
+    #[gpui::test]
+    async fn test_syntax_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        const CODE: &str = indoc! {"
+            struct Foo {
+                a: u32,
+            }
+
+            impl Foo {
+                // Returns a
+                fn a(&self) -> u32 { self.a }
+
+                fn b(&self) -> u32 {
+                    let b = self.a;
+                    b
+                }
+            }
+        "};
+
+        cx.set_state(&CODE.replace("self.a }", "self.ˇa }"), Mode::Normal);
+        cx.simulate_keystrokes("c i f");
+        cx.assert_state(&CODE.replace("{ self.a }", "{ ˇ }"), Mode::Insert);
+
+        // Whole functions are deleted along with their line
+        cx.set_state(&CODE.replace("        b\n", "        ˇb\n"), Mode::Normal);
+        cx.simulate_keystrokes("d a f");
+        cx.assert_state(
+            indoc! {"
+                struct Foo {
+                    a: u32,
+                }
+
+                impl Foo {
+                    // Returns a
+                    fn a(&self) -> u32 { self.a }
+
+                ˇ}
+            "},
+            Mode::Normal,
+        );
+
+        cx.set_state(&CODE.replace("a: u32", "a: ˇu32"), Mode::Normal);
+        cx.simulate_keystrokes("d a c");
+        cx.assert_state(
+            &CODE.replace("struct Foo {\n    a: u32,\n}\n", "ˇ"),
+            Mode::Normal,
+        );
+
+        cx.set_state(&CODE.replace("Returns", "Reˇturns"), Mode::Normal);
+        cx.simulate_keystrokes("d a g c");
+        cx.assert_state(
+            &CODE.replace("    // Returns a\n    fn a", "ˇ    fn a"),
+            Mode::Normal,
+        );
+
+        // The inside of the function is found from its signature
+        cx.set_state(&CODE.replace("fn b", "fn ˇb"), Mode::Normal);
+        cx.simulate_keystrokes("v i f");
+        cx.assert_state(
+            &CODE.replace(
+                "let b = self.a;\n        b",
+                "«let b = self.a;\n        bˇ»",
+            ),
+            Mode::Visual,
+        );
+    }
+
     #[gpui::test]
     async fn test_delete_surrounding_character_objects(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection!(
+    // Current schema shape using pseudo-rust syntax:
+    // vim_marks(
+    //   workspace_id: usize,
+    //   mark: String,
+    //   path: PathBuf,
+    //   line: u32,
+    //   col: u32,
+    // )
+    //
+    // vim_jumps(
+    //   workspace_id: usize,
+    //   position: usize,
+    //   path: PathBuf,
+    //   line: u32,
+    //   col: u32,
+    // )
+    pub static ref DB: VimDb<WorkspaceDb> =
+        &[sql! (
+            CREATE TABLE vim_marks(
+                workspace_id INTEGER NOT NULL,
+                mark TEXT NOT NULL,
+                path BLOB NOT NULL,
+                line INTEGER NOT NULL,
+                col INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, mark),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+
+            CREATE TABLE vim_jumps(
+                workspace_id INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                line INTEGER NOT NULL,
+                col INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, position),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        )];
+);
+
+impl VimDb {
+    query! {
+        pub fn get_marks(workspace_id: WorkspaceId) -> Result<Vec<(String, PathBuf, u32, u32)>> {
+            SELECT mark, path, line, col
+            FROM vim_marks
+            WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_mark(
+            workspace_id: WorkspaceId,
+            mark: String,
+            path: PathBuf,
+            line: u32,
+            col: u32
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO vim_marks(workspace_id, mark, path, line, col)
+            VALUES (?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub async fn delete_mark(workspace_id: WorkspaceId, mark: String) -> Result<()> {
+            DELETE FROM vim_marks
+            WHERE workspace_id = ? AND mark = ?
+        }
+    }
+
+    query! {
+        pub fn get_jumps(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, u32, u32)>> {
+            SELECT path, line, col
+            FROM vim_jumps
+            WHERE workspace_id = ?
+            ORDER BY position
+        }
+    }
+
+    /// Replaces the jump list saved for the workspace.
+    pub async fn save_jumps(
+        &self,
+        workspace_id: WorkspaceId,
+        jumps: Vec<(PathBuf, u32, u32)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_jumps", || {
+                conn.exec_bound(sql!(DELETE FROM vim_jumps WHERE workspace_id = ?))?(workspace_id)?;
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO vim_jumps(workspace_id, position, path, line, col)
+                    VALUES (?, ?, ?, ?, ?)
+                ))?;
+                for (position, (path, line, col)) in jumps.into_iter().enumerate() {
+                    insert((workspace_id, position, path, line, col))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+}
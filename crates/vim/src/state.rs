@@ -1,14 +1,15 @@
-use std::{fmt::Display, ops::Range, sync::Arc};
+use std::{collections::VecDeque, fmt::Display, ops::Range, path::PathBuf, sync::Arc};
 
 use crate::surrounds::SurroundsType;
 use crate::{motion::Motion, object::Object};
 use collections::HashMap;
-use editor::{Anchor, ClipboardSelection};
-use gpui::{Action, ClipboardItem, KeyContext};
-use language::{CursorShape, Selection, TransactionId};
+use editor::{Anchor, ClipboardSelection, Editor};
+use gpui::{Action, AppContext, ClipboardItem, EntityId, KeyContext, Keystroke, View, WeakModel};
+use language::{Buffer, CursorShape, Point, Selection, ToPoint, TransactionId};
+use project::{Project, ProjectPath};
 use serde::{Deserialize, Serialize};
 use ui::SharedString;
-use workspace::searchable::Direction;
+use workspace::{searchable::Direction, WorkspaceId};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Mode {
@@ -18,6 +19,8 @@ pub enum Mode {
     Visual,
     VisualLine,
     VisualBlock,
+    /// Helix-style normal mode, where motions select text.
+    HelixNormal,
 }
 
 impl Display for Mode {
@@ -29,6 +32,7 @@ impl Display for Mode {
             Mode::Visual => write!(f, "VISUAL"),
             Mode::VisualLine => write!(f, "VISUAL LINE"),
             Mode::VisualBlock => write!(f, "VISUAL BLOCK"),
+            Mode::HelixNormal => write!(f, "HELIX NORMAL"),
         }
     }
 }
@@ -36,7 +40,7 @@ impl Display for Mode {
 impl Mode {
     pub fn is_visual(&self) -> bool {
         match self {
-            Mode::Normal | Mode::Insert | Mode::Replace => false,
+            Mode::Normal | Mode::Insert | Mode::Replace | Mode::HelixNormal => false,
             Mode::Visual | Mode::VisualLine | Mode::VisualBlock => true,
         }
     }
@@ -68,6 +72,8 @@ pub enum Operator {
     Uppercase,
     OppositeCase,
     Register,
+    RecordRegister,
+    ReplayRegister,
 }
 
 #[derive(Default, Clone)]
@@ -119,6 +125,9 @@ pub enum RecordedSelection {
 pub struct Register {
     pub(crate) text: SharedString,
     pub(crate) clipboard_selections: Option<Vec<ClipboardSelection>>,
+    /// The actions recorded into this register with `q`, replayed by `@` in
+    /// preference to `text` until the register is overwritten.
+    pub(crate) recorded_actions: Option<Arc<[ReplayableAction]>>,
 }
 
 impl From<Register> for ClipboardItem {
@@ -137,6 +146,7 @@ impl From<ClipboardItem> for Register {
         Register {
             text: value.text().to_owned().into(),
             clipboard_selections: value.metadata::<Vec<ClipboardSelection>>(),
+            recorded_actions: None,
         }
     }
 }
@@ -146,6 +156,7 @@ impl From<String> for Register {
         Register {
             text: text.into(),
             clipboard_selections: None,
+            recorded_actions: None,
         }
     }
 }
@@ -164,6 +175,85 @@ pub struct WorkspaceState {
 
     pub last_yank: Option<SharedString>,
     pub registers: HashMap<char, Register>,
+
+    /// The register being recorded into with `q`, if any.
+    pub recording_register: Option<char>,
+    pub recorded_register_text: String,
+    pub recorded_register_actions: Vec<ReplayableAction>,
+    pub ignore_current_insertion: bool,
+    pub replaying_register: bool,
+    pub last_replayed_register: Option<char>,
+    pub register_replay_queue: VecDeque<ReplayStep>,
+    /// Whether the keystrokes being replayed come from a `noremap` mapping,
+    /// and so must not trigger other mappings.
+    pub noremap: bool,
+    /// How many mappings have been expanded during the current replay.
+    pub mapping_expansions: usize,
+
+    pub last_substitute: Option<SubstituteState>,
+    /// The pattern most recently searched for, substituted or used by `:g`,
+    /// in Zed's regex syntax. An empty pattern such as `:s//x/` refers to it.
+    pub last_search_pattern: Option<String>,
+    /// The transactions begun since a replayed `StartUndoGroup` step, if one is
+    /// in progress.
+    pub replay_undo_group: Option<Vec<TransactionId>>,
+
+    /// The global marks and jump list of each open workspace, keyed by the
+    /// workspace's entity id.
+    pub workspace_marks: HashMap<EntityId, WorkspaceMarks>,
+}
+
+/// The marks that can refer to any file in a workspace (`A`-`Z` and `0`-`9`),
+/// and the jump list. Both are saved in the workspace database.
+#[derive(Clone)]
+pub struct WorkspaceMarks {
+    pub workspace_id: Option<WorkspaceId>,
+    pub project: WeakModel<Project>,
+    /// Whether the marks saved for this workspace have been read from the
+    /// database yet.
+    pub loaded: bool,
+    pub marks: HashMap<char, FileMark>,
+    /// The positions jumped away from, oldest first.
+    pub jumps: Vec<FileMark>,
+    /// The marks as they were last saved to (or read from) the database, by name.
+    pub saved_marks: HashMap<char, (PathBuf, u32, u32)>,
+    /// The jump list as it was last saved to (or read from) the database.
+    pub saved_jumps: Vec<(PathBuf, u32, u32)>,
+    /// Whether the changes to the marks and jump list will be saved shortly.
+    pub save_scheduled: bool,
+}
+
+/// A position in a file of the project, which follows edits to the file
+/// while it is open.
+#[derive(Clone, Debug)]
+pub struct FileMark {
+    pub path: ProjectPath,
+    pub anchor: Option<(WeakModel<Buffer>, language::Anchor)>,
+    /// The position when the mark was set or loaded, used once the buffer
+    /// has been closed.
+    pub point: Point,
+}
+
+impl FileMark {
+    pub fn point(&self, cx: &AppContext) -> Point {
+        match &self.anchor {
+            Some((buffer, anchor)) => match buffer.upgrade() {
+                Some(buffer) => anchor.to_point(buffer.read(cx)),
+                None => self.point,
+            },
+            None => self.point,
+        }
+    }
+}
+
+/// The last `:s` command, which `:&` repeats and `~` refers to.
+#[derive(Clone, Debug, Default)]
+pub struct SubstituteState {
+    /// The pattern, in Zed's regex syntax.
+    pub pattern: String,
+    /// The replacement, in Vim's syntax.
+    pub replacement: String,
+    pub flags: String,
 }
 
 #[derive(Debug)]
@@ -190,6 +280,24 @@ impl Clone for ReplayableAction {
     }
 }
 
+/// A single step of a register being replayed with `@` (or of `:normal`).
+#[derive(Clone, Debug)]
+pub enum ReplayStep {
+    /// An action recorded with `q`.
+    Action(ReplayableAction),
+    /// A keystroke parsed from the text of the register.
+    Keystroke(Keystroke),
+    /// Moves the cursor to the first non-blank character of the anchor's line.
+    MoveToLine(Anchor),
+    /// Starts grouping the edits of the steps that follow into one undo step.
+    StartUndoGroup,
+    /// Groups the edits made in the editor since `StartUndoGroup` into one undo step.
+    EndUndoGroup(View<Editor>),
+    /// Sets whether the keystrokes that follow may trigger vimrc mappings,
+    /// around the right-hand side of a mapping.
+    SetRemap(bool),
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct SearchState {
     pub direction: Direction,
@@ -199,6 +307,17 @@ pub struct SearchState {
     pub prior_selections: Vec<Range<Anchor>>,
     pub prior_operator: Option<Operator>,
     pub prior_mode: Mode,
+    /// Set when searching within the selections of Helix mode.
+    pub within_selections: Option<SelectionSearch>,
+}
+
+/// What Helix mode does with the matches of a search within its selections.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SelectionSearch {
+    /// Select the matches.
+    Select,
+    /// Split the selections on the matches.
+    Split,
 }
 
 impl EditorState {
@@ -212,7 +331,9 @@ impl EditorState {
                 }
             }
             Mode::Replace => CursorShape::Underscore,
-            Mode::Visual | Mode::VisualLine | Mode::VisualBlock => CursorShape::Block,
+            Mode::Visual | Mode::VisualLine | Mode::VisualBlock | Mode::HelixNormal => {
+                CursorShape::Block
+            }
             Mode::Insert => CursorShape::Bar,
         }
     }
@@ -228,6 +349,8 @@ impl EditorState {
                 | Some(Operator::FindBackward { .. })
                 | Some(Operator::Mark)
                 | Some(Operator::Register)
+                | Some(Operator::RecordRegister)
+                | Some(Operator::ReplayRegister)
                 | Some(Operator::Jump { .. })
         )
     }
@@ -238,9 +361,12 @@ impl EditorState {
 
     pub fn clip_at_line_ends(&self) -> bool {
         match self.mode {
-            Mode::Insert | Mode::Visual | Mode::VisualLine | Mode::VisualBlock | Mode::Replace => {
-                false
-            }
+            Mode::Insert
+            | Mode::Visual
+            | Mode::VisualLine
+            | Mode::VisualBlock
+            | Mode::Replace
+            | Mode::HelixNormal => false,
             Mode::Normal => true,
         }
     }
@@ -258,6 +384,7 @@ impl EditorState {
                 Mode::Visual | Mode::VisualLine | Mode::VisualBlock => "visual",
                 Mode::Insert => "insert",
                 Mode::Replace => "replace",
+                Mode::HelixNormal => "helix_normal",
             },
         );
 
@@ -319,6 +446,8 @@ impl Operator {
             Operator::Lowercase => "gu",
             Operator::OppositeCase => "g~",
             Operator::Register => "\"",
+            Operator::RecordRegister => "q",
+            Operator::ReplayRegister => "@",
         }
     }
 
@@ -330,6 +459,8 @@ impl Operator {
             | Operator::Jump { .. }
             | Operator::FindBackward { .. }
             | Operator::Register
+            | Operator::RecordRegister
+            | Operator::ReplayRegister
             | Operator::Replace
             | Operator::AddSurrounds { target: Some(_) }
             | Operator::ChangeSurrounds { .. }
@@ -13,6 +13,7 @@ pub use vim_test_context::*;
 
 use indoc::indoc;
 use search::BufferSearchBar;
+use settings::{KeymapFile, Vimrc};
 
 use crate::{insert::NormalBefore, motion, state::Mode, ModeIndicator};
 
@@ -849,6 +850,38 @@ async fn test_jk_delay(cx: &mut gpui::TestAppContext) {
     cx.assert_state("jˇkhello", Mode::Normal);
 }
 
+#[gpui::test]
+async fn test_vimrc_mappings(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    let vimrc = Vimrc::parse("nnoremap j k\nnmap Q j\nnnoremap X j\nnmap a b\nnmap b a");
+    cx.update(|cx| {
+        KeymapFile::from_vim_mappings(&vimrc.mappings)
+            .add_to_cx(cx)
+            .unwrap()
+    });
+
+    cx.set_state("one\ntwo\nˇthree\nfour", Mode::Normal);
+    cx.simulate_keystrokes("j");
+    cx.run_until_parked();
+    cx.assert_state("one\nˇtwo\nthree\nfour", Mode::Normal);
+
+    // `noremap` types the keys without applying other mappings.
+    cx.simulate_keystrokes("shift-x");
+    cx.run_until_parked();
+    cx.assert_state("one\ntwo\nˇthree\nfour", Mode::Normal);
+
+    // `map` applies mappings to the keys it types.
+    cx.simulate_keystrokes("shift-q");
+    cx.run_until_parked();
+    cx.assert_state("one\nˇtwo\nthree\nfour", Mode::Normal);
+
+    // recursive mappings are abandoned.
+    cx.simulate_keystrokes("a");
+    cx.run_until_parked();
+    cx.assert_state("one\nˇtwo\nthree\nfour", Mode::Normal);
+}
+
 #[gpui::test]
 async fn test_comma_w(cx: &mut gpui::TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1073,6 +1106,25 @@ async fn test_lowercase_marks(cx: &mut TestAppContext) {
         .assert_eq("line one\nˇtwo\nline three");
 }
 
+#[gpui::test]
+async fn test_uppercase_marks(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("line one\nline ˇtwo\nline three", Mode::Normal);
+    cx.simulate_keystrokes("m A j ' A");
+    cx.assert_state("line one\nˇline two\nline three", Mode::Normal);
+    cx.simulate_keystrokes("G ` A");
+    cx.assert_state("line one\nline ˇtwo\nline three", Mode::Normal);
+
+    // the mark moves with edits to the file
+    cx.simulate_keystrokes("g g O z e r o escape ` A");
+    cx.assert_state("zero\nline one\nline ˇtwo\nline three", Mode::Normal);
+
+    cx.simulate_keystrokes(": d e l m space A enter");
+    cx.simulate_keystrokes("g g ` A");
+    cx.assert_state("ˇzero\nline one\nline two\nline three", Mode::Normal);
+}
+
 #[gpui::test]
 async fn test_lt_gt_marks(cx: &mut TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
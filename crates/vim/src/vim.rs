@@ -6,11 +6,14 @@ mod test;
 mod change_list;
 mod command;
 mod editor_events;
+mod helix;
 mod insert;
+mod marks_picker;
 mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod persistence;
 mod replace;
 mod state;
 mod surrounds;
@@ -19,14 +22,16 @@ mod visual;
 use anyhow::Result;
 use change_list::push_to_change_list;
 use collections::HashMap;
-use command_palette_hooks::{CommandPaletteFilter, CommandPaletteInterceptor};
+use command_palette_hooks::{
+    CommandPaletteFilter, CommandPaletteInterceptor, CommandPalettePreview,
+};
 use editor::{
     movement::{self, FindRange},
     Anchor, Bias, Editor, EditorEvent, EditorMode, ToPoint,
 };
 use gpui::{
-    actions, impl_actions, Action, AppContext, EntityId, FocusableView, Global, KeystrokeEvent,
-    Subscription, UpdateGlobal, View, ViewContext, WeakView, WindowContext,
+    actions, impl_actions, Action, AppContext, EntityId, FocusableView, Global, Keystroke,
+    KeystrokeEvent, Subscription, UpdateGlobal, View, ViewContext, WeakView, WindowContext,
 };
 use language::{CursorShape, Point, SelectionGoal, TransactionId};
 pub use mode_indicator::ModeIndicator;
@@ -156,10 +161,12 @@ fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
     insert::register(workspace, cx);
     motion::register(workspace, cx);
     command::register(workspace, cx);
+    helix::register(workspace, cx);
     replace::register(workspace, cx);
     object::register(workspace, cx);
     visual::register(workspace, cx);
     change_list::register(workspace, cx);
+    marks_picker::register(workspace, cx);
 }
 
 /// Called whenever an keystroke is typed so vim can observe all actions
@@ -170,17 +177,8 @@ fn observe_keystrokes(keystroke_event: &KeystrokeEvent, cx: &mut WindowContext)
         .as_ref()
         .map(|action| action.boxed_clone())
     {
-        Vim::update(cx, |vim, _| {
-            if vim.workspace_state.recording {
-                vim.workspace_state
-                    .recorded_actions
-                    .push(ReplayableAction::Action(action.boxed_clone()));
-
-                if vim.workspace_state.stop_recording_after_next_action {
-                    vim.workspace_state.recording = false;
-                    vim.workspace_state.stop_recording_after_next_action = false;
-                }
-            }
+        Vim::update(cx, |vim, cx| {
+            vim.observe_action(action.boxed_clone(), Some(&keystroke_event.keystroke), cx)
         });
 
         // Keystroke is handled by the vim system, so continue forward
@@ -201,7 +199,9 @@ fn observe_keystrokes(keystroke_event: &KeystrokeEvent, cx: &mut WindowContext)
             | Operator::DeleteSurrounds
             | Operator::Mark
             | Operator::Jump { .. }
-            | Operator::Register,
+            | Operator::Register
+            | Operator::RecordRegister
+            | Operator::ReplayRegister,
         ) => {}
         Some(_) => {
             vim.clear_operator(cx);
@@ -272,6 +272,10 @@ impl Vim {
             _ => {}
         }));
 
+        if self.state().mode == Mode::Normal && VimSettings::get_global(cx).helix_mode {
+            self.switch_mode(Mode::HelixNormal, false, cx);
+        }
+
         let editor = editor.read(cx);
         let editor_mode = editor.mode();
         let newest_selection_empty = editor.selections.newest::<usize>(cx).is_empty();
@@ -294,21 +298,42 @@ impl Vim {
         cx: &mut WindowContext,
     ) {
         Vim::update(cx, |vim, _| {
+            let insertion = ReplayableAction::Insertion {
+                text: text.clone(),
+                utf16_range_to_replace: range_to_replace,
+            };
             if vim.workspace_state.recording {
-                vim.workspace_state
-                    .recorded_actions
-                    .push(ReplayableAction::Insertion {
-                        text: text.clone(),
-                        utf16_range_to_replace: range_to_replace,
-                    });
+                vim.workspace_state.recorded_actions.push(insertion.clone());
                 if vim.workspace_state.stop_recording_after_next_action {
                     vim.workspace_state.recording = false;
                     vim.workspace_state.stop_recording_after_next_action = false;
                 }
             }
+            normal::macros::record_insertion(vim, insertion);
         });
     }
 
+    /// Records an action that was dispatched by a keystroke (or replayed from
+    /// a register), both for `.` and for any register being recorded with `q`.
+    fn observe_action(
+        &mut self,
+        action: Box<dyn Action>,
+        keystroke: Option<&Keystroke>,
+        cx: &mut WindowContext,
+    ) {
+        if self.workspace_state.recording {
+            self.workspace_state
+                .recorded_actions
+                .push(ReplayableAction::Action(action.boxed_clone()));
+
+            if self.workspace_state.stop_recording_after_next_action {
+                self.workspace_state.recording = false;
+                self.workspace_state.stop_recording_after_next_action = false;
+            }
+        }
+        normal::macros::record_action(self, action.as_ref(), keystroke, cx);
+    }
+
     fn update_active_editor<S>(
         &mut self,
         cx: &mut WindowContext,
@@ -410,6 +435,11 @@ impl Vim {
     }
 
     fn switch_mode(&mut self, mode: Mode, leave_selections: bool, cx: &mut WindowContext) {
+        let mode = if mode == Mode::Normal && VimSettings::get_global(cx).helix_mode {
+            Mode::HelixNormal
+        } else {
+            mode
+        };
         let state = self.state();
         let last_mode = state.mode;
         let prior_mode = state.last_mode;
@@ -433,6 +463,11 @@ impl Vim {
         }
 
         if leave_selections {
+            if mode == Mode::HelixNormal {
+                self.update_active_editor(cx, |_, editor, cx| {
+                    editor.change_selections(None, cx, |s| s.move_with(helix::select_cursor_char))
+                });
+            }
             return;
         }
 
@@ -478,7 +513,9 @@ impl Vim {
                 }
 
                 s.move_with(|map, selection| {
-                    if last_mode.is_visual() && !mode.is_visual() {
+                    if mode == Mode::HelixNormal && last_mode == Mode::Visual {
+                        // like Helix's select mode, leaving visual mode keeps the selection.
+                    } else if last_mode.is_visual() && !mode.is_visual() {
                         let mut point = selection.head();
                         if !selection.reversed && !selection.is_empty() {
                             point = movement::left(map, selection.head());
@@ -494,6 +531,9 @@ impl Vim {
                             selection.collapse_to(point, selection.goal)
                         }
                     }
+                    if mode == Mode::HelixNormal {
+                        helix::select_cursor_char(map, selection);
+                    }
                 });
             })
         });
@@ -559,6 +599,7 @@ impl Vim {
                 current.text = (current.text.to_string() + &content.text).into();
                 // not clear how to support appending to registers with multiple cursors
                 current.clipboard_selections.take();
+                current.recorded_actions.take();
                 let yanked = current.clone();
                 self.workspace_state.registers.insert('"', yanked);
             } else {
@@ -731,10 +772,14 @@ impl Vim {
     }
 
     fn transaction_begun(&mut self, transaction_id: TransactionId, _: &mut WindowContext) {
+        if let Some(transaction_ids) = &mut self.workspace_state.replay_undo_group {
+            transaction_ids.push(transaction_id);
+        }
         self.update_state(|state| {
             let mode = if (state.mode == Mode::Insert
                 || state.mode == Mode::Replace
-                || state.mode == Mode::Normal)
+                || state.mode == Mode::Normal
+                || state.mode == Mode::HelixNormal)
                 && state.current_tx.is_none()
             {
                 state.current_tx = Some(transaction_id);
@@ -851,7 +896,9 @@ impl Vim {
             }
             Some(Operator::Replace) => match Vim::read(cx).state().mode {
                 Mode::Normal => normal_replace(text, cx),
-                Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_replace(text, cx),
+                Mode::Visual | Mode::VisualLine | Mode::VisualBlock | Mode::HelixNormal => {
+                    visual_replace(text, cx)
+                }
                 _ => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
             },
             Some(Operator::AddSurrounds { target }) => match Vim::read(cx).state().mode {
@@ -903,6 +950,16 @@ impl Vim {
                 }
             }),
             Some(Operator::Jump { line }) => normal::mark::jump(text, line, cx),
+            Some(Operator::RecordRegister) => Vim::update(cx, |vim, cx| {
+                if let Some(register) = text.chars().next() {
+                    normal::macros::start_recording_register(vim, register, cx)
+                }
+            }),
+            Some(Operator::ReplayRegister) => {
+                if let Some(register) = text.chars().next() {
+                    normal::macros::replay_register(register, cx)
+                }
+            }
             _ => match Vim::read(cx).state().mode {
                 Mode::Replace => multi_replace(text, cx),
                 _ => {}
@@ -918,6 +975,9 @@ impl Vim {
             CommandPaletteInterceptor::update_global(cx, |interceptor, _| {
                 interceptor.clear();
             });
+            CommandPalettePreview::update_global(cx, |preview, _| {
+                preview.clear();
+            });
             CommandPaletteFilter::update_global(cx, |filter, _| {
                 filter.hide_namespace(Self::NAMESPACE);
             });
@@ -932,6 +992,9 @@ impl Vim {
         CommandPaletteInterceptor::update_global(cx, |interceptor, _| {
             interceptor.set(Box::new(command::command_interceptor));
         });
+        CommandPalettePreview::update_global(cx, |preview, _| {
+            preview.set(Box::new(command::command_preview));
+        });
 
         if let Some(active_window) = cx
             .active_window()
@@ -977,12 +1040,17 @@ impl Vim {
             let state = vim.state();
             editor.set_cursor_shape(state.cursor_shape(), cx);
             editor.set_clip_at_line_ends(state.clip_at_line_ends(), cx);
-            editor.set_collapse_matches(true);
+            // Helix selects search matches, rather than moving to them.
+            editor.set_collapse_matches(state.mode != Mode::HelixNormal);
             editor.set_input_enabled(!state.vim_controlled());
             editor.set_autoindent(state.should_autoindent());
             editor.selections.line_mode = matches!(state.mode, Mode::VisualLine);
             if editor.is_focused(cx) || editor.mouse_menu_is_focused(cx) {
-                editor.set_keymap_context_layer::<Self>(state.keymap_context_layer(), cx);
+                let mut context = state.keymap_context_layer();
+                if vim.workspace_state.noremap {
+                    context.add("VimNoremap");
+                }
+                editor.set_keymap_context_layer::<Self>(context, cx);
                 // disable vim mode if a sub-editor (inline assist, rename, etc.) is focused
             } else if editor.focus_handle(cx).contains_focused(cx) {
                 editor.remove_keymap_context_layer::<Self>(cx);
@@ -1035,6 +1103,7 @@ struct VimSettings {
     pub use_system_clipboard: UseSystemClipboard,
     pub use_multiline_find: bool,
     pub use_smartcase_find: bool,
+    pub helix_mode: bool,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1042,6 +1111,7 @@ struct VimSettingsContent {
     pub use_system_clipboard: Option<UseSystemClipboard>,
     pub use_multiline_find: Option<bool>,
     pub use_smartcase_find: Option<bool>,
+    pub helix_mode: Option<bool>,
 }
 
 impl Settings for VimSettings {
@@ -638,6 +638,38 @@ pub fn select_match(
             delete(vim, false, cx)
         }
         Some(Operator::Yank) => yank(vim, cx),
+        Some(
+            operator @ (Operator::Lowercase
+            | Operator::Uppercase
+            | Operator::OppositeCase
+            | Operator::Indent
+            | Operator::Outdent),
+        ) => {
+            vim.stop_recording();
+            vim.update_active_editor(cx, |_, editor, cx| {
+                editor.transact(cx, |editor, cx| {
+                    match operator {
+                        Operator::Lowercase => {
+                            editor.convert_to_lower_case(&Default::default(), cx)
+                        }
+                        Operator::Uppercase => {
+                            editor.convert_to_upper_case(&Default::default(), cx)
+                        }
+                        Operator::OppositeCase => {
+                            editor.convert_to_opposite_case(&Default::default(), cx)
+                        }
+                        Operator::Indent => editor.indent(&Default::default(), cx),
+                        _ => editor.outdent(&Default::default(), cx),
+                    }
+                    editor.change_selections(None, cx, |s| {
+                        s.move_with(|_, selection| {
+                            selection.collapse_to(selection.start, SelectionGoal::None)
+                        })
+                    });
+                });
+            });
+            vim.switch_mode(Mode::Normal, true, cx)
+        }
         _ => {} // Ignoring other operators
     }
 }
@@ -1254,6 +1286,19 @@ mod test {
         cx.shared_state().await.assert_eq("aa x ˇx aa aa");
     }
 
+    #[gpui::test]
+    async fn test_gn_case_operators(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("aaˇ aa aa aa aa").await;
+        cx.simulate_shared_keystrokes("/ a a enter").await;
+        cx.shared_state().await.assert_eq("aa ˇaa aa aa aa");
+        cx.simulate_shared_keystrokes("g shift-u g n").await;
+        cx.shared_state().await.assert_eq("aa ˇAA aa aa aa");
+        cx.simulate_shared_keystrokes("w .").await;
+        cx.shared_state().await.assert_eq("aa AA ˇAA aa aa");
+    }
+
     #[gpui::test]
     async fn test_cgn_nomatch(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
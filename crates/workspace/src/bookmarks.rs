@@ -0,0 +1,453 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use collections::{BTreeMap, HashMap};
+use gpui::{AppContext, Model, ModelContext, SharedString, Subscription, Task, WeakModel};
+use language::{Anchor, Buffer, BufferId, BufferSnapshot, Point};
+use util::ResultExt;
+
+use crate::{persistence::DB, WorkspaceId};
+
+const SERIALIZATION_THROTTLE_TIME: Duration = Duration::from_millis(500);
+
+/// A bookmarked line, with an optional note.
+#[derive(Clone, Debug)]
+pub struct Bookmark {
+    /// The start of the bookmarked line, while its file is open.
+    pub anchor: Option<Anchor>,
+    /// The bookmarked row, as of when its file was last open.
+    pub row: u32,
+    pub note: Option<SharedString>,
+    /// The text of the bookmarked line, as of when its file was last open,
+    /// which is used to find the line again after the file changes on disk.
+    line_text: String,
+}
+
+/// A bookmark, along with the file it's in and its current position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BookmarkEntry {
+    pub path: PathBuf,
+    pub row: u32,
+    pub line_text: String,
+    pub note: Option<SharedString>,
+}
+
+/// The bookmarks of a workspace. They're stored by the absolute paths of their
+/// files, and are anchored in the files' buffers while they're open, so that
+/// they follow the bookmarked lines as the files are edited.
+pub struct BookmarkStore {
+    workspace_id: Option<WorkspaceId>,
+    bookmarks: BTreeMap<PathBuf, Vec<Bookmark>>,
+    buffers: HashMap<BufferId, OpenBuffer>,
+    serialize_task: Option<Task<()>>,
+}
+
+struct OpenBuffer {
+    path: PathBuf,
+    buffer: WeakModel<Buffer>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl BookmarkStore {
+    pub fn new(workspace_id: Option<WorkspaceId>) -> Self {
+        let mut bookmarks = BTreeMap::<PathBuf, Vec<Bookmark>>::default();
+        let serialized_bookmarks = workspace_id
+            .and_then(|workspace_id| DB.bookmarks(workspace_id).log_err())
+            .unwrap_or_default();
+        for (path, row, line_text, note) in serialized_bookmarks {
+            bookmarks.entry(path).or_default().push(Bookmark {
+                anchor: None,
+                row,
+                note: note.map(SharedString::from),
+                line_text,
+            });
+        }
+
+        Self {
+            workspace_id,
+            bookmarks,
+            buffers: HashMap::default(),
+            serialize_task: None,
+        }
+    }
+
+    /// Anchors the bookmarks of the buffer's file in the buffer, and keeps
+    /// them anchored until the buffer is released. Only buffers of local files
+    /// can have bookmarks.
+    pub fn register_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.buffers.contains_key(&buffer_id) {
+            return;
+        }
+        let Some(path) = buffer_path(buffer, cx) else {
+            return;
+        };
+
+        self.buffers.insert(
+            buffer_id,
+            OpenBuffer {
+                path: path.clone(),
+                buffer: buffer.downgrade(),
+                _subscriptions: [
+                    cx.subscribe(
+                        buffer,
+                        |this, buffer, event: &language::Event, cx| match event {
+                            language::Event::Edited => this.buffer_edited(&buffer, cx),
+                            language::Event::Reloaded => this.reanchor_bookmarks(&buffer, cx),
+                            _ => {}
+                        },
+                    ),
+                    cx.observe_release(buffer, move |this, buffer, cx| {
+                        this.buffer_released(buffer_id, buffer, cx)
+                    }),
+                ],
+            },
+        );
+        self.reanchor_bookmarks(buffer, cx);
+    }
+
+    /// Returns the bookmarks of the buffer, if it's registered.
+    pub fn buffer_bookmarks(&self, buffer_id: BufferId) -> &[Bookmark] {
+        self.buffers
+            .get(&buffer_id)
+            .and_then(|open_buffer| self.bookmarks.get(&open_buffer.path))
+            .map_or(&[], |bookmarks| bookmarks.as_slice())
+    }
+
+    /// Returns the bookmark on the given row of the buffer.
+    pub fn bookmark_at(
+        &self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        cx: &AppContext,
+    ) -> Option<&Bookmark> {
+        let buffer = buffer.read(cx);
+        self.buffer_bookmarks(buffer.remote_id())
+            .iter()
+            .find(|bookmark| bookmark_row(bookmark, buffer) == row)
+    }
+
+    /// Adds a bookmark on the given row of the buffer, or removes the one that
+    /// is already there.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.register_buffer(buffer, cx);
+        let Some(path) = self.registered_path(buffer, cx) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let bookmarks = self.bookmarks.entry(path).or_default();
+        if let Some(ix) = bookmarks
+            .iter()
+            .position(|bookmark| bookmark_row(bookmark, buffer) == row)
+        {
+            bookmarks.remove(ix);
+        } else {
+            bookmarks.push(new_bookmark(buffer, row, None));
+        }
+        self.bookmarks_changed(cx);
+    }
+
+    /// Sets the note of the bookmark on the given row of the buffer, adding
+    /// the bookmark if there isn't one.
+    pub fn set_note(
+        &mut self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        note: Option<SharedString>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.register_buffer(buffer, cx);
+        let Some(path) = self.registered_path(buffer, cx) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let bookmarks = self.bookmarks.entry(path).or_default();
+        if let Some(bookmark) = bookmarks
+            .iter_mut()
+            .find(|bookmark| bookmark_row(bookmark, buffer) == row)
+        {
+            bookmark.note = note;
+        } else {
+            bookmarks.push(new_bookmark(buffer, row, note));
+        }
+        self.bookmarks_changed(cx);
+    }
+
+    /// Removes the bookmark at the given row of the file.
+    pub fn remove_bookmark(&mut self, path: &Path, row: u32, cx: &mut ModelContext<Self>) {
+        let buffer = self
+            .buffers
+            .values()
+            .find(|open_buffer| open_buffer.path == path)
+            .and_then(|open_buffer| open_buffer.buffer.upgrade());
+        let Some(bookmarks) = self.bookmarks.get_mut(path) else {
+            return;
+        };
+        bookmarks.retain(|bookmark| {
+            let bookmark_row = match &buffer {
+                Some(buffer) => bookmark_row(bookmark, buffer.read(cx)),
+                None => bookmark.row,
+            };
+            bookmark_row != row
+        });
+        self.bookmarks_changed(cx);
+    }
+
+    /// Returns all bookmarks, ordered by their paths and rows.
+    pub fn bookmarks(&self, cx: &AppContext) -> Vec<BookmarkEntry> {
+        let mut snapshots = HashMap::<&Path, BufferSnapshot>::default();
+        for open_buffer in self.buffers.values() {
+            if let Some(buffer) = open_buffer.buffer.upgrade() {
+                snapshots.insert(&open_buffer.path, buffer.read(cx).snapshot());
+            }
+        }
+
+        let mut entries = Vec::new();
+        for (path, bookmarks) in &self.bookmarks {
+            let snapshot = snapshots.get(path.as_path());
+            let start_ix = entries.len();
+            entries.extend(bookmarks.iter().map(|bookmark| {
+                let (row, line_text) = match (snapshot, bookmark.anchor) {
+                    (Some(snapshot), Some(anchor)) => {
+                        let row = anchor.to_point(snapshot).row;
+                        (row, line_text(snapshot, row))
+                    }
+                    _ => (bookmark.row, bookmark.line_text.clone()),
+                };
+                BookmarkEntry {
+                    path: path.clone(),
+                    row,
+                    line_text,
+                    note: bookmark.note.clone(),
+                }
+            }));
+            entries[start_ix..].sort_by_key(|entry| entry.row);
+        }
+        entries
+    }
+
+    fn registered_path(&self, buffer: &Model<Buffer>, cx: &AppContext) -> Option<PathBuf> {
+        let buffer_id = buffer.read(cx).remote_id();
+        Some(self.buffers.get(&buffer_id)?.path.clone())
+    }
+
+    fn buffer_edited(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        // The bookmarks move along with their lines, which changes their rows.
+        if !self
+            .buffer_bookmarks(buffer.read(cx).remote_id())
+            .is_empty()
+        {
+            self.schedule_serialization(cx);
+        }
+    }
+
+    /// Anchors the bookmarks of the buffer's file to the lines that have the
+    /// bookmarked text, nearest to where the bookmarks were. This finds the
+    /// lines again after they were moved by changes made outside of Zed.
+    fn reanchor_bookmarks(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let Some(path) = self.registered_path(buffer, cx) else {
+            return;
+        };
+        let Some(bookmarks) = self.bookmarks.get_mut(&path) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        for bookmark in bookmarks.iter_mut() {
+            let row_hint = bookmark
+                .anchor
+                .map_or(bookmark.row, |anchor| anchor.to_point(&snapshot).row);
+            let row = find_line(&snapshot, &bookmark.line_text, row_hint);
+            bookmark.row = row;
+            bookmark.anchor = Some(snapshot.anchor_after(Point::new(row, 0)));
+        }
+        bookmarks.sort_by_key(|bookmark| bookmark.row);
+        bookmarks.dedup_by_key(|bookmark| bookmark.row);
+        self.bookmarks_changed(cx);
+    }
+
+    fn buffer_released(
+        &mut self,
+        buffer_id: BufferId,
+        buffer: &mut Buffer,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(open_buffer) = self.buffers.remove(&buffer_id) else {
+            return;
+        };
+        let Some(bookmarks) = self.bookmarks.get_mut(&open_buffer.path) else {
+            return;
+        };
+        let snapshot = buffer.snapshot();
+        for bookmark in bookmarks.iter_mut() {
+            if let Some(anchor) = bookmark.anchor.take() {
+                bookmark.row = anchor.to_point(&snapshot).row;
+                bookmark.line_text = line_text(&snapshot, bookmark.row);
+            }
+        }
+        self.schedule_serialization(cx);
+    }
+
+    fn bookmarks_changed(&mut self, cx: &mut ModelContext<Self>) {
+        self.bookmarks.retain(|_, bookmarks| !bookmarks.is_empty());
+        self.schedule_serialization(cx);
+        cx.notify();
+    }
+
+    fn schedule_serialization(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(workspace_id) = self.workspace_id else {
+            return;
+        };
+        if self.serialize_task.is_some() {
+            return;
+        }
+        self.serialize_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(SERIALIZATION_THROTTLE_TIME)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                let bookmarks = this
+                    .bookmarks(cx)
+                    .into_iter()
+                    .map(|entry| {
+                        let note = entry.note.map(|note| note.to_string());
+                        (entry.path, entry.row, entry.line_text, note)
+                    })
+                    .collect();
+                cx.background_executor()
+                    .spawn(DB.save_bookmarks(workspace_id, bookmarks))
+                    .detach_and_log_err(cx);
+                this.serialize_task.take();
+            })
+            .log_err();
+        }));
+    }
+}
+
+fn buffer_path(buffer: &Model<Buffer>, cx: &AppContext) -> Option<PathBuf> {
+    let file = buffer.read(cx).file()?.as_local()?;
+    Some(file.abs_path(cx))
+}
+
+fn new_bookmark(buffer: &Buffer, row: u32, note: Option<SharedString>) -> Bookmark {
+    Bookmark {
+        anchor: Some(buffer.anchor_after(Point::new(row, 0))),
+        row,
+        note,
+        line_text: line_text(&buffer.snapshot(), row),
+    }
+}
+
+fn bookmark_row(bookmark: &Bookmark, buffer: &Buffer) -> u32 {
+    bookmark
+        .anchor
+        .map_or(bookmark.row, |anchor| anchor.to_point(buffer).row)
+}
+
+fn line_text(snapshot: &BufferSnapshot, row: u32) -> String {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect()
+}
+
+/// Returns the row nearest to `row_hint` whose text is `line_text`, or the
+/// hint if no row has that text.
+fn find_line(snapshot: &BufferSnapshot, text: &str, row_hint: u32) -> u32 {
+    let max_row = snapshot.max_point().row;
+    let row_hint = row_hint.min(max_row);
+    for distance in 0..=max_row {
+        let rows_above_and_below = [
+            row_hint.checked_sub(distance),
+            row_hint.checked_add(distance).filter(|row| *row <= max_row),
+        ];
+        if rows_above_and_below.iter().all(Option::is_none) {
+            break;
+        }
+        for row in rows_above_and_below.into_iter().flatten() {
+            if snapshot.line_len(row) as usize == text.len() && line_text(snapshot, row) == text {
+                return row;
+            }
+        }
+    }
+    row_hint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{Context, TestAppContext};
+    use project::Project;
+    use serde_json::json;
+
+    use crate::tests::init_test;
+
+    #[gpui::test]
+    async fn test_bookmarks_follow_their_lines(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "one\ntwo\nthree\n" }))
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let store = cx.new_model(|_| BookmarkStore::new(None));
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/a.txt", cx)
+            })
+            .await
+            .unwrap();
+        store.update(cx, |store, cx| {
+            store.toggle_bookmark(&buffer, 1, cx);
+            store.set_note(&buffer, 2, Some("third".into()), cx);
+        });
+
+        // Bookmarks move along with the lines they're on.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        let rows = |cx: &mut TestAppContext| {
+            store.read_with(cx, |store, cx| {
+                store
+                    .bookmarks(cx)
+                    .into_iter()
+                    .map(|entry| (entry.row, entry.line_text, entry.note))
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(
+            rows(cx),
+            [
+                (2, "two".to_string(), None),
+                (3, "three".to_string(), Some("third".into()))
+            ]
+        );
+
+        // Toggling a bookmarked line removes its bookmark.
+        store.update(cx, |store, cx| store.toggle_bookmark(&buffer, 2, cx));
+        assert_eq!(rows(cx), [(3, "three".to_string(), Some("third".into()))]);
+
+        // Once the buffer is closed, the file's bookmarks keep their rows, and
+        // are found again by their text if the file changes on disk.
+        buffer.update(cx, |buffer, cx| buffer.undo(cx));
+        drop(buffer);
+        cx.run_until_parked();
+        assert_eq!(rows(cx), [(2, "three".to_string(), Some("third".into()))]);
+
+        fs.insert_file("/root/a.txt", b"one\n\n\ntwo\nthree\n".to_vec())
+            .await;
+        cx.run_until_parked();
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/a.txt", cx)
+            })
+            .await
+            .unwrap();
+        store.update(cx, |store, cx| store.register_buffer(&buffer, cx));
+        assert_eq!(rows(cx), [(4, "three".to_string(), Some("third".into()))]);
+    }
+}
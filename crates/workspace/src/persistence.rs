@@ -1,6 +1,6 @@
 pub mod model;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, bail, Context, Result};
 use client::DevServerProjectId;
@@ -202,6 +202,14 @@ define_connection! {
     //     active: bool, // Indicates if this item is the active one in the pane
     //     preview: bool // Indicates if this item is a preview item
     // )
+    //
+    // bookmarks(
+    //     workspace_id: usize, // References workspaces table
+    //     path: PathBuf, // Absolute path of the bookmarked file
+    //     line: u32, // Zero-based row of the bookmarked line
+    //     line_text: String, // Text of the bookmarked line, used to find it again after changes on disk
+    //     note: Option<String>,
+    // )
     pub static ref DB: WorkspaceDb<()> =
     &[sql!(
         CREATE TABLE workspaces(
@@ -344,6 +352,18 @@ define_connection! {
     sql!(
         ALTER TABLE workspaces ADD COLUMN local_paths_order BLOB;
     ),
+    sql!(
+        CREATE TABLE bookmarks(
+            workspace_id INTEGER NOT NULL,
+            path BLOB NOT NULL,
+            line INTEGER NOT NULL,
+            line_text TEXT NOT NULL,
+            note TEXT,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        ) STRICT;
+    ),
     ];
 }
 
@@ -979,6 +999,37 @@ impl WorkspaceDb {
             WHERE workspace_id = ?1
         }
     }
+
+    query! {
+        pub(crate) fn bookmarks(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, u32, String, Option<String>)>> {
+            SELECT path, line, line_text, note
+            FROM bookmarks
+            WHERE workspace_id = ?
+            ORDER BY path, line
+        }
+    }
+
+    /// Replaces the bookmarks saved for the workspace.
+    pub(crate) async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: Vec<(PathBuf, u32, String, Option<String>)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_bookmarks", || {
+                conn.exec_bound(sql!(DELETE FROM bookmarks WHERE workspace_id = ?))?(workspace_id)?;
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO bookmarks(workspace_id, path, line, line_text, note)
+                    VALUES (?, ?, ?, ?, ?)
+                ))?;
+                for (path, line, line_text, note) in bookmarks {
+                    insert((workspace_id, path, line, line_text, note))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -39,6 +39,11 @@ pub struct SearchOptions {
     pub case: bool,
     pub word: bool,
     pub regex: bool,
+    /// Specifies whether the item can be searched with structural patterns,
+    /// matched against its syntax tree.
+    pub structural: bool,
+    /// Specifies whether matches can be restricted to code, comments or strings.
+    pub syntax_context: bool,
     /// Specifies whether the  supports search & replace.
     pub replacement: bool,
     pub selection: bool,
@@ -52,6 +57,8 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
             case: true,
             word: true,
             regex: true,
+            structural: true,
+            syntax_context: true,
             replacement: true,
             selection: true,
         }
@@ -65,6 +72,12 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
 
     fn toggle_filtered_search_ranges(&mut self, _enabled: bool, _cx: &mut ViewContext<Self>) {}
 
+    /// Returns whether the item's current content can tell code, comments and
+    /// strings apart, for matches to be restricted to one of them.
+    fn supports_syntax_context(&self, _cx: &AppContext) -> bool {
+        Self::supported_options().syntax_context
+    }
+
     fn clear_matches(&mut self, cx: &mut ViewContext<Self>);
     fn update_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>);
     fn query_suggestion(&mut self, cx: &mut ViewContext<Self>) -> String;
@@ -107,6 +120,7 @@ pub trait SearchableItemHandle: ItemHandle {
     fn downgrade(&self) -> Box<dyn WeakSearchableItemHandle>;
     fn boxed_clone(&self) -> Box<dyn SearchableItemHandle>;
     fn supported_options(&self) -> SearchOptions;
+    fn supports_syntax_context(&self, cx: &AppContext) -> bool;
     fn subscribe_to_search_events(
         &self,
         cx: &mut WindowContext,
@@ -159,6 +173,10 @@ impl<T: SearchableItem> SearchableItemHandle for View<T> {
         T::supported_options()
     }
 
+    fn supports_syntax_context(&self, cx: &AppContext) -> bool {
+        self.read(cx).supports_syntax_context(cx)
+    }
+
     fn subscribe_to_search_events(
         &self,
         cx: &mut WindowContext,
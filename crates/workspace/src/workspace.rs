@@ -1,3 +1,4 @@
+pub mod bookmarks;
 pub mod dock;
 pub mod item;
 mod modal_layer;
@@ -13,6 +14,7 @@ mod toolbar;
 mod workspace_settings;
 
 use anyhow::{anyhow, Context as _, Result};
+use bookmarks::BookmarkStore;
 use call::{call_settings::CallSettings, ActiveCall};
 use client::{
     proto::{self, ErrorCode, PeerId},
@@ -593,6 +595,7 @@ pub struct Workspace {
     titlebar_item: Option<AnyView>,
     notifications: Vec<(NotificationId, Box<dyn NotificationHandle>)>,
     project: Model<Project>,
+    bookmark_store: Model<BookmarkStore>,
     follower_states: HashMap<View<Pane>, FollowerState>,
     last_leaders_by_pane: HashMap<WeakView<Pane>, PeerId>,
     window_edited: bool,
@@ -871,6 +874,7 @@ impl Workspace {
             bottom_dock,
             right_dock,
             project: project.clone(),
+            bookmark_store: cx.new_model(|_| BookmarkStore::new(workspace_id)),
             follower_states: Default::default(),
             last_leaders_by_pane: Default::default(),
             dispatching_keystrokes: Default::default(),
@@ -1084,6 +1088,10 @@ impl Workspace {
         &self.project
     }
 
+    pub fn bookmark_store(&self) -> &Model<BookmarkStore> {
+        &self.bookmark_store
+    }
+
     pub fn recent_navigation_history(
         &self,
         limit: Option<usize>,
@@ -4885,8 +4893,13 @@ pub fn create_and_open_local_file(
         let fs = workspace.update(&mut cx, |workspace, _| workspace.app_state().fs.clone())?;
         if !fs.is_file(path).await {
             fs.create_file(path, Default::default()).await?;
-            fs.save(path, &default_content(), Default::default())
-                .await?;
+            fs.save(
+                path,
+                &default_content(),
+                Default::default(),
+                Default::default(),
+            )
+            .await?;
         }
 
         let mut items = workspace
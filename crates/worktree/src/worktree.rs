@@ -27,7 +27,7 @@ use git::{
 };
 use gpui::{
     AppContext, AsyncAppContext, BackgroundExecutor, Context, EventEmitter, Model, ModelContext,
-    Task,
+    Task, WeakModel,
 };
 use ignore::IgnoreStack;
 use parking_lot::Mutex;
@@ -58,7 +58,7 @@ use std::{
     time::{Duration, Instant, SystemTime},
 };
 use sum_tree::{Bias, Edit, SeekTarget, SumTree, TreeMap, TreeSet};
-use text::{LineEnding, Rope};
+use text::{Encoding, LineEnding, Rope};
 use util::{paths::home_dir, ResultExt};
 pub use worktree_settings::WorktreeSettings;
 
@@ -97,8 +97,26 @@ pub enum CreatedEntry {
 
 pub struct LoadedFile {
     pub file: Arc<File>,
-    pub text: String,
+    /// The file's text, with its line endings normalized.
+    pub text: Rope,
+    pub line_ending: LineEnding,
+    pub encoding: Encoding,
     pub diff_base: Option<String>,
+    /// Whether the file is larger than the large file threshold.
+    pub is_large: bool,
+}
+
+/// A file larger than the large file threshold that is being loaded.
+pub struct LargeFileLoad {
+    loaded_len: Arc<AtomicUsize>,
+    pub len: u64,
+}
+
+impl LargeFileLoad {
+    /// The number of bytes of the file loaded so far.
+    pub fn loaded_len(&self) -> u64 {
+        self.loaded_len.load(SeqCst) as u64
+    }
 }
 
 pub struct LocalWorktree {
@@ -114,6 +132,7 @@ pub struct LocalWorktree {
     next_entry_id: Arc<AtomicUsize>,
     settings: WorktreeSettings,
     share_private_files: bool,
+    large_file_loads: HashMap<Arc<Path>, LargeFileLoad>,
 }
 
 struct ScanRequest {
@@ -398,8 +417,11 @@ impl Worktree {
                 if let Self::Local(this) = this {
                     let settings = WorktreeSettings::get(settings_location, cx).clone();
                     if settings != this.settings {
+                        let rescan = this.settings.requires_rescan(&settings);
                         this.settings = settings;
-                        this.restart_background_scanners(cx);
+                        if rescan {
+                            this.restart_background_scanners(cx);
+                        }
                     }
                 }
             })
@@ -434,6 +456,7 @@ impl Worktree {
             let (path_prefixes_to_scan_tx, path_prefixes_to_scan_rx) = channel::unbounded();
             let mut worktree = LocalWorktree {
                 share_private_files: false,
+                large_file_loads: HashMap::default(),
                 next_entry_id,
                 snapshot,
                 is_scanning: watch::channel_with(true),
@@ -678,10 +701,11 @@ impl Worktree {
         path: &Path,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         match self {
-            Worktree::Local(this) => this.write_file(path, text, line_ending, cx),
+            Worktree::Local(this) => this.write_file(path, text, line_ending, encoding, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktree can't yet write files")))
             }
@@ -1239,19 +1263,43 @@ impl LocalWorktree {
         self.git_repositories.get(&repo.work_directory.0)
     }
 
+    /// Returns the files that are being loaded in large file mode.
+    pub fn large_file_loads(&self) -> impl Iterator<Item = (&Arc<Path>, &LargeFileLoad)> {
+        self.large_file_loads.iter()
+    }
+
     fn load_file(&self, path: &Path, cx: &mut ModelContext<Worktree>) -> Task<Result<LoadedFile>> {
         let path = Arc::from(path);
         let abs_path = self.absolutize(&path);
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
         let is_private = self.is_path_private(path.as_ref());
+        let large_file_threshold = self.settings.large_file_threshold;
 
         cx.spawn(|this, mut cx| async move {
             let abs_path = abs_path?;
-            let text = fs.load(&abs_path).await?;
+            let len = fs
+                .metadata(&abs_path)
+                .await?
+                .map_or(0, |metadata| metadata.len);
+            let is_large = len > large_file_threshold;
+            let (text, line_ending, encoding) = if is_large {
+                load_large_file(&this, &path, &abs_path, len, fs.as_ref(), &mut cx).await?
+            } else {
+                let (mut text, encoding) = fs.load_with_encoding(&abs_path, None).await?;
+                cx.background_executor()
+                    .spawn(async move {
+                        let line_ending = LineEnding::detect(&text);
+                        LineEnding::normalize(&mut text);
+                        (Rope::from(text), line_ending, encoding)
+                    })
+                    .await
+            };
             let mut index_task = None;
             let snapshot = this.update(&mut cx, |this, _| this.as_local().unwrap().snapshot())?;
-            if let Some(repo) = snapshot.repository_for_path(&path) {
+            // Large files aren't diffed against the index.
+            let repo = snapshot.repository_for_path(&path).filter(|_| !is_large);
+            if let Some(repo) = repo {
                 if let Some(repo_path) = repo.relativize(&snapshot, &path).log_err() {
                     if let Some(git_repo) = snapshot.git_repositories.get(&*repo.work_directory) {
                         let git_repo = git_repo.repo_ptr.clone();
@@ -1314,7 +1362,10 @@ impl LocalWorktree {
             Ok(LoadedFile {
                 file,
                 text,
+                line_ending,
+                encoding,
                 diff_base,
+                is_large,
             })
         })
     }
@@ -1352,9 +1403,14 @@ impl LocalWorktree {
                     .await
                     .with_context(|| format!("creating directory {task_abs_path:?}"))
             } else {
-                fs.save(&task_abs_path, &Rope::default(), LineEnding::default())
-                    .await
-                    .with_context(|| format!("creating file {task_abs_path:?}"))
+                fs.save(
+                    &task_abs_path,
+                    &Rope::default(),
+                    LineEnding::default(),
+                    Encoding::default(),
+                )
+                .await
+                .with_context(|| format!("creating file {task_abs_path:?}"))
             }
         });
 
@@ -1401,6 +1457,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
@@ -1413,7 +1470,7 @@ impl LocalWorktree {
         let write = cx.background_executor().spawn({
             let fs = fs.clone();
             let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
+            async move { fs.save(&abs_path, &text, line_ending, encoding).await }
         });
 
         cx.spawn(move |this, mut cx| async move {
@@ -1804,6 +1861,53 @@ impl LocalWorktree {
     }
 }
 
+/// Loads and decodes a file in chunks on the background, tracking its progress
+/// in the worktree's large file loads while it loads.
+async fn load_large_file(
+    worktree: &WeakModel<Worktree>,
+    path: &Arc<Path>,
+    abs_path: &Path,
+    len: u64,
+    fs: &dyn Fs,
+    cx: &mut AsyncAppContext,
+) -> Result<(Rope, LineEnding, Encoding)> {
+    const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+    let loaded_len = Arc::new(AtomicUsize::new(0));
+    worktree.update(cx, |worktree, cx| {
+        if let Some(worktree) = worktree.as_local_mut() {
+            let load = LargeFileLoad {
+                loaded_len: loaded_len.clone(),
+                len,
+            };
+            worktree.large_file_loads.insert(path.clone(), load);
+            cx.notify();
+        }
+    })?;
+
+    let report_progress = cx.spawn({
+        let worktree = worktree.clone();
+        |mut cx| async move {
+            loop {
+                cx.background_executor().timer(PROGRESS_INTERVAL).await;
+                if worktree.update(&mut cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    let result = fs.load_large(abs_path, loaded_len).await;
+    drop(report_progress);
+
+    worktree.update(cx, |worktree, cx| {
+        if let Some(worktree) = worktree.as_local_mut() {
+            worktree.large_file_loads.remove(path);
+            cx.notify();
+        }
+    })?;
+    result
+}
+
 impl RemoteWorktree {
     pub fn disconnected_from_host(&mut self) {
         self.updates_tx.take();
@@ -3030,12 +3134,16 @@ impl language::LocalFile for File {
         }
     }
 
-    fn load(&self, cx: &AppContext) -> Task<Result<String>> {
+    fn load(
+        &self,
+        encoding: Option<Encoding>,
+        cx: &AppContext,
+    ) -> Task<Result<(String, Encoding)>> {
         let worktree = self.worktree.read(cx).as_local().unwrap();
         let abs_path = worktree.absolutize(&self.path);
         let fs = worktree.fs.clone();
         cx.background_executor()
-            .spawn(async move { fs.load(&abs_path?).await })
+            .spawn(async move { fs.load_with_encoding(&abs_path?, encoding).await })
     }
 }
 
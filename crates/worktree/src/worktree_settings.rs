@@ -11,6 +11,8 @@ use util::paths::PathMatcher;
 pub struct WorktreeSettings {
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    /// The size in bytes above which files are opened in large file mode.
+    pub large_file_threshold: u64,
 }
 
 impl WorktreeSettings {
@@ -23,6 +25,17 @@ impl WorktreeSettings {
         path.ancestors()
             .any(|ancestor| self.file_scan_exclusions.is_match(&ancestor))
     }
+
+    /// Whether the worktree has to be rescanned when its settings change from
+    /// `self` to `new`, because the settings used while scanning changed.
+    pub fn requires_rescan(&self, new: &Self) -> bool {
+        let Self {
+            file_scan_exclusions,
+            private_files,
+            large_file_threshold: _,
+        } = self;
+        *file_scan_exclusions != new.file_scan_exclusions || *private_files != new.private_files
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -45,6 +58,13 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// Open files larger than this many megabytes in large file mode, which
+    /// loads them in the background and skips syntax highlighting, language
+    /// servers, git diffs and soft wrap.
+    ///
+    /// Default: 20
+    pub large_file_threshold_mb: Option<u64>,
 }
 
 impl Settings for WorktreeSettings {
@@ -64,6 +84,7 @@ impl Settings for WorktreeSettings {
         Ok(Self {
             file_scan_exclusions: path_matchers(&file_scan_exclusions, "file_scan_exclusions")?,
             private_files: path_matchers(&private_files, "private_files")?,
+            large_file_threshold: result.large_file_threshold_mb.unwrap_or(20) * 1024 * 1024,
         })
     }
 }
@@ -197,6 +197,11 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
 
     journal::init(app_state.clone(), cx);
     language_selector::init(cx);
+    encoding_selector::init(cx);
+    diff_view::init(cx);
+    local_history::init(cx);
+    bookmarks::init(cx);
+    merge_conflicts::init(cx);
     theme_selector::init(cx);
     language_tools::init(cx);
     call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -350,6 +355,11 @@ fn main() {
         fs.clone(),
         paths::keymap_file().clone(),
     );
+    let user_vimrc_file_rx = watch_config_file(
+        &app.background_executor(),
+        fs.clone(),
+        paths::vimrc_file().clone(),
+    );
 
     let login_shell_env_loaded = if stdout_is_a_pty() {
         Task::ready(())
@@ -392,7 +402,7 @@ fn main() {
 
         settings::init(cx);
         handle_settings_file_changes(user_settings_file_rx, cx);
-        handle_keymap_file_changes(user_keymap_file_rx, cx);
+        handle_keymap_file_changes(user_keymap_file_rx, user_vimrc_file_rx, cx);
 
         client::init_settings(cx);
         let client = Client::production(cx);
@@ -5,6 +5,7 @@ pub(crate) mod linux_prompts;
 #[cfg(not(target_os = "linux"))]
 pub(crate) mod only_instance;
 mod open_listener;
+mod vimrc;
 
 pub use app_menus::*;
 use breadcrumbs::Breadcrumbs;
@@ -136,6 +137,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             cx.new_view(|cx| diagnostics::items::DiagnosticIndicator::new(workspace, cx));
         let activity_indicator =
             activity_indicator::ActivityIndicator::new(workspace, app_state.languages.clone(), cx);
+        let active_buffer_encoding =
+            cx.new_view(|_| encoding_selector::ActiveBufferEncoding::default());
         let active_buffer_language =
             cx.new_view(|_| language_selector::ActiveBufferLanguage::new(workspace));
         let vim_mode_indicator = cx.new_view(|cx| vim::ModeIndicator::new(cx));
@@ -145,6 +148,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             status_bar.add_left_item(diagnostic_summary, cx);
             status_bar.add_left_item(activity_indicator, cx);
             status_bar.add_right_item(inline_completion_button, cx);
+            status_bar.add_right_item(active_buffer_encoding, cx);
             status_bar.add_right_item(active_buffer_language, cx);
             status_bar.add_right_item(vim_mode_indicator, cx);
             status_bar.add_right_item(cursor_position, cx);
@@ -663,6 +667,7 @@ fn open_log_file(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
 
 pub fn handle_keymap_file_changes(
     mut user_keymap_file_rx: mpsc::UnboundedReceiver<String>,
+    mut user_vimrc_file_rx: mpsc::UnboundedReceiver<String>,
     cx: &mut AppContext,
 ) {
     BaseKeymap::register(cx);
@@ -683,13 +688,22 @@ pub fn handle_keymap_file_changes(
     })
     .detach();
 
+    let mut project_vimrc_rx = vimrc::watch_project_vimrcs(cx);
+
     load_default_keymap(cx);
 
     cx.spawn(move |cx| async move {
         let mut user_keymap = KeymapFile::default();
+        let mut user_vimrc = String::new();
         loop {
             select_biased! {
                 _ = base_keymap_rx.next() => {}
+                _ = project_vimrc_rx.next() => {}
+                user_vimrc_content = user_vimrc_file_rx.next() => {
+                    if let Some(user_vimrc_content) = user_vimrc_content {
+                        user_vimrc = user_vimrc_content;
+                    }
+                }
                 user_keymap_content = user_keymap_file_rx.next() => {
                     if let Some(user_keymap_content) = user_keymap_content {
                         if let Some(keymap_content) = KeymapFile::parse(&user_keymap_content).log_err() {
@@ -700,15 +714,16 @@ pub fn handle_keymap_file_changes(
                     }
                 }
             }
-            cx.update(|cx| reload_keymaps(cx, &user_keymap)).ok();
+            cx.update(|cx| reload_keymaps(cx, &user_keymap, &user_vimrc)).ok();
         }
     })
     .detach();
 }
 
-fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile) {
+fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile, user_vimrc: &str) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
+    vimrc::load_vimrcs(user_vimrc, cx);
     keymap_content.clone().add_to_cx(cx).log_err();
     cx.set_menus(app_menus());
     cx.set_dock_menu(vec![MenuItem::action("New Window", workspace::NewWindow)])
@@ -2858,6 +2873,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2877,6 +2893,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2892,8 +2909,10 @@ mod tests {
                 app_state.fs.clone(),
                 PathBuf::from("/keymap.json"),
             );
+            let vimrc_rx =
+                watch_config_file(&executor, app_state.fs.clone(), PathBuf::from("/vimrc"));
             handle_settings_file_changes(settings_rx, cx);
-            handle_keymap_file_changes(keymap_rx, cx);
+            handle_keymap_file_changes(keymap_rx, vimrc_rx, cx);
         });
         workspace
             .update(cx, |workspace, cx| {
@@ -2929,6 +2948,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2954,6 +2974,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2998,6 +3019,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -3016,6 +3038,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -3031,9 +3054,11 @@ mod tests {
                 app_state.fs.clone(),
                 PathBuf::from("/keymap.json"),
             );
+            let vimrc_rx =
+                watch_config_file(&executor, app_state.fs.clone(), PathBuf::from("/vimrc"));
 
             handle_settings_file_changes(settings_rx, cx);
-            handle_keymap_file_changes(keymap_rx, cx);
+            handle_keymap_file_changes(keymap_rx, vimrc_rx, cx);
         });
 
         cx.background_executor.run_until_parked();
@@ -3063,6 +3088,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -3088,6 +3114,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -0,0 +1,235 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use futures::channel::mpsc;
+use gpui::{AppContext, EntityId, Global, Model, SharedString, UpdateGlobal, VisualContext};
+use project::{PathChange, Project, WorktreeId};
+use settings::{KeymapFile, Settings, SettingsStore, Vimrc};
+use util::{merge_non_null_json_value_into, ResultExt};
+use vim::VimModeSetting;
+use workspace::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    Workspace,
+};
+
+/// The vimrc files of the open projects, and the errors found when the vimrcs
+/// were last loaded.
+#[derive(Default)]
+struct Vimrcs {
+    /// The content of the `.zed/vimrc` file of each worktree of the open
+    /// projects, keyed by the file's path.
+    project: BTreeMap<PathBuf, ProjectVimrc>,
+    settings: serde_json::Value,
+    /// The settings applied for the options of each worktree's vimrc.
+    local_settings: BTreeMap<WorktreeId, serde_json::Value>,
+    errors: Option<SharedString>,
+}
+
+struct ProjectVimrc {
+    project: EntityId,
+    worktree_id: WorktreeId,
+    content: String,
+}
+
+impl Global for Vimrcs {}
+
+struct VimrcErrors;
+
+/// Keeps track of the `.zed/vimrc` files of the projects that are open,
+/// returning a receiver that's notified whenever one of them changes.
+pub(crate) fn watch_project_vimrcs(cx: &mut AppContext) -> mpsc::UnboundedReceiver<()> {
+    let (tx, rx) = mpsc::unbounded();
+    cx.set_global(Vimrcs::default());
+    cx.observe_new_views(move |workspace: &mut Workspace, cx| {
+        let project = workspace.project().clone();
+        let tx = tx.clone();
+        cx.subscribe(&project, move |_, project, event, cx| {
+            load_project_vimrc(project, event, tx.clone(), cx);
+        })
+        .detach();
+
+        let project_id = project.entity_id();
+        let tx = tx.clone();
+        cx.on_release(move |_, _, cx| {
+            let vimrcs = &mut cx.global_mut::<Vimrcs>().project;
+            let count = vimrcs.len();
+            vimrcs.retain(|_, vimrc| vimrc.project != project_id);
+            if vimrcs.len() != count {
+                tx.unbounded_send(()).ok();
+            }
+        })
+        .detach();
+
+        if let Some(errors) = cx.global::<Vimrcs>().errors.clone() {
+            show_errors(workspace, errors, cx);
+        }
+    })
+    .detach();
+    rx
+}
+
+fn load_project_vimrc(
+    project: Model<Project>,
+    event: &project::Event,
+    tx: mpsc::UnboundedSender<()>,
+    cx: &mut AppContext,
+) {
+    match event {
+        project::Event::WorktreeUpdatedEntries(worktree_id, changes) => {
+            let Some(worktree) = project.read(cx).worktree_for_id(*worktree_id, cx) else {
+                return;
+            };
+            let worktree = worktree.read(cx);
+            if worktree.is_remote() {
+                return;
+            }
+            let Some((path, _, change)) = changes
+                .iter()
+                .find(|(path, _, _)| path.as_ref() == paths::local_vimrc_file_relative_path())
+            else {
+                return;
+            };
+            let Some(abs_path) = worktree.absolutize(path).log_err() else {
+                return;
+            };
+
+            if *change == PathChange::Removed {
+                cx.global_mut::<Vimrcs>().project.remove(&abs_path);
+                tx.unbounded_send(()).ok();
+                return;
+            }
+            let project_id = project.entity_id();
+            let worktree_id = *worktree_id;
+            let fs = project.read(cx).fs().clone();
+            cx.spawn(|mut cx| async move {
+                let content = fs.load(&abs_path).await?;
+                cx.update(|cx| {
+                    cx.global_mut::<Vimrcs>().project.insert(
+                        abs_path,
+                        ProjectVimrc {
+                            project: project_id,
+                            worktree_id,
+                            content,
+                        },
+                    );
+                })?;
+                tx.unbounded_send(()).ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        }
+        project::Event::WorktreeRemoved(worktree_id) => {
+            let vimrcs = &mut cx.global_mut::<Vimrcs>().project;
+            let count = vimrcs.len();
+            vimrcs.retain(|_, vimrc| vimrc.worktree_id != *worktree_id);
+            if vimrcs.len() != count {
+                tx.unbounded_send(()).ok();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Applies the mappings and options of the user's vimrc, and the options of
+/// the vimrcs of the open projects to their worktrees, when Vim mode is enabled.
+/// Mappings are only read from the user's vimrc, so that opening a project
+/// can't change what keys do. Errors in the files are reported as a
+/// notification in each workspace.
+pub(crate) fn load_vimrcs(user_vimrc: &str, cx: &mut AppContext) {
+    let mut mappings = Vec::new();
+    let mut settings = serde_json::json!({});
+    let mut local_settings = BTreeMap::new();
+    let mut errors = Vec::new();
+    if VimModeSetting::get_global(cx).0 {
+        let vimrc = Vimrc::parse(user_vimrc);
+        mappings = vimrc.mappings;
+        settings = vimrc.settings;
+        errors.extend(
+            vimrc
+                .errors
+                .iter()
+                .map(|error| format!("{}, {error}", paths::vimrc_file().display())),
+        );
+
+        for (path, project_vimrc) in &cx.global::<Vimrcs>().project {
+            let vimrc = Vimrc::parse(&project_vimrc.content);
+            if !vimrc.mappings.is_empty() {
+                errors.push(format!(
+                    "{}, mappings are only read from your own vimrc",
+                    path.display()
+                ));
+            }
+            let worktree_settings = local_settings
+                .entry(project_vimrc.worktree_id)
+                .or_insert_with(|| serde_json::json!({}));
+            merge_non_null_json_value_into(vimrc.settings, worktree_settings);
+            errors.extend(
+                vimrc
+                    .errors
+                    .iter()
+                    .map(|error| format!("{}, {error}", path.display())),
+            );
+        }
+    }
+
+    KeymapFile::from_vim_mappings(&mappings)
+        .add_to_cx(cx)
+        .log_err();
+
+    if cx.global::<Vimrcs>().settings != settings {
+        cx.global_mut::<Vimrcs>().settings = settings.clone();
+        SettingsStore::update_global(cx, |store, cx| store.set_vimrc_settings(settings, cx))
+            .log_err();
+    }
+
+    let previous_local_settings = std::mem::take(&mut cx.global_mut::<Vimrcs>().local_settings);
+    SettingsStore::update_global(cx, |store, cx| {
+        for worktree_id in previous_local_settings.keys() {
+            if !local_settings.contains_key(worktree_id) {
+                store
+                    .set_local_vimrc_settings(worktree_id.to_usize(), None, cx)
+                    .log_err();
+            }
+        }
+        for (worktree_id, settings) in &local_settings {
+            if previous_local_settings.get(worktree_id) != Some(settings) {
+                store
+                    .set_local_vimrc_settings(worktree_id.to_usize(), Some(settings.clone()), cx)
+                    .log_err();
+            }
+        }
+    });
+    cx.global_mut::<Vimrcs>().local_settings = local_settings;
+
+    let errors: Option<SharedString> = if errors.is_empty() {
+        None
+    } else {
+        Some(format!("Errors in vimrc:\n{}", errors.join("\n")).into())
+    };
+    if cx.global::<Vimrcs>().errors == errors {
+        return;
+    }
+    cx.global_mut::<Vimrcs>().errors = errors.clone();
+    for window in cx.windows() {
+        let Some(window) = window.downcast::<Workspace>() else {
+            continue;
+        };
+        window
+            .update(cx, |workspace, cx| match errors.clone() {
+                Some(errors) => show_errors(workspace, errors, cx),
+                None => {
+                    workspace.dismiss_notification(&NotificationId::unique::<VimrcErrors>(), cx)
+                }
+            })
+            .log_err();
+    }
+}
+
+fn show_errors(
+    workspace: &mut Workspace,
+    errors: SharedString,
+    cx: &mut gpui::ViewContext<Workspace>,
+) {
+    workspace.show_notification(NotificationId::unique::<VimrcErrors>(), cx, |cx| {
+        cx.new_view(|_| MessageNotification::new(errors))
+    });
+}